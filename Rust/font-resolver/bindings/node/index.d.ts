@@ -29,8 +29,24 @@ export interface JsCacheStats {
   pinnedFonts: number
   memoryUsageMb: number
   diskUsageMb: number
+  hits: number
+  misses: number
+  evictions: number
+  hitRate: number
 }
-export declare function getFontSuggestions(fontName: string, includeInternet: boolean): Promise<Array<JsSuggestion>>
+export interface JsDanglingEntry {
+  fontName: string
+  path: string
+  /** "file missing" or "file modified" */
+  status: string
+}
+export interface JsVerifyReport {
+  checked: number
+  unverified: number
+  dangling: Array<JsDanglingEntry>
+  removed: number
+}
+export declare function getFontSuggestions(fontName: string, includeInternet: boolean, glyphDbPath?: string | undefined | null): Promise<Array<JsSuggestion>>
 export declare function normalizeFontName(fontName: string): string
 export declare function pinFont(fontName: string): void
 export declare function unpinFont(fontName: string): void
@@ -39,7 +55,64 @@ export declare function exportMetrics(fontName: string): string
 export declare function getEngineStats(): JsDatabaseStats
 export declare function getCacheStats(): JsCacheStats
 export declare function cleanupCache(aggressive: boolean): number
+/** Runs on a worker thread, since it may hash every cached font file */
+export declare function verifyCache(purge: boolean): Promise<JsVerifyReport>
 export declare function listPinnedFonts(): Array<string>
+export interface JsWebFontQuery {
+  text?: string
+  /** Category names: "serif", "sans-serif", "monospace", "display", "handwriting" */
+  categories?: Array<string>
+  subsets?: Array<string>
+  weights?: Array<number>
+  axes?: Array<string>
+  license?: string
+  commercialUse?: boolean
+  minPopularity?: number
+  maxFileSizeKb?: number
+  /** "relevance" (default), "popularity" or "name" */
+  sort?: string
+  offset?: number
+  limit?: number
+}
+export interface JsWebFontHit {
+  family: string
+  /** Same names as `JsWebFontQuery.categories` */
+  category: string
+  weights: Array<number>
+  subsets: Array<string>
+  axes: Array<string>
+  licenseName: string
+  popularity: number
+  score: number
+  nameScore: number
+}
+export interface JsWebFontSearchPage {
+  hits: Array<JsWebFontHit>
+  total: number
+  offset: number
+  limit: number
+}
+/** Search the web font catalogue by fuzzy name and facets */
+export declare function searchWebFonts(query: JsWebFontQuery): JsWebFontSearchPage
+export interface JsFontPairing {
+  heading: string
+  body: string
+  partner: string
+  /** e.g. "serif", "geometric sans" */
+  partnerClass: string
+  score: number
+  contrast: number
+  xHeightMatch?: number
+  widthMatch?: number
+  local: boolean
+  license: string
+  reasons: Array<string>
+}
+/**
+ * Heading/body partners for a font. `role` ("heading" or "body") is the part the font plays;
+ * `scope` is "available" (installed or open source), "local" or "license-safe".
+ */
+export declare function suggestFontPairings(fontName: string, role?: string | undefined | null, scope?: string | undefined | null, limit?: number | undefined | null): Array<JsFontPairing>
 export declare function updateDatabase(): Promise<void>
 export interface JsVisualMatch {
   family: string
@@ -106,9 +179,10 @@ export declare function extractGlyphSignature(fontPath: string, character: strin
 /**
  * Build a glyph signature database from font files
  *
- * Indexes the specified font files with Brotli-11 compression.
+ * Indexes the specified font files with Brotli-11 compression, for the given
+ * scripts ("latin", "greek", "cyrillic", "arabic", "devanagari", "cjk"; default Latin).
  */
-export declare function buildGlyphDatabase(fontPaths: Array<string>, outputPath: string): JsGlyphDbStats
+export declare function buildGlyphDatabase(fontPaths: Array<string>, outputPath: string, scripts?: Array<string> | undefined | null): JsGlyphDbStats
 /**
  * Compare the visual similarity of two font glyphs
  *
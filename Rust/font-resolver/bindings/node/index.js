@@ -9,8 +9,11 @@ const {
   getEngineStats,
   getCacheStats,
   cleanupCache,
+  verifyCache,
   listPinnedFonts,
-  updateDatabase
+  updateDatabase,
+  searchWebFonts,
+  suggestFontPairings
 } = require('./intellifont-engine.node');
 
 /**
@@ -31,8 +34,8 @@ function createFontFace(family, url, weight = 400, italic = false) {
 /**
  * Enhanced suggestion helper that includes license advice.
  */
-async function getEnhancedSuggestions(fontName, includeInternet = true) {
-  const suggestions = await getFontSuggestions(fontName, includeInternet);
+async function getEnhancedSuggestions(fontName, includeInternet = true, glyphDbPath = null) {
+  const suggestions = await getFontSuggestions(fontName, includeInternet, glyphDbPath);
 
   return suggestions.map(s => ({
     ...s,
@@ -103,12 +106,19 @@ if (require.main === module) {
           case 'stats':
             const cs = getCacheStats();
             console.log(`\nCACHE: ${cs.memoryEntries} mem / ${cs.diskEntries} disk (${cs.diskUsageMb.toFixed(2)} MB)`);
+            console.log(`  Hit rate: ${(cs.hitRate * 100).toFixed(1)}% (${cs.hits} hits, ${cs.misses} misses, ${cs.evictions} evictions)`);
             break;
           case 'cleanup':
             const agg = args.includes('--aggressive');
             const cleaned = cleanupCache(agg);
             console.log(`Cache cleared. Removed ${cleaned} entries.`);
             break;
+          case 'verify':
+            const report = await verifyCache(args.includes('--purge'));
+            console.log(`\nChecked ${report.checked} entries (${report.unverified} unverified)`);
+            report.dangling.forEach(d => console.log(`  - ${d.fontName} (${d.status}: ${d.path})`));
+            if (report.removed) console.log(`Removed ${report.removed} dangling entries.`);
+            break;
           case 'list':
             const pinned = listPinnedFonts();
             console.log("\nPINNED FONTS");
@@ -155,6 +165,41 @@ if (require.main === module) {
         console.log("\nConfiguration optimized for your system.");
         break;
 
+      case 'search': {
+        const flag = (name) => {
+          const i = args.indexOf(name);
+          return i >= 0 ? args[i + 1] : undefined;
+        };
+        const page = searchWebFonts({
+          text: params[0] && !params[0].startsWith('--') ? params[0] : undefined,
+          categories: flag('--category') ? [flag('--category')] : undefined,
+          subsets: flag('--subset') ? [flag('--subset')] : undefined,
+          license: flag('--license'),
+          commercialUse: args.includes('--commercial'),
+          limit: flag('--limit') ? parseInt(flag('--limit'), 10) : undefined,
+        });
+        console.log(`\nWeb fonts (${page.total} matches):`);
+        page.hits.forEach(h => {
+          console.log(` - ${h.family} [${h.category}] ${h.licenseName} - ${(h.score * 100).toFixed(0)}%`);
+        });
+        break;
+      }
+
+      case 'pair': {
+        const option = (name) => {
+          const i = args.indexOf(name);
+          return i >= 0 ? args[i + 1] : undefined;
+        };
+        const role = option('--role') || 'heading';
+        const pairings = suggestFontPairings(params[0] || "Arial", role, option('--scope'), 5);
+        console.log(`\n${role === 'body' ? 'Headings' : 'Body fonts'} for ${params[0] || "Arial"}:`);
+        pairings.forEach(p => {
+          console.log(` - ${p.partner} (${p.partnerClass}) - ${(p.score * 100).toFixed(0)}%`);
+          p.reasons.forEach(r => console.log(`     ${r}`));
+        });
+        break;
+      }
+
       case 'normalize':
         console.log(normalizeFontName(params[0] || "Arial"));
         break;
@@ -165,6 +210,8 @@ if (require.main === module) {
         console.log("Usage:");
         console.log("  intellifont suggest <name> [--internet]    - Find matching fonts");
         console.log("  intellifont resolve <name>                 - Fast lookup");
+        console.log("  intellifont search [text] [--category c]   - Browse web fonts by name and facets");
+        console.log("  intellifont pair <name> [--role body]      - Suggest heading/body pairings");
         console.log("  intellifont stats                          - Engine & Cache metrics");
         console.log("  intellifont scan                           - Refresh system font index");
         console.log("  intellifont update                         - Sync with global CDN signatures");
@@ -189,6 +236,9 @@ module.exports = {
   getEngineStats,
   getCacheStats,
   cleanupCache,
+  verifyCache,
   listPinnedFonts,
-  updateDatabase
+  updateDatabase,
+  searchWebFonts,
+  suggestFontPairings
 };
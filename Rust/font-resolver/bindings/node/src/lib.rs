@@ -47,17 +47,44 @@ pub struct JsCacheStats {
     pub pinned_fonts: u32,
     pub memory_usage_mb: f64,
     pub disk_usage_mb: f64,
+    pub hits: f64,
+    pub misses: f64,
+    pub evictions: f64,
+    pub hit_rate: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[napi(object)]
+pub struct JsDanglingEntry {
+    pub font_name: String,
+    pub path: String,
+    /// "file missing" or "file modified"
+    pub status: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[napi(object)]
+pub struct JsVerifyReport {
+    pub checked: u32,
+    pub unverified: u32,
+    pub dangling: Vec<JsDanglingEntry>,
+    pub removed: u32,
 }
 
 #[napi]
-pub async fn get_font_suggestions(font_name: String, include_internet: bool) -> napi::Result<Vec<JsSuggestion>> {
+pub async fn get_font_suggestions(font_name: String, include_internet: bool, glyph_db_path: Option<String>) -> napi::Result<Vec<JsSuggestion>> {
     let mut config = EnhancedResolverConfig::default();
     config.web_fonts_enabled = include_internet;
     
     // Load embedded database (bundled with the .node file)
     let db_bytes = include_bytes!("../font_database.bin");
     
-    let resolver = EnhancedFontResolver::new_with_database(config, db_bytes).map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    let mut resolver = EnhancedFontResolver::new_with_database(config, db_bytes).map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    
+    // Glyph signatures let suggestions rank by how fonts look, not just their names
+    if let Some(path) = glyph_db_path {
+        resolver.load_glyph_database(&path).map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    }
     
     let suggestions = resolver.get_suggestions(&font_name, include_internet).await
         .map_err(|e| napi::Error::from_reason(e.to_string()))?;
@@ -150,6 +177,10 @@ pub fn get_cache_stats() -> napi::Result<JsCacheStats> {
             pinned_fonts: stats.pinned_fonts as u32,
             memory_usage_mb: stats.memory_usage_mb,
             disk_usage_mb: stats.disk_usage_mb,
+            hits: stats.hits as f64,
+            misses: stats.misses as f64,
+            evictions: stats.evictions as f64,
+            hit_rate: stats.hit_rate,
         }),
         Some(Err(e)) => Err(napi::Error::from_reason(e.to_string())),
         None => Err(napi::Error::from_reason("Cache not available")),
@@ -163,6 +194,33 @@ pub fn cleanup_cache(aggressive: bool) -> napi::Result<u32> {
     resolver.cleanup_cache(aggressive).map(|c| c as u32).map_err(|e| napi::Error::from_reason(e.to_string()))
 }
 
+/// Runs on a worker thread, since it may hash every cached font file
+#[napi]
+pub async fn verify_cache(purge: bool) -> napi::Result<JsVerifyReport> {
+    let report = tokio::task::spawn_blocking(move || {
+        let config = EnhancedResolverConfig::default();
+        let resolver = EnhancedFontResolver::new(config).map_err(|e| e.to_string())?;
+        match resolver.verify_cache(purge) {
+            Some(report) => report.map_err(|e| e.to_string()),
+            None => Err("Cache not available".to_string()),
+        }
+    })
+    .await
+    .map_err(|e| napi::Error::from_reason(e.to_string()))?
+    .map_err(napi::Error::from_reason)?;
+    
+    Ok(JsVerifyReport {
+        checked: report.checked as u32,
+        unverified: report.unverified as u32,
+        dangling: report.dangling.into_iter().map(|d| JsDanglingEntry {
+            font_name: d.font_name,
+            path: d.path.to_string_lossy().to_string(),
+            status: d.status.to_string(),
+        }).collect(),
+        removed: report.removed as u32,
+    })
+}
+
 #[napi]
 pub fn list_pinned_fonts() -> napi::Result<Vec<String>> {
     let config = EnhancedResolverConfig::default();
@@ -170,6 +228,166 @@ pub fn list_pinned_fonts() -> napi::Result<Vec<String>> {
     Ok(resolver.list_pinned_fonts().unwrap_or_default())
 }
 
+#[derive(Serialize, Deserialize, Default)]
+#[napi(object)]
+pub struct JsWebFontQuery {
+    pub text: Option<String>,
+    /// Category names: "serif", "sans-serif", "monospace", "display", "handwriting"
+    pub categories: Option<Vec<String>>,
+    pub subsets: Option<Vec<String>>,
+    pub weights: Option<Vec<u32>>,
+    pub axes: Option<Vec<String>>,
+    pub license: Option<String>,
+    pub commercial_use: Option<bool>,
+    pub min_popularity: Option<u32>,
+    pub max_file_size_kb: Option<u32>,
+    /// "relevance" (default), "popularity" or "name"
+    pub sort: Option<String>,
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[napi(object)]
+pub struct JsWebFontHit {
+    pub family: String,
+    /// Same names as `JsWebFontQuery::categories`
+    pub category: String,
+    pub weights: Vec<u32>,
+    pub subsets: Vec<String>,
+    pub axes: Vec<String>,
+    pub license_name: String,
+    pub popularity: u32,
+    pub score: f64,
+    pub name_score: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[napi(object)]
+pub struct JsWebFontSearchPage {
+    pub hits: Vec<JsWebFontHit>,
+    pub total: u32,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+/// Search the web font catalogue by fuzzy name and facets
+#[napi]
+pub fn search_web_fonts(query: JsWebFontQuery) -> napi::Result<JsWebFontSearchPage> {
+    use font_web_db::{FontCategory, SearchSort, WebFontQuery};
+    
+    let config = EnhancedResolverConfig {
+        web_fonts_enabled: true,
+        ..Default::default()
+    };
+    let resolver = EnhancedFontResolver::new(config).map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    
+    let mut categories = Vec::new();
+    for name in query.categories.unwrap_or_default() {
+        let category = FontCategory::from_name(&name)
+            .ok_or_else(|| napi::Error::from_reason(format!("Unknown category: {}", name)))?;
+        categories.push(category);
+    }
+    
+    let sort = match query.sort.as_deref().unwrap_or("relevance") {
+        "relevance" => SearchSort::Relevance,
+        "popularity" => SearchSort::Popularity,
+        "name" => SearchSort::Name,
+        other => return Err(napi::Error::from_reason(format!("Unknown sort order: {}", other))),
+    };
+    
+    let web_query = WebFontQuery {
+        text: query.text,
+        categories,
+        subsets: query.subsets.unwrap_or_default(),
+        weights: query.weights.unwrap_or_default().into_iter().map(|w| w as u16).collect(),
+        axes: query.axes.unwrap_or_default(),
+        license: query.license,
+        commercial_use: query.commercial_use.unwrap_or(false),
+        min_popularity: query.min_popularity.map(|p| p.min(100) as u8),
+        max_file_size_kb: query.max_file_size_kb,
+        sort,
+        offset: query.offset.unwrap_or(0) as usize,
+        limit: query.limit.unwrap_or(0) as usize,
+    };
+    
+    let page = resolver.search_web_fonts(&web_query)
+        .ok_or_else(|| napi::Error::from_reason("Web font database not available"))?;
+    
+    Ok(JsWebFontSearchPage {
+        hits: page.hits.into_iter().map(|hit| JsWebFontHit {
+            category: hit.font.category.name().to_string(),
+            weights: hit.font.variants.iter().map(|v| v.weight as u32).collect(),
+            subsets: hit.font.subsets,
+            axes: hit.font.axes,
+            license_name: hit.font.license.name,
+            popularity: hit.font.popularity as u32,
+            score: hit.score as f64,
+            name_score: hit.name_score as f64,
+            family: hit.font.family,
+        }).collect(),
+        total: page.total as u32,
+        offset: page.offset as u32,
+        limit: page.limit as u32,
+    })
+}
+
+#[derive(Serialize, Deserialize)]
+#[napi(object)]
+pub struct JsFontPairing {
+    pub heading: String,
+    pub body: String,
+    pub partner: String,
+    /// e.g. "serif", "geometric sans"
+    pub partner_class: String,
+    pub score: f64,
+    pub contrast: f64,
+    pub x_height_match: Option<f64>,
+    pub width_match: Option<f64>,
+    pub local: bool,
+    pub license: String,
+    pub reasons: Vec<String>,
+}
+
+/// Heading/body partners for a font. `role` ("heading" or "body") is the part the font plays;
+/// `scope` is "available" (installed or open source), "local" or "license-safe".
+#[napi]
+pub fn suggest_font_pairings(font_name: String, role: Option<String>, scope: Option<String>, limit: Option<u32>) -> napi::Result<Vec<JsFontPairing>> {
+    use font_similarity::{PairingRole, PairingScope};
+    
+    let role = match role.as_deref().unwrap_or("heading") {
+        "heading" => PairingRole::Heading,
+        "body" => PairingRole::Body,
+        other => return Err(napi::Error::from_reason(format!("Unknown role: {}", other))),
+    };
+    let scope = match scope.as_deref().unwrap_or("available") {
+        "available" => PairingScope::Available,
+        "local" => PairingScope::Local,
+        "license-safe" => PairingScope::LicenseSafe,
+        other => return Err(napi::Error::from_reason(format!("Unknown scope: {}", other))),
+    };
+    
+    let db_bytes = include_bytes!("../font_database.bin");
+    let resolver = EnhancedFontResolver::new_with_database(EnhancedResolverConfig::default(), db_bytes)
+        .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    let pairings = resolver.find_pairings(&font_name, role, scope, limit.unwrap_or(5) as usize)
+        .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    
+    Ok(pairings.into_iter().map(|p| JsFontPairing {
+        partner_class: p.partner_class.describe().to_string(),
+        score: p.score as f64,
+        contrast: p.contrast as f64,
+        x_height_match: p.x_height_match.map(|m| m as f64),
+        width_match: p.width_match.map(|m| m as f64),
+        local: p.local,
+        license: format!("{:?}", p.license),
+        heading: p.heading,
+        body: p.body,
+        partner: p.partner,
+        reasons: p.reasons,
+    }).collect())
+}
+
 #[napi]
 pub async fn update_database() -> napi::Result<()> {
     let mut config = EnhancedResolverConfig::default();
@@ -499,15 +717,22 @@ pub fn extract_glyph_signature(
 
 /// Build a glyph signature database from font files
 /// 
-/// Indexes the specified font files with Brotli-11 compression.
+/// Indexes the specified font files with Brotli-11 compression, for the given
+/// scripts ("latin", "greek", "cyrillic", "arabic", "devanagari", "cjk"; default Latin).
 #[napi]
 pub fn build_glyph_database(
     font_paths: Vec<String>,
     output_path: String,
+    scripts: Option<Vec<String>>,
 ) -> napi::Result<JsGlyphDbStats> {
-    use font_glyph_db::GlyphDatabaseBuilder;
-    
-    let mut builder = GlyphDatabaseBuilder::new();
+    use font_glyph_db::{GlyphDatabaseBuilder, Script};
+    
+    let scripts = scripts.unwrap_or_else(|| vec!["latin".to_string()])
+        .iter()
+        .map(|name| Script::from_name(name)
+            .ok_or_else(|| napi::Error::from_reason(format!("Unknown script: {}", name))))
+        .collect::<napi::Result<Vec<Script>>>()?;
+    let mut builder = GlyphDatabaseBuilder::new().with_scripts(&scripts);
     
     for path in &font_paths {
         if let Err(e) = builder.add_font_auto(path) {
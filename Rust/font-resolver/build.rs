@@ -2,11 +2,13 @@
 use std::fs;
 use serde_json::{json, to_string_pretty};
 use chrono::{Utc};
+use sha2::{Digest, Sha256};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("cargo:warning=🚀 Build script is running!");
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=data/sources");
+    println!("cargo:rerun-if-changed=crates/font-license/data/font_knowledge.json");
     
     // Add Windows-specific build configurations
     #[cfg(target_os = "windows")]
@@ -181,7 +183,7 @@ fn create_json_database() -> Result<String, Box<dyn std::error::Error>> {
                 "include_full_data": true
             }},
             "fonts": [{}],
-            "similarity_matrix": null
+            "neighbour_index": null
         }}"#,
         fonts.len(),
         fonts.len() * 50 * 1024,
@@ -193,6 +195,13 @@ fn create_json_database() -> Result<String, Box<dyn std::error::Error>> {
 }
 
 fn create_update_manifest() -> Result<(), Box<dyn std::error::Error>> {
+    // The license knowledge base is versioned separately from the database
+    let knowledge = fs::read("crates/font-license/data/font_knowledge.json")?;
+    let knowledge_version = serde_json::from_slice::<serde_json::Value>(&knowledge)?["version"]
+        .as_str()
+        .unwrap_or("0")
+        .to_string();
+    
     let manifest = json!({
         "version": "1.0.0",
         "font_count": 1000,
@@ -206,6 +215,11 @@ fn create_update_manifest() -> Result<(), Box<dyn std::error::Error>> {
             "removed_fonts": [],
             "updated_fonts": [],
             "security_fixes": []
+        },
+        "knowledge_base": {
+            "version": knowledge_version,
+            "url": "https://updates.font-resolver.com/knowledge/font_knowledge.json",
+            "checksum": format!("{:x}", Sha256::digest(&knowledge))
         }
     });
     
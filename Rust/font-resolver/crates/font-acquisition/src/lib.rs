@@ -77,6 +77,11 @@ impl FontAcquisitionManager {
         }
     }
     
+    /// Directory downloaded fonts are kept in, named `{postscript}_{format}` without an extension
+    pub fn download_cache(&self) -> &Path {
+        &self.download_cache
+    }
+    
     pub fn add_provider(&mut self, name: &str, provider: Box<dyn FontProvider + Send + Sync>) {
         self.providers.insert(name.to_string(), Arc::from(provider));
     }
@@ -167,7 +172,7 @@ impl FontAcquisitionManager {
         self.save_to_cache(&cache_path, &download).await?;
         
         // Convert to FontDescriptor
-        self.create_font_descriptor(font, &download, &license_info).await
+        self.create_font_descriptor(font, &download, &license_info, &cache_path).await
     }
     
     async fn load_from_cache(
@@ -198,7 +203,11 @@ impl FontAcquisitionManager {
                 allows_modification: font_data.license.allows_modification,
                 requires_attribution: font_data.license.requires_attribution,
                 allows_commercial_use: font_data.license.allows_commercial_use, // ADDED THIS FIELD
+                embedding: None,
+                spdx: None,
+                reserved_font_names: Vec::new(),
             }),
+            classification: font_data.classification,
         })
     }
     
@@ -225,6 +234,7 @@ impl FontAcquisitionManager {
         font_data: &CompressedFontData,
         download: &FontDownload,
         license_info: &LicenseInfo,
+        cache_path: &Path,
     ) -> FontResult<FontDescriptor> {
         // Parse font file to get metrics
         // This would use font-parser
@@ -235,7 +245,7 @@ impl FontAcquisitionManager {
             subfamily: None,
             postscript_name: font_data.postscript_name.clone(),
             full_name: Some(font_data.family.clone()),
-            path: PathBuf::from(format!("/downloaded/{}", font_data.postscript_name)),
+            path: cache_path.to_path_buf(),
             format: download.format,
             weight: font_data.weight,
             italic: font_data.italic,
@@ -257,7 +267,11 @@ impl FontAcquisitionManager {
                 allows_modification: license_info.allows_modification,
                 requires_attribution: license_info.requires_attribution,
                 allows_commercial_use: license_info.allows_commercial_use, // ADDED THIS FIELD
+                embedding: None,
+                spdx: None,
+                reserved_font_names: Vec::new(),
             }),
+            classification: font_data.classification,
         })
     }
 }
@@ -309,6 +323,7 @@ impl GoogleFontsProvider {
             download_urls: HashMap::new(),
             file_size_kb: 50,
             popularity: 50,
+            classification: None,
         })
     }
 }
@@ -451,6 +466,7 @@ impl FontProvider for FontsourceProvider {
                     download_urls: std::collections::HashMap::new(),
                     file_size_kb: 50,
                     popularity: 50,
+                    classification: None,
                 });
             }
         }
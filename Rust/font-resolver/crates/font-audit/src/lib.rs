@@ -0,0 +1,1625 @@
+use font_core::{FontDescriptor, FontError, FontFormat, FontResult, LicenseIdentification, ResolutionResult};
+use font_license::{identify_license, known_license, Derivation, LicenseChecker, LicenseType};
+use font_normalizer::FontNormalizer;
+use font_parser::FontParser;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const FONT_EXTENSIONS: &[&str] = &["ttf", "otf", "ttc", "woff", "woff2"];
+const STYLESHEET_EXTENSIONS: &[&str] = &["css", "scss", "less", "html", "htm"];
+const DOCUMENT_EXTENSIONS: &[&str] = &["pdf"];
+
+static FONT_FACE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)@font-face\s*\{([^}]*)\}").unwrap());
+static FONT_FAMILY: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?i)font-family\s*:\s*["']?([^"';}]+?)["']?\s*(?:;|$)"#).unwrap());
+static SRC_URL: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?i)url\(\s*["']?([^"')]+?)["']?\s*\)"#).unwrap());
+static PDF_BASE_FONT: Lazy<regex::bytes::Regex> =
+    Lazy::new(|| regex::bytes::Regex::new(r"/BaseFont\s*/([^\s/\[\]<>(){}%]+)").unwrap());
+
+/// How seriously a rule violation is taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Allow,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Allow => write!(f, "allow"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Rules a project's fonts are checked against, usually loaded from `font-policy.toml`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditPolicy {
+    /// SPDX identifiers fonts may be licensed under; empty allows any identified license
+    pub allowed_licenses: Vec<String>,
+    /// OS/2 vendor IDs (achVendID) whose fonts must not be shipped
+    pub banned_vendors: Vec<String>,
+    /// Family names that must not be shipped, compared case-insensitively
+    pub banned_families: Vec<String>,
+    /// Every identified license must permit commercial use
+    pub require_commercial_use: bool,
+    /// Every font file must permit embedding (OS/2 fsType is not Restricted License)
+    pub require_embedding: bool,
+    /// Severity for fonts whose license could not be identified
+    pub unknown_license: Severity,
+    /// Directory names skipped while walking the project
+    pub exclude_dirs: Vec<String>,
+}
+
+impl Default for AuditPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_licenses: Vec::new(),
+            banned_vendors: Vec::new(),
+            banned_families: Vec::new(),
+            require_commercial_use: false,
+            require_embedding: false,
+            unknown_license: Severity::Warning,
+            exclude_dirs: vec![".git".to_string(), "target".to_string(), "node_modules".to_string()],
+        }
+    }
+}
+
+impl AuditPolicy {
+    /// Load a policy from TOML, or JSON when the file ends in `.json`
+    pub fn load<P: AsRef<Path>>(path: P) -> FontResult<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+        if is_json {
+            serde_json::from_str(&content)
+                .map_err(|e| FontError::Parse(format!("Invalid policy {}: {}", path.display(), e)))
+        } else {
+            toml::from_str(&content)
+                .map_err(|e| FontError::Parse(format!("Invalid policy {}: {}", path.display(), e)))
+        }
+    }
+}
+
+/// Where in the project a font was found
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FontReference {
+    /// A font file in the tree
+    File { path: PathBuf },
+    /// An `@font-face` rule; `url` is the `src` it points to, if any
+    Stylesheet { path: PathBuf, url: Option<String> },
+    /// A font named by a document (PDF `/BaseFont`)
+    Document { path: PathBuf, embedded_name: String },
+}
+
+impl FontReference {
+    pub fn path(&self) -> &Path {
+        match self {
+            FontReference::File { path }
+            | FontReference::Stylesheet { path, .. }
+            | FontReference::Document { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for FontReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontReference::File { path } => write!(f, "{}", path.display()),
+            FontReference::Stylesheet { path, url: Some(url) } => write!(f, "{} ({})", path.display(), url),
+            FontReference::Stylesheet { path, url: None } => write!(f, "{}", path.display()),
+            FontReference::Document { path, embedded_name } => write!(f, "{} ({})", path.display(), embedded_name),
+        }
+    }
+}
+
+/// A policy rule a font breaks
+#[derive(Debug, Clone, Serialize)]
+pub struct Violation {
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// One font reference found in the project, with its license verdict
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditedFont {
+    pub family: String,
+    pub reference: FontReference,
+    /// The font file that was inspected, when the reference could be followed to one
+    pub file: Option<PathBuf>,
+    pub vendor_id: Option<String>,
+    pub license_name: Option<String>,
+    pub license_type: LicenseType,
+    pub spdx: Option<LicenseIdentification>,
+    pub violations: Vec<Violation>,
+}
+
+impl AuditedFont {
+    /// Worst severity among the violations, `Allow` when there are none
+    pub fn severity(&self) -> Severity {
+        self.violations.iter().map(|v| v.severity).max().unwrap_or(Severity::Allow)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditReport {
+    pub root: PathBuf,
+    pub policy: AuditPolicy,
+    pub fonts: Vec<AuditedFont>,
+}
+
+/// Collects the fonts a project uses and checks them against an [`AuditPolicy`]
+pub struct FontAuditor {
+    policy: AuditPolicy,
+    parser: FontParser,
+    checker: LicenseChecker,
+    normalizer: FontNormalizer,
+}
+
+impl FontAuditor {
+    pub fn new(policy: AuditPolicy) -> Self {
+        Self {
+            policy,
+            parser: FontParser,
+            checker: LicenseChecker::new(),
+            normalizer: FontNormalizer,
+        }
+    }
+
+    /// Walk `root` and audit every font file, `@font-face` rule and PDF font reference
+    pub fn audit<P: AsRef<Path>>(&self, root: P) -> FontResult<AuditReport> {
+        let root = root.as_ref();
+        if !root.exists() {
+            return Err(FontError::NotFound(root.display().to_string()));
+        }
+
+        let mut font_files = Vec::new();
+        let mut stylesheets = Vec::new();
+        let mut documents = Vec::new();
+
+        let walker = WalkDir::new(root).follow_links(false).into_iter().filter_entry(|entry| {
+            entry.depth() == 0
+                || !entry.file_type().is_dir()
+                || !self.policy.exclude_dirs.iter().any(|dir| entry.file_name() == dir.as_str())
+        });
+
+        for entry in walker.filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()) {
+            let extension = entry.path().extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| ext.to_lowercase())
+                .unwrap_or_default();
+
+            if FONT_EXTENSIONS.contains(&extension.as_str()) {
+                font_files.push(entry.into_path());
+            } else if STYLESHEET_EXTENSIONS.contains(&extension.as_str()) {
+                stylesheets.push(entry.into_path());
+            } else if DOCUMENT_EXTENSIONS.contains(&extension.as_str()) {
+                documents.push(entry.into_path());
+            }
+        }
+
+        let mut fonts = Vec::new();
+        let mut seen_files: HashSet<PathBuf> = HashSet::new();
+
+        for path in font_files {
+            seen_files.insert(canonical(&path));
+            fonts.push(self.audit_file(&path, FontReference::File { path: path.clone() }, None));
+        }
+
+        for path in stylesheets {
+            fonts.extend(self.audit_stylesheet(&path, &mut seen_files));
+        }
+
+        for path in documents {
+            fonts.extend(self.audit_document(&path));
+        }
+
+        Ok(AuditReport {
+            root: root.to_path_buf(),
+            policy: self.policy.clone(),
+            fonts,
+        })
+    }
+
+    fn audit_file(&self, path: &Path, reference: FontReference, declared_family: Option<&str>) -> AuditedFont {
+        match self.parser.parse_font_file(path) {
+            Ok(descriptor) => {
+                let vendor_id = read_font_details(path).and_then(|details| details.vendor_id);
+                let mut font = self.evaluate(&descriptor, reference, Some(path), vendor_id, true);
+
+                // Subsetting tools tag the PostScript name, e.g. "ABCDEF+SourceSans3-Regular"
+                let is_subset = descriptor.postscript_name.split_once('+')
+                    .is_some_and(|(tag, _)| tag.len() == 6 && tag.chars().all(|c| c.is_ascii_uppercase()));
+                if is_subset {
+                    let check = self.checker.check_derived_font(&descriptor, &descriptor.family, Derivation::Subset);
+                    if !check.is_compliant() {
+                        font.violations.push(Violation {
+                            rule: "reserved-font-name".to_string(),
+                            severity: Severity::Error,
+                            message: check.message,
+                        });
+                    }
+                }
+                font
+            }
+            Err(e) => {
+                // WOFF/WOFF2 and damaged files can't be read; judge them by name alone
+                let family = declared_family.map(str::to_string).unwrap_or_else(|| {
+                    path.file_stem().and_then(|s| s.to_str()).unwrap_or("Unknown").to_string()
+                });
+                let mut font = self.evaluate(&name_only_descriptor(&family, &family, path), reference, Some(path), None, false);
+                font.violations.push(Violation {
+                    rule: "unreadable".to_string(),
+                    severity: Severity::Warning,
+                    message: format!("Could not read font file: {}", e),
+                });
+                font
+            }
+        }
+    }
+
+    fn audit_stylesheet(&self, path: &Path, seen_files: &mut HashSet<PathBuf>) -> Vec<AuditedFont> {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Vec::new();
+        };
+        let base = path.parent().unwrap_or(Path::new("."));
+        let mut fonts = Vec::new();
+
+        for (family, urls) in font_face_rules(&content) {
+            let mut referenced = false;
+
+            for url in &urls {
+                let reference = FontReference::Stylesheet { path: path.to_path_buf(), url: Some(url.clone()) };
+                match local_font_path(base, url) {
+                    // Already audited as a file in the tree
+                    Some(file) if seen_files.contains(&canonical(&file)) => referenced = true,
+                    Some(file) => {
+                        seen_files.insert(canonical(&file));
+                        fonts.push(self.audit_file(&file, reference, Some(&family)));
+                        referenced = true;
+                    }
+                    None if !is_remote(url) => {
+                        let mut font = self.evaluate(&name_only_descriptor(&family, &family, path), reference, None, None, false);
+                        font.violations.push(Violation {
+                            rule: "missing-file".to_string(),
+                            severity: Severity::Warning,
+                            message: format!("Font source '{}' does not exist", url),
+                        });
+                        fonts.push(font);
+                        referenced = true;
+                    }
+                    None => {}
+                }
+            }
+
+            // Remote sources and bare local() rules can only be judged by the family name
+            if !referenced {
+                let reference = FontReference::Stylesheet { path: path.to_path_buf(), url: urls.first().cloned() };
+                fonts.push(self.evaluate(&name_only_descriptor(&family, &family, path), reference, None, None, false));
+            }
+        }
+
+        fonts
+    }
+
+    /// PDF fonts are found by scanning for `/BaseFont` names, so fonts inside
+    /// compressed object streams are not seen
+    fn audit_document(&self, path: &Path) -> Vec<AuditedFont> {
+        let Ok(data) = fs::read(path) else {
+            return Vec::new();
+        };
+
+        pdf_font_names(&data)
+            .into_iter()
+            .map(|name| {
+                let family = family_from_postscript(&self.normalizer, &name);
+                let reference = FontReference::Document { path: path.to_path_buf(), embedded_name: name.clone() };
+                self.evaluate(&name_only_descriptor(&family, &name, path), reference, None, None, false)
+            })
+            .collect()
+    }
+
+    fn evaluate(
+        &self,
+        descriptor: &FontDescriptor,
+        reference: FontReference,
+        file: Option<&Path>,
+        vendor_id: Option<String>,
+        parsed: bool,
+    ) -> AuditedFont {
+        let warning = self.checker.check_font(descriptor);
+        let mut violations = Vec::new();
+        let family_lower = descriptor.family.to_lowercase();
+
+        if self.policy.banned_families.iter().any(|banned| banned.to_lowercase() == family_lower) {
+            violations.push(Violation {
+                rule: "banned-family".to_string(),
+                severity: Severity::Error,
+                message: format!("Family '{}' is banned by policy", descriptor.family),
+            });
+        }
+
+        if let Some(vendor) = &vendor_id {
+            if self.policy.banned_vendors.iter().any(|banned| banned.trim().eq_ignore_ascii_case(vendor)) {
+                violations.push(Violation {
+                    rule: "banned-vendor".to_string(),
+                    severity: Severity::Error,
+                    message: format!("Vendor '{}' is banned by policy", vendor),
+                });
+            }
+        }
+
+        match &warning.spdx {
+            Some(identification) => {
+                let allowed = self.policy.allowed_licenses.is_empty()
+                    || self.policy.allowed_licenses.iter().any(|id| id.eq_ignore_ascii_case(&identification.spdx_id));
+                if !allowed {
+                    violations.push(Violation {
+                        rule: "license-not-allowed".to_string(),
+                        severity: Severity::Error,
+                        message: format!("License {} is not in the allowed list", identification.spdx_id),
+                    });
+                }
+
+                let commercial = known_license(&identification.spdx_id)
+                    .map(|license| license.allows_commercial_use)
+                    .unwrap_or(false);
+                if self.policy.require_commercial_use && !commercial {
+                    violations.push(Violation {
+                        rule: "commercial-use".to_string(),
+                        severity: Severity::Error,
+                        message: format!("License {} does not permit commercial use", identification.spdx_id),
+                    });
+                }
+            }
+            // Known commercial families can't match an SPDX allow-list
+            None if warning.license_type == LicenseType::Commercial && !self.policy.allowed_licenses.is_empty() => {
+                violations.push(Violation {
+                    rule: "license-not-allowed".to_string(),
+                    severity: Severity::Error,
+                    message: format!("'{}' is a commercial font without an allowed license", descriptor.family),
+                });
+            }
+            None if self.policy.unknown_license != Severity::Allow => {
+                violations.push(Violation {
+                    rule: "unknown-license".to_string(),
+                    severity: self.policy.unknown_license,
+                    message: warning.message.clone(),
+                });
+            }
+            None => {}
+        }
+
+        // Commercial and OS-bundled fonts come with no license granting commercial use
+        let unlicensed_kind = match warning.license_type {
+            LicenseType::Commercial => Some("commercial"),
+            LicenseType::SystemEmbedded => Some("system-bundled"),
+            LicenseType::OpenSource | LicenseType::Unknown => None,
+        };
+        if let Some(kind) = unlicensed_kind.filter(|_| self.policy.require_commercial_use && warning.spdx.is_none()) {
+            violations.push(Violation {
+                rule: "commercial-use".to_string(),
+                severity: Severity::Error,
+                message: format!("'{}' is a {} font whose license does not grant commercial use", descriptor.family, kind),
+            });
+        }
+
+        if self.policy.require_embedding && parsed {
+            let permissions = descriptor.license.as_ref().and_then(|l| l.embedding);
+            if let Some(permissions) = permissions.filter(|p| !p.allows_embedding()) {
+                violations.push(Violation {
+                    rule: "embedding".to_string(),
+                    severity: Severity::Error,
+                    message: format!("Font does not permit embedding ({})", permissions),
+                });
+            }
+        }
+
+        AuditedFont {
+            family: descriptor.family.clone(),
+            reference,
+            file: file.map(Path::to_path_buf),
+            vendor_id,
+            license_name: descriptor.license.as_ref().map(|l| l.name.clone()),
+            license_type: warning.license_type,
+            spdx: warning.spdx,
+            violations,
+        }
+    }
+}
+
+/// Audit a project directory with the given policy
+pub fn audit_project<P: AsRef<Path>>(root: P, policy: &AuditPolicy) -> FontResult<AuditReport> {
+    FontAuditor::new(policy.clone()).audit(root)
+}
+
+impl AuditReport {
+    pub fn error_count(&self) -> usize {
+        self.fonts.iter().flat_map(|f| &f.violations).filter(|v| v.severity == Severity::Error).count()
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.fonts.iter().flat_map(|f| &f.violations).filter(|v| v.severity == Severity::Warning).count()
+    }
+
+    /// True when any font breaks an error-severity rule
+    pub fn has_violations(&self) -> bool {
+        self.error_count() > 0
+    }
+
+    pub fn to_json(&self) -> FontResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| FontError::Parse(format!("Failed to serialize audit report: {}", e)))
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+
+        md.push_str("# Font License Audit\n\n");
+        md.push_str(&format!("Project: `{}`\n\n", self.root.display()));
+        md.push_str(&format!(
+            "Fonts checked: {} | Errors: {} | Warnings: {}\n\n",
+            self.fonts.len(), self.error_count(), self.warning_count()
+        ));
+
+        if self.fonts.is_empty() {
+            md.push_str("No fonts found.\n");
+            return md;
+        }
+
+        md.push_str("| Status | Font | License | Vendor | Found in |\n");
+        md.push_str("|--------|------|---------|--------|----------|\n");
+        for font in &self.fonts {
+            let status = match font.severity() {
+                Severity::Error => "❌",
+                Severity::Warning => "⚠️",
+                Severity::Allow => "✅",
+            };
+            let license = font.spdx.as_ref()
+                .map(|id| id.spdx_id.clone())
+                .or_else(|| font.license_name.clone())
+                .unwrap_or_else(|| format!("{:?}", font.license_type));
+            md.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                status,
+                escape_markdown(&font.family),
+                escape_markdown(&license),
+                font.vendor_id.as_deref().unwrap_or("-"),
+                escape_markdown(&font.reference.to_string()),
+            ));
+        }
+
+        let flagged: Vec<_> = self.fonts.iter().filter(|f| !f.violations.is_empty()).collect();
+        if !flagged.is_empty() {
+            md.push_str("\n## Violations\n\n");
+            for font in flagged {
+                md.push_str(&format!("### {} ({})\n", font.family, font.reference));
+                for violation in &font.violations {
+                    md.push_str(&format!("- **{}** `{}`: {}\n", violation.severity, violation.rule, violation.message));
+                }
+                md.push('\n');
+            }
+        }
+
+        md
+    }
+
+    /// JUnit XML with one test case per font; errors are failures, warnings go to system-out
+    pub fn to_junit(&self) -> String {
+        let mut xml = String::new();
+
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuite name=\"font-license-audit\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\">\n",
+            self.fonts.len(),
+            self.fonts.iter().filter(|f| f.severity() == Severity::Error).count(),
+        ));
+
+        for font in &self.fonts {
+            xml.push_str(&format!(
+                "  <testcase classname=\"{}\" name=\"{}\">\n",
+                escape_xml(&font.reference.path().display().to_string()),
+                escape_xml(&font.family),
+            ));
+
+            let errors: Vec<_> = font.violations.iter().filter(|v| v.severity == Severity::Error).collect();
+            if let Some(first) = errors.first() {
+                let details: Vec<String> = errors.iter().map(|v| format!("{}: {}", v.rule, v.message)).collect();
+                xml.push_str(&format!(
+                    "    <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+                    escape_xml(&first.rule),
+                    escape_xml(&first.message),
+                    escape_xml(&details.join("\n")),
+                ));
+            }
+
+            let warnings: Vec<String> = font.violations.iter()
+                .filter(|v| v.severity == Severity::Warning)
+                .map(|v| format!("warning {}: {}", v.rule, v.message))
+                .collect();
+            if !warnings.is_empty() {
+                xml.push_str(&format!("    <system-out>{}</system-out>\n", escape_xml(&warnings.join("\n"))));
+            }
+
+            xml.push_str("  </testcase>\n");
+        }
+
+        xml.push_str("</testsuite>\n");
+        xml
+    }
+}
+
+/// One font file listed in a bill of materials
+#[derive(Debug, Clone, Serialize)]
+pub struct SbomComponent {
+    pub family: String,
+    pub style: Option<String>,
+    pub postscript_name: String,
+    /// Name ID 5, e.g. "Version 2.37"
+    pub version: Option<String>,
+    pub vendor_id: Option<String>,
+    /// Hex SHA-256 of the file contents
+    pub sha256: String,
+    /// SPDX expression of the detected license, if it could be identified
+    pub license: Option<String>,
+    /// License name as reported by the font or its source
+    pub license_name: Option<String>,
+    /// Name ID 13, kept for licenses that have no SPDX list entry
+    pub license_text: Option<String>,
+    /// Name ID 0
+    pub copyright: Option<String>,
+    /// Where the font came from: a project directory, a resolver source, the acquisition cache
+    pub origin: String,
+    pub path: PathBuf,
+}
+
+/// Font bill of materials, exportable as SPDX 2.3 or CycloneDX 1.5 JSON
+pub struct FontSbom {
+    pub name: String,
+    pub components: Vec<SbomComponent>,
+    parser: FontParser,
+    checker: LicenseChecker,
+}
+
+impl FontSbom {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            components: Vec::new(),
+            parser: FontParser,
+            checker: LicenseChecker::new(),
+        }
+    }
+
+    /// Add one font file; files already listed (same SHA-256) are skipped
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P, origin: &str) -> FontResult<()> {
+        let path = path.as_ref();
+        let descriptor = self.parser.parse_font_file(path)?;
+        self.add_descriptor(&descriptor, origin)
+    }
+
+    /// Add the file behind an already parsed or resolved font
+    pub fn add_descriptor(&mut self, descriptor: &FontDescriptor, origin: &str) -> FontResult<()> {
+        let path = &descriptor.path;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+        let sha256: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+
+        if self.components.iter().any(|c| c.sha256 == sha256) {
+            return Ok(());
+        }
+
+        let details = read_font_details(path).unwrap_or_default();
+        let warning = self.checker.check_font(descriptor);
+
+        self.components.push(SbomComponent {
+            family: descriptor.family.clone(),
+            style: descriptor.subfamily.clone(),
+            postscript_name: descriptor.postscript_name.clone(),
+            version: details.version,
+            vendor_id: details.vendor_id,
+            sha256,
+            license: warning.spdx.map(|id| id.spdx_id),
+            license_name: descriptor.license.as_ref().map(|l| l.name.clone()),
+            license_text: details.license_text,
+            copyright: details.copyright,
+            origin: origin.to_string(),
+            path: path.clone(),
+        });
+        Ok(())
+    }
+
+    /// Add a font the resolver picked, with the resolver source as its origin
+    pub fn add_resolved(&mut self, result: &ResolutionResult) -> FontResult<()> {
+        let origin = if result.substituted {
+            format!("{} (substitute for '{}')", result.source, result.original_name)
+        } else {
+            result.source.to_string()
+        };
+        self.add_descriptor(&result.font, &origin)
+    }
+
+    /// Add every font under `dir`. Files without an extension are tried too, since the
+    /// acquisition cache stores downloads that way. Returns the number of fonts added.
+    pub fn add_directory<P: AsRef<Path>>(&mut self, dir: P, origin: &str) -> FontResult<usize> {
+        let dir = dir.as_ref();
+        if !dir.is_dir() {
+            return Err(FontError::NotFound(dir.display().to_string()));
+        }
+        let before = self.components.len();
+
+        let mut paths: Vec<PathBuf> = WalkDir::new(dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let extension = path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_lowercase());
+            match extension {
+                Some(ext) if FONT_EXTENSIONS.contains(&ext.as_str()) => {
+                    if let Err(e) = self.add_file(&path, origin) {
+                        eprintln!("⚠️  Skipping {}: {}", path.display(), e);
+                    }
+                }
+                None => {
+                    let _ = self.add_file(&path, origin);
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(self.components.len() - before)
+    }
+
+    /// SPDX 2.3 document with one package per font file
+    pub fn to_spdx(&self) -> FontResult<String> {
+        let created = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+
+        let packages: Vec<serde_json::Value> = self.components.iter().enumerate()
+            .map(|(index, component)| {
+                let license = component.license.clone().unwrap_or_else(|| "NOASSERTION".to_string());
+                let mut package = serde_json::json!({
+                    "SPDXID": format!("SPDXRef-Font-{}", index + 1),
+                    "name": component.display_name(),
+                    "packageFileName": component.path.display().to_string(),
+                    "versionInfo": component.version.clone().unwrap_or_else(|| "NOASSERTION".to_string()),
+                    "supplier": component.vendor_id.as_ref()
+                        .map(|vendor| format!("Organization: {}", vendor))
+                        .unwrap_or_else(|| "NOASSERTION".to_string()),
+                    "downloadLocation": "NOASSERTION",
+                    "filesAnalyzed": false,
+                    "primaryPackagePurpose": "FILE",
+                    "checksums": [{ "algorithm": "SHA256", "checksumValue": component.sha256 }],
+                    "licenseConcluded": license,
+                    "licenseDeclared": license,
+                    "copyrightText": component.copyright.clone().unwrap_or_else(|| "NOASSERTION".to_string()),
+                    "sourceInfo": format!("Origin: {}", component.origin),
+                    "comment": format!("PostScript name: {}", component.postscript_name),
+                });
+                if let (None, Some(name)) = (&component.license, &component.license_name) {
+                    package["licenseComments"] = serde_json::json!(format!("Reported license: {}", name));
+                }
+                package
+            })
+            .collect();
+
+        let relationships: Vec<serde_json::Value> = packages.iter()
+            .map(|package| serde_json::json!({
+                "spdxElementId": "SPDXRef-DOCUMENT",
+                "relationshipType": "DESCRIBES",
+                "relatedSpdxElement": package["SPDXID"],
+            }))
+            .collect();
+
+        // LicenseRef- identifiers must be defined in the document itself
+        let mut extracted = Vec::new();
+        let mut seen = HashSet::new();
+        for component in &self.components {
+            let Some(license) = &component.license else { continue };
+            if license.starts_with("LicenseRef-") && seen.insert(license.clone()) {
+                let name = known_license(license).map(|known| known.name.to_string())
+                    .or_else(|| component.license_name.clone())
+                    .unwrap_or_else(|| license.clone());
+                extracted.push(serde_json::json!({
+                    "licenseId": license,
+                    "name": name,
+                    "extractedText": component.license_text.clone().unwrap_or_else(|| name.clone()),
+                }));
+            }
+        }
+
+        let mut document = serde_json::json!({
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": self.name,
+            "documentNamespace": format!("https://spdx.org/spdxdocs/{}-{}", slug(&self.name), self.document_uuid(&created)),
+            "creationInfo": {
+                "created": created,
+                "creators": [format!("Tool: font-audit-{}", env!("CARGO_PKG_VERSION"))],
+            },
+            "packages": packages,
+            "relationships": relationships,
+        });
+        if !extracted.is_empty() {
+            document["hasExtractedLicensingInfos"] = serde_json::Value::Array(extracted);
+        }
+
+        serde_json::to_string_pretty(&document)
+            .map_err(|e| FontError::Parse(format!("Failed to serialize SPDX document: {}", e)))
+    }
+
+    /// CycloneDX 1.5 BOM with one `file` component per font
+    pub fn to_cyclonedx(&self) -> FontResult<String> {
+        let timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+
+        let components: Vec<serde_json::Value> = self.components.iter()
+            .map(|component| {
+                let mut properties = vec![
+                    serde_json::json!({ "name": "font:family", "value": component.family }),
+                    serde_json::json!({ "name": "font:postscript-name", "value": component.postscript_name }),
+                    serde_json::json!({ "name": "font:origin", "value": component.origin }),
+                    serde_json::json!({ "name": "font:path", "value": component.path.display().to_string() }),
+                ];
+                if let Some(style) = &component.style {
+                    properties.push(serde_json::json!({ "name": "font:style", "value": style }));
+                }
+                if let Some(vendor) = &component.vendor_id {
+                    properties.push(serde_json::json!({ "name": "font:vendor-id", "value": vendor }));
+                }
+
+                let mut entry = serde_json::json!({
+                    "type": "file",
+                    "bom-ref": format!("font-{}", &component.sha256[..16]),
+                    "name": component.display_name(),
+                    "hashes": [{ "alg": "SHA-256", "content": component.sha256 }],
+                    "properties": properties,
+                });
+                if let Some(version) = &component.version {
+                    entry["version"] = serde_json::json!(version);
+                }
+                if let Some(vendor) = &component.vendor_id {
+                    entry["supplier"] = serde_json::json!({ "name": vendor });
+                }
+                if let Some(copyright) = &component.copyright {
+                    entry["copyright"] = serde_json::json!(copyright);
+                }
+
+                // CycloneDX ids must come from the SPDX list; anything else goes by name
+                let license = match (&component.license, &component.license_name) {
+                    (Some(id), _) if id.contains(" WITH ") => Some(serde_json::json!({ "expression": id })),
+                    (Some(id), _) if !id.starts_with("LicenseRef-") => Some(serde_json::json!({ "license": { "id": id } })),
+                    (Some(id), name) => Some(serde_json::json!({
+                        "license": { "name": known_license(id).map(|k| k.name.to_string()).or_else(|| name.clone()).unwrap_or_else(|| id.clone()) }
+                    })),
+                    (None, Some(name)) => Some(serde_json::json!({ "license": { "name": name } })),
+                    (None, None) => None,
+                };
+                if let Some(license) = license {
+                    entry["licenses"] = serde_json::json!([license]);
+                }
+                entry
+            })
+            .collect();
+
+        let bom = serde_json::json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "serialNumber": format!("urn:uuid:{}", self.document_uuid(&timestamp)),
+            "version": 1,
+            "metadata": {
+                "timestamp": timestamp,
+                "tools": {
+                    "components": [{ "type": "application", "name": "font-audit", "version": env!("CARGO_PKG_VERSION") }],
+                },
+                "component": { "type": "application", "name": self.name, "bom-ref": slug(&self.name) },
+            },
+            "components": components,
+        });
+
+        serde_json::to_string_pretty(&bom)
+            .map_err(|e| FontError::Parse(format!("Failed to serialize CycloneDX BOM: {}", e)))
+    }
+
+    /// UUID derived from the document contents and creation time
+    fn document_uuid(&self, created: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.name.as_bytes());
+        hasher.update(created.as_bytes());
+        for component in &self.components {
+            hasher.update(component.sha256.as_bytes());
+        }
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&hasher.finalize()[..16]);
+        // RFC 4122 version 4 / variant 1 layout
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+        let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+    }
+}
+
+impl SbomComponent {
+    /// "Family Style", or just the family for regular faces
+    pub fn display_name(&self) -> String {
+        match &self.style {
+            Some(style) if !style.eq_ignore_ascii_case("regular") => format!("{} {}", self.family, style),
+            _ => self.family.clone(),
+        }
+    }
+}
+
+/// License files looked for next to a font and one directory up
+const LICENSE_FILE_NAMES: &[&str] = &[
+    "OFL.txt", "OFL", "LICENSE", "LICENSE.txt", "LICENSE.md", "LICENCE", "LICENCE.txt",
+    "UFL.txt", "COPYING", "COPYING.txt", "license.txt", "licence.txt",
+];
+
+/// Name-table license records shorter than this are a pointer to the license, not its text
+const MIN_LICENSE_TEXT_LEN: usize = 400;
+
+/// Where a license text was taken from
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "path", rename_all = "snake_case")]
+pub enum LicenseTextSource {
+    /// A license file shipped alongside the font
+    File(PathBuf),
+    /// The font's own license description (name ID 13)
+    NameTable,
+}
+
+/// A font listed in a notice, with its copyright line
+#[derive(Debug, Clone, Serialize)]
+pub struct NoticeEntry {
+    pub name: String,
+    pub path: PathBuf,
+    /// Name ID 0
+    pub copyright: Option<String>,
+}
+
+/// Fonts sharing one license, and the text to ship with them
+#[derive(Debug, Clone, Serialize)]
+pub struct LicenseGroup {
+    /// SPDX identifier, or the reported license name when it couldn't be identified
+    pub license: String,
+    pub name: String,
+    pub text: Option<String>,
+    pub text_source: Option<LicenseTextSource>,
+    /// Whether the license must travel with the fonts
+    pub text_required: bool,
+    pub fonts: Vec<NoticeEntry>,
+}
+
+impl LicenseGroup {
+    /// Path of the license text inside a written bundle
+    pub fn text_file_name(&self) -> String {
+        format!("licenses/{}.txt", slug(&self.license))
+    }
+}
+
+/// NOTICE file and license texts for a set of fonts
+#[derive(Debug, Clone, Serialize)]
+pub struct NoticeBundle {
+    pub groups: Vec<LicenseGroup>,
+    pub warnings: Vec<String>,
+}
+
+impl NoticeBundle {
+    /// Group fonts by license and gather their copyright lines and license texts
+    pub fn build(fonts: &[FontDescriptor]) -> Self {
+        let checker = LicenseChecker::new();
+        let mut groups: Vec<LicenseGroup> = Vec::new();
+        let mut seen_paths = HashSet::new();
+
+        for font in fonts {
+            if !seen_paths.insert(canonical(&font.path)) {
+                continue;
+            }
+            let details = read_font_details(&font.path).unwrap_or_default();
+            let spdx = checker.check_font(font).spdx.map(|id| id.spdx_id);
+            let known = spdx.as_deref().and_then(known_license);
+            let info = font.license.as_ref();
+
+            let license = spdx.clone()
+                .or_else(|| info.map(|l| l.name.clone()))
+                .unwrap_or_else(|| "Unknown".to_string());
+            let text_required = info.is_some_and(|l| l.requires_attribution)
+                || known.is_some_and(|k| k.requires_attribution || k.spdx_id.starts_with("OFL-"));
+
+            let index = match groups.iter().position(|g| g.license == license) {
+                Some(index) => index,
+                None => {
+                    groups.push(LicenseGroup {
+                        name: known.map(|k| k.name.to_string()).unwrap_or_else(|| license.clone()),
+                        license: license.clone(),
+                        text: None,
+                        text_source: None,
+                        text_required: false,
+                        fonts: Vec::new(),
+                    });
+                    groups.len() - 1
+                }
+            };
+            let group = &mut groups[index];
+            group.text_required |= text_required;
+
+            // A bundled file beats a name record; a full name record beats a short pointer
+            let have_full_text = matches!(group.text_source, Some(LicenseTextSource::File(_)))
+                || group.text.as_ref().is_some_and(|t| t.len() >= MIN_LICENSE_TEXT_LEN);
+            if !have_full_text {
+                if let Some((path, text)) = find_license_file(&font.path, spdx.as_deref()) {
+                    group.text = Some(text);
+                    group.text_source = Some(LicenseTextSource::File(path));
+                } else if let Some(text) = details.license_text.filter(|t| {
+                    group.text.as_ref().is_none_or(|current| t.len() > current.len())
+                }) {
+                    group.text = Some(text);
+                    group.text_source = Some(LicenseTextSource::NameTable);
+                }
+            }
+
+            group.fonts.push(NoticeEntry {
+                name: match &font.subfamily {
+                    Some(style) if !style.eq_ignore_ascii_case("regular") => format!("{} {}", font.family, style),
+                    _ => font.family.clone(),
+                },
+                path: font.path.clone(),
+                copyright: details.copyright,
+            });
+        }
+
+        let mut warnings = Vec::new();
+        for group in &groups {
+            if !group.text_required {
+                continue;
+            }
+            let families: Vec<&str> = group.fonts.iter().map(|f| f.name.as_str()).collect();
+            match (&group.text, &group.text_source) {
+                (None, _) => warnings.push(format!(
+                    "No license text found for {} (used by {})", group.license, families.join(", ")
+                )),
+                (Some(text), Some(LicenseTextSource::NameTable)) if text.len() < MIN_LICENSE_TEXT_LEN => warnings.push(format!(
+                    "Only a short license notice was found for {} (used by {}); ship the full license text",
+                    group.license, families.join(", ")
+                )),
+                _ => {}
+            }
+        }
+
+        groups.sort_by(|a, b| a.license.cmp(&b.license));
+        Self { groups, warnings }
+    }
+
+    /// Plain-text THIRD-PARTY NOTICES listing each license group, its fonts and copyrights
+    pub fn to_notice(&self) -> String {
+        let mut notice = String::new();
+        let rule = "-".repeat(72);
+
+        notice.push_str("THIRD-PARTY FONT NOTICES\n\n");
+        notice.push_str("This product includes the fonts listed below, grouped by license.\n");
+
+        for group in &self.groups {
+            notice.push_str(&format!("\n{}\n", rule));
+            if group.name == group.license {
+                notice.push_str(&format!("{}\n", group.license));
+            } else {
+                notice.push_str(&format!("{} ({})\n", group.name, group.license));
+            }
+            notice.push_str(&format!("{}\n\n", rule));
+
+            for font in &group.fonts {
+                let file = font.path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                notice.push_str(&format!("* {} ({})\n", font.name, file));
+                if let Some(copyright) = &font.copyright {
+                    for line in copyright.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                        notice.push_str(&format!("  {}\n", line));
+                    }
+                }
+            }
+
+            if group.text.is_some() {
+                notice.push_str(&format!("\nLicense text: {}\n", group.text_file_name()));
+            } else if group.text_required {
+                notice.push_str("\nLicense text: NOT FOUND\n");
+            }
+        }
+
+        notice
+    }
+
+    /// Write `THIRD-PARTY-NOTICES.txt` and `licenses/*.txt` into `dir`; returns the notice path
+    pub fn write<P: AsRef<Path>>(&self, dir: P) -> FontResult<PathBuf> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir.join("licenses"))?;
+
+        for group in &self.groups {
+            if let Some(text) = &group.text {
+                fs::write(dir.join(group.text_file_name()), text)?;
+            }
+        }
+
+        let notice_path = dir.join("THIRD-PARTY-NOTICES.txt");
+        fs::write(&notice_path, self.to_notice())?;
+        Ok(notice_path)
+    }
+}
+
+/// A license file for the font. Next to the font or one level up, a file naming the
+/// font's own license is used. A file that names no known license, or one the font
+/// can't be checked against because its license wasn't identified, is used only from
+/// the font's own directory: one level up it is as likely the project's license.
+fn find_license_file(font_path: &Path, spdx_id: Option<&str>) -> Option<(PathBuf, String)> {
+    let dir = font_path.parent()?;
+    let candidates = [(Some(dir), true), (dir.parent(), false)];
+
+    for (dir, own_dir) in candidates {
+        let Some(dir) = dir else { continue };
+        for name in LICENSE_FILE_NAMES {
+            let path = dir.join(name);
+            let Ok(text) = fs::read_to_string(&path) else { continue };
+            let identified = identify_license(Some(&text), None).map(|(license, _)| license.spdx_id);
+            let matches = match (spdx_id, identified) {
+                (Some(expected), Some(found)) => expected.eq_ignore_ascii_case(found),
+                (Some(_), None) => false,
+                (None, _) => own_dir,
+            };
+            if matches && !text.trim().is_empty() {
+                return Some((path, text));
+            }
+        }
+    }
+    None
+}
+
+/// Descriptor for a font known only by name, so the license checker can judge it
+fn name_only_descriptor(family: &str, postscript_name: &str, path: &Path) -> FontDescriptor {
+    FontDescriptor {
+        family: family.to_string(),
+        subfamily: None,
+        postscript_name: postscript_name.to_string(),
+        full_name: None,
+        path: path.to_path_buf(),
+        format: FontFormat::Other,
+        weight: 400,
+        italic: false,
+        monospaced: false,
+        variable: false,
+        metrics: None,
+        license: None,
+        classification: None,
+    }
+}
+
+/// Name-table and OS/2 fields `FontDescriptor` doesn't carry
+#[derive(Debug, Clone, Default)]
+struct FontDetails {
+    /// The four-character OS/2 achVendID, trimmed of padding
+    vendor_id: Option<String>,
+    /// Name ID 5
+    version: Option<String>,
+    /// Name ID 0
+    copyright: Option<String>,
+    /// Name ID 13
+    license_text: Option<String>,
+}
+
+fn read_font_details(path: &Path) -> Option<FontDetails> {
+    let data = fs::read(path).ok()?;
+    let face = ttf_parser::Face::parse(&data, 0).ok()?;
+    let name = |name_id: u16| {
+        face.names()
+            .into_iter()
+            .filter(|name| name.name_id == name_id)
+            .find_map(|name| name.to_string())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    let vendor_id = face.raw_face()
+        .table(ttf_parser::Tag::from_bytes(b"OS/2"))
+        .and_then(|os2| os2.get(58..62))
+        .map(|bytes| String::from_utf8_lossy(bytes).trim_matches(|c: char| c.is_whitespace() || c == '\0').to_string())
+        .filter(|vendor| !vendor.is_empty());
+
+    Some(FontDetails {
+        vendor_id,
+        version: name(ttf_parser::name_id::VERSION),
+        copyright: name(ttf_parser::name_id::COPYRIGHT_NOTICE),
+        license_text: name(ttf_parser::name_id::LICENSE),
+    })
+}
+
+/// `@font-face` rules as (family, src urls)
+fn font_face_rules(content: &str) -> Vec<(String, Vec<String>)> {
+    FONT_FACE.captures_iter(content)
+        .filter_map(|block| {
+            let body = &block[1];
+            let family = FONT_FAMILY.captures(body)?[1].trim().to_string();
+            let urls = SRC_URL.captures_iter(body).map(|c| c[1].to_string()).collect();
+            Some((family, urls))
+        })
+        .collect()
+}
+
+/// Distinct `/BaseFont` names in a PDF, without subset prefixes
+fn pdf_font_names(data: &[u8]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    PDF_BASE_FONT.captures_iter(data)
+        .map(|capture| {
+            let raw = decode_pdf_name(&capture[1]);
+            // Subset fonts are prefixed with six capitals and '+', e.g. "ABCDEF+Helvetica"
+            match raw.split_once('+') {
+                Some((prefix, rest)) if prefix.len() == 6 && prefix.chars().all(|c| c.is_ascii_uppercase()) => rest.to_string(),
+                _ => raw,
+            }
+        })
+        .filter(|name| seen.insert(name.clone()))
+        .collect()
+}
+
+fn family_from_postscript(normalizer: &FontNormalizer, name: &str) -> String {
+    normalizer.normalize(name)
+        .map(|request| title_case(&request.family.replace('-', " ")))
+        .unwrap_or_else(|_| name.to_string())
+}
+
+/// Font families a document refers to: `@font-face` rules in stylesheets and HTML,
+/// `/BaseFont` entries in PDFs
+pub fn referenced_font_names<P: AsRef<Path>>(path: P) -> FontResult<Vec<String>> {
+    let path = path.as_ref();
+    let extension = path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default();
+
+    let mut names = if DOCUMENT_EXTENSIONS.contains(&extension.as_str()) {
+        let normalizer = FontNormalizer;
+        pdf_font_names(&fs::read(path)?)
+            .iter()
+            .map(|name| family_from_postscript(&normalizer, name))
+            .collect::<Vec<_>>()
+    } else if STYLESHEET_EXTENSIONS.contains(&extension.as_str()) {
+        font_face_rules(&fs::read_to_string(path)?)
+            .into_iter()
+            .map(|(family, _)| family)
+            .collect()
+    } else {
+        return Err(FontError::UnsupportedFormat);
+    };
+
+    // Bold and italic faces of one family collapse to a single name
+    let mut seen = HashSet::new();
+    names.retain(|name| seen.insert(name.to_lowercase()));
+    Ok(names)
+}
+
+fn is_remote(url: &str) -> bool {
+    let lower = url.to_lowercase();
+    lower.starts_with("http:") || lower.starts_with("https:") || lower.starts_with("//") || lower.starts_with("data:")
+}
+
+/// Resolve a stylesheet `url()` to an existing local file
+fn local_font_path(base: &Path, url: &str) -> Option<PathBuf> {
+    if is_remote(url) {
+        return None;
+    }
+    // Drop query strings and fragments used for cache busting or SVG ids
+    let clean = url.split(['?', '#']).next().unwrap_or(url);
+    let path = base.join(clean.trim_start_matches("./"));
+    path.is_file().then_some(path)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Decode `#xx` escapes in a PDF name object
+fn decode_pdf_name(raw: &[u8]) -> String {
+    let mut bytes = Vec::with_capacity(raw.len());
+    let mut i = 0;
+    while i < raw.len() {
+        if raw[i] == b'#' && i + 2 < raw.len() {
+            if let Ok(value) = u8::from_str_radix(&String::from_utf8_lossy(&raw[i + 1..i + 3]), 16) {
+                bytes.push(value);
+                i += 3;
+                continue;
+            }
+        }
+        bytes.push(raw[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// The normalizer lowercases families; restore a readable form for reports
+fn title_case(family: &str) -> String {
+    family.split(' ')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Lowercase alphanumerics and dashes, for document namespaces and refs
+fn slug(text: &str) -> String {
+    let slug: String = text.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    slug.trim_matches('-').to_string()
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use font_core::{EmbeddingPermissions, LicenseInfo};
+
+    fn font(family: &str, spdx_id: Option<&str>, fs_type: Option<u16>) -> FontDescriptor {
+        let mut descriptor = name_only_descriptor(family, &family.replace(' ', ""), Path::new("fonts/font.ttf"));
+        if spdx_id.is_some() || fs_type.is_some() {
+            descriptor.license = Some(LicenseInfo {
+                name: String::new(),
+                url: None,
+                allows_embedding: true,
+                allows_modification: true,
+                requires_attribution: false,
+                allows_commercial_use: true,
+                embedding: fs_type.map(EmbeddingPermissions::from_fs_type),
+                spdx: spdx_id.map(|id| LicenseIdentification { spdx_id: id.to_string(), confidence: 1.0 }),
+                reserved_font_names: Vec::new(),
+            });
+        }
+        descriptor
+    }
+
+    fn rules(policy: AuditPolicy, descriptor: &FontDescriptor, vendor_id: Option<&str>, parsed: bool) -> Vec<String> {
+        let reference = FontReference::File { path: descriptor.path.clone() };
+        FontAuditor::new(policy)
+            .evaluate(descriptor, reference, Some(&descriptor.path), vendor_id.map(str::to_string), parsed)
+            .violations.into_iter()
+            .map(|v| v.rule)
+            .collect()
+    }
+
+    fn report(family: &str, violations: Vec<Violation>) -> AuditReport {
+        AuditReport {
+            root: PathBuf::from("project"),
+            policy: AuditPolicy::default(),
+            fonts: vec![AuditedFont {
+                family: family.to_string(),
+                reference: FontReference::File { path: PathBuf::from("fonts/a&b.ttf") },
+                file: None,
+                vendor_id: None,
+                license_name: None,
+                license_type: LicenseType::Unknown,
+                spdx: None,
+                violations,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_policy_loads_toml_and_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml_path = dir.path().join("font-policy.toml");
+        fs::write(&toml_path, r#"
+            allowed_licenses = ["OFL-1.1", "Apache-2.0"]
+            banned_vendors = ["MONO"]
+            require_commercial_use = true
+            unknown_license = "error"
+        "#).unwrap();
+        let policy = AuditPolicy::load(&toml_path).unwrap();
+        assert_eq!(policy.allowed_licenses, ["OFL-1.1", "Apache-2.0"]);
+        assert_eq!(policy.banned_vendors, ["MONO"]);
+        assert!(policy.require_commercial_use && !policy.require_embedding);
+        assert_eq!(policy.unknown_license, Severity::Error);
+        // Unset keys keep their defaults
+        assert_eq!(policy.exclude_dirs, AuditPolicy::default().exclude_dirs);
+
+        let json_path = dir.path().join("policy.JSON");
+        fs::write(&json_path, r#"{ "banned_families": ["Comic Sans MS"], "unknown_license": "allow" }"#).unwrap();
+        let policy = AuditPolicy::load(&json_path).unwrap();
+        assert_eq!(policy.banned_families, ["Comic Sans MS"]);
+        assert_eq!(policy.unknown_license, Severity::Allow);
+
+        fs::write(&toml_path, "unknown_license = \"fatal\"").unwrap();
+        assert!(matches!(AuditPolicy::load(&toml_path), Err(FontError::Parse(_))));
+        assert!(AuditPolicy::load(dir.path().join("missing.toml")).is_err());
+    }
+
+    #[test]
+    fn test_banned_vendor_and_family() {
+        let policy = AuditPolicy {
+            banned_vendors: vec!["MONO ".to_string()],
+            banned_families: vec!["comic sans ms".to_string()],
+            ..Default::default()
+        };
+        let open = font("Open Sans", Some("OFL-1.1"), None);
+        assert_eq!(rules(policy.clone(), &open, Some("mono"), true), ["banned-vendor"]);
+        assert!(rules(policy.clone(), &open, Some("GOOG"), true).is_empty());
+        assert!(rules(policy.clone(), &open, None, true).is_empty());
+        assert_eq!(rules(policy, &font("Comic Sans MS", Some("OFL-1.1"), None), None, true), ["banned-family"]);
+    }
+
+    #[test]
+    fn test_license_not_allowed() {
+        let policy = AuditPolicy { allowed_licenses: vec!["apache-2.0".to_string()], ..Default::default() };
+        assert!(rules(policy.clone(), &font("Roboto", Some("Apache-2.0"), None), None, true).is_empty());
+        assert_eq!(rules(policy.clone(), &font("Open Sans", Some("OFL-1.1"), None), None, true), ["license-not-allowed"]);
+        // Commercial families without an SPDX license can never be on the allow-list
+        assert_eq!(rules(policy, &font("Helvetica", None, None), None, true), ["license-not-allowed"]);
+    }
+
+    #[test]
+    fn test_unknown_license_uses_policy_severity() {
+        let unknown = font("Mystery Grotesk", None, None);
+        let audited = |severity| {
+            let policy = AuditPolicy { unknown_license: severity, ..Default::default() };
+            FontAuditor::new(policy).evaluate(&unknown, FontReference::File { path: unknown.path.clone() }, None, None, true)
+        };
+        assert_eq!(audited(Severity::Warning).violations[0].rule, "unknown-license");
+        assert_eq!(audited(Severity::Warning).severity(), Severity::Warning);
+        assert_eq!(audited(Severity::Error).severity(), Severity::Error);
+        assert!(audited(Severity::Allow).violations.is_empty());
+    }
+
+    #[test]
+    fn test_commercial_use_required() {
+        let policy = AuditPolicy { require_commercial_use: true, unknown_license: Severity::Allow, ..Default::default() };
+        assert!(rules(policy.clone(), &font("Open Sans", Some("OFL-1.1"), None), None, true).is_empty());
+        assert_eq!(rules(policy.clone(), &font("Helvetica", None, None), None, true), ["commercial-use"]);
+        assert_eq!(rules(policy.clone(), &font("Arial", None, None), None, true), ["commercial-use"]);
+        // Unidentified licenses are left to the unknown-license rule
+        assert!(rules(policy, &font("Mystery Grotesk", None, None), None, true).is_empty());
+
+        let relaxed = AuditPolicy { unknown_license: Severity::Allow, ..Default::default() };
+        assert!(rules(relaxed, &font("Arial", None, None), None, true).is_empty());
+    }
+
+    #[test]
+    fn test_embedding_required() {
+        let policy = AuditPolicy { require_embedding: true, ..Default::default() };
+        let restricted = font("Open Sans", Some("OFL-1.1"), Some(EmbeddingPermissions::RESTRICTED_LICENSE));
+        assert_eq!(rules(policy.clone(), &restricted, None, true), ["embedding"]);
+        // Permissions of fonts that couldn't be parsed aren't known
+        assert!(rules(policy.clone(), &restricted, None, false).is_empty());
+        let printable = font("Open Sans", Some("OFL-1.1"), Some(EmbeddingPermissions::PREVIEW_AND_PRINT));
+        assert!(rules(policy.clone(), &printable, None, true).is_empty());
+        assert!(rules(AuditPolicy::default(), &restricted, None, true).is_empty());
+    }
+
+    #[test]
+    fn test_markdown_escapes_family_names() {
+        let violation = Violation { rule: "banned-family".to_string(), severity: Severity::Error, message: "banned".to_string() };
+        let flagged = report("Odd | Family", vec![violation]);
+        assert!(flagged.has_violations());
+
+        let md = flagged.to_markdown();
+        assert!(md.contains("Fonts checked: 1 | Errors: 1 | Warnings: 0"));
+        assert!(md.contains("| ❌ | Odd \\| Family | Unknown | - | fonts/a&b.ttf |"));
+        // Headings aren't tables, so pipes stay as they are
+        assert!(md.contains("### Odd | Family (fonts/a&b.ttf)"));
+        assert!(md.contains("- **error** `banned-family`: banned"));
+
+        assert!(report("Clean", Vec::new()).to_markdown().contains("| ✅ | Clean |"));
+    }
+
+    #[test]
+    fn test_junit_escapes_family_names() {
+        let violations = vec![
+            Violation { rule: "commercial-use".to_string(), severity: Severity::Error, message: "needs \"license\"".to_string() },
+            Violation { rule: "unreadable".to_string(), severity: Severity::Warning, message: "<damaged>".to_string() },
+        ];
+        let xml = report("Tom & \"Jerry\" <Sans>", violations).to_junit();
+
+        assert!(xml.contains(r#"<testsuite name="font-license-audit" tests="1" failures="1""#));
+        assert!(xml.contains(r#"<testcase classname="fonts/a&amp;b.ttf" name="Tom &amp; &quot;Jerry&quot; &lt;Sans&gt;">"#));
+        assert!(xml.contains(r#"<failure type="commercial-use" message="needs &quot;license&quot;">"#));
+        assert!(xml.contains("<system-out>warning unreadable: &lt;damaged&gt;</system-out>"));
+        assert!(!xml.contains("Tom & "));
+    }
+
+    #[test]
+    fn test_license_file_lookup() {
+        let root = tempfile::tempdir().unwrap();
+        let fonts_dir = root.path().join("fonts");
+        let family_dir = fonts_dir.join("family");
+        fs::create_dir_all(&family_dir).unwrap();
+        let font_path = family_dir.join("Family-Regular.ttf");
+        let ofl = "Copyright 2020 The Family Project Authors\n\nThis Font Software is licensed under the SIL Open Font License, Version 1.1.";
+        let project = "All rights reserved. Internal use only.";
+        let found = |spdx_id| find_license_file(&font_path, spdx_id).map(|(path, _)| path);
+
+        // An unidentified file one level up is likely the project's own license
+        fs::write(fonts_dir.join("LICENSE"), project).unwrap();
+        assert_eq!(found(None), None);
+        assert_eq!(found(Some("OFL-1.1")), None);
+
+        // One level up is fine when it names the font's license
+        fs::write(fonts_dir.join("OFL.txt"), ofl).unwrap();
+        assert_eq!(found(Some("OFL-1.1")), Some(fonts_dir.join("OFL.txt")));
+        assert_eq!(found(Some("Apache-2.0")), None);
+        assert_eq!(found(None), None);
+
+        // Next to the font, an unidentified file is used only when the font's license is unknown
+        fs::write(family_dir.join("LICENSE.txt"), project).unwrap();
+        assert_eq!(found(None), Some(family_dir.join("LICENSE.txt")));
+        assert_eq!(found(Some("OFL-1.1")), Some(fonts_dir.join("OFL.txt")));
+
+        fs::write(family_dir.join("OFL.txt"), ofl).unwrap();
+        assert_eq!(found(Some("OFL-1.1")), Some(family_dir.join("OFL.txt")));
+        assert_eq!(found(None), Some(family_dir.join("OFL.txt")));
+        fs::write(family_dir.join("OFL.txt"), "  \n").unwrap();
+        assert_eq!(found(None), Some(family_dir.join("LICENSE.txt")));
+    }
+
+    fn notice_font(dir: &Path, file: &str, family: &str, style: Option<&str>, spdx_id: Option<&str>) -> FontDescriptor {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, file).unwrap();
+        let mut descriptor = font(family, spdx_id, None);
+        descriptor.path = path;
+        descriptor.subfamily = style.map(str::to_string);
+        descriptor
+    }
+
+    #[test]
+    fn test_notice_bundle_groups_fonts_by_license() {
+        let root = tempfile::tempdir().unwrap();
+        let fonts_dir = root.path().join("fonts");
+        let ofl = "Copyright 2020 The Family Project Authors\n\nThis Font Software is licensed under the SIL Open Font License, Version 1.1.";
+        let regular = notice_font(&fonts_dir, "family/Family-Regular.ttf", "Family", Some("Regular"), Some("OFL-1.1"));
+        let bold = notice_font(&fonts_dir, "family/Family-Bold.ttf", "Family", Some("Bold"), Some("OFL-1.1"));
+        let other = notice_font(&fonts_dir, "other/Other.ttf", "Other Sans", None, Some("OFL-1.1"));
+        fs::write(fonts_dir.join("family/OFL.txt"), ofl).unwrap();
+
+        // The same file listed twice appears once
+        let bundle = NoticeBundle::build(&[regular.clone(), bold, other, regular]);
+
+        assert_eq!(bundle.groups.len(), 1);
+        let group = &bundle.groups[0];
+        assert_eq!(group.license, "OFL-1.1");
+        assert_eq!(group.name, "SIL Open Font License 1.1");
+        assert!(group.text_required);
+        let names: Vec<&str> = group.fonts.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["Family", "Family Bold", "Other Sans"]);
+        // One license file covers every font in the group
+        assert_eq!(group.text.as_deref(), Some(ofl));
+        assert!(matches!(&group.text_source, Some(LicenseTextSource::File(path)) if *path == fonts_dir.join("family/OFL.txt")));
+        assert!(bundle.warnings.is_empty(), "{:?}", bundle.warnings);
+    }
+
+    #[test]
+    fn test_notice_bundle_warns_about_missing_license_files() {
+        let root = tempfile::tempdir().unwrap();
+        let lonely = notice_font(root.path(), "lonely/Lonely.ttf", "Lonely Sans", None, Some("OFL-1.1"));
+        let apache = notice_font(root.path(), "apache/Roboto.ttf", "Roboto", None, Some("Apache-2.0"));
+        // Nothing has to ship with a font whose license doesn't ask for it
+        let mystery = notice_font(root.path(), "mystery/Mystery.ttf", "Mystery Grotesk", None, None);
+
+        let bundle = NoticeBundle::build(&[lonely, apache, mystery]);
+
+        let licenses: Vec<&str> = bundle.groups.iter().map(|g| g.license.as_str()).collect();
+        assert_eq!(licenses, ["Apache-2.0", "OFL-1.1", "Unknown"]);
+        assert!(bundle.groups.iter().all(|g| g.text.is_none()));
+        assert_eq!(bundle.warnings, [
+            "No license text found for OFL-1.1 (used by Lonely Sans)",
+            "No license text found for Apache-2.0 (used by Roboto)",
+        ]);
+    }
+
+    fn sbom(dir: &Path) -> FontSbom {
+        let mut sbom = FontSbom::new("My App 2.0");
+        for (file, family, spdx_id) in [
+            ("a/OpenSans.ttf", "Open Sans", Some("OFL-1.1")),
+            ("b/OpenSans.ttf", "Open Sans", Some("OFL-1.1")),
+            ("Mystery.ttf", "Mystery Grotesk", None),
+            ("copy/Mystery.ttf", "Mystery Grotesk", None),
+        ] {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            // The copy has the same bytes as the original and is listed once
+            let contents = if file.starts_with("copy/") { "Mystery.ttf" } else { file };
+            fs::write(&path, contents).unwrap();
+            let mut descriptor = font(family, spdx_id, None);
+            descriptor.path = path;
+            sbom.add_descriptor(&descriptor, "project").unwrap();
+        }
+        sbom
+    }
+
+    fn assert_uuid(uuid: &str) {
+        let groups: Vec<usize> = uuid.split('-').map(str::len).collect();
+        assert_eq!(groups, [8, 4, 4, 4, 12], "{}", uuid);
+        assert!(uuid.chars().all(|c| c == '-' || c.is_ascii_hexdigit()));
+        assert_eq!(&uuid[14..15], "4");
+        assert!("89ab".contains(&uuid[19..20]));
+    }
+
+    #[test]
+    fn test_spdx_document() {
+        let dir = tempfile::tempdir().unwrap();
+        let sbom = sbom(dir.path());
+        assert_eq!(sbom.components.len(), 3);
+
+        let document: serde_json::Value = serde_json::from_str(&sbom.to_spdx().unwrap()).unwrap();
+        assert_eq!(document["spdxVersion"], "SPDX-2.3");
+        let namespace = document["documentNamespace"].as_str().unwrap();
+        let uuid = namespace.strip_prefix("https://spdx.org/spdxdocs/my-app-2-0-").unwrap();
+        assert_uuid(uuid);
+
+        let packages = document["packages"].as_array().unwrap();
+        let ids: HashSet<&str> = packages.iter().map(|p| p["SPDXID"].as_str().unwrap()).collect();
+        assert_eq!(ids.len(), 3, "two files of the same family still get their own ids");
+        assert!(ids.iter().all(|id| id.starts_with("SPDXRef-") && id[8..].chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')));
+
+        let described: HashSet<&str> = document["relationships"].as_array().unwrap().iter()
+            .map(|r| r["relatedSpdxElement"].as_str().unwrap())
+            .collect();
+        assert_eq!(described, ids);
+
+        assert_eq!(packages[0]["licenseConcluded"], "OFL-1.1");
+        assert_eq!(packages[0]["licenseDeclared"], "OFL-1.1");
+        assert_eq!(packages[2]["name"], "Mystery Grotesk");
+        assert_eq!(packages[2]["licenseConcluded"], "NOASSERTION");
+        assert_eq!(packages[2]["licenseDeclared"], "NOASSERTION");
+        assert_eq!(packages[2]["versionInfo"], "NOASSERTION");
+        assert!(document.get("hasExtractedLicensingInfos").is_none());
+    }
+
+    #[test]
+    fn test_cyclonedx_bom() {
+        let dir = tempfile::tempdir().unwrap();
+        let sbom = sbom(dir.path());
+
+        let bom: serde_json::Value = serde_json::from_str(&sbom.to_cyclonedx().unwrap()).unwrap();
+        assert_eq!(bom["specVersion"], "1.5");
+        assert_uuid(bom["serialNumber"].as_str().unwrap().strip_prefix("urn:uuid:").unwrap());
+        assert_eq!(bom["metadata"]["component"]["bom-ref"], "my-app-2-0");
+
+        let components = bom["components"].as_array().unwrap();
+        let refs: HashSet<&str> = components.iter().map(|c| c["bom-ref"].as_str().unwrap()).collect();
+        assert_eq!(refs.len(), 3);
+        assert!(!refs.contains("my-app-2-0"));
+        for (component, listed) in components.iter().zip(&sbom.components) {
+            assert_eq!(component["bom-ref"], format!("font-{}", &listed.sha256[..16]));
+        }
+
+        assert_eq!(components[0]["licenses"][0]["license"]["id"], "OFL-1.1");
+        // Unidentified licenses are left out rather than asserted
+        assert!(components[2].get("licenses").is_none());
+    }
+}
@@ -1,57 +1,400 @@
 use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::fmt;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, Duration};
 
-use font_core::{FontDescriptor, FontError, FontResult, CacheStats};
+use font_core::{CacheCleanupMode, FontDescriptor, FontError, FontResult, CacheStats};
 use lru::LruCache;
 use parking_lot::{Mutex, RwLock};
 use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+
+/// Environment variable overriding the default cache directory
+pub const CACHE_DIR_ENV: &str = "FONT_RESOLVER_CACHE_DIR";
+
+const ENTRIES_DIR: &str = "entries";
+const INDEX_FILE: &str = "index.bin";
+const COUNTERS_FILE: &str = "counters.bin";
+const PINNED_FILE: &str = "pinned_fonts.bin";
+const ACCESS_COUNTS_FILE: &str = "access_counts.bin";
+const NOT_FOUND_FILE: &str = "not_found.bin";
+const LOCK_FILE: &str = "cache.lock";
+/// Bumped whenever the entry layout changes, so older entries are dropped instead of misread
+const ENTRY_MAGIC: &[u8; 4] = b"FCE8";
+const CHECKSUM_LEN: usize = 32;
+
+/// Entries not accessed for this long expire under `TimeBased` and `Smart`
+const MAX_IDLE_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Per-user cache directory, overridable through `FONT_RESOLVER_CACHE_DIR`
+pub fn default_cache_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(CACHE_DIR_ENV) {
+        return PathBuf::from(dir);
+    }
+    
+    directories::ProjectDirs::from("com", "font-resolver", "font-resolver")
+        .map(|dirs| dirs.cache_dir().to_path_buf())
+        .unwrap_or_else(|| PathBuf::from(".font_cache"))
+}
+
+/// Normalize a font name into a cache key (case and whitespace insensitive)
+pub fn normalize_key(font_name: &str) -> String {
+    font_name
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Path-safe file name for a cache key
+fn entry_file_name(key: &str) -> String {
+    let digest = Sha256::digest(key.as_bytes());
+    let hex: String = digest.iter().take(16).map(|b| format!("{:02x}", b)).collect();
+    format!("{}.bin", hex)
+}
+
+/// Write through a temporary file and rename it into place, so readers never see a partial file
+fn write_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let temp_path = path.with_file_name(format!(
+        ".{}.{}.{}.tmp",
+        file_name,
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    
+    let result = File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(data)?;
+            file.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Read a bincode file, treating a missing or unreadable file as empty
+fn read_or_default<T: DeserializeOwned + Default>(path: &Path) -> T {
+    std::fs::read(path)
+        .ok()
+        .and_then(|data| bincode::deserialize(&data).ok())
+        .unwrap_or_default()
+}
+
+fn serialize<T: Serialize>(value: &T, what: &str) -> FontResult<Vec<u8>> {
+    bincode::serialize(value)
+        .map_err(|e| FontError::CacheError(format!("Failed to serialize {}: {}", what, e)))
+}
+
+/// Exclusive advisory lock on the cache directory, held while shared files are read and rewritten.
+/// Every holder opens its own handle, so the lock also serializes threads of one process.
+struct CacheLock(File);
+
+impl CacheLock {
+    fn acquire(disk_path: &Path) -> FontResult<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(disk_path.join(LOCK_FILE))?;
+        file.lock()
+            .map_err(|e| FontError::CacheError(format!("Failed to lock cache directory: {}", e)))?;
+        Ok(Self(file))
+    }
+}
+
+impl Drop for CacheLock {
+    fn drop(&mut self) {
+        let _ = self.0.unlock();
+    }
+}
+
+fn hash_file(path: &Path) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Identity of the font file behind a cache entry, used to notice upgraded, moved or deleted files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileIdentity {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// Hex SHA-256 of the file contents
+    pub content_hash: String,
+}
+
+impl FileIdentity {
+    /// Identity of the file at `path`, or `None` if it is not a readable local file
+    pub fn of(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok().filter(|m| m.is_file())?;
+        Some(Self {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            content_hash: hash_file(path).ok()?,
+        })
+    }
+    
+    /// Compare with the file now at `path`. The hash is only recomputed when the size matches
+    /// but the mtime moved, so a touched but unchanged file stays valid.
+    pub fn check(&self, path: &Path) -> EntryStatus {
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return EntryStatus::Missing;
+        };
+        if metadata.len() != self.size {
+            return EntryStatus::Modified;
+        }
+        if metadata.modified().ok() == self.modified {
+            return EntryStatus::Valid;
+        }
+        
+        match hash_file(path) {
+            Ok(hash) if hash == self.content_hash => EntryStatus::Valid,
+            Ok(_) => EntryStatus::Modified,
+            Err(_) => EntryStatus::Missing,
+        }
+    }
+}
+
+/// Whether a cached descriptor still matches its font file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryStatus {
+    Valid,
+    /// No local file was recorded (e.g. web fonts), so there is nothing to check
+    Unverified,
+    Missing,
+    Modified,
+}
+
+impl EntryStatus {
+    pub fn is_stale(&self) -> bool {
+        matches!(self, EntryStatus::Missing | EntryStatus::Modified)
+    }
+}
+
+impl fmt::Display for EntryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryStatus::Valid => write!(f, "valid"),
+            EntryStatus::Unverified => write!(f, "unverified"),
+            EntryStatus::Missing => write!(f, "file missing"),
+            EntryStatus::Modified => write!(f, "file modified"),
+        }
+    }
+}
+
+/// The request an entry answers, stored with it so listings can show the font rather than its key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedRequest {
+    /// Family as the resolved font names it
+    pub family: String,
+    pub weight: u16,
+    pub italic: bool,
+    /// OS/2 width class, 5 = normal
+    pub width: u16,
+}
+
+impl fmt::Display for CachedRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}", self.family, self.weight)?;
+        if self.italic {
+            write!(f, ", italic")?;
+        }
+        if self.width != font_core::WIDTH_NORMAL {
+            write!(f, ", width {}", self.width)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Outcome of a cache lookup that revalidates the entry against its font file
+#[derive(Debug, Clone)]
+pub enum CacheLookup {
+    Hit(FontDescriptor),
+    /// The font file changed or disappeared; the entry has already been dropped
+    Stale { font: FontDescriptor, status: EntryStatus },
+    /// A recent lookup found nothing and the negative entry has not expired yet
+    NotFound,
+    Miss,
+}
+
+/// Cache entry whose font file no longer matches what was cached
+#[derive(Debug, Clone)]
+pub struct DanglingEntry {
+    pub font_name: String,
+    pub path: PathBuf,
+    pub status: EntryStatus,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VerifyReport {
+    pub checked: usize,
+    /// Entries without a recorded file identity
+    pub unverified: usize,
+    pub dangling: Vec<DanglingEntry>,
+    /// Dangling entries removed (only when purging)
+    pub removed: usize,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CacheEntry {
+    /// Normalized key, used to detect hash collisions and misplaced files
+    key: String,
+    /// Request the entry answers, when it was stored for one
+    request: Option<CachedRequest>,
     font: FontDescriptor,
     access_count: u32,
     last_accessed: SystemTime,
     created_at: SystemTime,
     is_pinned: bool,
     estimated_size_kb: usize,
+    /// Identity of `font.path` when the entry was written
+    file_identity: Option<FileIdentity>,
+}
+
+impl CacheEntry {
+    fn status(&self) -> EntryStatus {
+        match &self.file_identity {
+            Some(identity) => identity.check(&self.font.path),
+            None => EntryStatus::Unverified,
+        }
+    }
+    
+    /// Serialize with a magic header and SHA-256 checksum of the payload
+    fn encode(&self) -> FontResult<Vec<u8>> {
+        let payload = bincode::serialize(self)
+            .map_err(|e| FontError::CacheError(format!("Failed to serialize cache entry: {}", e)))?;
+        
+        let mut data = Vec::with_capacity(ENTRY_MAGIC.len() + CHECKSUM_LEN + payload.len());
+        data.extend_from_slice(ENTRY_MAGIC);
+        data.extend_from_slice(&Sha256::digest(&payload));
+        data.extend_from_slice(&payload);
+        Ok(data)
+    }
+    
+    fn decode(data: &[u8], expected_key: &str) -> Result<Self, String> {
+        let entry = Self::decode_unchecked_key(data)?;
+        if entry.key != expected_key {
+            return Err(format!("entry belongs to '{}'", entry.key));
+        }
+        Ok(entry)
+    }
+    
+    fn decode_unchecked_key(data: &[u8]) -> Result<Self, String> {
+        let header_len = ENTRY_MAGIC.len() + CHECKSUM_LEN;
+        if data.len() < header_len || &data[..ENTRY_MAGIC.len()] != ENTRY_MAGIC {
+            return Err("bad header".to_string());
+        }
+        
+        let (checksum, payload) = data[ENTRY_MAGIC.len()..].split_at(CHECKSUM_LEN);
+        if Sha256::digest(payload).as_slice() != checksum {
+            return Err("checksum mismatch".to_string());
+        }
+        
+        bincode::deserialize(payload).map_err(|e| format!("undecodable payload: {}", e))
+    }
+}
+
+/// Index record mapping a cache key to its entry file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    /// Name the entry was stored under, before normalization
+    font_name: String,
+    file_name: String,
+    size_bytes: u64,
+    created_at: SystemTime,
+    last_accessed: SystemTime,
+}
+
+/// Hit/miss/eviction totals, persisted across sessions in `counters.bin`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct CacheCounters {
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+/// An entry considered for eviction, from either tier
+struct EvictionCandidate {
+    key: String,
+    size_bytes: usize,
+    last_accessed: SystemTime,
+    access_count: u32,
+}
+
+impl EvictionCandidate {
+    fn idle_time(&self, now: SystemTime) -> Duration {
+        now.duration_since(self.last_accessed).unwrap_or_default()
+    }
+    
+    /// Retention value for `Smart`: frequently and recently used, small entries score highest
+    fn retention_score(&self, now: SystemTime) -> f64 {
+        let idle_days = self.idle_time(now).as_secs_f64() / 86_400.0;
+        let size_kb = (self.size_bytes as f64 / 1024.0).max(1.0);
+        (self.access_count.max(1) as f64) / (1.0 + idle_days) / size_kb.sqrt()
+    }
 }
 
 pub struct HybridFontCache {
     memory: Mutex<LruCache<String, CacheEntry>>,
     disk_path: PathBuf,
+    entries_path: PathBuf,
+    index: RwLock<HashMap<String, IndexEntry>>,
     memory_limit_bytes: usize,
     disk_limit_bytes: usize,
     auto_pin_threshold: u32,
     pinned_fonts: RwLock<HashSet<String>>,
     access_counts: RwLock<HashMap<String, u32>>,
+    /// Negative entries: key -> time the not-found result expires
+    not_found: RwLock<HashMap<String, SystemTime>>,
+    cleanup_mode: CacheCleanupMode,
+    /// Counters persisted by earlier sessions
+    stored_counters: Mutex<CacheCounters>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    /// Index records changed since the last save (`None` = removed), merged into the shared index
+    index_changes: Mutex<HashMap<String, Option<IndexEntry>>>,
+    /// Access count increments since the last save, added to the shared counts
+    access_deltas: Mutex<HashMap<String, u32>>,
+    removed_access: Mutex<HashSet<String>>,
 }
 
 impl HybridFontCache {
     pub fn new(memory_limit_mb: usize, disk_limit_mb: usize, auto_pin_threshold: u32) -> FontResult<Self> {
+        Self::with_dir(default_cache_dir(), memory_limit_mb, disk_limit_mb, auto_pin_threshold)
+    }
+    
+    /// Create a cache stored in `cache_dir` instead of the per-user default
+    pub fn with_dir<P: Into<PathBuf>>(
+        cache_dir: P,
+        memory_limit_mb: usize,
+        disk_limit_mb: usize,
+        auto_pin_threshold: u32,
+    ) -> FontResult<Self> {
         let memory_limit_bytes = memory_limit_mb * 1024 * 1024;
         let disk_limit_bytes = disk_limit_mb * 1024 * 1024;
         
-        // Create cache directory - Use local relative path for reliability
-        let disk_path = PathBuf::from(".font_cache");
-        std::fs::create_dir_all(&disk_path)?;
+        let disk_path = cache_dir.into();
+        let entries_path = disk_path.join(ENTRIES_DIR);
+        std::fs::create_dir_all(&entries_path)?;
         
-        // Load pinned fonts from disk
-        let pinned_path = disk_path.join("pinned_fonts.bin");
-        let pinned_fonts = if pinned_path.exists() {
-            let data = std::fs::read(&pinned_path)?;
-            bincode::deserialize(&data).unwrap_or_default()
-        } else {
-            HashSet::new()
-        };
-        
-        // Load access counts
-        let access_path = disk_path.join("access_counts.bin");
-        let access_counts = if access_path.exists() {
-            let data = std::fs::read(&access_path)?;
-            bincode::deserialize(&data).unwrap_or_default()
-        } else {
-            HashMap::new()
+        let (index, pinned_fonts, access_counts, not_found, stored_counters) = {
+            let _lock = CacheLock::acquire(&disk_path)?;
+            (
+                Self::load_index(&disk_path, &entries_path)?,
+                read_or_default::<HashSet<String>>(&disk_path.join(PINNED_FILE)),
+                read_or_default::<HashMap<String, u32>>(&disk_path.join(ACCESS_COUNTS_FILE)),
+                read_or_default::<HashMap<String, SystemTime>>(&disk_path.join(NOT_FOUND_FILE)),
+                read_or_default::<CacheCounters>(&disk_path.join(COUNTERS_FILE)),
+            )
         };
         
         Ok(Self {
@@ -59,133 +402,230 @@ impl HybridFontCache {
                 std::num::NonZeroUsize::new(1000).unwrap()
             )),
             disk_path,
+            entries_path,
+            index: RwLock::new(index),
             memory_limit_bytes,
             disk_limit_bytes,
             auto_pin_threshold,
             pinned_fonts: RwLock::new(pinned_fonts),
             access_counts: RwLock::new(access_counts),
+            not_found: RwLock::new(not_found),
+            cleanup_mode: CacheCleanupMode::Manual,
+            stored_counters: Mutex::new(stored_counters),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            index_changes: Mutex::new(HashMap::new()),
+            access_deltas: Mutex::new(HashMap::new()),
+            removed_access: Mutex::new(HashSet::new()),
         })
     }
     
+    /// Set the eviction order used on `put` and whether idle entries expire (defaults to `Manual`)
+    pub fn with_cleanup_mode(mut self, mode: CacheCleanupMode) -> Self {
+        self.cleanup_mode = mode;
+        self
+    }
+    
+    pub fn cleanup_mode(&self) -> &CacheCleanupMode {
+        &self.cleanup_mode
+    }
+    
     pub fn get(&self, font_name: &str) -> Option<FontDescriptor> {
-        let mut memory = self.memory.lock();
+        match self.lookup(font_name) {
+            CacheLookup::Hit(font) => Some(font),
+            _ => None,
+        }
+    }
+    
+    /// Look up an entry and revalidate it against its font file, dropping it if stale
+    pub fn lookup(&self, font_name: &str) -> CacheLookup {
+        let key = normalize_key(font_name);
+        let now = SystemTime::now();
+        
+        // A cached not-found result saves a search but still yields no font, so it's a miss
+        if self.not_found.read().get(&key).is_some_and(|expires| *expires > now) {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return CacheLookup::NotFound;
+        }
         
         // Update access count
-        {
+        let (access_count, should_pin) = {
             let mut access_counts = self.access_counts.write();
-            let count = access_counts.entry(font_name.to_string())
+            let count = access_counts.entry(key.clone())
                 .and_modify(|c| *c += 1)
                 .or_insert(1);
+            *self.access_deltas.lock().entry(key.clone()).or_insert(0) += 1;
             
-            // Auto-pin if used frequently
-            if *count >= self.auto_pin_threshold && !self.is_pinned(font_name) {
-                self.pin_font(font_name);
-            }
+            // Auto-pin if used frequently (a threshold of 0 disables auto-pinning)
+            (*count, self.auto_pin_threshold > 0 && *count >= self.auto_pin_threshold)
+        };
+        if should_pin && !self.is_pinned(&key) {
+            self.pin_font(&key);
         }
         
-        // Check memory cache first
-        if let Some(entry) = memory.get(font_name) {
-            let mut entry = entry.clone();
-            entry.last_accessed = SystemTime::now();
-            memory.put(font_name.to_string(), entry.clone());
-            return Some(entry.font);
+        // Check memory cache first, then disk
+        let memory_entry = self.memory.lock().get_mut(&key).map(|entry| {
+            entry.last_accessed = now;
+            entry.access_count = access_count;
+            entry.clone()
+        });
+        let in_memory = memory_entry.is_some();
+        let Some(mut entry) = memory_entry.or_else(|| self.load_from_disk(&key)) else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return CacheLookup::Miss;
+        };
+        
+        // Checked outside the memory lock, since it may hash the font file
+        let status = entry.status();
+        if status.is_stale() {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            self.memory.lock().pop(&key);
+            if let Err(e) = self.remove_from_disk(&key) {
+                eprintln!("⚠️  Failed to drop stale cache entry '{}': {}", key, e);
+            }
+            return CacheLookup::Stale { font: entry.font, status };
         }
         
-        // Check disk cache
-        if let Some(entry) = self.load_from_disk(font_name) {
+        self.record_hit(&key, now);
+        if !in_memory {
+            entry.last_accessed = now;
+            entry.access_count = access_count;
+            
             // Promote to memory if there's space
             let estimated_size = entry.estimated_size_kb * 1024;
             if self.current_memory_usage() + estimated_size <= self.memory_limit_bytes {
-                let mut entry = entry.clone();
-                entry.last_accessed = SystemTime::now();
-                memory.put(font_name.to_string(), entry.clone());
+                self.memory.lock().put(key, entry.clone());
+            }
+        }
+        CacheLookup::Hit(entry.font)
+    }
+    
+    /// Check every entry against its font file without touching access statistics.
+    /// Files are hashed without holding any cache lock, so lookups keep working meanwhile;
+    /// with `purge`, dangling entries are removed.
+    pub fn verify(&self, purge: bool) -> FontResult<VerifyReport> {
+        let entries: Vec<(String, String)> = self.index.read().iter()
+            .map(|(key, e)| (key.clone(), e.font_name.clone()))
+            .collect();
+        
+        let mut report = VerifyReport::default();
+        for (key, font_name) in entries {
+            let Some(entry) = self.load_from_disk(&key) else {
+                continue;
+            };
+            report.checked += 1;
+            
+            let status = entry.status();
+            if status == EntryStatus::Unverified {
+                report.unverified += 1;
+            }
+            if !status.is_stale() {
+                continue;
+            }
+            
+            if purge {
+                self.memory.lock().pop(&key);
+                if self.remove_from_disk(&key)? {
+                    report.removed += 1;
+                }
             }
-            return Some(entry.font);
+            report.dangling.push(DanglingEntry {
+                font_name,
+                path: entry.font.path,
+                status,
+            });
         }
         
-        None
+        Ok(report)
     }
     
     pub fn put(&self, font_name: &str, font: FontDescriptor) -> FontResult<()> {
-        let is_pinned = self.is_pinned(font_name);
+        self.put_entry(font_name, None, font)
+    }
+    
+    /// Store `font` under `font_name`, remembering the request it answers for listings
+    /// and family lookups
+    pub fn put_request(&self, font_name: &str, request: CachedRequest, font: FontDescriptor) -> FontResult<()> {
+        self.put_entry(font_name, Some(request), font)
+    }
+    
+    fn put_entry(&self, font_name: &str, request: Option<CachedRequest>, font: FontDescriptor) -> FontResult<()> {
+        let key = normalize_key(font_name);
+        let is_pinned = self.is_pinned(&key);
         let estimated_size_kb = self.estimate_font_size_kb(&font);
+        let access_count = self.access_counts.read().get(&key).copied().unwrap_or(1);
+        let file_identity = FileIdentity::of(&font.path);
         
-        // Check memory usage
-        let memory_needed = estimated_size_kb * 1024;
-        if self.current_memory_usage() + memory_needed > self.memory_limit_bytes {
-            return Err(FontError::MemoryLimitExceeded(
-                (self.current_memory_usage() + memory_needed) as f64 / (1024.0 * 1024.0),
-                self.memory_limit_bytes / (1024 * 1024)
-            ));
-        }
-        
-        // Check disk usage - use estimate to avoid hanging on large cache directories
-        // Estimate based on entry count rather than scanning filesystem
-        let estimated_disk_usage = self.estimate_disk_usage_fast();
-        if estimated_disk_usage + memory_needed > self.disk_limit_bytes {
-            return Err(FontError::DiskLimitExceeded(
-                (estimated_disk_usage + memory_needed) as f64 / (1024.0 * 1024.0),
-                self.disk_limit_bytes / (1024 * 1024)
-            ));
+        if self.not_found.read().contains_key(&key) {
+            self.update_not_found(&key, None)?;
         }
         
         let entry = CacheEntry {
+            key: key.clone(),
+            request,
             font,
-            access_count: 1,
+            access_count,
             last_accessed: SystemTime::now(),
             created_at: SystemTime::now(),
             is_pinned,
             estimated_size_kb,
+            file_identity,
         };
+        let data = entry.encode()?;
+        
+        // Expire idle entries, then evict until the new entry fits on disk, in every mode
+        if matches!(self.cleanup_mode, CacheCleanupMode::TimeBased | CacheCleanupMode::Smart) {
+            self.evict_expired()?;
+        }
+        self.make_disk_room(&key, data.len())?;
+        
+        // Demote entries from memory until the new entry fits
+        let memory_needed = estimated_size_kb * 1024;
+        self.make_memory_room(&key, memory_needed)?;
         
         // Store in memory
-        let mut memory = self.memory.lock();
-        memory.put(font_name.to_string(), entry.clone());
+        self.memory.lock().put(key.clone(), entry.clone());
         
         // Store in disk
-        self.save_to_disk(font_name, &entry)?;
+        self.write_entry(&key, font_name, &entry, &data)?;
         
         Ok(())
     }
     
+    /// Remember that `font_name` could not be resolved, so lookups short-circuit for `ttl`
+    pub fn put_not_found(&self, font_name: &str, ttl: Duration) -> FontResult<()> {
+        self.update_not_found(&normalize_key(font_name), Some(SystemTime::now() + ttl))
+    }
+    
     pub fn pin_font(&self, font_name: &str) {
-        let data = {
-            let mut pinned = self.pinned_fonts.write();
-            pinned.insert(font_name.to_string());
-            bincode::serialize(&*pinned).ok()
-        };
-        
-        if let Some(data) = data {
-            let pinned_path = self.disk_path.join("pinned_fonts.bin");
-            let _ = std::fs::write(pinned_path, data);
+        let key = normalize_key(font_name);
+        self.pinned_fonts.write().insert(key.clone());
+        if let Err(e) = self.update_pinned(&key, true) {
+            eprintln!("⚠️  Failed to save pinned fonts: {}", e);
         }
         
         // Update cache entry if in memory
-        if let Some(entry) = self.memory.lock().get_mut(font_name) {
+        if let Some(entry) = self.memory.lock().get_mut(&key) {
             entry.is_pinned = true;
         }
     }
     
     pub fn unpin_font(&self, font_name: &str) {
-        let data = {
-            let mut pinned = self.pinned_fonts.write();
-            pinned.remove(font_name);
-            bincode::serialize(&*pinned).ok()
-        };
-        
-        if let Some(data) = data {
-            let pinned_path = self.disk_path.join("pinned_fonts.bin");
-            let _ = std::fs::write(pinned_path, data);
+        let key = normalize_key(font_name);
+        self.pinned_fonts.write().remove(&key);
+        if let Err(e) = self.update_pinned(&key, false) {
+            eprintln!("⚠️  Failed to save pinned fonts: {}", e);
         }
         
         // Update cache entry if in memory
-        if let Some(entry) = self.memory.lock().get_mut(font_name) {
+        if let Some(entry) = self.memory.lock().get_mut(&key) {
             entry.is_pinned = false;
         }
     }
     
     pub fn is_pinned(&self, font_name: &str) -> bool {
-        self.pinned_fonts.read().contains(font_name)
+        self.pinned_fonts.read().contains(&normalize_key(font_name))
     }
     
     pub fn cleanup(&self, aggressive: bool) -> FontResult<usize> {
@@ -217,29 +657,36 @@ impl HybridFontCache {
         // Clean disk cache
         removed += self.cleanup_disk(aggressive)?;
         
-        // Save access counts
-        self.save_access_counts()?;
+        // Save access counts and index changes
+        self.flush()?;
         
         Ok(removed)
     }
 
     pub fn remove_entry(&self, font_name: &str) -> FontResult<bool> {
+        let key = normalize_key(font_name);
         let mut removed = false;
         
         // Remove from memory
-        if self.memory.lock().pop(font_name).is_some() {
+        if self.memory.lock().pop(&key).is_some() {
             removed = true;
         }
         
         // Remove from disk
-        let path = self.disk_path.join(format!("{}.bin", font_name));
-        if path.exists() {
-            std::fs::remove_file(path)?;
+        if self.remove_from_disk(&key)? {
+            removed = true;
+        }
+        
+        // Forget a cached not-found result
+        if self.not_found.read().contains_key(&key) {
+            self.update_not_found(&key, None)?;
             removed = true;
         }
         
         // Remove from access counts
-        self.access_counts.write().remove(font_name);
+        self.access_counts.write().remove(&key);
+        self.access_deltas.lock().remove(&key);
+        self.removed_access.lock().insert(key);
         
         Ok(removed)
     }
@@ -279,9 +726,13 @@ impl HybridFontCache {
             })
             .unwrap_or(0);
         
-        // Never touch filesystem - always return 0 for disk stats
-        let disk_entries = 0usize;
-        let disk_usage = 0usize;
+        // Disk stats come from the index, so the filesystem is never traversed
+        let (disk_entries, disk_usage) = self.index.try_read()
+            .map(|index| (index.len(), index.values().map(|e| e.size_bytes as usize).sum::<usize>()))
+            .unwrap_or((0, 0));
+        
+        let counters = self.counters();
+        let lookups = counters.hits + counters.misses;
         
         Ok(CacheStats {
             memory_entries,
@@ -289,12 +740,49 @@ impl HybridFontCache {
             pinned_fonts,
             memory_usage_mb: memory_usage as f64 / (1024.0 * 1024.0),
             disk_usage_mb: disk_usage as f64 / (1024.0 * 1024.0),
+            hits: counters.hits,
+            misses: counters.misses,
+            evictions: counters.evictions,
+            hit_rate: if lookups > 0 { counters.hits as f64 / lookups as f64 } else { 0.0 },
         })
     }
     
+    /// Pinned entries, described by the request they answer when known, otherwise by key
     pub fn list_pinned(&self) -> Vec<String> {
-        let pinned = self.pinned_fonts.read();
-        pinned.iter().cloned().collect()
+        let keys: Vec<String> = self.pinned_fonts.read().iter().cloned().collect();
+        let mut pinned: Vec<String> = keys.into_iter()
+            .map(|key| match self.request_for(&key) {
+                Some(request) => request.to_string(),
+                None => key,
+            })
+            .collect();
+        pinned.sort();
+        pinned
+    }
+    
+    /// The request a cached entry answers, if it was stored with one
+    pub fn request_for(&self, font_name: &str) -> Option<CachedRequest> {
+        let key = normalize_key(font_name);
+        if let Some(entry) = self.memory.lock().peek(&key) {
+            return entry.request.clone();
+        }
+        self.load_from_disk(&key).and_then(|entry| entry.request)
+    }
+    
+    /// Keys of the cached entries whose request satisfies `predicate`
+    pub fn keys_matching(&self, predicate: impl Fn(&CachedRequest) -> bool) -> Vec<String> {
+        let keys: Vec<String> = self.index.read().keys().cloned().collect();
+        let mut matching: Vec<String> = keys.into_iter()
+            .filter(|key| self.request_for(key).is_some_and(|request| predicate(&request)))
+            .collect();
+        matching.sort();
+        matching
+    }
+    
+    /// Whether `font_name` is stored or pinned under exactly this key
+    pub fn contains_key(&self, font_name: &str) -> bool {
+        let key = normalize_key(font_name);
+        self.index.read().contains_key(&key) || self.pinned_fonts.read().contains(&key)
     }
     
     pub fn suggest_cleanup(&self) -> FontResult<Vec<String>> {
@@ -311,24 +799,14 @@ impl HybridFontCache {
         }
         
         // Check disk cache
-        let entries = std::fs::read_dir(&self.disk_path)?;
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
+        for (key, index_entry) in self.index.read().iter() {
+            if self.is_pinned(key) {
+                continue;
+            }
             
-            if path.extension().map(|e| e == "bin").unwrap_or(false) {
-                if let Some(file_name) = path.file_stem() {
-                    let font_name = file_name.to_string_lossy().to_string();
-                    if !self.is_pinned(&font_name) {
-                        if let Ok(metadata) = std::fs::metadata(&path) {
-                            if let Ok(modified) = metadata.modified() {
-                                if SystemTime::now().duration_since(modified).unwrap_or_default() > Duration::from_secs(30 * 24 * 60 * 60) {
-                                    suggestions.push(format!("Disk: {} (not accessed in 30 days)", font_name));
-                                }
-                            }
-                        }
-                    }
-                }
+            let idle = SystemTime::now().duration_since(index_entry.last_accessed).unwrap_or_default();
+            if idle > Duration::from_secs(30 * 24 * 60 * 60) {
+                suggestions.push(format!("Disk: {} (not accessed in 30 days)", index_entry.font_name));
             }
         }
         
@@ -344,83 +822,241 @@ impl HybridFontCache {
             .sum()
     }
     
-    #[allow(dead_code)]
-    fn current_disk_usage(&self) -> FontResult<usize> {
-        Self::get_directory_size(&self.disk_path)
+    /// Totals including this session's activity
+    fn counters(&self) -> CacheCounters {
+        let stored = *self.stored_counters.lock();
+        CacheCounters {
+            hits: stored.hits + self.hits.load(Ordering::Relaxed),
+            misses: stored.misses + self.misses.load(Ordering::Relaxed),
+            evictions: stored.evictions + self.evictions.load(Ordering::Relaxed),
+        }
+    }
+    
+    fn record_hit(&self, key: &str, now: SystemTime) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        if let Some(index_entry) = self.index.write().get_mut(key) {
+            index_entry.last_accessed = now;
+            self.index_changes.lock().insert(key.to_string(), Some(index_entry.clone()));
+        }
+    }
+    
+    /// Order candidates so the first one is the next to evict under the current policy
+    fn sort_for_eviction(&self, candidates: &mut [EvictionCandidate]) {
+        match self.cleanup_mode {
+            // Least recently used first
+            CacheCleanupMode::Manual | CacheCleanupMode::TimeBased => {
+                candidates.sort_by_key(|c| c.last_accessed);
+            }
+            // Largest first, frees the space with the fewest evictions
+            CacheCleanupMode::SizeBased => {
+                candidates.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes)
+                    .then(a.last_accessed.cmp(&b.last_accessed)));
+            }
+            CacheCleanupMode::Smart => {
+                let now = SystemTime::now();
+                candidates.sort_by(|a, b| a.retention_score(now).total_cmp(&b.retention_score(now)));
+            }
+        }
+    }
+    
+    fn disk_candidates(&self, exclude: &str) -> Vec<EvictionCandidate> {
+        let pinned = self.pinned_fonts.read();
+        let access_counts = self.access_counts.read();
+        self.index.read().iter()
+            .filter(|(key, _)| key.as_str() != exclude && !pinned.contains(*key))
+            .map(|(key, entry)| EvictionCandidate {
+                key: key.clone(),
+                size_bytes: entry.size_bytes as usize,
+                last_accessed: entry.last_accessed,
+                access_count: access_counts.get(key).copied().unwrap_or(1),
+            })
+            .collect()
     }
     
-    /// Fast estimate of disk usage without filesystem traversal
-    /// Uses memory cache size as a proxy to avoid hanging
-    fn estimate_disk_usage_fast(&self) -> usize {
-        // Estimate: memory entries * average size (50KB) * 2 (disk overhead)
-        let memory_entries = self.memory.lock().len();
-        memory_entries * 50 * 1024 * 2
+    /// Remove unpinned entries that have been idle longer than `MAX_IDLE_AGE`
+    fn evict_expired(&self) -> FontResult<usize> {
+        let now = SystemTime::now();
+        let expired: Vec<String> = self.disk_candidates("").into_iter()
+            .filter(|c| c.idle_time(now) > MAX_IDLE_AGE)
+            // Smart keeps old entries that have proven useful
+            .filter(|c| self.cleanup_mode != CacheCleanupMode::Smart || c.access_count <= 1)
+            .map(|c| c.key)
+            .collect();
+        
+        for key in &expired {
+            self.evict(key)?;
+        }
+        Ok(expired.len())
     }
     
-    #[allow(dead_code)]
-    fn get_directory_size(path: &Path) -> FontResult<usize> {
-        let mut total = 0;
-        let mut file_count = 0;
-        let max_files = 5000; // Limit total files processed to prevent hangs
-        let max_depth = 3; // Only go 3 levels deep
+    /// Evict entries from both tiers until `needed` more bytes fit on disk
+    fn make_disk_room(&self, key: &str, needed: usize) -> FontResult<()> {
+        // Usage from the index, excluding the entry being replaced; both are read under one lock
+        let mut usage = {
+            let index = self.index.read();
+            let replaced = index.get(key).map(|e| e.size_bytes as usize).unwrap_or(0);
+            index.values().map(|e| e.size_bytes as usize).sum::<usize>().saturating_sub(replaced)
+        };
+        if usage + needed <= self.disk_limit_bytes {
+            return Ok(());
+        }
         
-        // Use a stack with depth tracking
-        let mut stack: Vec<(PathBuf, usize)> = vec![(path.to_path_buf(), 0)];
-        let mut visited = std::collections::HashSet::new();
+        let mut candidates = self.disk_candidates(key);
+        self.sort_for_eviction(&mut candidates);
         
-        while let Some((current_path, depth)) = stack.pop() {
-            // Skip if already visited or too deep
-            if visited.contains(&current_path) || depth > max_depth {
-                continue;
-            }
-            visited.insert(current_path.clone());
-            
-            // Stop if we've processed too many files
-            if file_count >= max_files {
-                eprintln!("⚠️  Warning: File limit reached ({}) while calculating directory size", max_files);
+        for candidate in candidates {
+            if usage + needed <= self.disk_limit_bytes {
                 break;
             }
-            
-            match std::fs::read_dir(&current_path) {
-                Ok(entries) => {
-                    for entry in entries {
-                        if file_count >= max_files {
-                            break;
-                        }
-                        
-                        match entry {
-                            Ok(entry) => {
-                                let entry_path = entry.path();
-                                
-                                // Skip symlinks
-                                if let Ok(metadata) = entry_path.symlink_metadata() {
-                                    if metadata.is_symlink() {
-                                        continue;
-                                    }
-                                    
-                                    if metadata.is_file() {
-                                        total += metadata.len() as usize;
-                                        file_count += 1;
-                                    } else if metadata.is_dir() && depth < max_depth {
-                                        stack.push((entry_path, depth + 1));
-                                    }
-                                }
-                            }
-                            Err(_) => {
-                                // Silently skip permission errors
-                                continue;
-                            }
-                        }
-                    }
-                }
-                Err(_) => {
-                    // Silently skip directories we can't read
-                    continue;
-                }
+            self.evict(&candidate.key)?;
+            usage = usage.saturating_sub(candidate.size_bytes);
+        }
+        
+        // What's left is pinned, or the new entry alone exceeds the limit
+        if usage + needed > self.disk_limit_bytes {
+            return Err(FontError::DiskLimitExceeded(
+                (usage + needed) as f64 / (1024.0 * 1024.0),
+                self.disk_limit_bytes / (1024 * 1024)
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Demote entries from memory (they stay on disk) until `needed` more bytes fit
+    fn make_memory_room(&self, key: &str, needed: usize) -> FontResult<()> {
+        let mut memory = self.memory.lock();
+        let replaced = memory.peek(key).map(|e| e.estimated_size_kb * 1024).unwrap_or(0);
+        let mut usage: usize = memory.iter().map(|(_, e)| e.estimated_size_kb * 1024).sum::<usize>() - replaced;
+        
+        if usage + needed <= self.memory_limit_bytes {
+            return Ok(());
+        }
+        
+        let pinned = self.pinned_fonts.read();
+        let mut candidates: Vec<EvictionCandidate> = memory.iter()
+            .filter(|(k, _)| k.as_str() != key && !pinned.contains(*k))
+            .map(|(k, e)| EvictionCandidate {
+                key: k.clone(),
+                size_bytes: e.estimated_size_kb * 1024,
+                last_accessed: e.last_accessed,
+                access_count: e.access_count,
+            })
+            .collect();
+        self.sort_for_eviction(&mut candidates);
+        
+        for candidate in candidates {
+            if usage + needed <= self.memory_limit_bytes {
+                break;
             }
+            memory.pop(&candidate.key);
+            usage = usage.saturating_sub(candidate.size_bytes);
+        }
+        
+        if usage + needed > self.memory_limit_bytes {
+            return Err(FontError::MemoryLimitExceeded(
+                (usage + needed) as f64 / (1024.0 * 1024.0),
+                self.memory_limit_bytes / (1024 * 1024)
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Remove an entry from both tiers and count it as an eviction
+    fn evict(&self, key: &str) -> FontResult<()> {
+        self.memory.lock().pop(key);
+        if self.remove_from_disk(key)? {
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+    
+    /// Merge this session's counters, access counts and index changes into the shared files.
+    /// Other processes' updates made since this cache was opened are kept, not overwritten.
+    pub fn flush(&self) -> FontResult<()> {
+        let _lock = self.lock()?;
+        
+        let mut counters: CacheCounters = read_or_default(&self.disk_path.join(COUNTERS_FILE));
+        counters.hits += self.hits.swap(0, Ordering::Relaxed);
+        counters.misses += self.misses.swap(0, Ordering::Relaxed);
+        counters.evictions += self.evictions.swap(0, Ordering::Relaxed);
+        write_atomic(&self.disk_path.join(COUNTERS_FILE), &serialize(&counters, "cache counters")?)?;
+        *self.stored_counters.lock() = counters;
+        
+        self.merge_access_counts()?;
+        self.merge_index()
+    }
+    
+    fn lock(&self) -> FontResult<CacheLock> {
+        CacheLock::acquire(&self.disk_path)
+    }
+    
+    /// Apply a pin change to the shared pinned set; the caller must not hold the cache lock
+    fn update_pinned(&self, key: &str, pinned: bool) -> FontResult<()> {
+        let _lock = self.lock()?;
+        let path = self.disk_path.join(PINNED_FILE);
+        let mut shared: HashSet<String> = read_or_default(&path);
+        if pinned {
+            shared.insert(key.to_string());
+        } else {
+            shared.remove(key);
+        }
+        write_atomic(&path, &serialize(&shared, "pinned fonts")?)?;
+        *self.pinned_fonts.write() = shared;
+        Ok(())
+    }
+    
+    /// Set or clear a negative entry in the shared set, dropping expired ones on the way
+    fn update_not_found(&self, key: &str, expires: Option<SystemTime>) -> FontResult<()> {
+        let _lock = self.lock()?;
+        let path = self.disk_path.join(NOT_FOUND_FILE);
+        let now = SystemTime::now();
+        
+        let mut shared: HashMap<String, SystemTime> = read_or_default(&path);
+        shared.retain(|_, expiry| *expiry > now);
+        match expires {
+            Some(expiry) => shared.insert(key.to_string(), expiry),
+            None => shared.remove(key),
+        };
+        write_atomic(&path, &serialize(&shared, "not-found entries")?)?;
+        *self.not_found.write() = shared;
+        Ok(())
+    }
+    
+    /// Add pending access count increments to the shared counts; requires the cache lock
+    fn merge_access_counts(&self) -> FontResult<()> {
+        let deltas = std::mem::take(&mut *self.access_deltas.lock());
+        let removed = std::mem::take(&mut *self.removed_access.lock());
+        
+        let path = self.disk_path.join(ACCESS_COUNTS_FILE);
+        let mut shared: HashMap<String, u32> = read_or_default(&path);
+        for key in &removed {
+            shared.remove(key);
+        }
+        for (key, delta) in deltas {
+            *shared.entry(key).or_insert(0) += delta;
+        }
+        write_atomic(&path, &serialize(&shared, "access counts")?)?;
+        *self.access_counts.write() = shared;
+        Ok(())
+    }
+    
+    /// Apply pending index changes on top of the shared index; requires the cache lock
+    fn merge_index(&self) -> FontResult<()> {
+        let changes = std::mem::take(&mut *self.index_changes.lock());
+        if changes.is_empty() {
+            return Ok(());
         }
         
-        Ok(total)
+        let mut shared = Self::read_index(&self.disk_path, &self.entries_path);
+        for (key, change) in changes {
+            match change {
+                Some(entry) => shared.insert(key, entry),
+                None => shared.remove(&key),
+            };
+        }
+        write_atomic(&self.disk_path.join(INDEX_FILE), &serialize(&shared, "cache index")?)?;
+        *self.index.write() = shared;
+        Ok(())
     }
     
     fn estimate_font_size_kb(&self, font: &FontDescriptor) -> usize {
@@ -432,95 +1068,166 @@ impl HybridFontCache {
         base_size + variant_penalty + metrics_penalty
     }
     
-    fn load_from_disk(&self, font_name: &str) -> Option<CacheEntry> {
-        let path = self.disk_path.join(format!("{}.bin", font_name));
+    /// Load the shared index, writing it back if it had to be rebuilt; requires the cache lock
+    fn load_index(disk_path: &Path, entries_path: &Path) -> FontResult<HashMap<String, IndexEntry>> {
+        let index_path = disk_path.join(INDEX_FILE);
+        if let Ok(data) = std::fs::read(&index_path) {
+            match bincode::deserialize(&data) {
+                Ok(index) => return Ok(index),
+                Err(e) => eprintln!("⚠️  Cache index is corrupted ({}), rebuilding...", e),
+            }
+        }
+        
+        let index = Self::rebuild_index(entries_path);
+        write_atomic(&index_path, &serialize(&index, "cache index")?)?;
+        Ok(index)
+    }
+    
+    fn read_index(disk_path: &Path, entries_path: &Path) -> HashMap<String, IndexEntry> {
+        std::fs::read(disk_path.join(INDEX_FILE))
+            .ok()
+            .and_then(|data| bincode::deserialize(&data).ok())
+            .unwrap_or_else(|| Self::rebuild_index(entries_path))
+    }
+    
+    /// Rebuild the index by reading every entry file, dropping unreadable ones
+    fn rebuild_index(entries_path: &Path) -> HashMap<String, IndexEntry> {
+        let mut index = HashMap::new();
+        let Ok(files) = std::fs::read_dir(entries_path) else {
+            return index;
+        };
+        
+        for file in files.flatten() {
+            let path = file.path();
+            let file_name = file.file_name().to_string_lossy().to_string();
+            
+            // Temporary files may belong to a write still in progress in another process
+            if file_name.starts_with('.') {
+                continue;
+            }
+            
+            // The key is only known after decoding, so check the file name against it
+            let entry = std::fs::read(&path).ok().and_then(|data| {
+                CacheEntry::decode_unchecked_key(&data).ok()
+                    .filter(|entry| entry_file_name(&entry.key) == file_name)
+                    .map(|entry| (entry, data.len() as u64))
+            });
+            
+            match entry {
+                Some((entry, size_bytes)) => {
+                    index.insert(entry.key.clone(), IndexEntry {
+                        font_name: entry.key.clone(),
+                        file_name,
+                        size_bytes,
+                        created_at: entry.created_at,
+                        last_accessed: entry.last_accessed,
+                    });
+                }
+                None => {
+                    eprintln!("⚠️  Dropping unreadable cache file: {}", path.display());
+                    let _ = std::fs::remove_file(&path);
+                }
+            }
+        }
+        
+        index
+    }
+    
+    fn save_index(&self) -> FontResult<()> {
+        let _lock = self.lock()?;
+        self.merge_index()
+    }
+    
+    fn load_from_disk(&self, key: &str) -> Option<CacheEntry> {
+        // Entries written by other processes are not in our index snapshot yet
+        let file_name = self.index.read().get(key)
+            .map(|e| e.file_name.clone())
+            .unwrap_or_else(|| entry_file_name(key));
+        let path = self.entries_path.join(file_name);
         if !path.exists() {
             return None;
         }
         
-        match std::fs::read(&path) {
-            Ok(data) => bincode::deserialize(&data).ok(),
-            Err(_) => None,
+        let result = std::fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| CacheEntry::decode(&data, key));
+        
+        match result {
+            Ok(entry) => Some(entry),
+            Err(reason) => {
+                eprintln!("⚠️  Dropping corrupted cache entry '{}': {}", key, reason);
+                let _ = self.remove_from_disk(key);
+                None
+            }
         }
     }
     
-    fn save_to_disk(&self, font_name: &str, entry: &CacheEntry) -> FontResult<()> {
-        let path = self.disk_path.join(format!("{}.bin", font_name));
-        let data = bincode::serialize(entry)
-            .map_err(|e| FontError::Parse(format!("Failed to serialize cache entry: {}", e)))?;
-        std::fs::write(path, data)?;
-        Ok(())
+    fn write_entry(&self, key: &str, font_name: &str, entry: &CacheEntry, data: &[u8]) -> FontResult<()> {
+        let file_name = entry_file_name(key);
+        write_atomic(&self.entries_path.join(&file_name), data)?;
+        
+        let index_entry = IndexEntry {
+            font_name: font_name.to_string(),
+            file_name,
+            size_bytes: data.len() as u64,
+            created_at: entry.created_at,
+            last_accessed: entry.last_accessed,
+        };
+        self.index.write().insert(key.to_string(), index_entry.clone());
+        self.index_changes.lock().insert(key.to_string(), Some(index_entry));
+        self.save_index()
     }
     
+    /// Delete an entry file and its index record; returns whether anything was removed
+    fn remove_from_disk(&self, key: &str) -> FontResult<bool> {
+        let removed = self.index.write().remove(key);
+        let path = self.entries_path.join(entry_file_name(key));
+        
+        // Another process may delete the same file between the check and the removal
+        let file_existed = match std::fs::remove_file(path) {
+            Ok(()) => true,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+            Err(e) => return Err(e.into()),
+        };
+        if removed.is_some() || file_existed {
+            self.index_changes.lock().insert(key.to_string(), None);
+            self.save_index()?;
+        }
+        
+        Ok(removed.is_some() || file_existed)
+    }
+    
+    /// Remove unpinned entries by their recorded use, not their files' modification times:
+    /// idle for 30 days, or with `aggressive` idle for 7 days or used only once
     fn cleanup_disk(&self, aggressive: bool) -> FontResult<usize> {
+        let now = SystemTime::now();
         let mut removed = 0;
-        let entries = std::fs::read_dir(&self.disk_path)?;
         
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
+        for candidate in self.disk_candidates("") {
+            let idle = candidate.idle_time(now);
+            let should_remove = if aggressive {
+                candidate.access_count <= 1 || idle > Duration::from_secs(7 * 24 * 60 * 60)
+            } else {
+                idle > Duration::from_secs(30 * 24 * 60 * 60)
+            };
             
-            if path.extension().map(|e| e == "bin").unwrap_or(false) {
-                if let Some(file_name) = path.file_stem() {
-                    let font_name = file_name.to_string_lossy().to_string();
-                    
-                    // Skip pinned fonts
-                    if self.is_pinned(&font_name) {
-                        continue;
-                    }
-                    
-                    // Check if file is old
-                    let metadata = std::fs::metadata(&path)?;
-                    let modified = metadata.modified()?;
-                    let age = SystemTime::now().duration_since(modified).unwrap_or_default();
-                    
-                    let should_remove = if aggressive {
-                        age > Duration::from_secs(7 * 24 * 60 * 60) // 7 days for aggressive
-                    } else {
-                        age > Duration::from_secs(30 * 24 * 60 * 60) // 30 days for normal
-                    };
-                    
-                    if should_remove {
-                        std::fs::remove_file(path)?;
-                        removed += 1;
-                    }
-                }
+            if should_remove && self.remove_from_disk(&candidate.key)? {
+                removed += 1;
             }
         }
         
-        Ok(removed)
-    }
-    
-    #[allow(dead_code)]
-    fn count_disk_entries(&self) -> usize {
-        match std::fs::read_dir(&self.disk_path) {
-            Ok(entries) => {
-                // Very aggressive limit to prevent hanging
-                // Count quickly and stop early if there are many files
-                let mut count = 0;
-                let max_to_count = 1000; // Only count first 1000 files
-                
-                for entry in entries.take(max_to_count + 1) {
-                    match entry {
-                        Ok(entry) => {
-                            if entry.path()
-                                .extension()
-                                .map(|ext| ext == "bin")
-                                .unwrap_or(false)
-                            {
-                                count += 1;
-                            }
-                        }
-                        Err(_) => continue,
-                    }
-                }
-                
-                // If we hit the limit, add "1000+" indicator would be shown in stats
-                // For now, just return the count we got
-                count
+        // Missing files are dropped from the index regardless of use
+        let missing: Vec<String> = self.index.read().iter()
+            .filter(|(_, entry)| !self.entries_path.join(&entry.file_name).exists())
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in missing {
+            if self.remove_from_disk(&key)? {
+                removed += 1;
             }
-            Err(_) => 0,
         }
+        
+        Ok(removed)
     }
     
     // REDUNDANT - Integrated into pin/unpin to avoid deadlocks
@@ -533,13 +1240,400 @@ impl HybridFontCache {
         }
     }
     */
-    
-    fn save_access_counts(&self) -> FontResult<()> {
-        let access_path = self.disk_path.join("access_counts.bin");
-        let access_counts = self.access_counts.read();
-        let data = bincode::serialize(&*access_counts)
-            .map_err(|e| FontError::Parse(format!("Failed to serialize access counts: {}", e)))?;
-        std::fs::write(access_path, data)?;
-        Ok(())
+}
+
+impl Drop for HybridFontCache {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            eprintln!("⚠️  Failed to save cache state: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use font_core::FontFormat;
+
+    fn font(family: &str, path: &Path) -> FontDescriptor {
+        FontDescriptor {
+            family: family.to_string(),
+            subfamily: None,
+            postscript_name: family.replace(' ', ""),
+            full_name: None,
+            path: path.to_path_buf(),
+            format: FontFormat::Ttf,
+            weight: 400,
+            italic: false,
+            monospaced: false,
+            variable: false,
+            metrics: None,
+            license: None,
+            classification: None,
+        }
+    }
+
+    fn open(dir: &Path) -> HybridFontCache {
+        HybridFontCache::with_dir(dir, 10, 10, 0).unwrap()
+    }
+
+    #[test]
+    fn test_instances_sharing_a_directory_merge_on_flush() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = open(dir.path());
+        let second = open(dir.path());
+
+        first.put("Alpha", font("Alpha", Path::new("/nonexistent/alpha.ttf"))).unwrap();
+        second.put("Beta", font("Beta", Path::new("/nonexistent/beta.ttf"))).unwrap();
+
+        assert!(first.get("Alpha").is_some());
+        assert!(first.get("Alpha").is_some());
+        assert!(second.get("alpha").is_some());
+        assert!(second.get("Beta").is_some());
+        assert!(second.get("Gamma").is_none());
+
+        first.flush().unwrap();
+        second.flush().unwrap();
+
+        let merged = open(dir.path());
+        let stats = merged.stats().unwrap();
+        assert_eq!(stats.disk_entries, 2);
+        assert_eq!(stats.hits, 4);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(merged.access_counts.read().get("alpha"), Some(&3));
+        assert_eq!(merged.access_counts.read().get("beta"), Some(&1));
+        assert!(merged.index.read().contains_key("alpha"));
+        assert!(merged.index.read().contains_key("beta"));
+    }
+
+    #[test]
+    fn test_entries_are_stored_under_hashed_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = open(dir.path());
+        cache.put("  Open   Sans ", font("Open Sans", Path::new("/nonexistent/a.ttf"))).unwrap();
+
+        let file_name = entry_file_name("open sans");
+        let hex = file_name.strip_suffix(".bin").unwrap();
+        assert_eq!(hex.len(), 32);
+        assert!(hex.chars().all(|c| c.is_ascii_hexdigit()));
+        assert!(dir.path().join(ENTRIES_DIR).join(&file_name).is_file());
+        assert_eq!(cache.index.read()["open sans"].file_name, file_name);
+        assert_eq!(cache.index.read()["open sans"].font_name, "  Open   Sans ");
+
+        // Any spelling normalizing to the same key finds the entry
+        assert_eq!(cache.get("OPEN sans").unwrap().family, "Open Sans");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_entry_has_magic_and_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = open(dir.path());
+        cache.put("Alpha", font("Alpha", Path::new("/nonexistent/a.ttf"))).unwrap();
+
+        let data = std::fs::read(dir.path().join(ENTRIES_DIR).join(entry_file_name("alpha"))).unwrap();
+        assert_eq!(&data[..4], ENTRY_MAGIC);
+        let (checksum, payload) = data[4..].split_at(CHECKSUM_LEN);
+        assert_eq!(Sha256::digest(payload).as_slice(), checksum);
+        assert_eq!(CacheEntry::decode(&data, "alpha").unwrap().font.family, "Alpha");
+
+        // Entries found under another key's file are rejected
+        assert!(CacheEntry::decode(&data, "beta").is_err());
+
+        let mut old_magic = data.clone();
+        old_magic[..4].copy_from_slice(b"FCE6");
+        assert!(CacheEntry::decode(&old_magic, "alpha").is_err());
+    }
+
+    #[test]
+    fn test_pinned_entries_are_listed_by_request() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = open(dir.path());
+        let bold = CachedRequest { family: "Open Sans".to_string(), weight: 700, italic: true, width: 3 };
+        cache.put_request("open-sans:700:italic:3@System[system]", bold.clone(), font("Open Sans", Path::new("/nonexistent/os.ttf"))).unwrap();
+        cache.put("Plain Name", font("Plain Name", Path::new("/nonexistent/plain.ttf"))).unwrap();
+        cache.pin_font("open-sans:700:italic:3@System[system]");
+        cache.pin_font("Plain Name");
+        drop(cache);
+
+        // Requests are read back from the entry files
+        let reopened = open(dir.path());
+        assert_eq!(reopened.request_for("open-sans:700:italic:3@system[system]"), Some(bold));
+        assert_eq!(reopened.list_pinned(), ["Open Sans (700, italic, width 3)", "plain name"]);
+        assert_eq!(reopened.keys_matching(|r| r.family == "Open Sans"), ["open-sans:700:italic:3@system[system]"]);
+        assert!(reopened.contains_key("PLAIN name"));
+        assert!(!reopened.contains_key("Open Sans"));
+    }
+
+    #[test]
+    fn test_corrupt_entry_is_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = open(dir.path());
+        cache.put("Alpha", font("Alpha", Path::new("/nonexistent/a.ttf"))).unwrap();
+        cache.memory.lock().clear();
+
+        let path = dir.path().join(ENTRIES_DIR).join(entry_file_name("alpha"));
+        let mut data = std::fs::read(&path).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 0xFF;
+        std::fs::write(&path, &data).unwrap();
+
+        assert!(matches!(cache.lookup("Alpha"), CacheLookup::Miss));
+        assert!(!path.exists());
+        assert!(!cache.index.read().contains_key("alpha"));
+    }
+
+    #[test]
+    fn test_index_rebuild_drops_corrupt_files() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let cache = open(dir.path());
+            cache.put("Alpha", font("Alpha", Path::new("/nonexistent/a.ttf"))).unwrap();
+            cache.put("Beta", font("Beta", Path::new("/nonexistent/b.ttf"))).unwrap();
+        }
+        let entries = dir.path().join(ENTRIES_DIR);
+        std::fs::write(entries.join(entry_file_name("beta")), b"FCE7 truncated").unwrap();
+        std::fs::write(dir.path().join(INDEX_FILE), b"not an index").unwrap();
+
+        let cache = open(dir.path());
+        assert_eq!(cache.index.read().keys().collect::<Vec<_>>(), vec!["alpha"]);
+        assert!(!entries.join(entry_file_name("beta")).exists());
+        assert!(cache.get("Alpha").is_some());
+    }
+
+    fn candidate(key: &str, size_bytes: usize, idle_days: u64, access_count: u32) -> EvictionCandidate {
+        EvictionCandidate {
+            key: key.to_string(),
+            size_bytes,
+            last_accessed: SystemTime::now() - Duration::from_secs(idle_days * 86_400),
+            access_count,
+        }
+    }
+
+    fn eviction_order(mode: CacheCleanupMode, candidates: Vec<EvictionCandidate>) -> Vec<String> {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = open(dir.path()).with_cleanup_mode(mode);
+        let mut candidates = candidates;
+        cache.sort_for_eviction(&mut candidates);
+        candidates.into_iter().map(|c| c.key).collect()
+    }
+
+    #[test]
+    fn test_eviction_order_per_mode() {
+        let candidates = || vec![
+            candidate("recent-small", 1_000, 1, 1),
+            candidate("old-popular", 4_000, 20, 50),
+            candidate("oldest-once", 2_000, 25, 1),
+            candidate("large", 64_000, 5, 3),
+        ];
+
+        // Least recently used first
+        let lru = ["oldest-once", "old-popular", "large", "recent-small"];
+        assert_eq!(eviction_order(CacheCleanupMode::Manual, candidates()), lru);
+        assert_eq!(eviction_order(CacheCleanupMode::TimeBased, candidates()), lru);
+
+        assert_eq!(
+            eviction_order(CacheCleanupMode::SizeBased, candidates()),
+            ["large", "old-popular", "oldest-once", "recent-small"]
+        );
+
+        // Smart weighs use against idleness and size, so a popular old entry outlives a large one
+        assert_eq!(
+            eviction_order(CacheCleanupMode::Smart, candidates()),
+            ["oldest-once", "large", "recent-small", "old-popular"]
+        );
+    }
+
+    /// Backdate an entry's last access in the index
+    fn backdate(cache: &HybridFontCache, key: &str, idle: Duration) {
+        cache.index.write().get_mut(key).unwrap().last_accessed = SystemTime::now() - idle;
+    }
+
+    #[test]
+    fn test_evict_expired_removes_idle_entries() {
+        let idle = MAX_IDLE_AGE + Duration::from_secs(86_400);
+        for (mode, expected) in [
+            (CacheCleanupMode::TimeBased, vec!["fresh", "pinned"]),
+            // Smart keeps idle entries that were used more than once
+            (CacheCleanupMode::Smart, vec!["fresh", "pinned", "popular"]),
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            let cache = open(dir.path()).with_cleanup_mode(mode.clone());
+            for name in ["fresh", "stale", "pinned", "popular"] {
+                cache.put(name, font(name, Path::new("/nonexistent/font.ttf"))).unwrap();
+            }
+            cache.pin_font("pinned");
+            cache.access_counts.write().insert("popular".to_string(), 5);
+            for name in ["stale", "pinned", "popular"] {
+                backdate(&cache, name, idle);
+            }
+            backdate(&cache, "fresh", MAX_IDLE_AGE - Duration::from_secs(86_400));
+
+            cache.evict_expired().unwrap();
+            let mut remaining: Vec<String> = cache.index.read().keys().cloned().collect();
+            remaining.sort();
+            assert_eq!(remaining, expected, "{}", mode);
+            assert_eq!(cache.counters().evictions, 4 - expected.len() as u64);
+        }
+    }
+
+    #[test]
+    fn test_put_evicts_to_fit_disk_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = open(dir.path()).with_cleanup_mode(CacheCleanupMode::TimeBased);
+        cache.put("font-a", font("font-a", Path::new("/nonexistent/a.ttf"))).unwrap();
+        let entry_size = cache.index.read()["font-a"].size_bytes as usize;
+        // Room for two entries, with slack for size differences between them
+        cache.disk_limit_bytes = entry_size * 2 + entry_size / 2;
+
+        cache.put("font-b", font("font-b", Path::new("/nonexistent/b.ttf"))).unwrap();
+        backdate(&cache, "font-a", Duration::from_secs(60));
+        backdate(&cache, "font-b", Duration::from_secs(120));
+
+        cache.put("font-c", font("font-c", Path::new("/nonexistent/c.ttf"))).unwrap();
+        let mut remaining: Vec<String> = cache.index.read().keys().cloned().collect();
+        remaining.sort();
+        assert_eq!(remaining, ["font-a", "font-c"]);
+        assert!(!dir.path().join(ENTRIES_DIR).join(entry_file_name("font-b")).exists());
+        assert_eq!(cache.counters().evictions, 1);
+
+        // Replacing an entry reuses its space instead of evicting another
+        cache.put("font-c", font("font-c", Path::new("/nonexistent/c.ttf"))).unwrap();
+        assert_eq!(cache.index.read().len(), 2);
+
+        // Manual mode evicts on put as well, it only skips idle expiry
+        cache.cleanup_mode = CacheCleanupMode::Manual;
+        cache.put("font-d", font("font-d", Path::new("/nonexistent/d.ttf"))).unwrap();
+        let mut remaining: Vec<String> = cache.index.read().keys().cloned().collect();
+        remaining.sort();
+        assert_eq!(remaining, ["font-c", "font-d"]);
+        assert_eq!(cache.counters().evictions, 2);
+
+        // Pinned entries are never evicted, so a put that can't fit fails
+        cache.pin_font("font-c");
+        cache.pin_font("font-d");
+        let result = cache.put("font-e", font("font-e", Path::new("/nonexistent/e.ttf")));
+        assert!(matches!(result, Err(FontError::DiskLimitExceeded(..))));
+        assert_eq!(cache.index.read().len(), 2);
+    }
+
+    #[test]
+    fn test_cleanup_ages_entries_by_use() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = open(dir.path());
+        for name in ["popular", "idle", "once"] {
+            cache.put(name, font(name, Path::new("/nonexistent/font.ttf"))).unwrap();
+        }
+
+        // Written long ago but read constantly: the entry file's age doesn't matter
+        let written = SystemTime::now() - Duration::from_secs(60 * 86_400);
+        let popular_file = dir.path().join(ENTRIES_DIR).join(entry_file_name("popular"));
+        File::options().write(true).open(popular_file).unwrap().set_modified(written).unwrap();
+        cache.access_counts.write().insert("popular".to_string(), 10);
+        cache.access_counts.write().insert("idle".to_string(), 10);
+        backdate(&cache, "idle", Duration::from_secs(40 * 86_400));
+
+        assert_eq!(cache.cleanup_disk(false).unwrap(), 1);
+        let mut remaining: Vec<String> = cache.index.read().keys().cloned().collect();
+        remaining.sort();
+        assert_eq!(remaining, ["once", "popular"]);
+
+        // Aggressive cleanup also drops entries used only once
+        assert_eq!(cache.cleanup_disk(true).unwrap(), 1);
+        assert_eq!(cache.index.read().keys().collect::<Vec<_>>(), ["popular"]);
+    }
+
+    #[test]
+    fn test_changed_font_file_makes_entry_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let font_path = dir.path().join("alpha.ttf");
+        std::fs::write(&font_path, b"original outlines").unwrap();
+        let cache = open(&dir.path().join("cache"));
+        cache.put("Alpha", font("Alpha", &font_path)).unwrap();
+
+        // A touched file with unchanged contents is still valid
+        let touched = SystemTime::now() + Duration::from_secs(3600);
+        File::options().write(true).open(&font_path).unwrap().set_modified(touched).unwrap();
+        assert!(matches!(cache.lookup("Alpha"), CacheLookup::Hit(_)));
+
+        // Same size, different contents
+        std::fs::write(&font_path, b"replaced outlines").unwrap();
+        match cache.lookup("Alpha") {
+            CacheLookup::Stale { font, status } => {
+                assert_eq!(font.family, "Alpha");
+                assert_eq!(status, EntryStatus::Modified);
+            }
+            other => panic!("expected a stale entry, got {:?}", other),
+        }
+        // The stale entry was dropped
+        assert!(matches!(cache.lookup("Alpha"), CacheLookup::Miss));
+
+        cache.put("Alpha", font("Alpha", &font_path)).unwrap();
+        std::fs::write(&font_path, b"a longer replacement").unwrap();
+        assert!(matches!(cache.lookup("Alpha"), CacheLookup::Stale { status: EntryStatus::Modified, .. }));
+    }
+
+    #[test]
+    fn test_verify_reports_and_purges_dangling_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("kept.ttf");
+        let deleted = dir.path().join("deleted.ttf");
+        let replaced = dir.path().join("replaced.ttf");
+        for path in [&kept, &deleted, &replaced] {
+            std::fs::write(path, b"outlines").unwrap();
+        }
+        let cache = open(&dir.path().join("cache"));
+        cache.put("Kept", font("Kept", &kept)).unwrap();
+        cache.put("Deleted", font("Deleted", &deleted)).unwrap();
+        cache.put("Replaced", font("Replaced", &replaced)).unwrap();
+        cache.put("Web", font("Web", Path::new("https://example.com/web.woff2"))).unwrap();
+
+        std::fs::remove_file(&deleted).unwrap();
+        std::fs::write(&replaced, b"new outlines").unwrap();
+
+        let report = cache.verify(false).unwrap();
+        assert_eq!(report.checked, 4);
+        assert_eq!(report.unverified, 1);
+        assert_eq!(report.removed, 0);
+        let mut dangling: Vec<(String, EntryStatus)> = report.dangling.iter()
+            .map(|d| (d.font_name.clone(), d.status))
+            .collect();
+        dangling.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(dangling, [
+            ("Deleted".to_string(), EntryStatus::Missing),
+            ("Replaced".to_string(), EntryStatus::Modified),
+        ]);
+        assert_eq!(cache.index.read().len(), 4);
+
+        let report = cache.verify(true).unwrap();
+        assert_eq!(report.removed, 2);
+        let mut remaining: Vec<String> = cache.index.read().keys().cloned().collect();
+        remaining.sort();
+        assert_eq!(remaining, ["kept", "web"]);
+        assert!(cache.verify(true).unwrap().dangling.is_empty());
+    }
+
+    #[test]
+    fn test_not_found_entries_expire() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = open(dir.path());
+        cache.put_not_found("Missing Sans", Duration::from_secs(3600)).unwrap();
+        cache.update_not_found("expired sans", Some(SystemTime::now() - Duration::from_secs(1))).unwrap();
+
+        assert!(matches!(cache.lookup("missing  sans"), CacheLookup::NotFound));
+        assert!(matches!(cache.lookup("Expired Sans"), CacheLookup::Miss));
+        // A cached not-found result yields no font, so it doesn't count as a hit
+        assert_eq!((cache.counters().hits, cache.counters().misses), (0, 2));
+
+        // Negative entries are shared through the cache directory; expired ones are pruned on write
+        cache.put_not_found("Other Sans", Duration::ZERO).unwrap();
+        let reopened = open(dir.path());
+        assert!(matches!(reopened.lookup("Missing Sans"), CacheLookup::NotFound));
+        assert!(!reopened.not_found.read().contains_key("expired sans"));
+        assert!(matches!(reopened.lookup("Other Sans"), CacheLookup::Miss));
+
+        // Storing the font clears its negative entry
+        reopened.put("Missing Sans", font("Missing Sans", Path::new("/nonexistent/m.ttf"))).unwrap();
+        assert!(matches!(reopened.lookup("Missing Sans"), CacheLookup::Hit(_)));
+        assert!(open(dir.path()).not_found.read().get("missing sans").is_none());
+    }
+}
@@ -1,10 +1,19 @@
 use std::collections::{HashMap, HashSet};
 use serde::{Serialize, Deserialize};
-use font_core::{FontDescriptor, FontFormat};
+use font_core::{FontClassification, FontDescriptor, FontFormat, ShapeClassification, TypefaceStyle};
+use font_index::FontIndex;
 
 // Add Write trait import
 use std::io::{Write, Cursor};
 
+/// Prefix of databases that carry a format version; older files are bare brotli streams
+pub const MAGIC_BYTES: &[u8; 8] = b"FONTCDB1";
+
+/// Layout version of `CompressedFontDatabase`, stored first in its metadata. Version 1
+/// is the unversioned layout that predates the field. bincode ignores `#[serde(default)]`,
+/// so any change to the stored fields needs a bump and an upgrade path.
+pub const FORMAT_VERSION: u32 = 2;
+
 // Fix FontCategory to derive Eq and Hash
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum FontCategory {
@@ -32,6 +41,9 @@ pub struct CompressedFontData {
     pub download_urls: HashMap<FontFormat, String>,
     pub file_size_kb: u32,
     pub popularity: u8, // 0-100
+    /// PANOSE and sFamilyClass from the font's OS/2 table
+    #[serde(default)]
+    pub classification: Option<FontClassification>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,6 +85,25 @@ impl FontCompressor {
         &self,
         fonts: &[FontDescriptor],
         include_similarity_data: bool,
+    ) -> Result<Vec<u8>, String> {
+        let mut neighbour_index = FontIndex::new();
+        self.compress_database(fonts, include_similarity_data.then_some(&mut neighbour_index))
+    }
+    
+    /// Like `compress_font_database`, adding the fonts to an existing neighbour index
+    /// (e.g. the one from the database being updated) instead of building one from scratch
+    pub fn compress_font_database_with_index(
+        &self,
+        fonts: &[FontDescriptor],
+        neighbour_index: &mut FontIndex,
+    ) -> Result<Vec<u8>, String> {
+        self.compress_database(fonts, Some(neighbour_index))
+    }
+    
+    fn compress_database(
+        &self,
+        fonts: &[FontDescriptor],
+        neighbour_index: Option<&mut FontIndex>,
     ) -> Result<Vec<u8>, String> {
         // 1. First, remove duplicate fonts
         let unique_fonts = self.remove_duplicates(fonts);
@@ -83,15 +114,17 @@ impl FontCompressor {
             .map(|font| self.font_to_compressed(font))
             .collect();
         
-        // 3. Build similarity matrix BEFORE creating database
-        let similarity_matrix = if include_similarity_data {
-            Some(self.build_similarity_matrix(&compressed_fonts))
-        } else {
-            None
-        };
+        // 3. Index the fonts BEFORE creating database; fonts already indexed get their features refreshed
+        let neighbour_index = neighbour_index.map(|index| {
+            for font in &unique_fonts {
+                index.insert_font(font);
+            }
+            index.clone()
+        });
         
         // 4. Create metadata (with placeholder for compressed_size_bytes)
         let metadata = FontDatabaseMetadata {
+            format_version: FORMAT_VERSION,
             version: env!("CARGO_PKG_VERSION").to_string(),
             font_count: compressed_fonts.len(),
             compressed_size_bytes: 0, // Placeholder - will be updated later
@@ -101,11 +134,11 @@ impl FontCompressor {
             include_full_data: self.include_full_data,
         };
         
-        // 5. Create database with the pre-built similarity matrix
+        // 5. Create database with the pre-built neighbour index
         let mut database = CompressedFontDatabase {
             metadata,
             fonts: compressed_fonts,
-            similarity_matrix,
+            neighbour_index,
         };
         
         // 6. Serialize with bincode
@@ -138,14 +171,14 @@ impl FontCompressor {
         
         // 8. Update the compressed size in the metadata
         // We need to update both the compressed_data metadata and the returned database
-        database.metadata.compressed_size_bytes = compressed_data.len();
+        database.metadata.compressed_size_bytes = MAGIC_BYTES.len() + compressed_data.len();
         
         // Re-serialize with updated metadata
         let final_serialized = bincode::serialize(&database)
             .map_err(|e| format!("Serialization failed: {}", e))?;
         
-        // Recompress with updated metadata
-        let mut final_compressed_data = Vec::new();
+        // Recompress with updated metadata, after the magic bytes
+        let mut final_compressed_data = MAGIC_BYTES.to_vec();
         {
             let mut encoder = brotli::CompressorWriter::new(
                 &mut final_compressed_data,
@@ -194,19 +227,37 @@ impl FontCompressor {
         unique_fonts
     }
     
-    /// Decompress font database
+    /// Decompress font database, upgrading databases written in an older layout
     pub fn decompress_font_database(
         &self,
         compressed_data: &[u8],
     ) -> Result<CompressedFontDatabase, String> {
+        let (versioned, compressed_data) = match compressed_data.strip_prefix(MAGIC_BYTES) {
+            Some(rest) => (true, rest),
+            None => (false, compressed_data),
+        };
+        
         let mut decompressed = Vec::new();
         let mut decoder = brotli::Decompressor::new(compressed_data, 4096);
         
         std::io::copy(&mut decoder, &mut decompressed)
             .map_err(|e| format!("Decompression failed: {}", e))?;
         
-        bincode::deserialize(&decompressed)
-            .map_err(|e| format!("Deserialization failed: {}", e))
+        if !versioned {
+            return bincode::deserialize::<FontDatabaseV1>(&decompressed)
+                .map(CompressedFontDatabase::from)
+                .map_err(|e| format!("Deserialization failed: {}", e));
+        }
+        
+        // The metadata comes first, and its format version first within it
+        let version = decompressed.get(0..4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().expect("four bytes")))
+            .ok_or_else(|| "Truncated database header".to_string())?;
+        match version {
+            FORMAT_VERSION => bincode::deserialize(&decompressed)
+                .map_err(|e| format!("Deserialization failed: {}", e)),
+            _ => Err(format!("Unsupported database format version {}", version)),
+        }
     }
     
     /// Smart compression: Only include full data for top 1000 fonts - HANDLES DUPLICATES
@@ -301,6 +352,7 @@ impl FontCompressor {
             download_urls: HashMap::new(),
             file_size_kb: self.estimate_file_size(font),
             popularity: 50,
+            classification: font.classification,
         }
     }
     
@@ -309,6 +361,8 @@ impl FontCompressor {
         
         if font.monospaced {
             FontCategory::Monospace
+        } else if let Some(category) = font.classification.as_ref().and_then(category_from_classification) {
+            category
         } else if family_lower.contains("serif") || 
                   family_lower.contains("times") || 
                   family_lower.contains("garamond") ||
@@ -356,89 +410,64 @@ impl FontCompressor {
         }
         categories
     }
+}
+
+/// Category from the style measured on the font's outlines when that is confident,
+/// otherwise as declared by its PANOSE digits, or its sFamilyClass when PANOSE is unset
+pub fn category_from_classification(classification: &FontClassification) -> Option<FontCategory> {
+    if let Some(category) = classification.shape.as_ref().and_then(category_from_shape) {
+        return Some(category);
+    }
     
-    fn build_similarity_matrix(&self, fonts: &[CompressedFontData]) -> HashMap<String, Vec<(String, f32)>> {
-        let mut matrix = HashMap::new();
-        
-        for i in 0..fonts.len() {
-            let font1 = &fonts[i];
-            let mut similarities = Vec::new();
-            
-            for j in 0..fonts.len() {
-                if i == j {
-                    continue;
-                }
-                let font2 = &fonts[j];
-                let similarity = self.calculate_font_similarity(font1, font2);
-                if similarity > 0.5 {
-                    similarities.push((font2.family.clone(), similarity));
-                }
-            }
-            
-            // Sort by similarity
-            similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-            
-            // Keep top 10
-            similarities.truncate(10);
-            matrix.insert(font1.family.clone(), similarities);
+    if classification.has_panose() {
+        let panose = &classification.panose;
+        let category = match panose[0] {
+            FontClassification::PANOSE_LATIN_TEXT => match (panose[1], panose[3]) {
+                (_, 9) => Some(FontCategory::Monospace), // bProportion: monospaced
+                (2..=10, _) => Some(FontCategory::Serif),
+                (11..=15, _) => Some(FontCategory::SansSerif),
+                _ => None,
+            },
+            FontClassification::PANOSE_LATIN_HAND_WRITTEN => Some(FontCategory::Handwriting),
+            FontClassification::PANOSE_LATIN_DECORATIVE => Some(FontCategory::Decorative),
+            FontClassification::PANOSE_LATIN_SYMBOL => Some(FontCategory::Symbol),
+            _ => None,
+        };
+        if category.is_some() {
+            return category;
         }
-        
-        matrix
     }
     
-    fn calculate_font_similarity(&self, font1: &CompressedFontData, font2: &CompressedFontData) -> f32 {
-        let mut score = 0.0;
-        
-        // Category match (30%)
-        if font1.category == font2.category {
-            score += 0.3;
-        }
-        
-        // Weight similarity (20%)
-        let weight_diff = (font1.weight as i32 - font2.weight as i32).abs();
-        score += (1.0 - (weight_diff as f32 / 800.0)).max(0.0) * 0.2;
-        
-        // Style match (20%)
-        if font1.italic == font2.italic {
-            score += 0.2;
-        }
-        
-        // Monospace match (10%)
-        if font1.monospaced == font2.monospaced {
-            score += 0.1;
-        }
-        
-        // Name similarity (20%)
-        let name_similarity = self.name_similarity(&font1.family, &font2.family);
-        score += name_similarity * 0.2;
-        
-        score
+    match classification.class_id() {
+        FontClassification::CLASS_OLDSTYLE_SERIFS
+        | FontClassification::CLASS_TRANSITIONAL_SERIFS
+        | FontClassification::CLASS_MODERN_SERIFS
+        | FontClassification::CLASS_CLARENDON_SERIFS
+        | FontClassification::CLASS_SLAB_SERIFS
+        | FontClassification::CLASS_FREEFORM_SERIFS => Some(FontCategory::Serif),
+        FontClassification::CLASS_SANS_SERIF => Some(FontCategory::SansSerif),
+        FontClassification::CLASS_ORNAMENTALS => Some(FontCategory::Decorative),
+        FontClassification::CLASS_SCRIPTS => Some(FontCategory::Handwriting),
+        FontClassification::CLASS_SYMBOLIC => Some(FontCategory::Symbol),
+        _ => None,
+    }
+}
+
+/// Category for a confident outline-based style label
+pub fn category_from_shape(shape: &ShapeClassification) -> Option<FontCategory> {
+    if shape.monospace >= ShapeClassification::CONFIDENT {
+        return Some(FontCategory::Monospace);
     }
     
-    fn name_similarity(&self, s1: &str, s2: &str) -> f32 {
-        let s1_lower = s1.to_lowercase();
-        let s2_lower = s2.to_lowercase();
-        
-        if s1_lower == s2_lower {
-            return 1.0;
-        }
-        
-        if s1_lower.contains(&s2_lower) || s2_lower.contains(&s1_lower) {
-            return 0.8;
-        }
-        
-        // Simple Jaccard similarity
-        let set1: std::collections::HashSet<char> = s1_lower.chars().collect();
-        let set2: std::collections::HashSet<char> = s2_lower.chars().collect();
-        
-        let intersection: usize = set1.intersection(&set2).count();
-        let union: usize = set1.union(&set2).count();
-        
-        if union == 0 {
-            return 0.0;
-        }
-        
-        intersection as f32 / union as f32
+    let (style, confidence) = shape.primary();
+    if confidence < ShapeClassification::CONFIDENT {
+        return None;
+    }
+    match style {
+        TypefaceStyle::Serif | TypefaceStyle::SlabSerif => Some(FontCategory::Serif),
+        TypefaceStyle::Script => Some(FontCategory::Handwriting),
+        TypefaceStyle::Blackletter => Some(FontCategory::Display),
+        _ => Some(FontCategory::SansSerif),
     }
 }
 
@@ -521,6 +550,7 @@ pub fn try_load_simple_database(data: &[u8]) -> Option<CompressedFontDatabase> {
             download_urls: HashMap::new(),
             file_size_kb: 50,
             popularity: 50,
+            classification: None,
         });
     }
     
@@ -530,6 +560,7 @@ pub fn try_load_simple_database(data: &[u8]) -> Option<CompressedFontDatabase> {
     
     // Create database metadata
     let metadata = FontDatabaseMetadata {
+        format_version: FORMAT_VERSION,
         version: "1.0.0-simple".to_string(),
         font_count: fonts.len(),
         compressed_size_bytes: data.len(),
@@ -548,12 +579,15 @@ pub fn try_load_simple_database(data: &[u8]) -> Option<CompressedFontDatabase> {
     Some(CompressedFontDatabase {
         metadata,
         fonts,
-        similarity_matrix: None,
+        neighbour_index: None,
     })
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FontDatabaseMetadata {
+    /// Layout version, see `FORMAT_VERSION`; must stay the first field
+    pub format_version: u32,
+    /// Version of the crate that built the database
     pub version: String,
     pub font_count: usize,
     pub compressed_size_bytes: usize,
@@ -567,5 +601,234 @@ pub struct FontDatabaseMetadata {
 pub struct CompressedFontDatabase {
     pub metadata: FontDatabaseMetadata,
     pub fonts: Vec<CompressedFontData>,
-    pub similarity_matrix: Option<HashMap<String, Vec<(String, f32)>>>,
-}
\ No newline at end of file
+    /// Nearest-neighbour index over the fonts' features, for similarity queries
+    pub neighbour_index: Option<FontIndex>,
+}
+
+// =============================================================================
+// LEGACY FORMATS
+// =============================================================================
+
+/// Unversioned layout 1, written before fonts carried a classification and while a
+/// pairwise similarity matrix stood in for the neighbour index
+#[derive(Deserialize)]
+struct FontDatabaseV1 {
+    metadata: FontDatabaseMetadataV1,
+    fonts: Vec<CompressedFontDataV1>,
+    _similarity_matrix: Option<HashMap<String, Vec<(String, f32)>>>,
+}
+
+#[derive(Deserialize)]
+struct FontDatabaseMetadataV1 {
+    version: String,
+    font_count: usize,
+    compressed_size_bytes: usize,
+    original_size_bytes: usize,
+    created_at: String,
+    categories: HashMap<FontCategory, usize>,
+    include_full_data: bool,
+}
+
+#[derive(Deserialize)]
+struct CompressedFontDataV1 {
+    family: String,
+    postscript_name: String,
+    weight: u16,
+    italic: bool,
+    monospaced: bool,
+    metrics: Option<CompressedMetrics>,
+    license: CompressedLicense,
+    category: FontCategory,
+    similar_fonts: Vec<String>,
+    download_urls: HashMap<FontFormat, String>,
+    file_size_kb: u32,
+    popularity: u8,
+}
+
+/// The matrix can't seed the neighbour index, which needs the fonts' features;
+/// rebuilding the database (e.g. through the updater) restores it
+impl From<FontDatabaseV1> for CompressedFontDatabase {
+    fn from(database: FontDatabaseV1) -> Self {
+        let metadata = database.metadata;
+        CompressedFontDatabase {
+            metadata: FontDatabaseMetadata {
+                format_version: FORMAT_VERSION,
+                version: metadata.version,
+                font_count: metadata.font_count,
+                compressed_size_bytes: metadata.compressed_size_bytes,
+                original_size_bytes: metadata.original_size_bytes,
+                created_at: metadata.created_at,
+                categories: metadata.categories,
+                include_full_data: metadata.include_full_data,
+            },
+            fonts: database.fonts.into_iter().map(|font| CompressedFontData {
+                family: font.family,
+                postscript_name: font.postscript_name,
+                weight: font.weight,
+                italic: font.italic,
+                monospaced: font.monospaced,
+                metrics: font.metrics,
+                license: font.license,
+                category: font.category,
+                similar_fonts: font.similar_fonts,
+                download_urls: font.download_urls,
+                file_size_kb: font.file_size_kb,
+                popularity: font.popularity,
+                classification: None,
+            }).collect(),
+            neighbour_index: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font(family: &str, classification: Option<FontClassification>) -> FontDescriptor {
+        FontDescriptor {
+            family: family.to_string(),
+            subfamily: None,
+            postscript_name: family.replace(' ', ""),
+            full_name: None,
+            path: std::path::PathBuf::from(format!("/fonts/{}.ttf", family)),
+            format: FontFormat::Ttf,
+            weight: 400,
+            italic: false,
+            monospaced: false,
+            variable: false,
+            metrics: None,
+            license: None,
+            classification,
+        }
+    }
+
+    fn license() -> CompressedLicense {
+        CompressedLicense {
+            name: "OFL".to_string(),
+            url: String::new(),
+            allows_embedding: true,
+            allows_modification: true,
+            requires_attribution: true,
+            allows_commercial_use: true,
+        }
+    }
+
+    /// Metadata as format 1 stored it, without the format version
+    fn legacy_metadata() -> (String, usize, usize, usize, String, HashMap<FontCategory, usize>, bool) {
+        ("0.1.0".to_string(), 1, 0, 51_200, "2026-01-01T00:00:00Z".to_string(), HashMap::new(), true)
+    }
+
+    /// A font as format 1 stored it, without a classification
+    fn legacy_font(family: &str) -> impl Serialize {
+        (
+            (family.to_string(), family.to_string(), 700u16, false, false, None::<CompressedMetrics>),
+            license(),
+            FontCategory::Serif,
+            vec!["Similar".to_string()],
+            HashMap::<FontFormat, String>::new(),
+            50u32,
+            80u8,
+        )
+    }
+
+    fn brotli(data: &[u8]) -> Vec<u8> {
+        let mut output = Vec::new();
+        let mut encoder = brotli::CompressorWriter::new(&mut output, 4096, 5, 22);
+        encoder.write_all(data).unwrap();
+        drop(encoder);
+        output
+    }
+
+    /// The unversioned format was a bare brotli stream
+    fn compress_legacy<T: Serialize>(database: &T) -> Vec<u8> {
+        brotli(&bincode::serialize(database).unwrap())
+    }
+
+    #[test]
+    fn test_roundtrip_current_format() {
+        let classification = FontClassification {
+            panose: [2, 11, 5, 2, 2, 2, 4, 2, 2, 4],
+            family_class: 0x0801,
+            shape: Some(ShapeClassification { humanist: 0.9, sans_serif: 0.8, ..Default::default() }),
+        };
+        let compressor = FontCompressor::new(5, true);
+        let data = compressor
+            .compress_font_database(&[font("New Sans", Some(classification)), font("Other Serif", None)], true)
+            .unwrap();
+        assert!(data.starts_with(MAGIC_BYTES));
+
+        let database = compressor.decompress_font_database(&data).unwrap();
+        assert_eq!(database.metadata.format_version, FORMAT_VERSION);
+        assert_eq!(database.fonts.len(), 2);
+        assert_eq!(database.fonts[0].classification, Some(classification));
+        assert_eq!(database.neighbour_index.map(|index| index.len()), Some(2));
+    }
+
+    #[test]
+    fn test_rejects_unknown_format_version() {
+        let compressor = FontCompressor::new(5, true);
+        let data = compressor.compress_font_database(&[font("New Sans", None)], false).unwrap();
+        let mut decompressed = Vec::new();
+        std::io::copy(&mut brotli::Decompressor::new(&data[MAGIC_BYTES.len()..], 4096), &mut decompressed).unwrap();
+        decompressed[0..4].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+
+        let mut future = MAGIC_BYTES.to_vec();
+        future.extend(brotli(&decompressed));
+        let error = compressor.decompress_font_database(&future).unwrap_err();
+        assert!(error.contains("Unsupported database format version"), "{}", error);
+    }
+
+    #[test]
+    fn test_upgrades_format_1_database() {
+        // Baseline layout: no classification, similarity matrix after the fonts
+        let matrix = HashMap::from([("Old Serif".to_string(), vec![("Other".to_string(), 0.9f32)])]);
+        let data = compress_legacy(&(legacy_metadata(), vec![legacy_font("Old Serif")], Some(matrix)));
+
+        let database = FontCompressor::new(5, true).decompress_font_database(&data).unwrap();
+        assert_eq!(database.metadata.format_version, FORMAT_VERSION);
+        assert_eq!(database.metadata.version, "0.1.0");
+        assert_eq!(database.fonts[0].family, "Old Serif");
+        assert_eq!(database.fonts[0].weight, 700);
+        assert_eq!(database.fonts[0].popularity, 80);
+        assert_eq!(database.fonts[0].similar_fonts, ["Similar"]);
+        assert!(database.fonts[0].license.allows_commercial_use);
+        assert!(database.fonts[0].classification.is_none());
+        assert!(database.neighbour_index.is_none());
+    }
+
+    #[test]
+    fn test_category_from_classification() {
+        let classify = |panose: [u8; 10], family_class: i16, shape: Option<ShapeClassification>| {
+            category_from_classification(&FontClassification { panose, family_class, shape })
+        };
+        let unset = [0; 10];
+
+        // PANOSE Latin text: bSerifStyle 2-10 serifed, 11-15 sans, bProportion 9 monospaced
+        assert_eq!(classify([2, 2, 6, 3, 5, 4, 5, 2, 3, 4], 0, None), Some(FontCategory::Serif));
+        assert_eq!(classify([2, 11, 6, 4, 2, 2, 2, 2, 2, 4], 0, None), Some(FontCategory::SansSerif));
+        assert_eq!(classify([2, 11, 6, 9, 2, 2, 2, 2, 2, 4], 0, None), Some(FontCategory::Monospace));
+        assert_eq!(classify([3, 0, 0, 0, 0, 0, 0, 0, 0, 0], 0, None), Some(FontCategory::Handwriting));
+        assert_eq!(classify([4, 0, 0, 0, 0, 0, 0, 0, 0, 0], 0, None), Some(FontCategory::Decorative));
+        assert_eq!(classify([5, 0, 0, 0, 0, 0, 0, 0, 0, 0], 0, None), Some(FontCategory::Symbol));
+
+        // sFamilyClass when PANOSE is unset or inconclusive
+        assert_eq!(classify(unset, 0x0105, None), Some(FontCategory::Serif));
+        assert_eq!(classify(unset, 0x0501, None), Some(FontCategory::Serif));
+        assert_eq!(classify(unset, 0x0802, None), Some(FontCategory::SansSerif));
+        assert_eq!(classify(unset, 0x0900, None), Some(FontCategory::Decorative));
+        assert_eq!(classify(unset, 0x0A05, None), Some(FontCategory::Handwriting));
+        assert_eq!(classify(unset, 0x0C00, None), Some(FontCategory::Symbol));
+        assert_eq!(classify([2, 1, 0, 0, 0, 0, 0, 0, 0, 0], 0x0802, None), Some(FontCategory::SansSerif));
+        assert_eq!(classify(unset, 0, None), None);
+
+        // A confident outline shape wins over what the font declares; a weak one doesn't
+        let serif = ShapeClassification { serif: 0.9, ..Default::default() };
+        let weak = ShapeClassification { script: 0.4, ..Default::default() };
+        let mono = ShapeClassification { monospace: 0.8, sans_serif: 0.9, ..Default::default() };
+        assert_eq!(classify(unset, 0x0802, Some(serif)), Some(FontCategory::Serif));
+        assert_eq!(classify(unset, 0x0802, Some(weak)), Some(FontCategory::SansSerif));
+        assert_eq!(classify(unset, 0, Some(mono)), Some(FontCategory::Monospace));
+        assert_eq!(classify(unset, 0, Some(weak)), None);
+    }
+}
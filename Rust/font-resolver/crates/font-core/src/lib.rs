@@ -2,6 +2,7 @@
 
 use std::path::PathBuf;
 use std::fmt;
+use std::sync::OnceLock;
 use serde::{Serialize, Deserialize};
 
 /// Represents a font file with all metadata
@@ -42,6 +43,171 @@ pub struct FontDescriptor {
     
     /// License information
     pub license: Option<LicenseInfo>,
+    
+    /// Design classification: OS/2 PANOSE and sFamilyClass, plus the style measured from outlines
+    #[serde(default)]
+    pub classification: Option<FontClassification>,
+}
+
+/// Design classification of a face
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FontClassification {
+    /// PANOSE digits, bFamilyType through bXHeight; 0 means "any" and 1 "no fit"
+    pub panose: [u8; 10],
+    /// sFamilyClass: class ID in the high byte, subclass ID in the low byte
+    pub family_class: i16,
+    /// Style measured from the glyph outlines, independent of what the font declares
+    #[serde(default)]
+    pub shape: Option<ShapeClassification>,
+}
+
+impl FontClassification {
+    /// PANOSE family kinds
+    pub const PANOSE_LATIN_TEXT: u8 = 2;
+    pub const PANOSE_LATIN_HAND_WRITTEN: u8 = 3;
+    pub const PANOSE_LATIN_DECORATIVE: u8 = 4;
+    pub const PANOSE_LATIN_SYMBOL: u8 = 5;
+    
+    /// sFamilyClass class IDs
+    pub const CLASS_OLDSTYLE_SERIFS: u8 = 1;
+    pub const CLASS_TRANSITIONAL_SERIFS: u8 = 2;
+    pub const CLASS_MODERN_SERIFS: u8 = 3;
+    pub const CLASS_CLARENDON_SERIFS: u8 = 4;
+    pub const CLASS_SLAB_SERIFS: u8 = 5;
+    pub const CLASS_FREEFORM_SERIFS: u8 = 7;
+    pub const CLASS_SANS_SERIF: u8 = 8;
+    pub const CLASS_ORNAMENTALS: u8 = 9;
+    pub const CLASS_SCRIPTS: u8 = 10;
+    pub const CLASS_SYMBOLIC: u8 = 12;
+    
+    /// Whether the font sets a PANOSE family kind (many fonts leave all digits at 0)
+    pub fn has_panose(&self) -> bool {
+        self.panose[0] >= Self::PANOSE_LATIN_TEXT
+    }
+    
+    pub fn class_id(&self) -> u8 {
+        (self.family_class >> 8) as u8
+    }
+    
+    pub fn subclass_id(&self) -> u8 {
+        (self.family_class & 0xFF) as u8
+    }
+}
+
+/// Typeface style labels that can be told apart from glyph outlines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TypefaceStyle {
+    Serif,
+    SlabSerif,
+    SansSerif,
+    Geometric,
+    Humanist,
+    Grotesque,
+    Script,
+    Blackletter,
+    Monospace,
+}
+
+impl TypefaceStyle {
+    pub const ALL: [TypefaceStyle; 9] = [
+        TypefaceStyle::Serif,
+        TypefaceStyle::SlabSerif,
+        TypefaceStyle::SansSerif,
+        TypefaceStyle::Geometric,
+        TypefaceStyle::Humanist,
+        TypefaceStyle::Grotesque,
+        TypefaceStyle::Script,
+        TypefaceStyle::Blackletter,
+        TypefaceStyle::Monospace,
+    ];
+}
+
+impl fmt::Display for TypefaceStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypefaceStyle::Serif => write!(f, "serif"),
+            TypefaceStyle::SlabSerif => write!(f, "slab serif"),
+            TypefaceStyle::SansSerif => write!(f, "sans-serif"),
+            TypefaceStyle::Geometric => write!(f, "geometric"),
+            TypefaceStyle::Humanist => write!(f, "humanist"),
+            TypefaceStyle::Grotesque => write!(f, "grotesque"),
+            TypefaceStyle::Script => write!(f, "script"),
+            TypefaceStyle::Blackletter => write!(f, "blackletter"),
+            TypefaceStyle::Monospace => write!(f, "monospace"),
+        }
+    }
+}
+
+/// Style of a face measured from its outlines, with a confidence (0.0 - 1.0) per label.
+/// Serif, sans-serif, script and blackletter are alternatives; slab is a kind of serif,
+/// geometric, humanist and grotesque are kinds of sans-serif, and monospace stands apart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShapeClassification {
+    pub serif: f32,
+    pub slab_serif: f32,
+    pub sans_serif: f32,
+    pub geometric: f32,
+    pub humanist: f32,
+    pub grotesque: f32,
+    pub script: f32,
+    pub blackletter: f32,
+    pub monospace: f32,
+}
+
+impl ShapeClassification {
+    /// Confidence above which a label is trusted over declared or name-based categories
+    pub const CONFIDENT: f32 = 0.6;
+    
+    pub fn confidence(&self, style: TypefaceStyle) -> f32 {
+        match style {
+            TypefaceStyle::Serif => self.serif,
+            TypefaceStyle::SlabSerif => self.slab_serif,
+            TypefaceStyle::SansSerif => self.sans_serif,
+            TypefaceStyle::Geometric => self.geometric,
+            TypefaceStyle::Humanist => self.humanist,
+            TypefaceStyle::Grotesque => self.grotesque,
+            TypefaceStyle::Script => self.script,
+            TypefaceStyle::Blackletter => self.blackletter,
+            TypefaceStyle::Monospace => self.monospace,
+        }
+    }
+    
+    /// Labels with a non-zero confidence, strongest first
+    pub fn labels(&self) -> Vec<(TypefaceStyle, f32)> {
+        let mut labels: Vec<_> = TypefaceStyle::ALL.iter()
+            .map(|&style| (style, self.confidence(style)))
+            .filter(|(_, confidence)| *confidence > 0.0)
+            .collect();
+        labels.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        labels
+    }
+    
+    /// The strongest of serif, sans-serif, script and blackletter
+    pub fn primary(&self) -> (TypefaceStyle, f32) {
+        [
+            (TypefaceStyle::Serif, self.serif),
+            (TypefaceStyle::SansSerif, self.sans_serif),
+            (TypefaceStyle::Script, self.script),
+            (TypefaceStyle::Blackletter, self.blackletter),
+        ]
+        .into_iter()
+        .fold((TypefaceStyle::SansSerif, 0.0), |best, label| if label.1 > best.1 { label } else { best })
+    }
+    
+    /// The strongest sans-serif kind (geometric, humanist or grotesque)
+    pub fn sans_kind(&self) -> Option<(TypefaceStyle, f32)> {
+        [
+            (TypefaceStyle::Geometric, self.geometric),
+            (TypefaceStyle::Humanist, self.humanist),
+            (TypefaceStyle::Grotesque, self.grotesque),
+        ]
+        .into_iter()
+        .filter(|(_, confidence)| *confidence > 0.0)
+        .fold(None, |best: Option<(TypefaceStyle, f32)>, label| match best {
+            Some(best) if best.1 >= label.1 => Some(best),
+            _ => Some(label),
+        })
+    }
 }
 
 /// Font file format
@@ -101,6 +267,109 @@ pub struct LicenseInfo {
     pub allows_modification: bool,
     pub requires_attribution: bool,
     pub allows_commercial_use: bool, // ADDED THIS FIELD
+    /// Permissions declared by the font itself (OS/2 `fsType`), when it could be read
+    #[serde(default)]
+    pub embedding: Option<EmbeddingPermissions>,
+    /// License identified from the font's license description and URL (name IDs 13/14)
+    #[serde(default)]
+    pub spdx: Option<LicenseIdentification>,
+    /// OFL Reserved Font Names declared in the copyright or license records (name IDs 0/13)
+    #[serde(default)]
+    pub reserved_font_names: Vec<String>,
+}
+
+/// SPDX identifier matched against known license texts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LicenseIdentification {
+    /// SPDX expression, or a `LicenseRef-` identifier for proprietary EULAs
+    pub spdx_id: String,
+    /// 0.0-1.0: canonical URLs and versioned license titles score highest
+    pub confidence: f32,
+}
+
+/// Usage permission from the low bits of OS/2 `fsType`, least restrictive first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EmbeddingLevel {
+    /// May be embedded and installed permanently on the target system
+    Installable,
+    /// May be embedded in documents that are editable
+    Editable,
+    /// May be embedded only for viewing and printing; documents must be opened read-only
+    PreviewAndPrint,
+    /// Must not be embedded without the legal owner's permission
+    RestrictedLicense,
+}
+
+impl fmt::Display for EmbeddingLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmbeddingLevel::Installable => write!(f, "Installable"),
+            EmbeddingLevel::Editable => write!(f, "Editable"),
+            EmbeddingLevel::PreviewAndPrint => write!(f, "Preview & Print"),
+            EmbeddingLevel::RestrictedLicense => write!(f, "Restricted License"),
+        }
+    }
+}
+
+/// Raw OS/2 `fsType` value with accessors for its permission bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingPermissions {
+    pub fs_type: u16,
+}
+
+impl EmbeddingPermissions {
+    pub const RESTRICTED_LICENSE: u16 = 0x0002;
+    pub const PREVIEW_AND_PRINT: u16 = 0x0004;
+    pub const EDITABLE: u16 = 0x0008;
+    pub const NO_SUBSETTING: u16 = 0x0100;
+    pub const BITMAP_ONLY: u16 = 0x0200;
+    
+    pub fn from_fs_type(fs_type: u16) -> Self {
+        Self { fs_type }
+    }
+    
+    /// Usage permission; old fonts may set several bits, in which case the least
+    /// restrictive one applies, as the OpenType spec prescribes
+    pub fn level(&self) -> EmbeddingLevel {
+        if self.fs_type & 0x000F == 0 {
+            EmbeddingLevel::Installable
+        } else if self.fs_type & Self::EDITABLE != 0 {
+            EmbeddingLevel::Editable
+        } else if self.fs_type & Self::PREVIEW_AND_PRINT != 0 {
+            EmbeddingLevel::PreviewAndPrint
+        } else if self.fs_type & Self::RESTRICTED_LICENSE != 0 {
+            EmbeddingLevel::RestrictedLicense
+        } else {
+            // Only the reserved bit 0 is set
+            EmbeddingLevel::Installable
+        }
+    }
+    
+    pub fn allows_subsetting(&self) -> bool {
+        self.fs_type & Self::NO_SUBSETTING == 0
+    }
+    
+    /// Only bitmaps contained in the font may be embedded, not outlines
+    pub fn bitmap_only(&self) -> bool {
+        self.fs_type & Self::BITMAP_ONLY != 0
+    }
+    
+    pub fn allows_embedding(&self) -> bool {
+        self.level() != EmbeddingLevel::RestrictedLicense
+    }
+}
+
+impl fmt::Display for EmbeddingPermissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.level())?;
+        if !self.allows_subsetting() {
+            write!(f, ", no subsetting")?;
+        }
+        if self.bitmap_only() {
+            write!(f, ", bitmap only")?;
+        }
+        Ok(())
+    }
 }
 
 impl LicenseInfo {
@@ -143,6 +412,51 @@ pub struct FontRequest {
     
     /// Is monospaced requested? (default: false)
     pub monospaced: bool,
+    
+    /// Requested width class (1-9 as in OS/2 usWidthClass, 5 = normal)
+    pub width: u16,
+}
+
+/// OS/2 width class of the normal width
+pub const WIDTH_NORMAL: u16 = 5;
+
+impl FontRequest {
+    /// Key identifying the requested face: family, weight, style and width
+    pub fn cache_key(&self) -> String {
+        format!(
+            "{}:{}:{}:{}",
+            self.family.to_lowercase(),
+            self.weight,
+            if self.italic { "italic" } else { "normal" },
+            self.width
+        )
+    }
+}
+
+/// Width class implied by a style or font name ("Condensed", "Narrow", "Expanded", ...)
+pub fn width_class_from_name(name: &str) -> u16 {
+    let lower = name.to_lowercase().replace([' ', '-', '_'], "");
+    
+    // Longer prefixes first so "semicondensed" is not read as "condensed"
+    if lower.contains("ultracondensed") {
+        1
+    } else if lower.contains("extracondensed") || lower.contains("compressed") {
+        2
+    } else if lower.contains("semicondensed") {
+        4
+    } else if lower.contains("condensed") || lower.contains("narrow") {
+        3
+    } else if lower.contains("semiexpanded") {
+        6
+    } else if lower.contains("ultraexpanded") {
+        9
+    } else if lower.contains("extraexpanded") {
+        8
+    } else if lower.contains("expanded") || lower.contains("extended") || lower.contains("wide") {
+        7
+    } else {
+        WIDTH_NORMAL
+    }
 }
 
 /// Font style
@@ -354,12 +668,17 @@ impl fmt::Display for FontSourcePriority {
     }
 }
 
-/// Cache cleanup mode
+/// Cache cleanup mode. Every mode evicts on `put` when the disk limit is reached;
+/// the mode picks the eviction order and whether idle entries expire.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum CacheCleanupMode {
+    /// Least recently used first; idle entries are only removed by an explicit cleanup
     Manual,
+    /// Largest first
     SizeBased,
+    /// Least recently used first, expiring idle entries
     TimeBased,
+    /// By use, idleness and size, expiring idle entries used only once
     Smart,
 }
 
@@ -382,6 +701,11 @@ pub struct CacheStats {
     pub pinned_fonts: usize,
     pub memory_usage_mb: f64,
     pub disk_usage_mb: f64,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    /// Hits / (hits + misses), 0.0 before the first lookup
+    pub hit_rate: f64,
 }
 
 /// Extended configuration for font resolution
@@ -396,6 +720,12 @@ pub struct EnhancedResolverConfig {
     pub disk_limit_mb: usize,
     pub auto_pin_threshold: u32,
     pub cache_cleanup_mode: CacheCleanupMode,
+    /// Cache directory; `None` uses the per-user cache directory
+    #[serde(default)]
+    pub cache_dir: Option<PathBuf>,
+    /// How long a not-found result is remembered; 0 disables negative caching
+    #[serde(default = "default_negative_cache_ttl")]
+    pub negative_cache_ttl_secs: u64,
     
     // Font sources
     pub system_fonts_enabled: bool,
@@ -414,6 +744,14 @@ pub struct EnhancedResolverConfig {
     // Learning & Custom Assets
     pub dynamic_learning_enabled: bool,
     pub project_asset_dirs: Vec<PathBuf>,
+    
+    /// Scoring profile to use instead of the default one (e.g. from `fr fit-profile`)
+    #[serde(default)]
+    pub scoring_profile: Option<PathBuf>,
+}
+
+fn default_negative_cache_ttl() -> u64 {
+    60 * 60
 }
 
 impl Default for EnhancedResolverConfig {
@@ -425,6 +763,8 @@ impl Default for EnhancedResolverConfig {
             disk_limit_mb: 10,
             auto_pin_threshold: 5,
             cache_cleanup_mode: CacheCleanupMode::Manual,
+            cache_dir: None,
+            negative_cache_ttl_secs: default_negative_cache_ttl(),
             system_fonts_enabled: true,
             web_fonts_enabled: false, // Disabled by default
             custom_fonts_enabled: false,
@@ -435,10 +775,174 @@ impl Default for EnhancedResolverConfig {
             telemetry_enabled: false,
             dynamic_learning_enabled: true,
             project_asset_dirs: Vec::new(),
+            scoring_profile: None,
         }
     }
 }
 
+impl EnhancedResolverConfig {
+    /// The configured scoring profile, or the embedded one when none is set
+    pub fn load_scoring_profile(&self) -> FontResult<ScoringProfile> {
+        match &self.scoring_profile {
+            Some(path) => ScoringProfile::load_from_file(path),
+            None => Ok(ScoringProfile::embedded()),
+        }
+    }
+}
+
+/// Overrides the data directory holding downloaded databases and the license knowledge base
+pub const DATA_DIR_ENV: &str = "FONT_RESOLVER_DATA_DIR";
+
+/// Data directory: `data` under the working directory unless `FONT_RESOLVER_DATA_DIR` is set
+pub fn default_data_dir() -> PathBuf {
+    std::env::var_os(DATA_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("data"))
+}
+
+/// Where `fr fit-profile` writes fitted profiles by default; set `scoring_profile`
+/// in the configuration to use one
+pub const DEFAULT_SCORING_PROFILE_PATH: &str = "data/scoring_profile.json";
+
+static EMBEDDED_SCORING_PROFILE: &str = include_str!("../data/scoring_profile.json");
+
+static SHARED_SCORING_PROFILE: OnceLock<ScoringProfile> = OnceLock::new();
+
+/// Weights and thresholds used to score font matches. Loaded from JSON so they can be
+/// tuned for a corpus (see `font_similarity::ProfileFitter`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoringProfile {
+    pub name: String,
+    pub version: String,
+    /// `FontResolver` direct matches
+    pub resolver: ResolverWeights,
+    /// `FontSimilarityEngine` tiered matches
+    pub similarity: SimilarityWeights,
+    /// `MicroSignature::similarity`
+    pub signature: SignatureWeights,
+    /// `GlyphDescriptor::similarity`
+    pub descriptor: DescriptorWeights,
+    pub tiers: TierThresholds,
+    pub suggestions: SuggestionThresholds,
+}
+
+/// Weights of the component scores in `FontResolver`; they should sum to 1.0
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ResolverWeights {
+    pub family: f32,
+    pub weight: f32,
+    pub style: f32,
+    pub monospaced: f32,
+    pub metrics: f32,
+}
+
+/// Weights of the component scores in `FontSimilarityEngine`; the first five should sum to 1.0
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SimilarityWeights {
+    pub name: f32,
+    pub weight: f32,
+    pub style: f32,
+    pub category: f32,
+    pub metrics: f32,
+    /// Score multiplier when exactly one side is monospaced
+    pub monospace_mismatch_penalty: f32,
+    /// Share of the overall score taken by glyph shape similarity, when both fonts have signatures
+    pub visual: f32,
+}
+
+/// Weights of the twelve scalar `MicroSignature` bytes, in byte order, and of its feature hash
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SignatureWeights {
+    pub features: [f32; 12],
+    pub feature_hash: f32,
+}
+
+/// Weights of the `GlyphDescriptor` components; they should sum to 1.0
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DescriptorWeights {
+    pub fourier: f32,
+    /// Ink density grids, coarsest (2×2) first
+    pub grid: [f32; 3],
+    pub stroke_width: f32,
+    pub stroke_contrast: f32,
+    pub counters: f32,
+}
+
+/// Lowest scores of the exact and similar match tiers
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TierThresholds {
+    pub exact: f32,
+    pub similar: f32,
+}
+
+/// Score thresholds used when trimming suggestion lists
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SuggestionThresholds {
+    /// Look for metric substitutes when every suggestion scores below this
+    pub substitute_below: f32,
+    /// A best score above this keeps only it and three alternatives
+    pub perfect: f32,
+    /// A best score above this drops suggestions under `good_min_score`
+    pub good: f32,
+    pub good_min_score: f32,
+}
+
+impl ScoringProfile {
+    /// The profile compiled into this build
+    pub fn embedded() -> Self {
+        serde_json::from_str(EMBEDDED_SCORING_PROFILE).expect("embedded scoring profile is valid JSON")
+    }
+    
+    /// Parse a profile; fields it leaves out keep their embedded values
+    pub fn from_json(json: &str) -> FontResult<Self> {
+        let invalid = |e: serde_json::Error| FontError::Parse(format!("Invalid scoring profile: {}", e));
+        
+        let mut merged: serde_json::Value = serde_json::from_str(EMBEDDED_SCORING_PROFILE).map_err(invalid)?;
+        merge_json(&mut merged, serde_json::from_str(json).map_err(invalid)?);
+        serde_json::from_value(merged).map_err(invalid)
+    }
+    
+    pub fn load_from_file<P: AsRef<std::path::Path>>(path: P) -> FontResult<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+    
+    pub fn save_to_file<P: AsRef<std::path::Path>>(&self, path: P) -> FontResult<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| FontError::Parse(format!("Failed to serialize scoring profile: {}", e)))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+    
+    /// The embedded profile, parsed once, for callers that are given no profile.
+    /// A configured profile is passed to each component instead (see `with_profile`).
+    pub fn shared() -> &'static ScoringProfile {
+        SHARED_SCORING_PROFILE.get_or_init(Self::embedded)
+    }
+}
+
+impl Default for ScoringProfile {
+    fn default() -> Self {
+        Self::embedded()
+    }
+}
+
+/// Overlay `overlay` onto `base`, recursing into objects so nested fields can be set one at a time
+fn merge_json(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
 /// Setup configuration from interactive prompts
 #[derive(Debug, Clone)]
 pub struct SetupConfig {
@@ -480,6 +984,9 @@ pub enum FontError {
     #[error("Invalid font name: {0}")]
     InvalidFontName(String),
     
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    
     #[error("Platform not supported: {0}")]
     PlatformNotSupported(String),
     
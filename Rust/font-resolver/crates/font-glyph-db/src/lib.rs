@@ -5,13 +5,19 @@
 //! This crate provides:
 //! - `GlyphDatabaseBuilder` - Builds the compressed database from font files
 //! - `LshIndex` - Locality-Sensitive Hashing for fast candidate retrieval
+//! - Re-ranking of LSH candidates with `GlyphDescriptor`s where both sides have them
+//! - Per-script character sets, so fonts are only matched against queries in scripts they were indexed for
+//! - Matching of unlabeled glyphs, inferring which character each one is
 //! - Brotli-11 compression for ultra-compact storage
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::io::{Write, Cursor, Read};
 use std::path::Path;
 use serde::{Serialize, Deserialize};
-use font_glyph::{GlyphExtractor, MicroSignature, GlyphError};
+use font_core::{DescriptorWeights, ScoringProfile, SignatureWeights};
+use font_glyph::{GlyphDescriptor, GlyphExtractor, GlyphOutline, MicroSignature, GlyphError};
+
+pub use font_glyph::Script;
 
 // =============================================================================
 // CONSTANTS
@@ -21,7 +27,16 @@ use font_glyph::{GlyphExtractor, MicroSignature, GlyphError};
 pub const MAGIC_BYTES: &[u8; 8] = b"GLYPHDB1";
 
 /// Current database format version
-pub const FORMAT_VERSION: u32 = 1;
+pub const FORMAT_VERSION: u32 = 3;
+
+/// Candidates fetched from the LSH index per requested result when re-ranking
+/// with descriptors, since the compact signature orders them only roughly
+pub const RERANK_CANDIDATES_PER_RESULT: usize = 4;
+
+/// Least similarity at which two glyphs of an unlabeled query are taken to be the same
+/// character. Under 0.1% of same-font pairs of different characters in the shipped
+/// glyph database come this close, mostly true look-alikes such as I/l and O/0.
+pub const SAME_GLYPH_SIMILARITY: f32 = 0.97;
 
 /// Number of LSH hash tables
 pub const LSH_TABLE_COUNT: usize = 8;
@@ -29,9 +44,44 @@ pub const LSH_TABLE_COUNT: usize = 8;
 /// Number of buckets per table
 pub const LSH_BUCKET_COUNT: usize = 256;
 
-/// Standard alphanumeric characters to index
+/// Standard alphanumeric characters to index (the default Latin character set)
 pub const ALPHANUMERIC_CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
 
+/// Share of a character set a font must have glyphs for to be indexed for its script
+pub const MIN_SCRIPT_COVERAGE: f32 = 0.5;
+
+// =============================================================================
+// CHARACTER SETS
+// =============================================================================
+
+/// Characters indexed for one script
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CharacterSet {
+    pub script: Script,
+    pub characters: String,
+}
+
+impl CharacterSet {
+    /// Index `characters` for `script`
+    pub fn new(script: Script, characters: &str) -> Self {
+        Self { script, characters: characters.to_string() }
+    }
+    
+    /// The script's default characters (see `Script::default_characters`)
+    pub fn default_for(script: Script) -> Self {
+        Self::new(script, script.default_characters())
+    }
+    
+    /// Number of characters in the set
+    pub fn len(&self) -> usize {
+        self.characters.chars().count()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.characters.is_empty()
+    }
+}
+
 // =============================================================================
 // LSH INDEX
 // =============================================================================
@@ -170,6 +220,23 @@ pub struct FontEntry {
     pub subfamily: Option<String>,
     /// Signatures for each indexed character (char -> signature)
     pub signatures: Vec<(char, MicroSignature)>,
+    /// Rich descriptors for re-ranking; empty in databases built before format version 2
+    pub descriptors: Vec<(char, GlyphDescriptor)>,
+    /// Scripts the font was indexed for
+    pub scripts: Vec<Script>,
+}
+
+impl FontEntry {
+    /// Whether the font was indexed for every script in `scripts`
+    pub fn is_indexed_for<'a>(&self, mut scripts: impl Iterator<Item = &'a Script>) -> bool {
+        scripts.all(|script| self.scripts.contains(script))
+    }
+    
+
+    /// Descriptor stored for a character
+    pub fn descriptor(&self, character: char) -> Option<&GlyphDescriptor> {
+        self.descriptors.iter().find(|(c, _)| *c == character).map(|(_, d)| d)
+    }
 }
 
 // =============================================================================
@@ -183,8 +250,8 @@ pub struct DatabaseHeader {
     pub version: u32,
     /// Number of fonts
     pub font_count: u32,
-    /// Number of characters per font
-    pub char_count: u8,
+    /// Number of characters across all character sets
+    pub char_count: u32,
     /// Flags (reserved)
     pub flags: u8,
     /// Offset to LSH index data
@@ -193,6 +260,8 @@ pub struct DatabaseHeader {
     pub signatures_offset: u64,
     /// Offset to font names
     pub names_offset: u64,
+    /// Character sets fonts were indexed with
+    pub character_sets: Vec<CharacterSet>,
 }
 
 // =============================================================================
@@ -208,27 +277,114 @@ pub struct GlyphDatabase {
     pub lsh_index: LshIndex,
     /// All font entries
     pub fonts: Vec<FontEntry>,
+    /// Weights glyphs are compared with; not stored in the database file
+    #[serde(skip)]
+    pub weights: GlyphWeights,
+}
+
+/// Signature and descriptor weights from a scoring profile
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphWeights {
+    pub signature: SignatureWeights,
+    pub descriptor: DescriptorWeights,
+}
+
+impl GlyphWeights {
+    pub fn from_profile(profile: &ScoringProfile) -> Self {
+        Self {
+            signature: profile.signature,
+            descriptor: profile.descriptor,
+        }
+    }
+}
+
+/// The embedded profile's weights
+impl Default for GlyphWeights {
+    fn default() -> Self {
+        Self::from_profile(ScoringProfile::shared())
+    }
 }
 
 impl GlyphDatabase {
+    /// Compare glyphs with `profile`'s weights instead of the embedded profile's
+    pub fn with_profile(mut self, profile: &ScoringProfile) -> Self {
+        self.set_profile(profile);
+        self
+    }
+    
+    pub fn set_profile(&mut self, profile: &ScoringProfile) {
+        self.weights = GlyphWeights::from_profile(profile);
+    }
+    
     /// Find fonts matching a signature
     pub fn find_matches(&self, signature: &MicroSignature, limit: usize) -> Vec<MatchResult> {
+        self.find_matches_with(signature, None, None, limit)
+    }
+    
+    /// Find fonts indexed for `script` matching a glyph of that script, comparing
+    /// it only with their glyphs of that script
+    pub fn find_matches_in_script(&self, signature: &MicroSignature, script: Script, limit: usize) -> Vec<MatchResult> {
+        self.find_matches_with(signature, None, Some(script), limit)
+    }
+    
+    /// Find fonts matching a glyph, retrieving candidates with its signature and
+    /// ranking them by descriptor similarity where the font has descriptors.
+    /// With a `script`, only fonts indexed for it are considered, as in `find_matches_in_script`.
+    pub fn find_matches_reranked(
+        &self,
+        signature: &MicroSignature,
+        descriptor: &GlyphDescriptor,
+        script: Option<Script>,
+        limit: usize,
+    ) -> Vec<MatchResult> {
+        self.find_matches_with(signature, Some(descriptor), script, limit)
+    }
+    
+    fn find_matches_with(
+        &self,
+        signature: &MicroSignature,
+        descriptor: Option<&GlyphDescriptor>,
+        script: Option<Script>,
+        limit: usize,
+    ) -> Vec<MatchResult> {
         // Use LSH to get candidates
         let candidates = self.lsh_index.get_candidates(signature, 2);
+        let candidate_count = if descriptor.is_some() { limit * RERANK_CANDIDATES_PER_RESULT } else { limit * 2 };
+        let in_script = |ch: char| script.is_none_or(|script| Script::of(ch).is_none_or(|s| s == script));
         
         let mut results = Vec::new();
         
-        for (font_id, votes) in candidates.iter().take(limit * 2) {
+        let candidates = candidates.iter()
+            .filter(|(font_id, _)| {
+                self.fonts.get(*font_id as usize)
+                    .is_some_and(|font| font.is_indexed_for(script.iter()))
+            })
+            .take(candidate_count);
+        
+        for (font_id, votes) in candidates {
             if let Some(font) = self.fonts.get(*font_id as usize) {
                 // Calculate actual similarity
                 let mut best_similarity = 0.0f32;
                 let mut matched_char = None;
                 
-                for (ch, font_sig) in &font.signatures {
-                    let sim = signature.similarity(font_sig);
-                    if sim > best_similarity {
-                        best_similarity = sim;
-                        matched_char = Some(*ch);
+                match descriptor {
+                    Some(descriptor) if !font.descriptors.is_empty() => {
+                        for (ch, font_descriptor) in font.descriptors.iter().filter(|(ch, _)| in_script(*ch)) {
+                            let sim = descriptor.similarity_with(font_descriptor, &self.weights.descriptor);
+                            if sim > best_similarity {
+                                best_similarity = sim;
+                                matched_char = Some(*ch);
+                            }
+                        }
+                    }
+                    _ => {
+                        for (ch, font_sig) in font.signatures.iter().filter(|(ch, _)| in_script(*ch)) {
+                            let sim = signature.similarity_with(font_sig, &self.weights.signature);
+                            if sim > best_similarity {
+                                best_similarity = sim;
+                                matched_char = Some(*ch);
+                            }
+                        }
                     }
                 }
                 
@@ -239,6 +395,7 @@ impl GlyphDatabase {
                     similarity: best_similarity,
                     lsh_votes: *votes,
                     matched_char,
+                    inferred_chars: Vec::new(),
                 });
             }
         }
@@ -250,18 +407,46 @@ impl GlyphDatabase {
         results
     }
     
-    /// Find fonts matching multiple signatures (higher accuracy)
+    /// Find fonts matching multiple signatures (higher accuracy). Only fonts indexed
+    /// for the scripts of all the query's characters are considered.
     pub fn find_matches_multi(&self, signatures: &[(char, MicroSignature)], limit: usize) -> Vec<MatchResult> {
+        self.find_matches_multi_with(signatures, &[], limit)
+    }
+    
+    /// Like `find_matches_multi`, comparing characters by descriptor where both the
+    /// query and the font have one for it
+    pub fn find_matches_multi_reranked(
+        &self,
+        signatures: &[(char, MicroSignature)],
+        descriptors: &[(char, GlyphDescriptor)],
+        limit: usize,
+    ) -> Vec<MatchResult> {
+        self.find_matches_multi_with(signatures, descriptors, limit)
+    }
+    
+    fn find_matches_multi_with(
+        &self,
+        signatures: &[(char, MicroSignature)],
+        descriptors: &[(char, GlyphDescriptor)],
+        limit: usize,
+    ) -> Vec<MatchResult> {
         if signatures.is_empty() {
             return Vec::new();
         }
         
+        let scripts: BTreeSet<Script> = signatures.iter().filter_map(|(c, _)| Script::of(*c)).collect();
+        
         // Aggregate votes from all signatures
         let mut combined_candidates: HashMap<u16, (usize, f32)> = HashMap::new();
         
         for (_, sig) in signatures {
             let candidates = self.lsh_index.get_candidates(sig, 1);
             for (font_id, votes) in candidates {
+                let indexed = self.fonts.get(font_id as usize)
+                    .is_some_and(|font| font.is_indexed_for(scripts.iter()));
+                if !indexed {
+                    continue;
+                }
                 let entry = combined_candidates.entry(font_id).or_insert((0, 0.0));
                 entry.0 += votes;
             }
@@ -276,10 +461,17 @@ impl GlyphDatabase {
                 let mut match_count = 0;
                 
                 for (query_char, query_sig) in signatures {
+                    let query_descriptor = descriptors.iter().find(|(c, _)| c == query_char).map(|(_, d)| d);
+                    if let (Some(query_descriptor), Some(font_descriptor)) = (query_descriptor, font.descriptor(*query_char)) {
+                        total_sim += query_descriptor.similarity_with(font_descriptor, &self.weights.descriptor);
+                        match_count += 1;
+                        continue;
+                    }
+                    
                     // Find matching character in font
                     for (font_char, font_sig) in &font.signatures {
                         if font_char == query_char {
-                            total_sim += query_sig.similarity(font_sig);
+                            total_sim += query_sig.similarity_with(font_sig, &self.weights.signature);
                             match_count += 1;
                             break;
                         }
@@ -294,6 +486,7 @@ impl GlyphDatabase {
                         similarity: total_sim / match_count as f32,
                         lsh_votes: *total_votes,
                         matched_char: None,
+                        inferred_chars: Vec::new(),
                     });
                 }
             }
@@ -304,6 +497,128 @@ impl GlyphDatabase {
         
         results
     }
+    
+    /// Find fonts matching glyphs whose characters are unknown, e.g. ones cut out of
+    /// an image. Glyphs at least `SAME_GLYPH_SIMILARITY` alike are taken to be one
+    /// character repeated, as in "Hello", and share a character. For each candidate
+    /// font the groups of glyphs are jointly assigned to distinct characters of its
+    /// alphabet (of `script`, if given) so that the total similarity is highest, and
+    /// the font is scored on that assignment. Glyphs left over when there are more
+    /// groups than characters score 0.0.
+    /// 
+    /// Each result's `inferred_chars` holds the character assigned to each glyph,
+    /// in query order.
+    pub fn find_matches_unlabeled(&self, glyphs: &[UnlabeledGlyph], script: Option<Script>, limit: usize) -> Vec<MatchResult> {
+        if glyphs.is_empty() {
+            return Vec::new();
+        }
+        
+        // Aggregate votes from all glyphs
+        let mut votes: HashMap<u16, usize> = HashMap::new();
+        for glyph in glyphs {
+            for (font_id, font_votes) in self.lsh_index.get_candidates(&glyph.signature, 1) {
+                *votes.entry(font_id).or_insert(0) += font_votes;
+            }
+        }
+        let mut candidates: Vec<(u16, usize)> = votes.into_iter()
+            .filter(|(font_id, _)| {
+                self.fonts.get(*font_id as usize)
+                    .is_some_and(|font| font.is_indexed_for(script.iter()))
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        candidates.truncate(limit * RERANK_CANDIDATES_PER_RESULT);
+        
+        let in_script = |ch: char| script.is_none_or(|script| Script::of(ch).is_none_or(|s| s == script));
+        let groups = self.repeated_glyph_groups(glyphs);
+        let mut results = Vec::new();
+        
+        for (font_id, total_votes) in candidates {
+            let Some(font) = self.fonts.get(font_id as usize) else {
+                continue;
+            };
+            let alphabet: Vec<(char, &MicroSignature)> = font.signatures.iter()
+                .filter(|(ch, _)| in_script(*ch))
+                .map(|(ch, sig)| (*ch, sig))
+                .collect();
+            if alphabet.is_empty() {
+                continue;
+            }
+            
+            let similarities: Vec<Vec<f32>> = glyphs.iter()
+                .map(|glyph| {
+                    alphabet.iter()
+                        .map(|(ch, font_sig)| match (&glyph.descriptor, font.descriptor(*ch)) {
+                            (Some(descriptor), Some(font_descriptor)) => descriptor.similarity_with(font_descriptor, &self.weights.descriptor),
+                            _ => glyph.signature.similarity_with(font_sig, &self.weights.signature),
+                        })
+                        .collect()
+                })
+                .collect();
+            
+            // A group's similarity to a character is the sum over its glyphs
+            let group_similarities: Vec<Vec<f32>> = (0..=groups.iter().copied().max().unwrap_or(0))
+                .map(|group| {
+                    (0..alphabet.len())
+                        .map(|column| groups.iter().zip(&similarities)
+                            .filter(|(g, _)| **g == group)
+                            .map(|(_, row)| row[column])
+                            .sum())
+                        .collect()
+                })
+                .collect();
+            let group_assignment = assign_max_similarity(&group_similarities);
+            let assignment: Vec<Option<usize>> = groups.iter().map(|group| group_assignment[*group]).collect();
+            let total: f32 = assignment.iter().enumerate()
+                .filter_map(|(glyph, column)| column.map(|column| similarities[glyph][column]))
+                .sum();
+            
+            results.push(MatchResult {
+                font_id,
+                family: font.family.clone(),
+                subfamily: font.subfamily.clone(),
+                similarity: total / glyphs.len() as f32,
+                lsh_votes: total_votes,
+                matched_char: None,
+                inferred_chars: assignment.iter().map(|column| column.map(|column| alphabet[column].0)).collect(),
+            });
+        }
+        
+        results.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap());
+        results.truncate(limit);
+        
+        results
+    }
+    
+    /// Group of each glyph, numbered in order of first appearance; a glyph joins the
+    /// first group whose first glyph it resembles by at least `SAME_GLYPH_SIMILARITY`
+    fn repeated_glyph_groups(&self, glyphs: &[UnlabeledGlyph]) -> Vec<usize> {
+        let mut representatives: Vec<&UnlabeledGlyph> = Vec::new();
+        glyphs.iter()
+            .map(|glyph| {
+                let similarity = |other: &UnlabeledGlyph| match (&glyph.descriptor, &other.descriptor) {
+                    (Some(a), Some(b)) => a.similarity_with(b, &self.weights.descriptor),
+                    _ => glyph.signature.similarity_with(&other.signature, &self.weights.signature),
+                };
+                representatives.iter()
+                    .position(|representative| similarity(representative) >= SAME_GLYPH_SIMILARITY)
+                    .unwrap_or_else(|| {
+                        representatives.push(glyph);
+                        representatives.len() - 1
+                    })
+            })
+            .collect()
+    }
+    
+    /// Number of fonts indexed for each of the database's scripts
+    pub fn fonts_per_script(&self) -> Vec<(Script, usize)> {
+        let mut scripts: Vec<Script> = self.header.character_sets.iter().map(|set| set.script).collect();
+        scripts.sort();
+        scripts.dedup();
+        scripts.into_iter()
+            .map(|script| (script, self.fonts.iter().filter(|font| font.scripts.contains(&script)).count()))
+            .collect()
+    }
 }
 
 /// Result of a font match query
@@ -321,6 +636,112 @@ pub struct MatchResult {
     pub lsh_votes: usize,
     /// Character that matched best
     pub matched_char: Option<char>,
+    /// For unlabeled queries, the character assigned to each query glyph (`None`
+    /// when there were more glyphs than characters); empty otherwise
+    pub inferred_chars: Vec<Option<char>>,
+}
+
+/// A glyph whose character is unknown, for `GlyphDatabase::find_matches_unlabeled`
+#[derive(Debug, Clone)]
+pub struct UnlabeledGlyph {
+    pub signature: MicroSignature,
+    /// Used instead of the signature for fonts that have descriptors
+    pub descriptor: Option<GlyphDescriptor>,
+}
+
+impl UnlabeledGlyph {
+    /// Signature and descriptor of an outline; its `character` is ignored
+    pub fn from_outline(outline: &GlyphOutline) -> Self {
+        Self {
+            signature: MicroSignature::from_outline(outline),
+            descriptor: Some(GlyphDescriptor::from_outline(outline)),
+        }
+    }
+}
+
+impl From<MicroSignature> for UnlabeledGlyph {
+    fn from(signature: MicroSignature) -> Self {
+        Self { signature, descriptor: None }
+    }
+}
+
+// =============================================================================
+// ASSIGNMENT
+// =============================================================================
+
+/// Assign each row to a distinct column so that the summed similarity is highest
+/// (the Hungarian algorithm on `1 - similarity`). Returns the column of each row,
+/// `None` for rows left over when there are more rows than columns.
+fn assign_max_similarity(similarities: &[Vec<f32>]) -> Vec<Option<usize>> {
+    let rows = similarities.len();
+    let real_columns = similarities.first().map_or(0, Vec::len);
+    // Pad with columns of similarity 0.0 so every row can be assigned
+    let columns = real_columns.max(rows);
+    let cost = |row: usize, column: usize| {
+        if column < real_columns { 1.0 - similarities[row][column] as f64 } else { 1.0 }
+    };
+    
+    // Potentials and matching are 1-based, with 0 as a virtual row and column
+    let mut row_potential = vec![0.0_f64; rows + 1];
+    let mut column_potential = vec![0.0_f64; columns + 1];
+    let mut column_row = vec![0_usize; columns + 1];
+    let mut previous = vec![0_usize; columns + 1];
+    
+    for row in 1..=rows {
+        column_row[0] = row;
+        let mut column = 0;
+        let mut min_slack = vec![f64::INFINITY; columns + 1];
+        let mut used = vec![false; columns + 1];
+        
+        // Grow an alternating path from `row` until it reaches a free column
+        loop {
+            used[column] = true;
+            let current_row = column_row[column];
+            let mut delta = f64::INFINITY;
+            let mut next = 0;
+            for candidate in 1..=columns {
+                if used[candidate] {
+                    continue;
+                }
+                let slack = cost(current_row - 1, candidate - 1) - row_potential[current_row] - column_potential[candidate];
+                if slack < min_slack[candidate] {
+                    min_slack[candidate] = slack;
+                    previous[candidate] = column;
+                }
+                if min_slack[candidate] < delta {
+                    delta = min_slack[candidate];
+                    next = candidate;
+                }
+            }
+            for candidate in 0..=columns {
+                if used[candidate] {
+                    row_potential[column_row[candidate]] += delta;
+                    column_potential[candidate] -= delta;
+                } else {
+                    min_slack[candidate] -= delta;
+                }
+            }
+            column = next;
+            if column_row[column] == 0 {
+                break;
+            }
+        }
+        
+        // Flip the path
+        while column != 0 {
+            let before = previous[column];
+            column_row[column] = column_row[before];
+            column = before;
+        }
+    }
+    
+    let mut assignment = vec![None; rows];
+    for (column, &row) in column_row.iter().enumerate().take(real_columns + 1).skip(1) {
+        if row != 0 {
+            assignment[row - 1] = Some(column - 1);
+        }
+    }
+    assignment
 }
 
 // =============================================================================
@@ -332,26 +753,79 @@ pub struct GlyphDatabaseBuilder {
     fonts: Vec<FontEntry>,
     lsh_index: LshIndex,
     extractor: GlyphExtractor,
+    character_sets: Vec<CharacterSet>,
 }
 
 impl GlyphDatabaseBuilder {
-    /// Create a new database builder
+    /// Create a new database builder indexing the default Latin character set
     pub fn new() -> Self {
         Self {
             fonts: Vec::new(),
             lsh_index: LshIndex::new(),
             extractor: GlyphExtractor::new(),
+            character_sets: vec![CharacterSet::default_for(Script::Latin)],
         }
     }
     
-    /// Add a font file to the database
+    /// Index these character sets instead of the default Latin one
+    pub fn with_character_sets(mut self, character_sets: Vec<CharacterSet>) -> Self {
+        self.character_sets = character_sets;
+        self
+    }
+    
+    /// Index the default character sets of these scripts
+    pub fn with_scripts(self, scripts: &[Script]) -> Self {
+        self.with_character_sets(scripts.iter().map(|script| CharacterSet::default_for(*script)).collect())
+    }
+    
+    /// Character sets fonts are indexed with
+    pub fn character_sets(&self) -> &[CharacterSet] {
+        &self.character_sets
+    }
+    
+    /// Number of characters across all character sets
+    pub fn char_count(&self) -> usize {
+        self.character_sets.iter().map(CharacterSet::len).sum()
+    }
+    
+    /// Add a font file to the database. The font is indexed for each script whose
+    /// character set it covers at least `MIN_SCRIPT_COVERAGE` of.
     pub fn add_font<P: AsRef<Path>>(&mut self, font_path: P, family: &str, subfamily: Option<&str>) -> Result<(), GlyphError> {
-        let signatures = self.extractor.extract_alphanumeric_signatures(&font_path)?;
+        let font_data = std::fs::read(font_path.as_ref())
+            .map_err(|e| GlyphError::IoError(e.to_string()))?;
         
-        if signatures.is_empty() {
+        let mut scripts = Vec::new();
+        let mut outlines = Vec::new();
+        for set in &self.character_sets {
+            let set_outlines: Vec<_> = self.extractor.extract_outlines_from_data(&font_data, &set.characters)?
+                .into_iter()
+                .filter(|outline| !outline.segments.is_empty())
+                .collect();
+            
+            if !set.is_empty() && set_outlines.len() as f32 / set.len() as f32 >= MIN_SCRIPT_COVERAGE {
+                if !scripts.contains(&set.script) {
+                    scripts.push(set.script);
+                }
+                for outline in set_outlines {
+                    // Character sets may share characters, such as digits
+                    if !outlines.iter().any(|existing: &GlyphOutline| existing.character == outline.character) {
+                        outlines.push(outline);
+                    }
+                }
+            }
+        }
+        
+        if outlines.is_empty() {
             return Ok(()); // Skip fonts with no supported characters
         }
         
+        let signatures: Vec<(char, MicroSignature)> = outlines.iter()
+            .map(|outline| (outline.character, MicroSignature::from_outline(outline)))
+            .collect();
+        let descriptors = outlines.iter()
+            .map(|outline| (outline.character, GlyphDescriptor::from_outline(outline)))
+            .collect();
+        
         let font_id = self.fonts.len() as u16;
         
         // Add to LSH index
@@ -362,6 +836,8 @@ impl GlyphDatabaseBuilder {
             family: family.to_string(),
             subfamily: subfamily.map(|s| s.to_string()),
             signatures,
+            descriptors,
+            scripts,
         });
         
         Ok(())
@@ -377,17 +853,18 @@ impl GlyphDatabaseBuilder {
             .map_err(|e| GlyphError::ParseError(format!("Failed to parse font: {:?}", e)))?;
         
         // Extract family name
+        // First record that decodes; Mac Roman records come first in many fonts and don't
         let family = face.names()
             .into_iter()
-            .find(|name| name.name_id == ttf_parser::name_id::FAMILY)
-            .and_then(|name| name.to_string())
+            .filter(|name| name.name_id == ttf_parser::name_id::FAMILY)
+            .find_map(|name| name.to_string())
             .unwrap_or_else(|| "Unknown".to_string());
         
         // Extract subfamily
         let subfamily = face.names()
             .into_iter()
-            .find(|name| name.name_id == ttf_parser::name_id::SUBFAMILY)
-            .and_then(|name| name.to_string());
+            .filter(|name| name.name_id == ttf_parser::name_id::SUBFAMILY)
+            .find_map(|name| name.to_string());
         
         self.add_font(font_path, &family, subfamily.as_deref())
     }
@@ -402,17 +879,19 @@ impl GlyphDatabaseBuilder {
         let header = DatabaseHeader {
             version: FORMAT_VERSION,
             font_count: self.fonts.len() as u32,
-            char_count: ALPHANUMERIC_CHARS.len() as u8,
+            char_count: self.char_count() as u32,
             flags: 0,
             lsh_offset: 0, // Will be set during serialization
             signatures_offset: 0,
             names_offset: 0,
+            character_sets: self.character_sets.clone(),
         };
         
         GlyphDatabase {
             header,
             lsh_index: self.lsh_index.clone(),
             fonts: self.fonts.clone(),
+            weights: GlyphWeights::default(),
         }
     }
     
@@ -463,11 +942,13 @@ impl GlyphDatabaseBuilder {
         let elapsed = start.elapsed();
         
         // Calculate uncompressed size (approximate)
-        let uncompressed_estimate = self.fonts.len() * 62 * 16; // fonts × chars × sig_size
+        let uncompressed_estimate: usize = self.fonts.iter()
+            .map(|font| font.signatures.len() * 16 + font.descriptors.iter().map(|(_, d)| d.to_bytes().len()).sum::<usize>())
+            .sum();
         
         Ok(DatabaseStats {
             font_count: self.fonts.len(),
-            char_count: 62,
+            char_count: self.char_count(),
             uncompressed_size: uncompressed_estimate,
             compressed_size: compressed.len(),
             compression_ratio: if uncompressed_estimate > 0 {
@@ -505,9 +986,101 @@ pub fn load_database(data: &[u8]) -> Result<GlyphDatabase, DatabaseError> {
     decoder.read_to_end(&mut decompressed)
         .map_err(|e| DatabaseError::DecompressionError(e.to_string()))?;
     
+    // The header comes first, and its version first within it
+    let version = decompressed.get(0..4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().expect("four bytes")))
+        .ok_or_else(|| DatabaseError::InvalidFormat("Truncated header".to_string()))?;
+    
     // Deserialize
-    bincode::deserialize(&decompressed)
-        .map_err(|e| DatabaseError::DeserializationError(e.to_string()))
+    let invalid = |e: bincode::Error| DatabaseError::DeserializationError(e.to_string());
+    match version {
+        FORMAT_VERSION => bincode::deserialize(&decompressed).map_err(invalid),
+        2 => bincode::deserialize::<LegacyGlyphDatabase<FontEntryV2>>(&decompressed)
+            .map(LegacyGlyphDatabase::upgrade)
+            .map_err(invalid),
+        1 => bincode::deserialize::<LegacyGlyphDatabase<FontEntryV1>>(&decompressed)
+            .map(LegacyGlyphDatabase::upgrade)
+            .map_err(invalid),
+        _ => Err(DatabaseError::InvalidFormat(format!("Unsupported format version {}", version))),
+    }
+}
+
+/// Layout of format versions 1 and 2, which indexed Latin alphanumerics only
+#[derive(Deserialize)]
+struct LegacyGlyphDatabase<F> {
+    header: LegacyDatabaseHeader,
+    lsh_index: LshIndex,
+    fonts: Vec<F>,
+}
+
+#[derive(Deserialize)]
+struct LegacyDatabaseHeader {
+    _version: u32,
+    font_count: u32,
+    char_count: u8,
+    flags: u8,
+    lsh_offset: u64,
+    signatures_offset: u64,
+    names_offset: u64,
+}
+
+/// Format version 1 had no descriptors
+#[derive(Deserialize)]
+struct FontEntryV1 {
+    family: String,
+    subfamily: Option<String>,
+    signatures: Vec<(char, MicroSignature)>,
+}
+
+#[derive(Deserialize)]
+struct FontEntryV2 {
+    family: String,
+    subfamily: Option<String>,
+    signatures: Vec<(char, MicroSignature)>,
+    descriptors: Vec<(char, GlyphDescriptor)>,
+}
+
+impl From<FontEntryV1> for FontEntryV2 {
+    fn from(font: FontEntryV1) -> Self {
+        Self {
+            family: font.family,
+            subfamily: font.subfamily,
+            signatures: font.signatures,
+            descriptors: Vec::new(),
+        }
+    }
+}
+
+impl<F: Into<FontEntryV2>> LegacyGlyphDatabase<F> {
+    fn upgrade(self) -> GlyphDatabase {
+        let header = self.header;
+        GlyphDatabase {
+            header: DatabaseHeader {
+                version: FORMAT_VERSION,
+                font_count: header.font_count,
+                char_count: header.char_count as u32,
+                flags: header.flags,
+                lsh_offset: header.lsh_offset,
+                signatures_offset: header.signatures_offset,
+                names_offset: header.names_offset,
+                character_sets: vec![CharacterSet::default_for(Script::Latin)],
+            },
+            lsh_index: self.lsh_index,
+            fonts: self.fonts.into_iter()
+                .map(|font| {
+                    let font = font.into();
+                    FontEntry {
+                        family: font.family,
+                        subfamily: font.subfamily,
+                        signatures: font.signatures,
+                        descriptors: font.descriptors,
+                        scripts: vec![Script::Latin],
+                    }
+                })
+                .collect(),
+            weights: GlyphWeights::default(),
+        }
+    }
 }
 
 /// Load a compressed database from file
@@ -537,7 +1110,7 @@ impl std::fmt::Display for DatabaseStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Database Statistics:\n")?;
         write!(f, "  Fonts indexed: {}\n", self.font_count)?;
-        write!(f, "  Characters per font: {}\n", self.char_count)?;
+        write!(f, "  Characters indexed: {}\n", self.char_count)?;
         write!(f, "  Uncompressed size: {:.2} MB\n", self.uncompressed_size as f64 / 1_000_000.0)?;
         write!(f, "  Compressed size: {:.2} MB\n", self.compressed_size as f64 / 1_000_000.0)?;
         write!(f, "  Compression ratio: {:.1}%\n", self.compression_ratio)?;
@@ -641,6 +1214,8 @@ mod tests {
             family: "TestFont".to_string(),
             subfamily: Some("Regular".to_string()),
             signatures: vec![('A', sig)],
+            descriptors: Vec::new(),
+            scripts: vec![Script::Latin],
         });
         
         // Compress and decompress
@@ -650,4 +1225,199 @@ mod tests {
         assert_eq!(loaded.fonts.len(), 1);
         assert_eq!(loaded.fonts[0].family, "TestFont");
     }
+    
+    fn compress(serialized: &[u8]) -> Vec<u8> {
+        let mut output = MAGIC_BYTES.to_vec();
+        let mut encoder = brotli::CompressorWriter::new(&mut output, 4096, 1, 22);
+        encoder.write_all(serialized).unwrap();
+        drop(encoder);
+        output
+    }
+    
+    #[test]
+    fn test_loads_version_1_database() {
+        let sig = MicroSignature::default();
+        // Version 1 headers were (version, font_count, char_count: u8, flags, offsets)
+        // and font entries (family, subfamily, signatures)
+        let header = (1u32, 1u32, 62u8, 0u8, 0u64, 0u64, 0u64);
+        let fonts = vec![("OldFont".to_string(), None::<String>, vec![('A', sig)])];
+        let data = compress(&bincode::serialize(&(header, LshIndex::new(), fonts)).unwrap());
+        
+        let loaded = load_database(&data).expect("Should load version 1");
+        assert_eq!(loaded.header.version, FORMAT_VERSION);
+        assert_eq!(loaded.fonts[0].family, "OldFont");
+        assert_eq!(loaded.fonts[0].signatures, vec![('A', sig)]);
+        assert!(loaded.fonts[0].descriptors.is_empty());
+        assert_eq!(loaded.fonts[0].scripts, vec![Script::Latin]);
+        assert_eq!(loaded.header.char_count, 62);
+        
+        let mut future = bincode::serialize(&GlyphDatabaseBuilder::new().build()).unwrap();
+        future[0..4].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert!(matches!(load_database(&compress(&future)), Err(DatabaseError::InvalidFormat(_))));
+    }
+    
+    #[test]
+    fn test_descriptors_rerank_candidates() {
+        use font_glyph::{BoundingBox, GlyphOutline, PathSegment};
+        
+        let rect = |width: f32| {
+            let mut outline = GlyphOutline::new('l', 1000);
+            outline.segments = vec![
+                PathSegment::MoveTo(0.0, 0.0),
+                PathSegment::LineTo(width, 0.0),
+                PathSegment::LineTo(width, 700.0),
+                PathSegment::LineTo(0.0, 700.0),
+                PathSegment::Close,
+            ];
+            outline.bounds = BoundingBox::new(0.0, 0.0, width, 700.0);
+            GlyphDescriptor::from_outline(&outline)
+        };
+        
+        // Same compact signature, so only the descriptors tell the fonts apart
+        let sig = MicroSignature::default();
+        let mut builder = GlyphDatabaseBuilder::new();
+        for (font_id, (family, width)) in [("Thin", 60.0), ("Heavy", 200.0)].into_iter().enumerate() {
+            builder.lsh_index.add_font(font_id as u16, &[('l', sig)]);
+            builder.fonts.push(FontEntry {
+                family: family.to_string(),
+                subfamily: None,
+                signatures: vec![('l', sig)],
+                descriptors: vec![('l', rect(width))],
+                scripts: vec![Script::Latin],
+            });
+        }
+        let db = builder.build();
+        
+        for (family, width) in [("Thin", 65.0), ("Heavy", 190.0)] {
+            let matches = db.find_matches_reranked(&sig, &rect(width), None, 2);
+            assert_eq!(matches[0].family, family);
+            
+            let matches = db.find_matches_multi_reranked(&[('l', sig)], &[('l', rect(width))], 2);
+            assert_eq!(matches[0].family, family);
+        }
+    }
+    
+    #[test]
+    fn test_queries_restricted_to_indexed_scripts() {
+        let sig = MicroSignature::default();
+        let mut builder = GlyphDatabaseBuilder::new().with_scripts(&[Script::Latin, Script::Cyrillic]);
+        for (font_id, (family, scripts)) in [("LatinOnly", vec![Script::Latin]), ("Both", vec![Script::Latin, Script::Cyrillic])]
+            .into_iter()
+            .enumerate()
+        {
+            let signatures = vec![('A', sig), ('Ж', sig)];
+            builder.lsh_index.add_font(font_id as u16, &signatures);
+            builder.fonts.push(FontEntry {
+                family: family.to_string(),
+                subfamily: None,
+                signatures,
+                descriptors: Vec::new(),
+                scripts,
+            });
+        }
+        let db = builder.build();
+        assert_eq!(db.header.char_count as usize, ALPHANUMERIC_CHARS.len() + Script::Cyrillic.default_characters().chars().count());
+        assert_eq!(db.fonts_per_script(), vec![(Script::Latin, 2), (Script::Cyrillic, 1)]);
+        
+        let families = |matches: Vec<MatchResult>| matches.into_iter().map(|m| m.family).collect::<Vec<_>>();
+        assert_eq!(families(db.find_matches_in_script(&sig, Script::Cyrillic, 5)), vec!["Both"]);
+        assert_eq!(families(db.find_matches_multi(&[('Ж', sig), ('7', sig)], 5)), vec!["Both"]);
+        assert_eq!(db.find_matches_multi(&[('A', sig)], 5).len(), 2);
+        assert!(db.find_matches_in_script(&sig, Script::Greek, 5).is_empty());
+        
+        let matched = db.find_matches_in_script(&sig, Script::Latin, 5);
+        assert!(matched.iter().all(|m| m.matched_char == Some('A')));
+    }
+    
+    #[test]
+    fn test_assignment_maximises_total_similarity() {
+        // Greedy would give row 0 column 0 and leave row 1 with 0.1
+        let similarities = vec![vec![0.9, 0.8], vec![0.85, 0.1]];
+        assert_eq!(assign_max_similarity(&similarities), vec![Some(1), Some(0)]);
+        
+        // More rows than columns: the worst fit is left out
+        let similarities = vec![vec![0.2], vec![0.9], vec![0.5]];
+        assert_eq!(assign_max_similarity(&similarities), vec![None, Some(0), None]);
+    }
+    
+    #[test]
+    fn test_unlabeled_glyphs_infer_characters() {
+        let glyph = |aspect_ratio: u8, curve_ratio: u8| MicroSignature { aspect_ratio, curve_ratio, ..MicroSignature::default() };
+        let (o, l, m) = (glyph(230, 250), glyph(40, 0), glyph(250, 120));
+        
+        let mut builder = GlyphDatabaseBuilder::new();
+        for (font_id, (family, signatures)) in [
+            ("Source", vec![('o', o), ('l', l), ('m', m)]),
+            ("Other", vec![('o', glyph(120, 60)), ('l', glyph(120, 60)), ('m', glyph(120, 60))]),
+        ].into_iter().enumerate() {
+            builder.lsh_index.add_font(font_id as u16, &signatures);
+            builder.fonts.push(FontEntry {
+                family: family.to_string(),
+                subfamily: None,
+                signatures,
+                descriptors: Vec::new(),
+                scripts: vec![Script::Latin],
+            });
+        }
+        let db = builder.build();
+        
+        // Slightly distorted copies, out of order and without their characters
+        let query: Vec<UnlabeledGlyph> = [glyph(245, 125), glyph(225, 245), glyph(45, 5)]
+            .into_iter()
+            .map(UnlabeledGlyph::from)
+            .collect();
+        let matches = db.find_matches_unlabeled(&query, Some(Script::Latin), 2);
+        
+        assert_eq!(matches[0].family, "Source");
+        assert_eq!(matches[0].inferred_chars, vec![Some('m'), Some('o'), Some('l')]);
+        assert!(matches[0].similarity > matches[1].similarity);
+    }
+    
+    #[test]
+    fn test_repeated_glyphs_share_a_character() {
+        let glyph = |aspect_ratio: u8, curve_ratio: u8| MicroSignature { aspect_ratio, curve_ratio, ..MicroSignature::default() };
+        let alphabet = [
+            ('H', glyph(200, 0)), ('e', glyph(90, 220)), ('l', glyph(20, 0)),
+            ('o', glyph(250, 250)), ('a', glyph(160, 140)), ('p', glyph(100, 60)),
+        ];
+        
+        let mut builder = GlyphDatabaseBuilder::new();
+        for (font_id, (family, signatures)) in [
+            ("Source", alphabet.to_vec()),
+            ("Other", alphabet.iter().map(|(ch, _)| (*ch, glyph(130, 100))).collect()),
+        ].into_iter().enumerate() {
+            builder.lsh_index.add_font(font_id as u16, &signatures);
+            builder.fonts.push(FontEntry {
+                family: family.to_string(),
+                subfamily: None,
+                signatures,
+                descriptors: Vec::new(),
+                scripts: vec![Script::Latin],
+            });
+        }
+        let db = builder.build();
+        
+        // Each occurrence slightly distorted, as glyphs cut from an image would be
+        let text = |word: &str| -> Vec<UnlabeledGlyph> {
+            word.chars().enumerate()
+                .map(|(i, ch)| {
+                    let (_, sig) = alphabet.iter().find(|(c, _)| *c == ch).unwrap();
+                    let jitter = (i % 2) as u8 * 3;
+                    UnlabeledGlyph::from(glyph(sig.aspect_ratio + jitter, sig.curve_ratio.saturating_sub(jitter)))
+                })
+                .collect()
+        };
+        
+        for word in ["Hello", "appeal"] {
+            let matches = db.find_matches_unlabeled(&text(word), Some(Script::Latin), 2);
+            assert_eq!(matches[0].family, "Source");
+            let inferred: String = matches[0].inferred_chars.iter().map(|ch| ch.unwrap()).collect();
+            assert_eq!(inferred, word);
+            assert!(matches[0].similarity > SAME_GLYPH_SIMILARITY, "{}: {}", word, matches[0].similarity);
+        }
+        
+        // Distinct characters are never merged
+        assert_eq!(db.repeated_glyph_groups(&text("Help")), vec![0, 1, 2, 3]);
+        assert_eq!(db.repeated_glyph_groups(&text("appeal")), vec![0, 1, 1, 2, 0, 3]);
+    }
 }
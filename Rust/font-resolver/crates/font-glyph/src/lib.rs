@@ -6,10 +6,14 @@
 //! This crate provides:
 //! - `GlyphOutline` - Vector path representation extracted from font files
 //! - `MicroSignature` - 16-byte compact fingerprint for fast similarity matching
+//! - `GlyphDescriptor` - Richer versioned shape descriptor for re-ranking matches
 //! - `GlyphExtractor` - Extracts glyph outlines using ttf_parser
+//! - `Script` - Writing systems glyphs can be indexed for, with default character sets
+//! - `ShapeClassifier` - Labels a font's style (serif, sans, script, ...) from its outlines
 
 use std::path::Path;
 use serde::{Serialize, Deserialize};
+use font_core::{DescriptorWeights, ScoringProfile, ShapeClassification, SignatureWeights};
 
 // =============================================================================
 // PATH SEGMENT TYPES
@@ -248,6 +252,10 @@ impl SegmentStats {
 // MICRO SIGNATURE (16 bytes)
 // =============================================================================
 
+/// Stroke thickness, as a share of the em, that maps to the top of the `stroke_width`
+/// scale; regular text faces come out near 0.09, bold ones near 0.18
+const MAX_STROKE_EM: f32 = 0.25;
+
 /// Ultra-compact 16-byte glyph signature for fast similarity matching
 /// 
 /// This signature captures the essential visual characteristics of a glyph
@@ -275,7 +283,7 @@ pub struct MicroSignature {
     pub x_balance: u8,
     /// Vertical center of mass (0=bottom, 128=center, 255=top)
     pub y_balance: u8,
-    /// Estimated stroke width category (0-255)
+    /// Median stroke thickness as a share of the em, scaled so `MAX_STROKE_EM` is 255
     pub stroke_width: u8,
     /// Serif detection score (0=sans, 255=serif)
     pub serif_score: u8,
@@ -379,20 +387,21 @@ impl MicroSignature {
         }
     }
     
-    /// Estimate stroke width category
+    /// Estimate stroke width category from the median stroke thickness as a share of
+    /// the em, so it is comparable across fonts (255 = `MAX_STROKE_EM` or thicker)
     fn estimate_stroke_width(outline: &GlyphOutline) -> u8 {
-        // Use ratio of bounding box area to control point count as proxy
-        let area = outline.bounds.width() * outline.bounds.height();
-        let stats = outline.segment_stats();
-        let total_segments = stats.line_count + stats.quad_count + stats.cubic_count;
+        if outline.units_per_em == 0 {
+            return 0;
+        }
         
-        if total_segments > 0 && area > 0.0 {
-            let ratio = area / total_segments as f32;
-            // Normalize to 0-255 range (higher = thicker strokes)
-            (ratio / 100.0).min(1.0).max(0.0) as u8 * 255
-        } else {
-            128
+        let edges = Edges::from_outline(outline);
+        let mut thicknesses = GlyphDescriptor::stroke_thicknesses(&edges, &outline.bounds);
+        if thicknesses.is_empty() {
+            return 0;
         }
+        thicknesses.sort_by(|a, b| a.total_cmp(b));
+        let median = thicknesses[thicknesses.len() / 2] / outline.units_per_em as f32;
+        ((median / MAX_STROKE_EM).clamp(0.0, 1.0) * 255.0) as u8
     }
     
     /// Estimate serif score based on segment patterns
@@ -489,38 +498,27 @@ impl MicroSignature {
     }
     
     /// Calculate similarity score between two signatures (0.0 - 1.0)
-    /// Uses weighted Manhattan distance converted to similarity
+    /// Uses weighted Manhattan distance converted to similarity, with the embedded scoring profile's weights
     pub fn similarity(&self, other: &Self) -> f32 {
-        // Weights for each feature (sum to 1.0)
-        const WEIGHTS: [f32; 12] = [
-            0.10,  // aspect_ratio
-            0.05,  // density
-            0.10,  // quadrant_nw
-            0.10,  // quadrant_ne
-            0.10,  // quadrant_sw
-            0.10,  // quadrant_se
-            0.10,  // curve_ratio
-            0.05,  // point_count
-            0.08,  // x_balance
-            0.08,  // y_balance
-            0.07,  // stroke_width
-            0.07,  // serif_score
-        ];
-        
+        self.similarity_with(other, &ScoringProfile::shared().signature)
+    }
+    
+    /// Like `similarity`, with explicit feature weights
+    pub fn similarity_with(&self, other: &Self, weights: &SignatureWeights) -> f32 {
         let self_bytes = self.to_bytes();
         let other_bytes = other.to_bytes();
         
         let mut weighted_distance = 0.0_f32;
         
-        for i in 0..12 {
+        for (i, weight) in weights.features.iter().enumerate() {
             let diff = (self_bytes[i] as i16 - other_bytes[i] as i16).abs() as f32;
             let normalized_diff = diff / 255.0;  // Normalize to 0-1
-            weighted_distance += normalized_diff * WEIGHTS[i];
+            weighted_distance += normalized_diff * weight;
         }
         
         // Also consider feature hash (XOR distance)
         let hash_diff = (self.feature_hash ^ other.feature_hash).count_ones() as f32 / 16.0;
-        weighted_distance += hash_diff * 0.05;
+        weighted_distance += hash_diff * weights.feature_hash;
         
         // Convert distance to similarity (1.0 = identical, 0.0 = completely different)
         (1.0 - weighted_distance).max(0.0)
@@ -533,6 +531,433 @@ impl Default for MicroSignature {
     }
 }
 
+// =============================================================================
+// GLYPH DESCRIPTOR (versioned, variable length)
+// =============================================================================
+
+/// Version written by `GlyphDescriptor::to_bytes`
+pub const DESCRIPTOR_VERSION: u8 = 1;
+
+/// Fourier magnitudes kept from the outer contour's centroid-distance signature
+pub const FOURIER_COEFFICIENTS: usize = 12;
+
+/// Counters recorded by area, largest first
+pub const MAX_COUNTERS: usize = 3;
+
+/// Points the outer contour is resampled to before the Fourier transform
+const CONTOUR_SAMPLES: usize = 64;
+
+/// Cells per side of the finest ink density grid; the coarser grids halve it
+const GRID_SIZE: usize = 8;
+
+/// Horizontal scan lines per row of the finest grid
+const SCANS_PER_ROW: usize = 4;
+
+/// Encoded size of a version 1 descriptor
+const DESCRIPTOR_V1_LEN: usize = 1 + FOURIER_COEFFICIENTS * 4 + 4 + 16 + 64 + 4 + 4 + 1 + MAX_COUNTERS * 4;
+
+/// Richer glyph descriptor for re-ranking candidates found with `MicroSignature`
+/// 
+/// Unlike the signature it works on the filled shape rather than on segment
+/// counts, so it doesn't depend on how the outline was drawn. Serialized in a
+/// versioned byte layout (see `to_bytes`), which serde uses as well.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "Vec<u8>", try_from = "Vec<u8>")]
+pub struct GlyphDescriptor {
+    /// Magnitudes of the Fourier transform of the outer contour's distance from
+    /// its centroid, harmonics 1.. divided by the mean distance; invariant to
+    /// position, scale, rotation and starting point
+    pub fourier: [f32; FOURIER_COEFFICIENTS],
+    /// Ink coverage of a 2×2 grid over the bounding box, row by row from the top (0-255)
+    pub grid_2: [u8; 4],
+    /// Ink coverage of a 4×4 grid
+    pub grid_4: [u8; 16],
+    /// Ink coverage of an 8×8 grid
+    pub grid_8: [u8; 64],
+    /// Median stroke thickness relative to the larger side of the bounding box
+    pub stroke_width: f32,
+    /// 1 - thin / thick stroke (10th and 90th percentile thickness)
+    pub stroke_contrast: f32,
+    /// Number of enclosed counters
+    pub counter_count: u8,
+    /// Areas of the largest counters relative to the bounding box, largest first
+    pub counter_areas: [f32; MAX_COUNTERS],
+}
+
+impl GlyphDescriptor {
+    /// Describe a glyph outline; empty outlines give an all-zero descriptor
+    pub fn from_outline(outline: &GlyphOutline) -> Self {
+        let mut descriptor = Self {
+            fourier: [0.0; FOURIER_COEFFICIENTS],
+            grid_2: [0; 4],
+            grid_4: [0; 16],
+            grid_8: [0; 64],
+            stroke_width: 0.0,
+            stroke_contrast: 0.0,
+            counter_count: 0,
+            counter_areas: [0.0; MAX_COUNTERS],
+        };
+        let bounds = outline.bounds;
+        if outline.segments.is_empty() || bounds.width() <= 0.0 || bounds.height() <= 0.0 {
+            return descriptor;
+        }
+        
+        let contours = contours(outline);
+        let areas: Vec<f32> = contours.iter().map(|contour| polygon_area(contour)).collect();
+        let Some(outer) = (0..contours.len()).max_by(|&a, &b| areas[a].abs().total_cmp(&areas[b].abs())) else {
+            return descriptor;
+        };
+        
+        descriptor.fourier = Self::fourier(&contours[outer]);
+        
+        // Counters wind the other way from the outer contour
+        let box_area = bounds.width() * bounds.height();
+        let mut counters: Vec<f32> = areas.iter()
+            .filter(|area| area.signum() != areas[outer].signum() && **area != 0.0)
+            .map(|area| area.abs() / box_area)
+            .collect();
+        counters.sort_by(|a, b| b.total_cmp(a));
+        descriptor.counter_count = counters.len().min(255) as u8;
+        for (slot, area) in descriptor.counter_areas.iter_mut().zip(counters) {
+            *slot = area.min(1.0);
+        }
+        
+        let edges = Edges::from_outline(outline);
+        let fine = Self::ink_grid(&edges, &bounds);
+        let mut grid_4 = [0.0_f32; 16];
+        let mut grid_2 = [0.0_f32; 4];
+        for row in 0..GRID_SIZE {
+            for col in 0..GRID_SIZE {
+                let coverage = fine[row * GRID_SIZE + col];
+                descriptor.grid_8[row * GRID_SIZE + col] = to_byte(coverage);
+                grid_4[(row / 2) * 4 + col / 2] += coverage / 4.0;
+                grid_2[(row / 4) * 2 + col / 4] += coverage / 16.0;
+            }
+        }
+        descriptor.grid_4 = grid_4.map(to_byte);
+        descriptor.grid_2 = grid_2.map(to_byte);
+        
+        let mut thicknesses = Self::stroke_thicknesses(&edges, &bounds);
+        if !thicknesses.is_empty() {
+            thicknesses.sort_by(|a, b| a.total_cmp(b));
+            let percentile = |p: f32| thicknesses[((thicknesses.len() - 1) as f32 * p).round() as usize];
+            descriptor.stroke_width = percentile(0.5) / bounds.width().max(bounds.height());
+            if percentile(0.9) > 0.0 {
+                descriptor.stroke_contrast = 1.0 - percentile(0.1) / percentile(0.9);
+            }
+        }
+        
+        descriptor
+    }
+    
+    /// Centroid-distance Fourier magnitudes of a closed polygon
+    fn fourier(contour: &[(f32, f32)]) -> [f32; FOURIER_COEFFICIENTS] {
+        let mut coefficients = [0.0; FOURIER_COEFFICIENTS];
+        
+        // Resample at equal arc length so the transform doesn't depend on how
+        // densely each part of the contour was drawn
+        let lengths: Vec<f32> = (0..contour.len())
+            .map(|i| {
+                let (a, b) = (contour[i], contour[(i + 1) % contour.len()]);
+                ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt()
+            })
+            .collect();
+        let perimeter: f32 = lengths.iter().sum();
+        if perimeter <= 0.0 {
+            return coefficients;
+        }
+        
+        let mut samples = Vec::with_capacity(CONTOUR_SAMPLES);
+        let mut edge = 0;
+        let mut walked = 0.0;
+        for k in 0..CONTOUR_SAMPLES {
+            let target = perimeter * k as f32 / CONTOUR_SAMPLES as f32;
+            while edge < lengths.len() - 1 && walked + lengths[edge] < target {
+                walked += lengths[edge];
+                edge += 1;
+            }
+            let (a, b) = (contour[edge], contour[(edge + 1) % contour.len()]);
+            let t = if lengths[edge] > 0.0 { ((target - walked) / lengths[edge]).clamp(0.0, 1.0) } else { 0.0 };
+            samples.push((a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t));
+        }
+        
+        let centroid = samples.iter()
+            .fold((0.0, 0.0), |sum, p| (sum.0 + p.0, sum.1 + p.1));
+        let centroid = (centroid.0 / CONTOUR_SAMPLES as f32, centroid.1 / CONTOUR_SAMPLES as f32);
+        let distances: Vec<f32> = samples.iter()
+            .map(|p| ((p.0 - centroid.0).powi(2) + (p.1 - centroid.1).powi(2)).sqrt())
+            .collect();
+        let mean = distances.iter().sum::<f32>() / CONTOUR_SAMPLES as f32;
+        if mean <= 0.0 {
+            return coefficients;
+        }
+        
+        for (harmonic, coefficient) in coefficients.iter_mut().enumerate() {
+            let frequency = (harmonic + 1) as f32 * std::f32::consts::TAU / CONTOUR_SAMPLES as f32;
+            let (mut re, mut im) = (0.0, 0.0);
+            for (n, distance) in distances.iter().enumerate() {
+                re += distance * (frequency * n as f32).cos();
+                im -= distance * (frequency * n as f32).sin();
+            }
+            *coefficient = (re * re + im * im).sqrt() / CONTOUR_SAMPLES as f32 / mean;
+        }
+        
+        coefficients
+    }
+    
+    /// Share of each cell of a `GRID_SIZE`² grid over `bounds` covered by ink,
+    /// row by row from the top
+    fn ink_grid(edges: &Edges, bounds: &BoundingBox) -> [f32; GRID_SIZE * GRID_SIZE] {
+        let mut grid = [0.0; GRID_SIZE * GRID_SIZE];
+        let cell_width = bounds.width() / GRID_SIZE as f32;
+        let scans = GRID_SIZE * SCANS_PER_ROW;
+        
+        for scan in 0..scans {
+            let y = bounds.y_max - bounds.height() * (scan as f32 + 0.5) / scans as f32;
+            let row = scan / SCANS_PER_ROW;
+            let origin = bounds.x_min - 1.0;
+            for (start, end) in edges.runs((origin, y), (1.0, 0.0)) {
+                let (start, end) = (origin + start - bounds.x_min, origin + end - bounds.x_min);
+                for col in 0..GRID_SIZE {
+                    let (left, right) = (col as f32 * cell_width, (col + 1) as f32 * cell_width);
+                    let overlap = end.min(right) - start.max(left);
+                    if overlap > 0.0 {
+                        grid[row * GRID_SIZE + col] += overlap / cell_width / SCANS_PER_ROW as f32;
+                    }
+                }
+            }
+        }
+        
+        grid.map(|coverage| coverage.min(1.0))
+    }
+    
+    /// Stroke thickness sampled at the middle of every horizontal ink run, as the
+    /// shorter of the horizontal and vertical runs through that point
+    fn stroke_thicknesses(edges: &Edges, bounds: &BoundingBox) -> Vec<f32> {
+        let scans = GRID_SIZE * SCANS_PER_ROW;
+        let mut thicknesses = Vec::new();
+        
+        for scan in 0..scans {
+            let y = bounds.y_max - bounds.height() * (scan as f32 + 0.5) / scans as f32;
+            let origin = bounds.x_min - 1.0;
+            for (start, end) in edges.runs((origin, y), (1.0, 0.0)) {
+                let x = origin + (start + end) / 2.0;
+                let below = bounds.y_min - 1.0;
+                let vertical = edges.runs((x, below), (0.0, 1.0)).into_iter()
+                    .find(|(v_start, v_end)| *v_start <= y - below && y - below <= *v_end)
+                    .map(|(v_start, v_end)| v_end - v_start)
+                    .unwrap_or(f32::MAX);
+                thicknesses.push((end - start).min(vertical));
+            }
+        }
+        
+        thicknesses
+    }
+    
+    /// Similarity between two descriptors (0.0 - 1.0), with the embedded scoring profile's weights
+    pub fn similarity(&self, other: &Self) -> f32 {
+        self.similarity_with(other, &ScoringProfile::shared().descriptor)
+    }
+    
+    /// Like `similarity`, with explicit component weights
+    pub fn similarity_with(&self, other: &Self, weights: &DescriptorWeights) -> f32 {
+        // Bray-Curtis similarity, so it doesn't matter how large the harmonics are overall
+        let difference: f32 = self.fourier.iter().zip(&other.fourier).map(|(a, b)| (a - b).abs()).sum();
+        let total: f32 = self.fourier.iter().zip(&other.fourier).map(|(a, b)| a + b).sum();
+        let fourier = if total > 0.0 { 1.0 - difference / total } else { 1.0 };
+        
+        let grid = |a: &[u8], b: &[u8]| {
+            let difference: u32 = a.iter().zip(b).map(|(a, b)| a.abs_diff(*b) as u32).sum();
+            1.0 - difference as f32 / (a.len() as f32 * 255.0)
+        };
+        let grids = [
+            grid(&self.grid_2, &other.grid_2),
+            grid(&self.grid_4, &other.grid_4),
+            grid(&self.grid_8, &other.grid_8),
+        ];
+        
+        let widest = self.stroke_width.max(other.stroke_width);
+        let stroke_width = if widest > 0.0 { self.stroke_width.min(other.stroke_width) / widest } else { 1.0 };
+        let stroke_contrast = 1.0 - (self.stroke_contrast - other.stroke_contrast).abs().min(1.0);
+        
+        let area_difference: f32 = self.counter_areas.iter().zip(&other.counter_areas).map(|(a, b)| (a - b).abs()).sum();
+        let same_count = if self.counter_count == other.counter_count { 1.0 } else { 0.0 };
+        let counters = 0.5 * same_count + 0.5 * (1.0 - area_difference.min(1.0));
+        
+        let score = weights.fourier * fourier
+            + weights.grid.iter().zip(grids).map(|(weight, score)| weight * score).sum::<f32>()
+            + weights.stroke_width * stroke_width
+            + weights.stroke_contrast * stroke_contrast
+            + weights.counters * counters;
+        score.clamp(0.0, 1.0)
+    }
+    
+    /// Encode as `DESCRIPTOR_VERSION` followed by the fields in declaration order,
+    /// floats as little-endian `f32`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(DESCRIPTOR_V1_LEN);
+        bytes.push(DESCRIPTOR_VERSION);
+        for value in self.fourier {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&self.grid_2);
+        bytes.extend_from_slice(&self.grid_4);
+        bytes.extend_from_slice(&self.grid_8);
+        bytes.extend_from_slice(&self.stroke_width.to_le_bytes());
+        bytes.extend_from_slice(&self.stroke_contrast.to_le_bytes());
+        bytes.push(self.counter_count);
+        for value in self.counter_areas {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+    
+    /// Decode bytes written by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, GlyphError> {
+        match bytes.first() {
+            Some(&DESCRIPTOR_VERSION) if bytes.len() == DESCRIPTOR_V1_LEN => {}
+            Some(&DESCRIPTOR_VERSION) => {
+                return Err(GlyphError::ParseError(format!(
+                    "Glyph descriptor is {} bytes, expected {}", bytes.len(), DESCRIPTOR_V1_LEN
+                )));
+            }
+            Some(version) => {
+                return Err(GlyphError::ParseError(format!("Unsupported glyph descriptor version {}", version)));
+            }
+            None => return Err(GlyphError::ParseError("Empty glyph descriptor".to_string())),
+        }
+        
+        let mut rest = &bytes[1..];
+        let fourier = std::array::from_fn(|_| read_f32(&mut rest));
+        let grid_2 = take(&mut rest, 4).try_into().expect("length checked above");
+        let grid_4 = take(&mut rest, 16).try_into().expect("length checked above");
+        let grid_8 = take(&mut rest, 64).try_into().expect("length checked above");
+        let stroke_width = read_f32(&mut rest);
+        let stroke_contrast = read_f32(&mut rest);
+        let counter_count = take(&mut rest, 1)[0];
+        let counter_areas = std::array::from_fn(|_| read_f32(&mut rest));
+        
+        Ok(Self {
+            fourier,
+            grid_2,
+            grid_4,
+            grid_8,
+            stroke_width,
+            stroke_contrast,
+            counter_count,
+            counter_areas,
+        })
+    }
+}
+
+/// Quantize a share in 0.0 - 1.0 to 0-255
+fn to_byte(share: f32) -> u8 {
+    (share.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Split `len` bytes off the front of `bytes`
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> &'a [u8] {
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    head
+}
+
+fn read_f32(bytes: &mut &[u8]) -> f32 {
+    f32::from_le_bytes(take(bytes, 4).try_into().expect("four bytes"))
+}
+
+impl From<GlyphDescriptor> for Vec<u8> {
+    fn from(descriptor: GlyphDescriptor) -> Self {
+        descriptor.to_bytes()
+    }
+}
+
+impl TryFrom<Vec<u8>> for GlyphDescriptor {
+    type Error = GlyphError;
+    
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::from_bytes(&bytes)
+    }
+}
+
+// =============================================================================
+// SCRIPTS
+// =============================================================================
+
+/// Writing systems a font's glyphs can be indexed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Arabic,
+    Devanagari,
+    /// Han ideographs, kana and Hangul
+    Cjk,
+}
+
+impl Script {
+    pub const ALL: [Script; 6] = [
+        Script::Latin,
+        Script::Greek,
+        Script::Cyrillic,
+        Script::Arabic,
+        Script::Devanagari,
+        Script::Cjk,
+    ];
+    
+    /// Characters indexed for the script unless a database is configured otherwise.
+    /// Arabic letters are the isolated forms the cmap maps them to, since glyphs
+    /// are looked up without shaping.
+    pub fn default_characters(&self) -> &'static str {
+        match self {
+            Script::Latin => "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+            Script::Greek => "ΑΒΓΔΕΖΗΘΙΚΛΜΝΞΟΠΡΣΤΥΦΧΨΩαβγδεζηθικλμνξοπρστυφχψω",
+            Script::Cyrillic => "АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯабвгдеёжзийклмнопрстуфхцчшщъыьэюя",
+            Script::Arabic => "ابتثجحخدذرزسشصضطظعغفقكلمنهوي٠١٢٣٤٥٦٧٨٩",
+            Script::Devanagari => "अआइईउऊऋएऐओऔकखगघङचछजझञटठडढणतथदधनपफबभमयरलवशषसह०१२३४५६७८९",
+            Script::Cjk => "的一是不了人我在有他这中大来上国个到说们为子和你地出道也时年永東京語あいうえおかきくけこアイウエオカキクケコ한국어",
+        }
+    }
+    
+    /// Script a character belongs to; `None` for characters shared between
+    /// scripts, such as ASCII digits and punctuation
+    pub fn of(character: char) -> Option<Script> {
+        match character as u32 {
+            0x41..=0x5A | 0x61..=0x7A | 0xC0..=0x24F | 0x1E00..=0x1EFF => Some(Script::Latin),
+            0x370..=0x3FF | 0x1F00..=0x1FFF => Some(Script::Greek),
+            0x400..=0x52F => Some(Script::Cyrillic),
+            0x600..=0x6FF | 0x750..=0x77F | 0xFB50..=0xFDFF | 0xFE70..=0xFEFF => Some(Script::Arabic),
+            0x900..=0x97F => Some(Script::Devanagari),
+            0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF => Some(Script::Cjk),
+            _ => None,
+        }
+    }
+    
+    /// Lowercase name, as used on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Script::Latin => "latin",
+            Script::Greek => "greek",
+            Script::Cyrillic => "cyrillic",
+            Script::Arabic => "arabic",
+            Script::Devanagari => "devanagari",
+            Script::Cjk => "cjk",
+        }
+    }
+    
+    /// Script with the given `name`, ignoring case
+    pub fn from_name(name: &str) -> Option<Script> {
+        Script::ALL.into_iter().find(|script| script.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+impl std::fmt::Display for Script {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 // =============================================================================
 // GLYPH EXTRACTOR
 // =============================================================================
@@ -616,6 +1041,11 @@ impl GlyphExtractor {
         let face = ttf_parser::Face::parse(font_data, 0)
             .map_err(|e| GlyphError::ParseError(format!("Failed to parse font: {:?}", e)))?;
         
+        self.extract_from_face(&face, character)
+    }
+    
+    /// Extract glyph outline from an already parsed face
+    pub fn extract_from_face(&self, face: &ttf_parser::Face, character: char) -> Result<GlyphOutline, GlyphError> {
         let glyph_id = face.glyph_index(character)
             .ok_or_else(|| GlyphError::GlyphNotFound(character))?;
         
@@ -646,19 +1076,24 @@ impl GlyphExtractor {
         Ok(outline)
     }
     
-    /// Extract signatures for multiple characters from a font file
-    pub fn extract_signatures<P: AsRef<Path>>(&self, font_path: P, characters: &str) -> Result<Vec<(char, MicroSignature)>, GlyphError> {
+    /// Extract outlines for multiple characters from a font file, skipping those it lacks
+    pub fn extract_outlines<P: AsRef<Path>>(&self, font_path: P, characters: &str) -> Result<Vec<GlyphOutline>, GlyphError> {
         let font_data = std::fs::read(font_path.as_ref())
             .map_err(|e| GlyphError::IoError(e.to_string()))?;
         
-        let mut signatures = Vec::new();
+        self.extract_outlines_from_data(&font_data, characters)
+    }
+    
+    /// Extract outlines for multiple characters from font data bytes, skipping those it lacks
+    pub fn extract_outlines_from_data(&self, font_data: &[u8], characters: &str) -> Result<Vec<GlyphOutline>, GlyphError> {
+        let face = ttf_parser::Face::parse(font_data, 0)
+            .map_err(|e| GlyphError::ParseError(format!("Failed to parse font: {:?}", e)))?;
+        
+        let mut outlines = Vec::new();
         
         for ch in characters.chars() {
-            match self.extract_from_data(&font_data, ch) {
-                Ok(outline) => {
-                    let sig = MicroSignature::from_outline(&outline);
-                    signatures.push((ch, sig));
-                }
+            match self.extract_from_face(&face, ch) {
+                Ok(outline) => outlines.push(outline),
                 Err(GlyphError::GlyphNotFound(_)) => {
                     // Skip characters not in the font
                     continue;
@@ -667,13 +1102,28 @@ impl GlyphExtractor {
             }
         }
         
-        Ok(signatures)
+        Ok(outlines)
+    }
+    
+    /// Extract signatures for multiple characters from a font file
+    pub fn extract_signatures<P: AsRef<Path>>(&self, font_path: P, characters: &str) -> Result<Vec<(char, MicroSignature)>, GlyphError> {
+        Ok(self.extract_outlines(font_path, characters)?
+            .iter()
+            .map(|outline| (outline.character, MicroSignature::from_outline(outline)))
+            .collect())
+    }
+    
+    /// Extract rich descriptors for multiple characters from a font file
+    pub fn extract_descriptors<P: AsRef<Path>>(&self, font_path: P, characters: &str) -> Result<Vec<(char, GlyphDescriptor)>, GlyphError> {
+        Ok(self.extract_outlines(font_path, characters)?
+            .iter()
+            .map(|outline| (outline.character, GlyphDescriptor::from_outline(outline)))
+            .collect())
     }
     
     /// Extract signatures for all alphanumeric characters (A-Z, a-z, 0-9)
     pub fn extract_alphanumeric_signatures<P: AsRef<Path>>(&self, font_path: P) -> Result<Vec<(char, MicroSignature)>, GlyphError> {
-        const ALPHANUMERIC: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
-        self.extract_signatures(font_path, ALPHANUMERIC)
+        self.extract_signatures(font_path, Script::Latin.default_characters())
     }
 }
 
@@ -683,6 +1133,481 @@ impl Default for GlyphExtractor {
     }
 }
 
+// =============================================================================
+// SHAPE CLASSIFIER
+// =============================================================================
+
+/// Glyphs measured by `ShapeClassifier`; 'i' and 'm' only contribute advance widths
+pub const CLASSIFIER_CHARS: &str = "oneaHim";
+
+/// Line segments each curve is flattened into before measuring
+const CURVE_STEPS: usize = 8;
+
+/// Directions sampled around the counter of 'o' (every 2.5 degrees over a half turn)
+const BOWL_DIRECTIONS: usize = 72;
+
+/// Style features measured on key glyphs; `None` when the glyph is missing or
+/// its shape couldn't be measured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShapeFeatures {
+    /// 1 - thinnest / thickest stroke of 'o' (0.0 = monoline)
+    pub stroke_contrast: Option<f32>,
+    /// Tilt of the thin-stroke axis of 'o' from vertical, in degrees; positive
+    /// leans left at the top, as in broad-nib (old-style and humanist) designs
+    pub stress_angle: Option<f32>,
+    /// Widest ink at the foot and top of 'H' relative to its stem width
+    pub serif_spread: Option<f32>,
+    /// Height of the serifs of 'H' relative to its stem width
+    pub serif_thickness: Option<f32>,
+    /// Share of directions out of the lower counter of 'e' that escape without hitting ink
+    pub aperture: Option<f32>,
+    /// Height of 'n' relative to 'H'
+    pub x_height_ratio: Option<f32>,
+    /// Width / height of 'o'
+    pub roundness: Option<f32>,
+    /// Whether 'a' has a single bowl
+    pub single_story_a: Option<bool>,
+    /// Curve share of the segments of 'o' (low for the broken bowls of blackletter)
+    pub bowl_curve_ratio: Option<f32>,
+    /// Whether 'H' and 'n' stand on straight, near-vertical stems
+    pub straight_stems: Option<bool>,
+    /// Spread of the advance widths of the measured glyphs relative to the widest
+    pub advance_spread: Option<f32>,
+}
+
+impl ShapeFeatures {
+    /// Measure features from outlines of `CLASSIFIER_CHARS`; other characters are ignored
+    pub fn measure(outlines: &[GlyphOutline]) -> Self {
+        let glyph = |c: char| outlines.iter()
+            .find(|outline| outline.character == c && !outline.segments.is_empty() && outline.bounds.height() > 0.0);
+        
+        let mut features = ShapeFeatures::default();
+        
+        if let Some(o) = glyph('o') {
+            let edges = Edges::from_outline(o);
+            if let Some((contrast, stress)) = edges.bowl_stroke(&o.bounds) {
+                features.stroke_contrast = Some(contrast);
+                features.stress_angle = Some(stress);
+            }
+            features.roundness = Some(o.bounds.aspect_ratio());
+            features.bowl_curve_ratio = Some(o.segment_stats().curve_ratio());
+        }
+        
+        if let Some(h) = glyph('H') {
+            if let Some((spread, thickness)) = Edges::from_outline(h).serifs(&h.bounds) {
+                features.serif_spread = Some(spread);
+                features.serif_thickness = Some(thickness);
+            }
+            let n_straight = glyph('n').map(has_straight_stem);
+            features.straight_stems = Some(has_straight_stem(h) && n_straight.unwrap_or(true));
+            
+            if let Some(n) = glyph('n') {
+                features.x_height_ratio = Some(n.bounds.height() / h.bounds.height());
+            }
+        }
+        
+        if let Some(e) = glyph('e') {
+            features.aperture = Edges::from_outline(e).aperture(&e.bounds);
+        }
+        
+        if let Some(a) = glyph('a') {
+            let x = a.bounds.x_min + a.bounds.width() * 0.45;
+            let runs = Edges::from_outline(a).runs((x, a.bounds.y_min - 1.0), (0.0, 1.0));
+            features.single_story_a = match runs.len() {
+                2 => Some(true),
+                3 => Some(false),
+                _ => None,
+            };
+        }
+        
+        let advances: Vec<f32> = outlines.iter()
+            .filter(|outline| CLASSIFIER_CHARS.contains(outline.character))
+            .map(|outline| outline.advance_width)
+            .filter(|advance| *advance > 0.0)
+            .collect();
+        if advances.len() >= 3 {
+            let widest = advances.iter().cloned().fold(0.0, f32::max);
+            let narrowest = advances.iter().cloned().fold(f32::MAX, f32::min);
+            features.advance_spread = Some((widest - narrowest) / widest);
+        }
+        
+        features
+    }
+    
+    /// Per-label confidences; `None` when neither 'o' nor 'H' could be measured
+    pub fn classify(&self) -> Option<ShapeClassification> {
+        if self.stroke_contrast.is_none() && self.serif_spread.is_none() {
+            return None;
+        }
+        let contrast = self.stroke_contrast.unwrap_or(0.2);
+        let stress = self.stress_angle.unwrap_or(0.0).abs();
+        
+        let script: f32 = match self.straight_stems {
+            Some(false) => 0.9,
+            Some(true) => 0.0,
+            None => 0.3,
+        };
+        let blackletter = ramp_down(self.bowl_curve_ratio.unwrap_or(1.0), 0.2, 0.6) * ramp(contrast, 0.25, 0.5);
+        let other = script.max(blackletter);
+        
+        let serif_like = match self.serif_spread {
+            Some(spread) => ramp(spread, 1.15, 1.6),
+            // Without 'H', high contrast is the best hint of a serif design
+            None => ramp(contrast, 0.3, 0.6) * 0.6,
+        };
+        let serif = serif_like * (1.0 - other);
+        let sans_serif = (1.0 - serif_like) * (1.0 - other);
+        let slab_serif = serif
+            * ramp(self.serif_thickness.unwrap_or(0.0), 0.35, 0.7)
+            * ramp_down(contrast, 0.3, 0.55);
+        
+        // Split the sans-serif confidence between its kinds
+        let aperture = self.aperture.unwrap_or(0.2);
+        let single_story = self.single_story_a.map(|single| if single { 1.0 } else { 0.0 }).unwrap_or(0.5);
+        let roundness = self.roundness.unwrap_or(0.93);
+        let geometric = 0.55 * ramp(roundness, 0.9, 1.02) + 0.45 * single_story;
+        let humanist = 0.5 * ramp(aperture, 0.15, 0.35)
+            + 0.25 * ramp(stress, 5.0, 20.0)
+            + 0.25 * ramp_down(roundness, 0.85, 0.95);
+        let grotesque = 0.6 * ramp_down(aperture, 0.1, 0.3)
+            + 0.25 * (1.0 - single_story)
+            + 0.15 * ramp_down(stress, 5.0, 15.0);
+        let kinds_total = (geometric + humanist + grotesque).max(f32::EPSILON);
+        
+        let monospace = self.advance_spread.map(|spread| ramp_down(spread, 0.0, 0.03)).unwrap_or(0.0);
+        
+        Some(ShapeClassification {
+            serif,
+            slab_serif,
+            sans_serif,
+            geometric: sans_serif * geometric / kinds_total,
+            humanist: sans_serif * humanist / kinds_total,
+            grotesque: sans_serif * grotesque / kinds_total,
+            script,
+            blackletter,
+            monospace,
+        })
+    }
+}
+
+/// Labels a font's style from the outlines of `CLASSIFIER_CHARS`
+pub struct ShapeClassifier;
+
+impl ShapeClassifier {
+    /// Create a new shape classifier
+    pub fn new() -> Self {
+        Self
+    }
+    
+    /// Classify the font in a file; `Ok(None)` when it lacks the Latin glyphs to measure
+    pub fn classify_file<P: AsRef<Path>>(&self, font_path: P) -> Result<Option<ShapeClassification>, GlyphError> {
+        let font_data = std::fs::read(font_path.as_ref())
+            .map_err(|e| GlyphError::IoError(e.to_string()))?;
+        
+        self.classify_data(&font_data)
+    }
+    
+    /// Classify the font in raw font data
+    pub fn classify_data(&self, font_data: &[u8]) -> Result<Option<ShapeClassification>, GlyphError> {
+        let face = ttf_parser::Face::parse(font_data, 0)
+            .map_err(|e| GlyphError::ParseError(format!("Failed to parse font: {:?}", e)))?;
+        
+        Ok(self.classify_face(&face))
+    }
+    
+    /// Measure the style features of the font in a file
+    pub fn measure_file<P: AsRef<Path>>(&self, font_path: P) -> Result<ShapeFeatures, GlyphError> {
+        let font_data = std::fs::read(font_path.as_ref())
+            .map_err(|e| GlyphError::IoError(e.to_string()))?;
+        let face = ttf_parser::Face::parse(&font_data, 0)
+            .map_err(|e| GlyphError::ParseError(format!("Failed to parse font: {:?}", e)))?;
+        
+        Ok(self.measure_face(&face))
+    }
+    
+    /// Classify an already parsed face
+    pub fn classify_face(&self, face: &ttf_parser::Face) -> Option<ShapeClassification> {
+        self.measure_face(face).classify()
+    }
+    
+    /// Measure the style features of an already parsed face
+    pub fn measure_face(&self, face: &ttf_parser::Face) -> ShapeFeatures {
+        let extractor = GlyphExtractor::new();
+        let outlines: Vec<GlyphOutline> = CLASSIFIER_CHARS.chars()
+            .filter_map(|c| extractor.extract_from_face(face, c).ok())
+            .collect();
+        
+        ShapeFeatures::measure(&outlines)
+    }
+}
+
+impl Default for ShapeClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 0.0 at or below `low`, 1.0 at or above `high`, linear in between
+fn ramp(value: f32, low: f32, high: f32) -> f32 {
+    ((value - low) / (high - low)).clamp(0.0, 1.0)
+}
+
+/// 1.0 at or below `low`, 0.0 at or above `high`, linear in between
+fn ramp_down(value: f32, low: f32, high: f32) -> f32 {
+    1.0 - ramp(value, low, high)
+}
+
+fn cross(a: (f32, f32), b: (f32, f32)) -> f32 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+/// Whether the outline has a straight line segment that is close to vertical and
+/// spans at least 40% of the glyph height
+fn has_straight_stem(outline: &GlyphOutline) -> bool {
+    let min_length = outline.bounds.height() * 0.4;
+    let mut current = (0.0, 0.0);
+    
+    for seg in &outline.segments {
+        match *seg {
+            PathSegment::MoveTo(x, y) => current = (x, y),
+            PathSegment::LineTo(x, y) => {
+                let (dx, dy) = (x - current.0, y - current.1);
+                if dy.abs() >= min_length && dx.abs() <= dy.abs() * 0.25 {
+                    return true;
+                }
+                current = (x, y);
+            }
+            PathSegment::QuadTo { x, y, .. } | PathSegment::CurveTo { x, y, .. } => current = (x, y),
+            PathSegment::Close => {}
+        }
+    }
+    false
+}
+
+/// An outline flattened into line segments, for measuring strokes along scan lines
+struct Edges(Vec<((f32, f32), (f32, f32))>);
+
+/// The contours of an outline as closed polygons, with curves flattened into
+/// `CURVE_STEPS` line segments each
+fn contours(outline: &GlyphOutline) -> Vec<Vec<(f32, f32)>> {
+    let mut contours = Vec::new();
+    let mut points: Vec<(f32, f32)> = Vec::new();
+    let mut current = (0.0, 0.0);
+    
+    for seg in &outline.segments {
+        match *seg {
+            PathSegment::MoveTo(x, y) => {
+                if points.len() > 1 {
+                    contours.push(std::mem::take(&mut points));
+                }
+                points.clear();
+                current = (x, y);
+                points.push(current);
+            }
+            PathSegment::LineTo(x, y) => {
+                if points.is_empty() {
+                    points.push(current);
+                }
+                current = (x, y);
+                points.push(current);
+            }
+            PathSegment::QuadTo { cx, cy, x, y } => {
+                if points.is_empty() {
+                    points.push(current);
+                }
+                let p0 = current;
+                for step in 1..=CURVE_STEPS {
+                    let t = step as f32 / CURVE_STEPS as f32;
+                    let u = 1.0 - t;
+                    current = (
+                        u * u * p0.0 + 2.0 * u * t * cx + t * t * x,
+                        u * u * p0.1 + 2.0 * u * t * cy + t * t * y,
+                    );
+                    points.push(current);
+                }
+            }
+            PathSegment::CurveTo { cx1, cy1, cx2, cy2, x, y } => {
+                if points.is_empty() {
+                    points.push(current);
+                }
+                let p0 = current;
+                for step in 1..=CURVE_STEPS {
+                    let t = step as f32 / CURVE_STEPS as f32;
+                    let u = 1.0 - t;
+                    current = (
+                        u * u * u * p0.0 + 3.0 * u * u * t * cx1 + 3.0 * u * t * t * cx2 + t * t * t * x,
+                        u * u * u * p0.1 + 3.0 * u * u * t * cy1 + 3.0 * u * t * t * cy2 + t * t * t * y,
+                    );
+                    points.push(current);
+                }
+            }
+            PathSegment::Close => {
+                if points.len() > 1 {
+                    current = points[0];
+                    contours.push(std::mem::take(&mut points));
+                }
+            }
+        }
+    }
+    if points.len() > 1 {
+        contours.push(points);
+    }
+    
+    contours
+}
+
+/// Signed area of a closed polygon (positive when counter-clockwise)
+fn polygon_area(points: &[(f32, f32)]) -> f32 {
+    let mut twice_area = 0.0;
+    for (i, &a) in points.iter().enumerate() {
+        twice_area += cross(a, points[(i + 1) % points.len()]);
+    }
+    twice_area / 2.0
+}
+
+impl Edges {
+    fn from_outline(outline: &GlyphOutline) -> Self {
+        let mut edges = Vec::new();
+        
+        for contour in contours(outline) {
+            for (i, &a) in contour.iter().enumerate() {
+                let b = contour[(i + 1) % contour.len()];
+                if a != b {
+                    edges.push((a, b));
+                }
+            }
+        }
+        
+        Edges(edges)
+    }
+    
+    /// Ink runs `(start, end)` along the line `origin + t * dir`, as values of `t`,
+    /// using the non-zero winding rule so overlapping contours count once
+    fn runs(&self, origin: (f32, f32), dir: (f32, f32)) -> Vec<(f32, f32)> {
+        let mut crossings: Vec<(f32, i32)> = Vec::new();
+        
+        for &(a, b) in &self.0 {
+            let edge = (b.0 - a.0, b.1 - a.1);
+            let denom = cross(dir, edge);
+            if denom.abs() < f32::EPSILON {
+                continue;
+            }
+            let offset = (a.0 - origin.0, a.1 - origin.1);
+            let s = cross(offset, dir) / denom;
+            if (0.0..1.0).contains(&s) {
+                crossings.push((cross(offset, edge) / denom, if denom > 0.0 { 1 } else { -1 }));
+            }
+        }
+        crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        
+        let mut runs = Vec::new();
+        let mut winding = 0;
+        let mut start = 0.0;
+        for (t, direction) in crossings {
+            let was_inside = winding != 0;
+            winding += direction;
+            if !was_inside && winding != 0 {
+                start = t;
+            } else if was_inside && winding == 0 && t > start {
+                runs.push((start, t));
+            }
+        }
+        runs
+    }
+    
+    /// Widest run along a horizontal line at `y`
+    fn widest_at(&self, bounds: &BoundingBox, y: f32) -> f32 {
+        self.runs((bounds.x_min - 1.0, y), (1.0, 0.0)).iter()
+            .map(|(start, end)| end - start)
+            .fold(0.0, f32::max)
+    }
+    
+    /// Stroke contrast and stress angle of a bowl, from the wall thickness on
+    /// either side of its counter in every direction
+    fn bowl_stroke(&self, bounds: &BoundingBox) -> Option<(f32, f32)> {
+        let center = (bounds.center_x(), bounds.center_y());
+        let mut thinnest = (f32::MAX, 0.0);
+        let mut thickest = 0.0_f32;
+        let mut measured = 0;
+        
+        for step in 0..BOWL_DIRECTIONS {
+            let angle = step as f32 * 180.0 / BOWL_DIRECTIONS as f32;
+            let dir = (angle.to_radians().cos(), angle.to_radians().sin());
+            let runs = self.runs(center, dir);
+            
+            // One wall on either side of the counter
+            if runs.len() != 2 || runs[0].1 > 0.0 || runs[1].0 < 0.0 {
+                continue;
+            }
+            let thickness = ((runs[0].1 - runs[0].0) + (runs[1].1 - runs[1].0)) / 2.0;
+            if thickness < thinnest.0 {
+                thinnest = (thickness, angle);
+            }
+            thickest = thickest.max(thickness);
+            measured += 1;
+        }
+        
+        if measured < BOWL_DIRECTIONS / 2 || thickest <= 0.0 {
+            return None;
+        }
+        
+        let contrast = 1.0 - thinnest.0 / thickest;
+        Some((contrast, thinnest.1 - 90.0))
+    }
+    
+    /// How far the foot and top of a two-stemmed glyph spread past its stems,
+    /// and how tall that spread is, both relative to the stem width
+    fn serifs(&self, bounds: &BoundingBox) -> Option<(f32, f32)> {
+        let height = bounds.height();
+        let stems = self.runs((bounds.x_min - 1.0, bounds.y_min + height * 0.25), (1.0, 0.0));
+        if stems.len() != 2 {
+            return None;
+        }
+        let stem = stems.iter().map(|(start, end)| end - start).sum::<f32>() / 2.0;
+        if stem <= 0.0 {
+            return None;
+        }
+        
+        let foot = self.widest_at(bounds, bounds.y_min + height * 0.02);
+        let top = self.widest_at(bounds, bounds.y_max - height * 0.02);
+        let spread = (foot + top) / 2.0 / stem;
+        
+        // Walk up from the baseline until the ink narrows back to the stem
+        let step = height * 0.005;
+        let mut y = bounds.y_min + step;
+        while y < bounds.y_min + height * 0.25 && self.widest_at(bounds, y) > stem * 1.3 {
+            y += step;
+        }
+        let thickness = (y - bounds.y_min) / stem;
+        
+        Some((spread, thickness))
+    }
+    
+    /// Share of directions from the lower counter of an 'e' (between its bottom
+    /// stroke and crossbar) that leave the glyph without crossing ink
+    fn aperture(&self, bounds: &BoundingBox) -> Option<f32> {
+        let x = bounds.center_x();
+        let runs = self.runs((x, bounds.y_min - 1.0), (0.0, 1.0));
+        
+        // Bottom stroke, crossbar and top stroke
+        if runs.len() != 3 {
+            return None;
+        }
+        let origin = (x, bounds.y_min - 1.0 + (runs[0].1 + runs[1].0) / 2.0);
+        
+        // From straight down, round through the open side to 30 degrees above horizontal
+        let directions = 60;
+        let open = (0..=directions)
+            .filter(|step| {
+                let angle = (-90.0 + *step as f32 * 2.0).to_radians();
+                !self.runs(origin, (angle.cos(), angle.sin())).iter().any(|(_, end)| *end > 0.0)
+            })
+            .count();
+        
+        Some(open as f32 / (directions + 1) as f32)
+    }
+}
+
 // =============================================================================
 // ERRORS
 // =============================================================================
@@ -834,4 +1759,191 @@ mod tests {
         assert_eq!(stats.cubic_count, 0);
         assert_eq!(stats.total_control_points, 1);
     }
+    
+    fn rect(x_min: f32, y_min: f32, x_max: f32, y_max: f32) -> Vec<PathSegment> {
+        vec![
+            PathSegment::MoveTo(x_min, y_min),
+            PathSegment::LineTo(x_max, y_min),
+            PathSegment::LineTo(x_max, y_max),
+            PathSegment::LineTo(x_min, y_max),
+            PathSegment::Close,
+        ]
+    }
+    
+    /// Polygonal ellipse; clockwise contours cut counters out of counter-clockwise ones
+    fn ellipse(rx: f32, ry: f32, clockwise: bool) -> Vec<PathSegment> {
+        let points = 96;
+        let mut segments: Vec<PathSegment> = (0..points)
+            .map(|i| {
+                let mut angle = i as f32 / points as f32 * std::f32::consts::TAU;
+                if clockwise {
+                    angle = -angle;
+                }
+                let (x, y) = (rx * angle.cos(), ry * angle.sin());
+                if i == 0 { PathSegment::MoveTo(x, y) } else { PathSegment::LineTo(x, y) }
+            })
+            .collect();
+        segments.push(PathSegment::Close);
+        segments
+    }
+    
+    fn outline(character: char, segments: Vec<PathSegment>, advance_width: f32) -> GlyphOutline {
+        let mut builder = OutlineBuilder::new();
+        for seg in &segments {
+            match *seg {
+                PathSegment::MoveTo(x, y) | PathSegment::LineTo(x, y) => builder.update_bounds(x, y),
+                _ => {}
+            }
+        }
+        let mut outline = GlyphOutline::new(character, 1000);
+        outline.bounds = BoundingBox::new(builder.x_min, builder.y_min, builder.x_max, builder.y_max);
+        outline.segments = segments;
+        outline.advance_width = advance_width;
+        outline
+    }
+    
+    fn letter_h(serifs: bool) -> GlyphOutline {
+        let mut segments = [rect(0.0, 0.0, 80.0, 700.0), rect(420.0, 0.0, 500.0, 700.0), rect(0.0, 330.0, 500.0, 390.0)].concat();
+        if serifs {
+            for (x_min, x_max) in [(-80.0, 160.0), (340.0, 580.0)] {
+                segments.extend(rect(x_min, 0.0, x_max, 30.0));
+                segments.extend(rect(x_min, 670.0, x_max, 700.0));
+            }
+        }
+        outline('H', segments, 600.0)
+    }
+    
+    #[test]
+    fn test_micro_signature_stroke_width_is_per_em() {
+        // 80-unit stems on a 1000-unit em
+        let regular = MicroSignature::from_outline(&letter_h(false)).stroke_width;
+        assert_eq!(regular, (0.08 / MAX_STROKE_EM * 255.0) as u8);
+        
+        // Twice the stem on the same em is twice as wide
+        let bold = outline('H', [rect(0.0, 0.0, 160.0, 700.0), rect(340.0, 0.0, 500.0, 700.0), rect(0.0, 320.0, 500.0, 440.0)].concat(), 600.0);
+        let bold = MicroSignature::from_outline(&bold).stroke_width;
+        assert!((bold as i32 - 2 * regular as i32).abs() <= 2, "{} vs {}", bold, regular);
+        
+        // The same design on a 2048-unit em
+        let mut scaled = letter_h(false);
+        let scale = 2048.0 / 1000.0;
+        scaled.units_per_em = 2048;
+        scaled.bounds = BoundingBox::new(0.0, 0.0, 500.0 * scale, 700.0 * scale);
+        for segment in &mut scaled.segments {
+            if let PathSegment::MoveTo(x, y) | PathSegment::LineTo(x, y) = segment {
+                *x *= scale;
+                *y *= scale;
+            }
+        }
+        assert!((MicroSignature::from_outline(&scaled).stroke_width as i32 - regular as i32).abs() <= 1);
+    }
+    
+    #[test]
+    fn test_serifs_measured_on_h() {
+        let sans = ShapeFeatures::measure(&[letter_h(false)]);
+        let serif = ShapeFeatures::measure(&[letter_h(true)]);
+        
+        assert!((sans.serif_spread.unwrap() - 1.0).abs() < 0.01);
+        assert!((serif.serif_spread.unwrap() - 3.0).abs() < 0.01);
+        assert_eq!(sans.straight_stems, Some(true));
+        
+        let sans = sans.classify().unwrap();
+        let serif = serif.classify().unwrap();
+        assert_eq!(sans.primary().0, font_core::TypefaceStyle::SansSerif);
+        assert_eq!(serif.primary().0, font_core::TypefaceStyle::Serif);
+        assert!(serif.serif > 0.9 && sans.sans_serif > 0.9);
+    }
+    
+    #[test]
+    fn test_bowl_contrast_and_stress() {
+        // Side walls 100 units thick, top and bottom 30
+        let o = outline('o', [ellipse(300.0, 300.0, false), ellipse(200.0, 270.0, true)].concat(), 650.0);
+        let features = ShapeFeatures::measure(&[o]);
+        
+        let contrast = features.stroke_contrast.unwrap();
+        assert!((contrast - 0.7).abs() < 0.05, "contrast {}", contrast);
+        assert!(features.stress_angle.unwrap().abs() < 5.0);
+        
+        // A monoline ring has no contrast to speak of
+        let o = outline('o', [ellipse(300.0, 300.0, false), ellipse(220.0, 220.0, true)].concat(), 650.0);
+        assert!(ShapeFeatures::measure(&[o]).stroke_contrast.unwrap() < 0.05);
+    }
+    
+    #[test]
+    fn test_monospace_from_advances() {
+        let glyphs = |advances: [f32; 3]| -> Vec<GlyphOutline> {
+            ['H', 'i', 'm'].iter().zip(advances)
+                .map(|(&c, advance)| GlyphOutline { character: c, advance_width: advance, ..letter_h(false) })
+                .collect()
+        };
+        
+        let mono = ShapeFeatures::measure(&glyphs([600.0, 600.0, 600.0])).classify().unwrap();
+        let proportional = ShapeFeatures::measure(&glyphs([720.0, 280.0, 880.0])).classify().unwrap();
+        assert_eq!(mono.monospace, 1.0);
+        assert_eq!(proportional.monospace, 0.0);
+    }
+    
+    #[test]
+    fn test_descriptor_shape_features() {
+        let ring = GlyphDescriptor::from_outline(&outline('o', [ellipse(300.0, 300.0, false), ellipse(200.0, 200.0, true)].concat(), 650.0));
+        assert_eq!(ring.counter_count, 1);
+        // pi * 200² over a 600 × 600 box
+        assert!((ring.counter_areas[0] - 0.349).abs() < 0.01, "counter {}", ring.counter_areas[0]);
+        // A circle's distance from its centre doesn't vary
+        assert!(ring.fourier.iter().all(|c| *c < 0.01));
+        
+        let block = GlyphDescriptor::from_outline(&outline('l', rect(0.0, 0.0, 100.0, 700.0), 200.0));
+        assert_eq!(block.counter_count, 0);
+        assert!(block.grid_8.iter().chain(&block.grid_2).all(|cell| *cell == 255));
+        assert!((block.stroke_width - 100.0 / 700.0).abs() < 0.01);
+        assert!(block.stroke_contrast < 0.01);
+        
+        assert!((ring.similarity(&ring) - 1.0).abs() < 0.001);
+        assert!(ring.similarity(&block) < 0.7, "got {}", ring.similarity(&block));
+    }
+    
+    #[test]
+    fn test_descriptor_is_scale_invariant() {
+        let small = GlyphDescriptor::from_outline(&letter_h(true));
+        let mut large = letter_h(true);
+        for seg in &mut large.segments {
+            if let PathSegment::MoveTo(x, y) | PathSegment::LineTo(x, y) = seg {
+                *x *= 3.0;
+                *y *= 3.0;
+            }
+        }
+        large.bounds = BoundingBox::new(large.bounds.x_min * 3.0, 0.0, large.bounds.x_max * 3.0, 2100.0);
+        
+        assert!(small.similarity(&GlyphDescriptor::from_outline(&large)) > 0.99);
+    }
+    
+    #[test]
+    fn test_descriptor_bytes_roundtrip() {
+        let descriptor = GlyphDescriptor::from_outline(&letter_h(true));
+        let bytes = descriptor.to_bytes();
+        assert_eq!(bytes[0], DESCRIPTOR_VERSION);
+        assert_eq!(GlyphDescriptor::from_bytes(&bytes).unwrap(), descriptor);
+        
+        let mut future = bytes.clone();
+        future[0] = DESCRIPTOR_VERSION + 1;
+        assert!(GlyphDescriptor::from_bytes(&future).is_err());
+        assert!(GlyphDescriptor::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+    
+    #[test]
+    fn test_default_characters_belong_to_their_script() {
+        for script in Script::ALL {
+            for c in script.default_characters().chars() {
+                assert!(Script::of(c).is_none_or(|s| s == script), "{:?} in the {} set", c, script);
+            }
+        }
+        assert_eq!(Script::of('Ж'), Some(Script::Cyrillic));
+        assert_eq!(Script::of('7'), None);
+    }
+    
+    #[test]
+    fn test_classify_needs_key_glyphs() {
+        let only_i = outline('i', rect(0.0, 0.0, 80.0, 500.0), 280.0);
+        assert!(ShapeFeatures::measure(&[only_i]).classify().is_none());
+    }
 }
@@ -0,0 +1,635 @@
+use font_core::{FontClassification, FontDescriptor, FontMetrics, FontRequest};
+use ordered_float::OrderedFloat;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Links per node above layer 0; layer 0 allows twice as many
+pub const DEFAULT_MAX_LINKS: usize = 16;
+
+/// Candidate list size while inserting; larger builds a better graph, more slowly
+pub const DEFAULT_EF_CONSTRUCTION: usize = 100;
+
+/// Smallest candidate list used while searching
+const EF_SEARCH: usize = 64;
+
+/// Highest layer a node can be assigned to
+const MAX_LEVEL: usize = 16;
+
+/// Serif, sans-serif, script, decorative, symbol
+pub const CATEGORY_DIMENSIONS: usize = 5;
+
+/// PANOSE digits after the family kind
+pub const PANOSE_DIMENSIONS: usize = 9;
+
+/// x-height, cap height, ascender, descender and average width, per em
+pub const METRIC_DIMENSIONS: usize = 5;
+
+/// The twelve feature bytes of a glyph `MicroSignature`, averaged over a family
+pub const VISUAL_DIMENSIONS: usize = 12;
+
+// Relative weight of each feature group in the distance
+const WEIGHT_GROUP: f32 = 1.0;
+const ITALIC_GROUP: f32 = 0.5;
+const MONOSPACED_GROUP: f32 = 0.5;
+const CATEGORY_GROUP: f32 = 1.0;
+const PANOSE_GROUP: f32 = 0.75;
+const METRICS_GROUP: f32 = 0.5;
+const VISUAL_GROUP: f32 = 1.0;
+
+/// Squared difference charged for a group only one of the two fonts has
+const MISSING_TERM: f32 = 0.25;
+
+/// Differences at which a PANOSE digit, metric ratio or visual feature counts as entirely different
+const PANOSE_SCALE: f32 = 1.0 / 3.0;
+const METRIC_SCALE: f32 = 0.25;
+const VISUAL_SCALE: f32 = 0.25;
+
+/// Numeric description of a font's design, compared by `FontFeatures::distance`.
+///
+/// Groups a font has no data for are `None`; they are left out when neither font has them
+/// and charged a fixed difference when only one does.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FontFeatures {
+    /// (weight - 100) / 800
+    pub weight: f32,
+    pub italic: bool,
+    pub monospaced: bool,
+    /// Confidence per category, from the outline classifier or the OS/2 classification
+    pub category: Option<[f32; CATEGORY_DIMENSIONS]>,
+    /// PANOSE digits / 15, 0 meaning "any"
+    pub panose: Option<[f32; PANOSE_DIMENSIONS]>,
+    pub metrics: Option<[f32; METRIC_DIMENSIONS]>,
+    /// Glyph signature features / 255
+    pub visual: Option<[f32; VISUAL_DIMENSIONS]>,
+}
+
+impl FontFeatures {
+    pub fn from_descriptor(font: &FontDescriptor) -> Self {
+        let classification = font.classification.as_ref();
+        Self {
+            weight: weight_feature(font.weight),
+            italic: font.italic,
+            monospaced: font.monospaced,
+            category: classification.and_then(category_features),
+            panose: classification.and_then(panose_features),
+            metrics: font.metrics.as_ref().and_then(metric_features),
+            visual: None,
+        }
+    }
+
+    /// Features for a request, with the requested family's classification when it is known
+    pub fn from_request(request: &FontRequest, reference: Option<&FontClassification>) -> Self {
+        Self {
+            weight: weight_feature(request.weight),
+            italic: request.italic,
+            monospaced: request.monospaced,
+            category: reference.and_then(category_features),
+            panose: reference.and_then(panose_features),
+            metrics: None,
+            visual: None,
+        }
+    }
+
+    pub fn with_visual(mut self, embedding: [f32; VISUAL_DIMENSIONS]) -> Self {
+        self.visual = Some(embedding);
+        self
+    }
+
+    /// The same design at another weight and style
+    pub fn restyled(mut self, weight: u16, italic: bool) -> Self {
+        self.weight = weight_feature(weight);
+        self.italic = italic;
+        self
+    }
+
+    /// Whether both fonts have a design group (category, PANOSE, metrics or glyph shapes)
+    /// in common, i.e. whether their distance says anything about how they look
+    pub fn shares_design_features(&self, other: &Self) -> bool {
+        (self.category.is_some() && other.category.is_some())
+            || (self.panose.is_some() && other.panose.is_some())
+            || (self.metrics.is_some() && other.metrics.is_some())
+            || (self.visual.is_some() && other.visual.is_some())
+    }
+
+    /// Weighted root-mean-square difference over the groups either font has, from 0 (same) to 1
+    pub fn distance(&self, other: &Self) -> f32 {
+        let mut total = 0.0;
+        let mut weights = 0.0;
+        let mut add = |weight: f32, term: Option<f32>| {
+            if let Some(term) = term {
+                total += weight * term;
+                weights += weight;
+            }
+        };
+
+        add(WEIGHT_GROUP, Some((self.weight - other.weight).powi(2)));
+        add(ITALIC_GROUP, Some(if self.italic == other.italic { 0.0 } else { 1.0 }));
+        add(MONOSPACED_GROUP, Some(if self.monospaced == other.monospaced { 0.0 } else { 1.0 }));
+        // Two different one-hot categories are 2 apart
+        add(CATEGORY_GROUP, group_term(&self.category, &other.category, |a, b| {
+            (a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f32>() / 2.0).min(1.0)
+        }));
+        add(PANOSE_GROUP, group_term(&self.panose, &other.panose, |a, b| {
+            let compared: Vec<f32> = a.iter().zip(b)
+                .filter(|(x, y)| **x > 0.0 && **y > 0.0)
+                .map(|(x, y)| scaled_square(x - y, PANOSE_SCALE))
+                .collect();
+            if compared.is_empty() { 0.0 } else { compared.iter().sum::<f32>() / compared.len() as f32 }
+        }));
+        add(METRICS_GROUP, group_term(&self.metrics, &other.metrics, |a, b| mean_scaled_square(a, b, METRIC_SCALE)));
+        add(VISUAL_GROUP, group_term(&self.visual, &other.visual, |a, b| mean_scaled_square(a, b, VISUAL_SCALE)));
+
+        (total / weights).sqrt()
+    }
+
+    pub fn similarity(&self, other: &Self) -> f32 {
+        1.0 - self.distance(other)
+    }
+}
+
+fn weight_feature(weight: u16) -> f32 {
+    ((weight as f32 - 100.0) / 800.0).clamp(0.0, 1.0)
+}
+
+fn category_features(classification: &FontClassification) -> Option<[f32; CATEGORY_DIMENSIONS]> {
+    if let Some(shape) = &classification.shape {
+        return Some([shape.serif.max(shape.slab_serif), shape.sans_serif, shape.script, shape.blackletter, 0.0]);
+    }
+
+    let from_panose = if classification.has_panose() {
+        match classification.panose[0] {
+            FontClassification::PANOSE_LATIN_TEXT => match classification.panose[1] {
+                2..=10 => Some(0),
+                11..=15 => Some(1),
+                _ => None,
+            },
+            FontClassification::PANOSE_LATIN_HAND_WRITTEN => Some(2),
+            FontClassification::PANOSE_LATIN_DECORATIVE => Some(3),
+            FontClassification::PANOSE_LATIN_SYMBOL => Some(4),
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    let slot = from_panose.or(match classification.class_id() {
+        FontClassification::CLASS_OLDSTYLE_SERIFS
+        | FontClassification::CLASS_TRANSITIONAL_SERIFS
+        | FontClassification::CLASS_MODERN_SERIFS
+        | FontClassification::CLASS_CLARENDON_SERIFS
+        | FontClassification::CLASS_SLAB_SERIFS
+        | FontClassification::CLASS_FREEFORM_SERIFS => Some(0),
+        FontClassification::CLASS_SANS_SERIF => Some(1),
+        FontClassification::CLASS_SCRIPTS => Some(2),
+        FontClassification::CLASS_ORNAMENTALS => Some(3),
+        FontClassification::CLASS_SYMBOLIC => Some(4),
+        _ => None,
+    })?;
+
+    let mut category = [0.0; CATEGORY_DIMENSIONS];
+    category[slot] = 1.0;
+    Some(category)
+}
+
+fn panose_features(classification: &FontClassification) -> Option<[f32; PANOSE_DIMENSIONS]> {
+    if !classification.has_panose() {
+        return None;
+    }
+    let mut digits = [0.0; PANOSE_DIMENSIONS];
+    for (value, digit) in digits.iter_mut().zip(&classification.panose[1..]) {
+        *value = *digit as f32 / 15.0;
+    }
+    Some(digits)
+}
+
+fn metric_features(metrics: &FontMetrics) -> Option<[f32; METRIC_DIMENSIONS]> {
+    if metrics.units_per_em == 0 {
+        return None;
+    }
+    let em = metrics.units_per_em as f32;
+    let ratio = |value: i16| (value as f32 / em).abs().min(2.0);
+    Some([
+        ratio(metrics.x_height),
+        ratio(metrics.cap_height),
+        ratio(metrics.ascender),
+        ratio(metrics.descender),
+        ratio(metrics.average_width),
+    ])
+}
+
+fn group_term<const N: usize>(
+    a: &Option<[f32; N]>,
+    b: &Option<[f32; N]>,
+    term: impl Fn(&[f32; N], &[f32; N]) -> f32,
+) -> Option<f32> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(term(a, b)),
+        (None, None) => None,
+        _ => Some(MISSING_TERM),
+    }
+}
+
+fn scaled_square(difference: f32, scale: f32) -> f32 {
+    (difference / scale).clamp(-1.0, 1.0).powi(2)
+}
+
+fn mean_scaled_square(a: &[f32], b: &[f32], scale: f32) -> f32 {
+    a.iter().zip(b).map(|(x, y)| scaled_square(x - y, scale)).sum::<f32>() / a.len().max(1) as f32
+}
+
+/// Family name as the index compares it: lowercase letters and digits only, so that
+/// "DejaVu Sans" and a normalized "dejavu-sans" are the same family
+pub fn family_key(family: &str) -> String {
+    family.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Key identifying a face in the index: family, weight and style, as the compressor deduplicates
+pub fn font_key(font: &FontDescriptor) -> String {
+    format!("{}-{}-{}", font.family.to_lowercase(), font.weight, font.italic)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub key: String,
+    pub family: String,
+    pub features: FontFeatures,
+}
+
+#[derive(Debug, Clone)]
+pub struct Neighbour<'a> {
+    pub entry: &'a IndexEntry,
+    pub distance: f32,
+}
+
+impl Neighbour<'_> {
+    pub fn similarity(&self) -> f32 {
+        1.0 - self.distance
+    }
+}
+
+/// Approximate nearest-neighbour index over font features (a hierarchical navigable small
+/// world graph). Fonts can be added at any time; each insertion and query visits a
+/// logarithmic number of nodes rather than every font.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontIndex {
+    max_links: usize,
+    ef_construction: usize,
+    entries: Vec<IndexEntry>,
+    /// Neighbour ids of each entry, per layer from 0 up to the entry's level
+    links: Vec<Vec<Vec<u32>>>,
+    entry_point: Option<u32>,
+    keys: HashMap<String, u32>,
+    /// `family_key` -> entry ids
+    families: HashMap<String, Vec<u32>>,
+}
+
+impl Default for FontIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FontIndex {
+    pub fn new() -> Self {
+        Self::with_params(DEFAULT_MAX_LINKS, DEFAULT_EF_CONSTRUCTION)
+    }
+
+    pub fn with_params(max_links: usize, ef_construction: usize) -> Self {
+        let max_links = max_links.max(2);
+        Self {
+            max_links,
+            ef_construction: ef_construction.max(max_links),
+            entries: Vec::new(),
+            links: Vec::new(),
+            entry_point: None,
+            keys: HashMap::new(),
+            families: HashMap::new(),
+        }
+    }
+
+    pub fn from_fonts(fonts: &[FontDescriptor]) -> Self {
+        let mut index = Self::new();
+        for font in fonts {
+            index.insert_font(font);
+        }
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.contains_key(key)
+    }
+
+    pub fn entries(&self) -> &[IndexEntry] {
+        &self.entries
+    }
+
+    /// Indexed faces of a family
+    pub fn family(&self, family: &str) -> impl Iterator<Item = &IndexEntry> {
+        self.families.get(&family_key(family))
+            .into_iter()
+            .flatten()
+            .map(|&id| &self.entries[id as usize])
+    }
+
+    /// Add a font under `font_key`; see `insert`
+    pub fn insert_font(&mut self, font: &FontDescriptor) -> bool {
+        self.insert(&font_key(font), &font.family, FontFeatures::from_descriptor(font))
+    }
+
+    /// Add a font, or replace the features of one already indexed under `key`, moving it
+    /// in the graph. Returns whether the font is new.
+    pub fn insert(&mut self, key: &str, family: &str, features: FontFeatures) -> bool {
+        if let Some(&id) = self.keys.get(key) {
+            if self.entries[id as usize].features != features {
+                self.entries[id as usize].features = features;
+                self.relink(id);
+            }
+            return false;
+        }
+
+        let id = self.entries.len() as u32;
+        self.entries.push(IndexEntry { key: key.to_string(), family: family.to_string(), features });
+        self.keys.insert(key.to_string(), id);
+        self.families.entry(family_key(family)).or_default().push(id);
+        self.link(id);
+        true
+    }
+
+    /// Up to `k` indexed fonts closest to `query`, nearest first
+    pub fn nearest(&self, query: &FontFeatures, k: usize) -> Vec<Neighbour<'_>> {
+        let Some(mut entry_point) = self.entry_point else {
+            return Vec::new();
+        };
+
+        let top = self.links[entry_point as usize].len() - 1;
+        for layer in (1..=top).rev() {
+            entry_point = self.search_layer(query, &[entry_point], 1, layer)[0].1;
+        }
+
+        self.search_layer(query, &[entry_point], k.max(EF_SEARCH), 0)
+            .into_iter()
+            .take(k)
+            .map(|(distance, id)| Neighbour { entry: &self.entries[id as usize], distance: distance.0 })
+            .collect()
+    }
+
+    fn link(&mut self, id: u32) {
+        let level = self.level_for(&self.entries[id as usize].key);
+        self.links.push(vec![Vec::new(); level + 1]);
+
+        let Some(entry_point) = self.entry_point else {
+            self.entry_point = Some(id);
+            return;
+        };
+
+        let top = self.links[entry_point as usize].len() - 1;
+        self.connect(id, entry_point);
+        if level > top {
+            self.entry_point = Some(id);
+        }
+    }
+
+    /// Move a node whose features changed: drop every link to and from it, then connect it
+    /// again at its new position, so searches never follow links chosen for the old one
+    fn relink(&mut self, id: u32) {
+        let level = self.links[id as usize].len() - 1;
+        self.links[id as usize] = vec![Vec::new(); level + 1];
+        for layers in &mut self.links {
+            for links in layers.iter_mut().take(level + 1) {
+                links.retain(|&n| n != id);
+            }
+        }
+
+        // The entry point keeps its level, so search from the highest other node if it moved
+        let start = if self.entry_point == Some(id) {
+            (0..self.links.len() as u32)
+                .filter(|&n| n != id)
+                .max_by_key(|&n| self.links[n as usize].len())
+        } else {
+            self.entry_point
+        };
+        if let Some(start) = start {
+            self.connect(id, start);
+        }
+    }
+
+    /// Link a node to its closest nodes on each of its layers, searching from `entry_point`
+    fn connect(&mut self, id: u32, mut entry_point: u32) {
+        let level = self.links[id as usize].len() - 1;
+        let query = self.entries[id as usize].features.clone();
+        let top = self.links[entry_point as usize].len() - 1;
+        for layer in (level + 1..=top).rev() {
+            entry_point = self.search_layer(&query, &[entry_point], 1, layer)[0].1;
+        }
+
+        let mut entry_points = vec![entry_point];
+        for layer in (0..=level.min(top)).rev() {
+            let candidates = self.search_layer(&query, &entry_points, self.ef_construction, layer);
+            let neighbours: Vec<u32> = candidates.iter().take(self.max_links).map(|(_, n)| *n).collect();
+
+            for &neighbour in &neighbours {
+                self.links[neighbour as usize][layer].push(id);
+                self.prune(neighbour, layer);
+            }
+            self.links[id as usize][layer] = neighbours;
+            entry_points = candidates.into_iter().map(|(_, n)| n).collect();
+        }
+    }
+
+    /// Keep a node's closest links when it has more than its layer allows
+    fn prune(&mut self, node: u32, layer: usize) {
+        let capacity = if layer == 0 { self.max_links * 2 } else { self.max_links };
+        let links = &mut self.links[node as usize][layer];
+        if links.len() <= capacity {
+            return;
+        }
+
+        let features = &self.entries[node as usize].features;
+        let entries = &self.entries;
+        links.sort_by_cached_key(|&n| OrderedFloat(features.distance(&entries[n as usize].features)));
+        links.truncate(capacity);
+    }
+
+    /// Closest `ef` nodes to `query` reachable on `layer` from the entry points, nearest first
+    fn search_layer(&self, query: &FontFeatures, entry_points: &[u32], ef: usize, layer: usize) -> Vec<(OrderedFloat<f32>, u32)> {
+        let mut visited: HashSet<u32> = entry_points.iter().copied().collect();
+        let mut candidates = BinaryHeap::new();
+        // Max-heap, so the furthest of the closest found so far is on top
+        let mut found = BinaryHeap::new();
+
+        for &point in entry_points {
+            let distance = OrderedFloat(query.distance(&self.entries[point as usize].features));
+            candidates.push(Reverse((distance, point)));
+            found.push((distance, point));
+        }
+        while found.len() > ef {
+            found.pop();
+        }
+
+        while let Some(Reverse((distance, point))) = candidates.pop() {
+            if found.len() >= ef && found.peek().is_some_and(|(furthest, _)| distance > *furthest) {
+                break;
+            }
+
+            for &neighbour in &self.links[point as usize][layer] {
+                if !visited.insert(neighbour) {
+                    continue;
+                }
+                let distance = OrderedFloat(query.distance(&self.entries[neighbour as usize].features));
+                if found.len() < ef || found.peek().is_some_and(|(furthest, _)| distance < *furthest) {
+                    candidates.push(Reverse((distance, neighbour)));
+                    found.push((distance, neighbour));
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+
+        found.into_sorted_vec()
+    }
+
+    /// Layer of a new node, drawn from the usual exponential distribution but seeded by its
+    /// key so that the same fonts always build the same graph
+    fn level_for(&self, key: &str) -> usize {
+        let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+        let uniform = (splitmix64(hash) >> 11) as f64 / (1u64 << 53) as f64;
+        let level = -(uniform.max(f64::MIN_POSITIVE)).ln() / (self.max_links as f64).ln();
+        (level as usize).min(MAX_LEVEL)
+    }
+}
+
+fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random features covering every group
+    fn features(seed: u64) -> FontFeatures {
+        let mut state = seed;
+        let mut next = || {
+            state = splitmix64(state);
+            (state >> 40) as f32 / (1u64 << 24) as f32
+        };
+        let array = |next: &mut dyn FnMut() -> f32| {
+            let mut values = [0.0; VISUAL_DIMENSIONS];
+            values.iter_mut().for_each(|v| *v = next());
+            values
+        };
+        let visual = array(&mut next);
+        let metrics = array(&mut next);
+        let panose = array(&mut next);
+        FontFeatures {
+            weight: next(),
+            italic: next() > 0.8,
+            monospaced: next() > 0.9,
+            category: Some([next(), next(), next(), next(), next()]),
+            panose: Some(panose[..PANOSE_DIMENSIONS].try_into().unwrap()),
+            metrics: Some(metrics[..METRIC_DIMENSIONS].try_into().unwrap()),
+            visual: Some(visual),
+        }
+    }
+
+    #[test]
+    fn test_nearest_matches_brute_force() {
+        let mut index = FontIndex::new();
+        for i in 0..2000 {
+            index.insert(&format!("font-{}", i), &format!("Family {}", i / 4), features(i));
+        }
+
+        let recall = recall(&index);
+        assert!(recall > 0.9, "recall {}", recall);
+    }
+
+    /// Share of the true 10 nearest fonts that `nearest` finds, over 50 queries
+    fn recall(index: &FontIndex) -> f32 {
+        let mut hits = 0;
+        let queries = 50;
+        for q in 0..queries {
+            let query = features(10_000 + q);
+            let mut exact: Vec<(f32, &str)> = index.entries().iter()
+                .map(|e| (query.distance(&e.features), e.key.as_str()))
+                .collect();
+            exact.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+            let found: Vec<&str> = index.nearest(&query, 10).iter().map(|n| n.entry.key.as_str()).collect();
+            hits += exact[..10].iter().filter(|(_, key)| found.contains(key)).count();
+        }
+        hits as f32 / (queries as f32 * 10.0)
+    }
+
+    #[test]
+    fn test_reinserted_fonts_move_in_the_graph() {
+        let mut index = FontIndex::new();
+        for i in 0..1000 {
+            index.insert(&format!("font-{}", i), &format!("Family {}", i / 4), features(i));
+        }
+
+        // Move a third of the fonts, as when visual embeddings arrive after the first build
+        for i in (0..1000).step_by(3) {
+            assert!(!index.insert(&format!("font-{}", i), &format!("Family {}", i / 4), features(50_000 + i)));
+        }
+        assert_eq!(index.len(), 1000);
+
+        let recall = recall(&index);
+        assert!(recall > 0.9, "recall {}", recall);
+        for i in (0..1000).step_by(99) {
+            let key = format!("font-{}", i);
+            let moved = if i % 3 == 0 { features(50_000 + i) } else { features(i) };
+            assert_eq!(index.nearest(&moved, 1)[0].entry.key, key);
+        }
+
+        // No node keeps a link to itself
+        for (id, layers) in index.links.iter().enumerate() {
+            assert!(layers.iter().all(|links| !links.contains(&(id as u32))));
+        }
+    }
+
+    #[test]
+    fn test_insert_is_incremental() {
+        let mut index = FontIndex::new();
+        assert!(index.nearest(&features(1), 5).is_empty());
+
+        assert!(index.insert("a", "Alpha", features(1)));
+        assert!(index.insert("b", "Beta", features(2)));
+        assert!(!index.insert("a", "Alpha", features(3)));
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.family("ALPHA").next().unwrap().features, features(3));
+
+        let nearest = index.nearest(&features(2), 1);
+        assert_eq!(nearest[0].entry.key, "b");
+        assert!(nearest[0].similarity() > 0.999);
+    }
+
+    #[test]
+    fn test_missing_groups() {
+        let full = features(7);
+        let bare = FontFeatures { category: None, panose: None, metrics: None, visual: None, ..full.clone() };
+
+        // Groups neither font has are ignored; groups only one has count against it
+        let heavier = bare.clone().restyled(900, bare.italic);
+        let expected = ((1.0 - bare.weight).powi(2) / (WEIGHT_GROUP + ITALIC_GROUP + MONOSPACED_GROUP)).sqrt();
+        assert!((bare.distance(&heavier) - expected).abs() < 1e-5);
+        assert!(bare.distance(&bare) < 1e-6);
+        assert!(full.distance(&bare) > 0.3);
+        assert!(!full.shares_design_features(&bare));
+        assert!(full.shares_design_features(&full));
+    }
+}
@@ -1,25 +1,28 @@
 // use font_core::{FontDescriptor, LicenseInfo, FontError};
 // use regex::Regex;
-use font_core::FontDescriptor;
+use font_core::{default_data_dir, EmbeddingLevel, EmbeddingPermissions, FontDescriptor, FontError, FontResult, LicenseIdentification};
+use serde::{Deserialize, Serialize};
 use once_cell::sync::Lazy;
 use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
 
 pub struct LicenseChecker {
-    commercial_fonts: HashSet<String>,
-    commercial_postscript: HashSet<String>,
-    free_alternatives: Vec<FreeAlternative>,
+    knowledge: FontKnowledgeBase,
 }
 
 #[derive(Debug, Clone)]
 pub struct LicenseWarning {
     pub font_name: String,
     pub license_type: LicenseType,
+    /// License identified from the font's license text, when confident enough
+    pub spdx: Option<LicenseIdentification>,
     pub warning_level: WarningLevel,
     pub message: String,
     pub alternatives: Vec<FreeAlternative>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum LicenseType {
     OpenSource,
     Commercial,
@@ -27,122 +30,642 @@ pub enum LicenseType {
     SystemEmbedded, // Fonts that come with OS (may have restrictions)
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum WarningLevel {
     Info,
     Warning,
     Critical,
 }
 
+/// Evidence below this confidence is ignored when classifying a font
+pub const MIN_LICENSE_CONFIDENCE: f32 = 0.5;
+
+/// A license recognized in name-table text, with the rights it grants
+#[derive(Debug, Clone)]
+pub struct KnownLicense {
+    pub spdx_id: &'static str,
+    pub name: &'static str,
+    pub open_source: bool,
+    pub allows_embedding: bool,
+    pub allows_modification: bool,
+    pub requires_attribution: bool,
+    pub allows_commercial_use: bool,
+    /// Lowercase substrings of canonical license URLs
+    url_patterns: &'static [&'static str],
+    /// Lowercase substrings of the license description, with the confidence each one gives;
+    /// parts joined by " && " must all be present
+    text_patterns: &'static [(&'static str, f32)],
+}
+
+const fn open_license(
+    spdx_id: &'static str,
+    name: &'static str,
+    requires_attribution: bool,
+    url_patterns: &'static [&'static str],
+    text_patterns: &'static [(&'static str, f32)],
+) -> KnownLicense {
+    KnownLicense {
+        spdx_id,
+        name,
+        open_source: true,
+        allows_embedding: true,
+        allows_modification: true,
+        requires_attribution,
+        allows_commercial_use: true,
+        url_patterns,
+        text_patterns,
+    }
+}
+
+const fn eula(
+    spdx_id: &'static str,
+    name: &'static str,
+    url_patterns: &'static [&'static str],
+    text_patterns: &'static [(&'static str, f32)],
+) -> KnownLicense {
+    KnownLicense {
+        spdx_id,
+        name,
+        open_source: false,
+        allows_embedding: false,
+        allows_modification: false,
+        requires_attribution: true,
+        allows_commercial_use: false,
+        url_patterns,
+        text_patterns,
+    }
+}
+
+/// Known license texts, most specific first: the first of equally confident matches wins
+static KNOWN_LICENSES: &[KnownLicense] = &[
+    open_license("OFL-1.1", "SIL Open Font License 1.1", false, &[
+        "scripts.sil.org/ofl", "openfontlicense.org", "opensource.org/licenses/ofl-1.1",
+    ], &[
+        ("open font license, version 1.1", 0.9),
+        ("ofl 1.1", 0.8),
+        ("open font license", 0.7),
+    ]),
+    // Shares the OFL URL, so only its versioned title can make it win over 1.1
+    open_license("OFL-1.0", "SIL Open Font License 1.0", false, &["scripts.sil.org/ofl"], &[
+        ("open font license, version 1.0", 0.9),
+        ("ofl 1.0", 0.8),
+    ]),
+    open_license("Apache-2.0", "Apache License 2.0", true, &[
+        "apache.org/licenses/license-2.0",
+    ], &[
+        ("apache license, version 2.0", 0.9),
+        ("apache license 2.0", 0.9),
+        ("apache license", 0.6),
+    ]),
+    open_license("UFL-1.0", "Ubuntu Font Licence 1.0", true, &["font.ubuntu.com/ufl"], &[
+        ("ubuntu font licence", 0.9),
+        ("ubuntu font license", 0.9),
+    ]),
+    open_license("Bitstream-Vera", "Bitstream Vera Fonts License", true, &[
+        "gnome.org/fonts", "dejavu-fonts.org/wiki/license", "dejavu.sourceforge.net/wiki/index.php/license",
+    ], &[
+        ("bitstream vera", 0.9),
+        ("fonts are (c) bitstream", 0.8),
+    ]),
+    open_license("GPL-3.0-or-later WITH Font-exception-2.0", "GNU GPL v3 with font exception", true, &[], &[
+        ("version 3 of the license && as a special exception, if you create a document which uses this font", 0.95),
+        ("gpl-3.0 with font exception", 0.9),
+        ("gplv3 && font exception", 0.9),
+    ]),
+    open_license("GPL-2.0-or-later WITH Font-exception-2.0", "GNU GPL v2 with font exception", true, &[], &[
+        ("as a special exception, if you create a document which uses this font", 0.9),
+        ("gpl with font exception", 0.8),
+        ("font exception", 0.6),
+    ]),
+    open_license("MIT", "MIT License", true, &["opensource.org/licenses/mit"], &[
+        ("permission is hereby granted, free of charge, to any person obtaining a copy", 0.85),
+        ("mit license", 0.8),
+    ]),
+    open_license("CC0-1.0", "Creative Commons Zero 1.0", false, &["creativecommons.org/publicdomain/zero"], &[
+        ("cc0 1.0", 0.9),
+        ("cc0", 0.7),
+        ("public domain dedication", 0.6),
+    ]),
+    eula("LicenseRef-Monotype-EULA", "Monotype EULA", &["monotype.com/legal", "fonts.com/info/legal"], &[
+        ("monotype imaging", 0.6),
+        // A bare foundry mention is recorded but stays below MIN_LICENSE_CONFIDENCE
+        ("monotype", 0.4),
+    ]),
+    eula("LicenseRef-Microsoft-EULA", "Microsoft font EULA", &["microsoft.com/typography", "learn.microsoft.com/typography"], &[
+        ("microsoft supplied font", 0.9),
+        ("you may use this font as permitted by the eula for the product", 0.9),
+    ]),
+    eula("LicenseRef-Adobe-EULA", "Adobe font EULA", &["adobe.com/type/legal", "adobe.com/products/type/font-licensing"], &[
+        ("adobe systems incorporated. all rights reserved", 0.6),
+        ("adobe font license", 0.8),
+    ]),
+    eula("LicenseRef-Apple-EULA", "Apple font EULA", &["apple.com/legal"], &[
+        ("apple computer, inc. all rights reserved", 0.6),
+        ("apple inc. all rights reserved", 0.6),
+    ]),
+    eula("LicenseRef-Proprietary-EULA", "Proprietary EULA", &[], &[
+        ("end user license agreement", 0.7),
+        ("eula", 0.6),
+        ("may not be redistributed", 0.6),
+        ("commercial license", 0.5),
+    ]),
+];
+
+/// Look up a license by its SPDX identifier
+pub fn known_license(spdx_id: &str) -> Option<&'static KnownLicense> {
+    KNOWN_LICENSES.iter().find(|l| l.spdx_id.eq_ignore_ascii_case(spdx_id))
+}
+
+/// Classify a font's license description (name ID 13) and license URL (name ID 14).
+/// A canonical URL alone gives 0.95; a specific title agreeing with it raises confidence further.
+pub fn identify_license(description: Option<&str>, url: Option<&str>) -> Option<(&'static KnownLicense, LicenseIdentification)> {
+    let normalize = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    let description = description.map(normalize).unwrap_or_default();
+    let url = url.map(normalize).unwrap_or_default();
+    
+    let mut best: Option<(&'static KnownLicense, f32)> = None;
+    for license in KNOWN_LICENSES {
+        let url_score = license.url_patterns.iter()
+            .any(|p| url.contains(p) || description.contains(p))
+            .then_some(0.95f32);
+        let text_score = license.text_patterns.iter()
+            .filter(|(pattern, _)| pattern.split(" && ").all(|part| description.contains(part)))
+            .map(|(_, score)| *score)
+            .fold(None, |best: Option<f32>, score| Some(best.map_or(score, |b| b.max(score))));
+        
+        let confidence = match (url_score, text_score) {
+            (Some(u), Some(t)) if t >= 0.8 => (u.max(t) + 0.05).min(1.0),
+            (Some(u), Some(t)) => u.max(t),
+            (Some(score), None) | (None, Some(score)) => score,
+            (None, None) => continue,
+        };
+        if best.is_none_or(|(_, b)| confidence > b) {
+            best = Some((license, confidence));
+        }
+    }
+    
+    best.map(|(license, confidence)| (license, LicenseIdentification {
+        spdx_id: license.spdx_id.to_string(),
+        confidence,
+    }))
+}
+
+/// Operations governed by a font's declared embedding permissions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontOperation {
+    EmbedInPdf,
+    SubsetForWeb,
+    EditDocument,
+}
+
+impl fmt::Display for FontOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontOperation::EmbedInPdf => write!(f, "embed into PDF"),
+            FontOperation::SubsetForWeb => write!(f, "subset for web"),
+            FontOperation::EditDocument => write!(f, "edit document"),
+        }
+    }
+}
+
+/// Verdict for one operation; `Critical` means the font forbids it
+#[derive(Debug, Clone)]
+pub struct OperationCheck {
+    pub font_name: String,
+    pub operation: FontOperation,
+    pub permissions: Option<EmbeddingPermissions>,
+    pub warning_level: WarningLevel,
+    pub message: String,
+}
+
+impl OperationCheck {
+    pub fn is_allowed(&self) -> bool {
+        self.warning_level != WarningLevel::Critical
+    }
+}
+
+static RESERVED_FONT_NAME: Lazy<regex::Regex> = Lazy::new(|| {
+    regex::Regex::new(r"(?i)(\bno\s+)?reserved\s+font\s+names?\b\s*:?\s*").unwrap()
+});
+static QUOTED_NAME: Lazy<regex::Regex> = Lazy::new(|| {
+    regex::Regex::new(r#"["“”'‘’]([^"“”'‘’]+)["“”'‘’]"#).unwrap()
+});
+
+/// Reserved Font Names declared in OFL copyright lines, e.g.
+/// `Copyright 2014 Adobe (http://www.adobe.com/), with Reserved Font Name 'Source'.`
+pub fn extract_reserved_font_names(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    
+    for declaration in RESERVED_FONT_NAME.captures_iter(text) {
+        // "with no Reserved Font Name" and the license body's own definition of the term
+        if declaration.get(1).is_some() {
+            continue;
+        }
+        let rest = &text[declaration.get(0).unwrap().end()..];
+        let end = rest.find(['\n', ';', '(']).unwrap_or(rest.len());
+        let mut clause = &rest[..end];
+        // Stop at the sentence end; a period inside quotes belongs to the name
+        if let Some(stop) = clause.char_indices()
+            .filter(|&(i, c)| c == '.' && clause[..i].chars().filter(|q| "\"“”".contains(*q)).count() % 2 == 0)
+            .map(|(i, _)| i)
+            .next()
+        {
+            clause = &clause[..stop];
+        }
+        
+        let found: Vec<String> = if QUOTED_NAME.is_match(clause) {
+            QUOTED_NAME.captures_iter(clause).map(|c| c[1].to_string()).collect()
+        } else {
+            clause.split([',', '&'])
+                .flat_map(|part| part.split(" and "))
+                .map(str::to_string)
+                .collect()
+        };
+        
+        for name in found {
+            let name = name.trim().trim_end_matches('.').trim();
+            // Skip template placeholders and prose picked up by mistake
+            let plausible = !name.is_empty()
+                && name.len() <= 64
+                && !name.contains(['<', '>'])
+                && !name.eq_ignore_ascii_case("is")
+                && name.split_whitespace().count() <= 5;
+            if plausible && !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    
+    names
+}
+
+/// Reserved Font Names declared in a license file such as a bundled `OFL.txt`
+pub fn reserved_font_names_in_file<P: AsRef<Path>>(path: P) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .map(|text| {
+            // The declaration lives in the copyright header, before the license body
+            let header = text.find("PREAMBLE").map_or(text.as_str(), |end| &text[..end]);
+            extract_reserved_font_names(header)
+        })
+        .unwrap_or_default()
+}
+
+/// How a font was derived from the original; OFL treats all of these as Modified Versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Derivation {
+    Subset,
+    Converted,
+    Instanced,
+}
+
+impl Derivation {
+    /// Short tag used when suggesting a family name for the derived font
+    fn tag(&self) -> &'static str {
+        match self {
+            Derivation::Subset => "Subset",
+            Derivation::Converted => "Web",
+            Derivation::Instanced => "Static",
+        }
+    }
+}
+
+impl fmt::Display for Derivation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Derivation::Subset => write!(f, "subset"),
+            Derivation::Converted => write!(f, "format conversion"),
+            Derivation::Instanced => write!(f, "variable font instance"),
+        }
+    }
+}
+
+/// Verdict on the family name a derived font is published under
+#[derive(Debug, Clone)]
+pub struct ReservedNameCheck {
+    pub font_name: String,
+    pub derived_family: String,
+    pub derivation: Derivation,
+    pub reserved_names: Vec<String>,
+    /// Reserved names the derived family still uses
+    pub conflicts: Vec<String>,
+    /// A family name free of reserved names, when the current one conflicts
+    pub suggested_family: Option<String>,
+    pub warning_level: WarningLevel,
+    pub message: String,
+}
+
+impl ReservedNameCheck {
+    pub fn is_compliant(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Lowercase letters and digits only, so "SourceSans" and "Source Sans" compare equal
+fn name_key(name: &str) -> String {
+    name.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Lowercase words of a family name, split at spaces and punctuation, case changes
+/// ("SourceSans", "IBMPlex") and between letters and digits
+fn name_words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+    
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        let boundary = i > 0 && chars[i - 1].is_alphanumeric() && {
+            let previous = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            (c.is_uppercase() && (previous.is_lowercase() || (previous.is_uppercase() && next_lower)))
+                || c.is_numeric() != previous.is_numeric()
+        };
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.extend(c.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Whether `family` uses `reserved` as whole words: "Source Sans 3" and "SourceSansPro"
+/// use "Source" and "Source Sans", "Resource Sans" uses neither
+fn uses_reserved_name(family: &str, reserved: &str) -> bool {
+    let reserved = name_key(reserved);
+    if reserved.is_empty() {
+        return false;
+    }
+    
+    let words = name_words(family);
+    let mut boundaries = vec![0];
+    for word in &words {
+        boundaries.push(boundaries.last().unwrap() + word.len());
+    }
+    words.concat()
+        .match_indices(reserved.as_str())
+        .any(|(start, _)| boundaries.contains(&start) && boundaries.contains(&(start + reserved.len())))
+}
+
+/// Family name for a derived font with every reserved name removed
+pub fn suggest_family_name(family: &str, reserved_names: &[String], derivation: Derivation) -> String {
+    let reserved_words: HashSet<String> = reserved_names.iter()
+        .flat_map(|name| name.split_whitespace().map(name_key).collect::<Vec<_>>())
+        .collect();
+    
+    let kept: Vec<&str> = family.split_whitespace()
+        .filter(|word| {
+            !reserved_words.contains(&name_key(word)) && !reserved_names.iter().any(|r| uses_reserved_name(word, r))
+        })
+        .collect();
+    
+    let suggestion = if kept.is_empty() {
+        format!("{} Font", derivation.tag())
+    } else if kept.iter().any(|word| word.eq_ignore_ascii_case(derivation.tag())) {
+        kept.join(" ")
+    } else {
+        format!("{} {}", derivation.tag(), kept.join(" "))
+    };
+    
+    // The tag itself could collide with an unusual reserved name
+    if reserved_names.iter().any(|r| uses_reserved_name(&suggestion, r)) {
+        "Derived Font".to_string()
+    } else {
+        suggestion
+    }
+}
+
+/// Knowledge base updated through `FontUpdater`; replaces the embedded one when newer
+pub const KNOWLEDGE_FILE: &str = "font_knowledge.json";
+/// User additions and overrides, merged on top of whichever base is in use
+pub const LOCAL_KNOWLEDGE_FILE: &str = "font_knowledge.local.json";
+
+/// Installed knowledge base in the data directory
+pub fn knowledge_path() -> PathBuf {
+    default_data_dir().join(KNOWLEDGE_FILE)
+}
+
+/// Local additions in the data directory
+pub fn local_knowledge_path() -> PathBuf {
+    default_data_dir().join(LOCAL_KNOWLEDGE_FILE)
+}
+
+static EMBEDDED_KNOWLEDGE: &str = include_str!("../data/font_knowledge.json");
+
+static DEFAULT_KNOWLEDGE: Lazy<FontKnowledgeBase> = Lazy::new(FontKnowledgeBase::load_default);
+
+/// How a foundry's or family's fonts are normally licensed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Licensing {
+    Commercial,
+    /// Ships with an operating system or office suite; usable there, not redistributable
+    SystemBundled,
+    OpenSource,
+}
+
+/// Why an alternative is suggested
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AlternativeRelation {
+    /// Same advance widths, so it can replace the original without reflowing text
+    MetricCompatible,
+    LookAlike,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Foundry {
+    pub id: String,
+    pub name: String,
+    /// OS/2 achVendID values the foundry uses
+    #[serde(default)]
+    pub vendor_ids: Vec<String>,
+    #[serde(default)]
+    pub licensing: Option<Licensing>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// A free font suggested in place of another, with the reason it was chosen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CuratedAlternative {
+    pub family: String,
+    /// SPDX identifier of the alternative's license
+    pub license: String,
+    pub relation: AlternativeRelation,
+    pub score: f32,
+    pub evidence: String,
+    /// "sans-serif", "serif" or "monospace"; only used by generic alternatives
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnownFamily {
+    pub family: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Lowercase substrings of PostScript names belonging to the family
+    #[serde(default)]
+    pub postscript_patterns: Vec<String>,
+    /// `Foundry::id`
+    #[serde(default)]
+    pub foundry: Option<String>,
+    pub licensing: Licensing,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub alternatives: Vec<CuratedAlternative>,
+}
+
+impl KnownFamily {
+    fn matches_name(&self, family_lower: &str) -> bool {
+        self.family.to_lowercase() == family_lower
+            || self.aliases.iter().any(|alias| alias.to_lowercase() == family_lower)
+    }
+}
+
+/// Versioned dataset of foundries, commercial families and their free alternatives
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontKnowledgeBase {
+    /// Dotted numeric version, e.g. "2026.10.1"; a newer version replaces an older one
+    pub version: String,
+    #[serde(default)]
+    pub foundries: Vec<Foundry>,
+    #[serde(default)]
+    pub families: Vec<KnownFamily>,
+    /// Fallback suggestions by category for families without curated alternatives
+    #[serde(default)]
+    pub generic_alternatives: Vec<CuratedAlternative>,
+}
+
+impl FontKnowledgeBase {
+    /// The dataset compiled into this build
+    pub fn embedded() -> Self {
+        Self::from_json(EMBEDDED_KNOWLEDGE).expect("embedded font knowledge base is valid JSON")
+    }
+    
+    pub fn from_json(json: &str) -> FontResult<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| FontError::Parse(format!("Invalid font knowledge base: {}", e)))
+    }
+    
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> FontResult<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+    
+    /// Embedded dataset, replaced by a newer downloaded one, with local additions merged on top
+    pub fn load_default() -> Self {
+        let mut knowledge = Self::embedded();
+        
+        let installed = knowledge_path();
+        if installed.exists() {
+            match Self::load_from_file(&installed) {
+                Ok(updated) if updated.is_newer_than(&knowledge) => knowledge = updated,
+                Ok(_) => {}
+                Err(e) => eprintln!("⚠️  Ignoring {}: {}", installed.display(), e),
+            }
+        }
+        
+        let local = local_knowledge_path();
+        if local.exists() {
+            match Self::load_from_file(&local) {
+                Ok(local) => knowledge.merge(local),
+                Err(e) => eprintln!("⚠️  Ignoring {}: {}", local.display(), e),
+            }
+        }
+        
+        knowledge
+    }
+    
+    pub fn is_newer_than(&self, other: &FontKnowledgeBase) -> bool {
+        fn parts(version: &str) -> Vec<u64> {
+            version.split('.').map(|part| part.trim().parse().unwrap_or(0)).collect()
+        }
+        parts(&self.version) > parts(&other.version)
+    }
+    
+    /// Add or replace foundries (by id) and families (by name) from `overlay`
+    pub fn merge(&mut self, overlay: FontKnowledgeBase) {
+        for foundry in overlay.foundries {
+            match self.foundries.iter_mut().find(|f| f.id.eq_ignore_ascii_case(&foundry.id)) {
+                Some(existing) => *existing = foundry,
+                None => self.foundries.push(foundry),
+            }
+        }
+        for family in overlay.families {
+            let key = family.family.to_lowercase();
+            match self.families.iter_mut().find(|f| f.family.to_lowercase() == key) {
+                Some(existing) => *existing = family,
+                None => self.families.push(family),
+            }
+        }
+        for alternative in overlay.generic_alternatives {
+            self.generic_alternatives.retain(|a| !a.family.eq_ignore_ascii_case(&alternative.family));
+            self.generic_alternatives.push(alternative);
+        }
+    }
+    
+    /// Family matching the name or an alias exactly, otherwise by PostScript pattern
+    pub fn find_family(&self, family: &str, postscript_name: &str) -> Option<&KnownFamily> {
+        let family_lower = family.to_lowercase();
+        let postscript_lower = postscript_name.to_lowercase();
+        
+        self.families.iter().find(|f| f.matches_name(&family_lower)).or_else(|| {
+            self.families.iter().find(|f| {
+                !postscript_lower.is_empty()
+                    && f.postscript_patterns.iter().any(|pattern| postscript_lower.contains(pattern.as_str()))
+            })
+        })
+    }
+    
+    pub fn foundry(&self, id: &str) -> Option<&Foundry> {
+        self.foundries.iter().find(|f| f.id.eq_ignore_ascii_case(id))
+    }
+    
+    /// Foundry using an OS/2 vendor ID; padding spaces are ignored
+    pub fn foundry_for_vendor(&self, vendor_id: &str) -> Option<&Foundry> {
+        let vendor_id = vendor_id.trim();
+        self.foundries.iter().find(|f| f.vendor_ids.iter().any(|v| v.trim().eq_ignore_ascii_case(vendor_id)))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FreeAlternative {
     pub family: String,
     pub similarity_score: f32,
     pub license: LicenseType,
     pub reason: String,
+    /// Can replace the original without reflowing text
+    pub metric_compatible: bool,
 }
 
 impl LicenseChecker {
     pub fn new() -> Self {
-        static COMMERCIAL_FONTS: Lazy<HashSet<String>> = Lazy::new(|| {
-            let mut set = HashSet::new();
-            // Known commercial font families
-            set.insert("helvetica".to_string());
-            set.insert("helvetica neue".to_string());
-            set.insert("futura".to_string());
-            set.insert("gill sans".to_string());
-            set.insert("optima".to_string());
-            set.insert("palatino".to_string());
-            set.insert("didot".to_string());
-            set.insert("bembo".to_string());
-            set.insert("garamond premium".to_string());
-            set.insert("minion pro".to_string());
-            set.insert("myriad pro".to_string());
-            set.insert("trajan pro".to_string());
-            set.insert("univers".to_string());
-            set.insert("franklin gothic".to_string());
-            set.insert("copperplate gothic".to_string());
-            set
-        });
-        
-        static COMMERCIAL_POSTSCRIPT: Lazy<HashSet<String>> = Lazy::new(|| {
-            let mut set = HashSet::new();
-            // Known commercial PostScript names
-            set.insert("helveticaneue".to_string());
-            set.insert("helveticaneuepro".to_string());
-            set.insert("futura".to_string());
-            set.insert("gill-sans".to_string());
-            set.insert("optima".to_string());
-            set.insert("palatino".to_string());
-            set.insert("didot".to_string());
-            set.insert("bembo".to_string());
-            set
-        });
-        
-        let free_alternatives = vec![
-            FreeAlternative {
-                family: "Roboto".to_string(),
-                similarity_score: 0.9,
-                license: LicenseType::OpenSource,
-                reason: "Apache 2.0 license, similar to Helvetica".to_string(),
-            },
-            FreeAlternative {
-                family: "Open Sans".to_string(),
-                similarity_score: 0.85,
-                license: LicenseType::OpenSource,
-                reason: "Apache 2.0 license, humanist sans-serif".to_string(),
-            },
-            FreeAlternative {
-                family: "Lato".to_string(),
-                similarity_score: 0.8,
-                license: LicenseType::OpenSource,
-                reason: "OFL license, professional sans-serif".to_string(),
-            },
-            FreeAlternative {
-                family: "Montserrat".to_string(),
-                similarity_score: 0.75,
-                license: LicenseType::OpenSource,
-                reason: "OFL license, geometric sans-serif".to_string(),
-            },
-            FreeAlternative {
-                family: "Source Sans Pro".to_string(),
-                similarity_score: 0.7,
-                license: LicenseType::OpenSource,
-                reason: "OFL license, Adobe's first open source font".to_string(),
-            },
-            FreeAlternative {
-                family: "Noto Sans".to_string(),
-                similarity_score: 0.9,
-                license: LicenseType::OpenSource,
-                reason: "OFL license, Google's universal font".to_string(),
-            },
-            FreeAlternative {
-                family: "Liberation Sans".to_string(),
-                similarity_score: 0.95,
-                license: LicenseType::OpenSource,
-                reason: "SIL Open Font License, metric-compatible with Arial".to_string(),
-            },
-            FreeAlternative {
-                family: "DejaVu Sans".to_string(),
-                similarity_score: 0.8,
-                license: LicenseType::OpenSource,
-                reason: "Bitstream Vera License, extensive character set".to_string(),
-            },
-        ];
-        
-        Self {
-            commercial_fonts: COMMERCIAL_FONTS.clone(),
-            commercial_postscript: COMMERCIAL_POSTSCRIPT.clone(),
-            free_alternatives,
-        }
+        Self::with_knowledge_base(DEFAULT_KNOWLEDGE.clone())
+    }
+    
+    pub fn with_knowledge_base(knowledge: FontKnowledgeBase) -> Self {
+        Self { knowledge }
+    }
+    
+    pub fn knowledge_base(&self) -> &FontKnowledgeBase {
+        &self.knowledge
     }
     
     pub fn check_font(&self, font: &FontDescriptor) -> LicenseWarning {
         let license_type = self.detect_license_type(font);
-        let warning_level = self.determine_warning_level(&license_type, font);
+        let mut warning_level = Self::warning_level(&license_type);
         let alternatives = self.find_alternatives(font);
         
-        let message = match license_type {
+        let mut message = match license_type {
             LicenseType::Commercial => {
                 format!("Commercial font '{}' may require a license for distribution.", font.family)
             }
@@ -157,43 +680,202 @@ impl LicenseChecker {
             }
         };
         
+        // The font's declared embedding permissions can only make the verdict stricter
+        if let Some(permissions) = font.license.as_ref().and_then(|l| l.embedding) {
+            let level = match permissions.level() {
+                EmbeddingLevel::RestrictedLicense => WarningLevel::Critical,
+                EmbeddingLevel::PreviewAndPrint => WarningLevel::Warning,
+                _ if !permissions.allows_subsetting() || permissions.bitmap_only() => WarningLevel::Warning,
+                _ => WarningLevel::Info,
+            };
+            if level > WarningLevel::Info {
+                message.push_str(&format!(" Embedding permissions: {}.", permissions));
+            }
+            warning_level = warning_level.max(level);
+        }
+        
         LicenseWarning {
             font_name: font.family.clone(),
             license_type,
+            spdx: self.license_evidence(font).map(|(_, identification)| identification),
             warning_level,
             message,
             alternatives,
         }
     }
     
-    fn detect_license_type(&self, font: &FontDescriptor) -> LicenseType {
-        // Check if we have license info in the font metadata
-        if let Some(license_info) = &font.license {
-            let license_name = license_info.name.to_lowercase();
-            
-            if license_name.contains("commercial") || 
-               license_name.contains("proprietary") ||
-               license_name.contains("copyright") {
-                return LicenseType::Commercial;
+    /// Decide whether the font's declared permissions allow an operation
+    pub fn check_operation(&self, font: &FontDescriptor, operation: FontOperation) -> OperationCheck {
+        let permissions = font.license.as_ref().and_then(|l| l.embedding);
+        
+        let (warning_level, reason) = match permissions {
+            Some(permissions) => Self::judge_operation(&permissions, operation),
+            None => match &font.license {
+                Some(license) if license.allows_embedding => {
+                    (WarningLevel::Info, "no fsType declared; license allows embedding".to_string())
+                }
+                _ => (
+                    WarningLevel::Warning,
+                    "no fsType declared and embedding rights are unknown".to_string(),
+                ),
+            },
+        };
+        
+        let verdict = match warning_level {
+            WarningLevel::Critical => "Refused",
+            WarningLevel::Warning => "Caution",
+            WarningLevel::Info => "Allowed",
+        };
+        
+        OperationCheck {
+            font_name: font.family.clone(),
+            operation,
+            permissions,
+            warning_level,
+            message: format!("{}: {} '{}' ({}).", verdict, operation, font.family, reason),
+        }
+    }
+    
+    fn judge_operation(permissions: &EmbeddingPermissions, operation: FontOperation) -> (WarningLevel, String) {
+        let level = permissions.level();
+        if level == EmbeddingLevel::RestrictedLicense {
+            return (WarningLevel::Critical, "the font declares Restricted License embedding".to_string());
+        }
+        
+        match operation {
+            FontOperation::EmbedInPdf => {
+                if permissions.bitmap_only() {
+                    (WarningLevel::Warning, "only embedded bitmaps may be used, not outlines".to_string())
+                } else if !permissions.allows_subsetting() {
+                    (WarningLevel::Warning, "the full font must be embedded, subsetting is not allowed".to_string())
+                } else {
+                    (WarningLevel::Info, format!("{} embedding is permitted", level))
+                }
             }
-            
-            if license_name.contains("ofl") ||
-               license_name.contains("sil") ||
-               license_name.contains("apache") ||
-               license_name.contains("mit") ||
-               license_name.contains("bsd") ||
-               license_name.contains("gpl") {
-                return LicenseType::OpenSource;
+            FontOperation::SubsetForWeb => {
+                if !permissions.allows_subsetting() {
+                    (WarningLevel::Critical, "the font forbids subsetting".to_string())
+                } else if permissions.bitmap_only() {
+                    (WarningLevel::Critical, "only bitmaps may be embedded, which web fonts cannot use".to_string())
+                } else if level == EmbeddingLevel::PreviewAndPrint {
+                    (WarningLevel::Warning, "the font only permits preview & print embedding".to_string())
+                } else {
+                    (WarningLevel::Info, format!("{} embedding with subsetting is permitted", level))
+                }
             }
+            FontOperation::EditDocument => match level {
+                EmbeddingLevel::PreviewAndPrint => {
+                    (WarningLevel::Critical, "documents embedding it must be opened read-only".to_string())
+                }
+                _ if permissions.bitmap_only() => {
+                    (WarningLevel::Warning, "only embedded bitmaps may be used while editing".to_string())
+                }
+                _ => (WarningLevel::Info, format!("{} embedding allows editing", level)),
+            },
         }
+    }
+    
+    /// Reserved Font Names from the font's records and an `OFL.txt` shipped beside it
+    pub fn reserved_font_names(&self, font: &FontDescriptor) -> Vec<String> {
+        let mut names = font.license.as_ref()
+            .map(|l| l.reserved_font_names.clone())
+            .unwrap_or_default();
         
-        // Check against known commercial fonts
-        let font_lower = font.family.to_lowercase();
-        let postscript_lower = font.postscript_name.to_lowercase();
+        if let Some(dir) = font.path.parent() {
+            for file in ["OFL.txt", "OFL", "LICENSE.txt", "LICENSE"] {
+                for name in reserved_font_names_in_file(dir.join(file)) {
+                    if !names.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+                        names.push(name);
+                    }
+                }
+            }
+        }
+        names
+    }
+    
+    /// Check that a subset, converted or instanced font doesn't keep a Reserved Font Name
+    pub fn check_derived_font(&self, font: &FontDescriptor, derived_family: &str, derivation: Derivation) -> ReservedNameCheck {
+        let reserved_names = self.reserved_font_names(font);
+        let conflicts: Vec<String> = reserved_names.iter()
+            .filter(|name| uses_reserved_name(derived_family, name))
+            .cloned()
+            .collect();
+        
+        let (warning_level, message, suggested_family) = if !conflicts.is_empty() {
+            let suggestion = suggest_family_name(derived_family, &reserved_names, derivation);
+            (
+                WarningLevel::Critical,
+                format!(
+                    "A {} of '{}' may not be named '{}': {} reserved by the OFL. Rename it, e.g. '{}'.",
+                    derivation, font.family, derived_family,
+                    conflicts.iter().map(|n| format!("'{}'", n)).collect::<Vec<_>>().join(", "),
+                    suggestion,
+                ),
+                Some(suggestion),
+            )
+        } else if reserved_names.is_empty() {
+            let is_ofl = self.license_evidence(font)
+                .is_some_and(|(license, _)| license.spdx_id.starts_with("OFL-"));
+            let message = if is_ofl {
+                format!("'{}' declares no Reserved Font Name; '{}' may be used for its {}.", font.family, derived_family, derivation)
+            } else {
+                format!("No Reserved Font Name found for '{}'.", font.family)
+            };
+            (WarningLevel::Info, message, None)
+        } else {
+            (
+                WarningLevel::Info,
+                format!("'{}' avoids the reserved name(s) of '{}'.", derived_family, font.family),
+                None,
+            )
+        };
+        
+        ReservedNameCheck {
+            font_name: font.family.clone(),
+            derived_family: derived_family.to_string(),
+            derivation,
+            reserved_names,
+            conflicts,
+            suggested_family,
+            warning_level,
+            message,
+        }
+    }
+    
+    /// License named by the font's metadata: the identification recorded by the parser,
+    /// otherwise a classification of the stored license name and URL
+    fn license_evidence(&self, font: &FontDescriptor) -> Option<(&'static KnownLicense, LicenseIdentification)> {
+        let info = font.license.as_ref()?;
+        let evidence = match &info.spdx {
+            Some(identification) => known_license(&identification.spdx_id)
+                .map(|license| (license, identification.clone())),
+            None => identify_license(Some(&info.name), info.url.as_deref()),
+        };
+        evidence.filter(|(_, identification)| identification.confidence >= MIN_LICENSE_CONFIDENCE)
+    }
+    
+    fn detect_license_type(&self, font: &FontDescriptor) -> LicenseType {
+        let known = self.knowledge.find_family(&font.family, &font.postscript_name);
+        
+        // Trust the font's own license text when it names a known license. A EULA
+        // on a family that ships with the OS is that OS's license, not a purchase.
+        if let Some((license, _)) = self.license_evidence(font) {
+            return if license.open_source {
+                LicenseType::OpenSource
+            } else if known.is_some_and(|known| known.licensing == Licensing::SystemBundled) {
+                LicenseType::SystemEmbedded
+            } else {
+                LicenseType::Commercial
+            };
+        }
         
-        if self.commercial_fonts.contains(&font_lower) ||
-           self.commercial_postscript.iter().any(|ps| postscript_lower.contains(ps)) {
-            return LicenseType::Commercial;
+        // Check against known commercial and OS-bundled families
+        if let Some(known) = known {
+            return match known.licensing {
+                Licensing::Commercial => LicenseType::Commercial,
+                Licensing::SystemBundled => LicenseType::SystemEmbedded,
+                Licensing::OpenSource => LicenseType::OpenSource,
+            };
         }
         
         // Check for system fonts (Windows, macOS)
@@ -238,88 +920,41 @@ impl LicenseChecker {
         font_lower.contains("droid")
     }
     
-    fn determine_warning_level(&self, license_type: &LicenseType, font: &FontDescriptor) -> WarningLevel {
+    /// Level before embedding permissions are considered
+    fn warning_level(license_type: &LicenseType) -> WarningLevel {
         match license_type {
-            LicenseType::Commercial => {
-                // Check if it's a very common commercial font
-                let font_lower = font.family.to_lowercase();
-                let common_system_fonts = [
-                    "arial", "times new roman", "courier new", "verdana",
-                    "tahoma", "segoe ui", "calibri", "cambria", "consolas",
-                    "ms sans serif", "ms serif", "wingdings"
-                ];
-                
-                if common_system_fonts.contains(&font_lower.as_str()) {
-                    WarningLevel::Info  // Common system font - just informational
-                } else {
-                    WarningLevel::Warning  // Less common commercial font
-                }
-            }
-            LicenseType::SystemEmbedded => WarningLevel::Info,  // Changed from Warning to Info
+            LicenseType::Commercial => WarningLevel::Warning,
+            // Fonts that ship with the OS are commonly available - just informational
+            LicenseType::SystemEmbedded => WarningLevel::Info,
             LicenseType::Unknown => WarningLevel::Info,
             LicenseType::OpenSource => WarningLevel::Info,
         }
     }
     
     fn find_alternatives(&self, font: &FontDescriptor) -> Vec<FreeAlternative> {
-        let font_lower = font.family.to_lowercase();
-        let mut alternatives = Vec::new();
-        
-        // Match based on font characteristics
-        let is_serif = font_lower.contains("serif") || 
-                      font_lower.contains("times") ||
-                      font_lower.contains("garamond") ||
-                      font_lower.contains("baskerville");
-        
-        let is_sans_serif = font_lower.contains("sans") || 
-                           font_lower.contains("helvetica") ||
-                           font_lower.contains("arial") ||
-                           font_lower.contains("futura");
-        
-        let is_monospace = font.monospaced || 
-                          font_lower.contains("mono") ||
-                          font_lower.contains("console") ||
-                          font_lower.contains("courier");
-        
-        // Filter and score alternatives
-        for alt in &self.free_alternatives {
-            let alt_lower = alt.family.to_lowercase();
-            
-            let matches_style = if is_serif {
-                alt_lower.contains("serif") || alt_lower.contains("noto serif")
-            } else if is_sans_serif {
-                alt_lower.contains("sans") && !alt_lower.contains("serif")
-            } else if is_monospace {
-                alt_lower.contains("mono") || alt_lower.contains("source code")
-            } else {
-                true // Generic match
+        let known = self.knowledge.find_family(&font.family, &font.postscript_name);
+        
+        // Curated alternatives for this family come first
+        let mut alternatives: Vec<FreeAlternative> = known
+            .map(|family| family.alternatives.iter().map(Self::to_free_alternative).collect())
+            .unwrap_or_default();
+        
+        let category = known.and_then(|family| family.category.clone())
+            .or_else(|| Self::guess_category(font).map(str::to_string));
+        
+        // Fill up with generic alternatives of the same style
+        for alt in &self.knowledge.generic_alternatives {
+            let matches_style = match (&category, &alt.category) {
+                (Some(wanted), Some(offered)) => wanted == offered,
+                _ => true, // Generic match
             };
-            
-            if matches_style {
-                // Adjust score based on specific matches
-                let mut score = alt.similarity_score;
-                
-                // Boost score for direct alternatives
-                if font_lower.contains("helvetica") && alt_lower.contains("roboto") {
-                    score = 0.95;
-                } else if font_lower.contains("arial") && alt_lower.contains("liberation sans") {
-                    score = 0.98; // Metric compatible
-                } else if font_lower.contains("times") && alt_lower.contains("liberation serif") {
-                    score = 0.98; // Metric compatible
-                } else if font_lower.contains("courier") && alt_lower.contains("liberation mono") {
-                    score = 0.98; // Metric compatible
-                }
-                
-                let mut alt_clone = alt.clone();
-                alt_clone.similarity_score = score;
-                alternatives.push(alt_clone);
+            if matches_style && !alternatives.iter().any(|a| a.family.eq_ignore_ascii_case(&alt.family)) {
+                alternatives.push(Self::to_free_alternative(alt));
             }
         }
         
         // Sort by similarity score (highest first)
-        alternatives.sort_by(|a, b| {
-            b.similarity_score.partial_cmp(&a.similarity_score).unwrap()
-        });
+        alternatives.sort_by(|a, b| b.similarity_score.total_cmp(&a.similarity_score));
         
         // Take top 3
         alternatives.truncate(3);
@@ -327,6 +962,31 @@ impl LicenseChecker {
         alternatives
     }
     
+    fn guess_category(font: &FontDescriptor) -> Option<&'static str> {
+        let font_lower = font.family.to_lowercase();
+        
+        if font.monospaced || font_lower.contains("mono") || font_lower.contains("console") || font_lower.contains("courier") {
+            Some("monospace")
+        } else if font_lower.contains("sans") || font_lower.contains("helvetica") || font_lower.contains("arial") || font_lower.contains("futura") {
+            Some("sans-serif")
+        } else if font_lower.contains("serif") || font_lower.contains("times") || font_lower.contains("garamond") || font_lower.contains("baskerville") {
+            Some("serif")
+        } else {
+            None
+        }
+    }
+    
+    fn to_free_alternative(alt: &CuratedAlternative) -> FreeAlternative {
+        let license_name = known_license(&alt.license).map_or(alt.license.as_str(), |l| l.name);
+        FreeAlternative {
+            family: alt.family.clone(),
+            similarity_score: alt.score,
+            license: LicenseType::OpenSource,
+            reason: format!("{}, {}", license_name, alt.evidence),
+            metric_compatible: alt.relation == AlternativeRelation::MetricCompatible,
+        }
+    }
+    
     pub fn generate_report(&self, fonts: &[FontDescriptor]) -> LicenseReport {
         let mut warnings = Vec::new();
         let mut has_critical = false;
@@ -435,4 +1095,383 @@ impl LicenseReport {
         
         md
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use font_core::{FontFormat, LicenseInfo};
+
+    fn font(family: &str, license: Option<LicenseInfo>) -> FontDescriptor {
+        FontDescriptor {
+            family: family.to_string(),
+            subfamily: Some("Regular".to_string()),
+            postscript_name: format!("{}-Regular", family.replace(' ', "")),
+            full_name: None,
+            path: std::path::PathBuf::from(format!("fonts/{}.ttf", family.replace(' ', ""))),
+            format: FontFormat::Ttf,
+            weight: 400,
+            italic: false,
+            monospaced: false,
+            variable: false,
+            metrics: None,
+            license,
+            classification: None,
+        }
+    }
+
+    fn license_info(name: &str) -> LicenseInfo {
+        LicenseInfo {
+            name: name.to_string(),
+            url: None,
+            allows_embedding: true,
+            allows_modification: false,
+            requires_attribution: false,
+            allows_commercial_use: false,
+            embedding: None,
+            spdx: None,
+            reserved_font_names: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_identify_license() {
+        // (description, url, SPDX id, confidence)
+        let cases: &[(&str, Option<&str>, &str, f32)] = &[
+            ("This Font Software is licensed under the SIL Open Font License, Version 1.1.", None, "OFL-1.1", 0.9),
+            ("Licensed under OFL 1.1", None, "OFL-1.1", 0.8),
+            ("SIL  OPEN FONT\nLICENSE", None, "OFL-1.1", 0.7),
+            ("", Some("https://openfontlicense.org"), "OFL-1.1", 0.95),
+            ("See http://scripts.sil.org/OFL for details", None, "OFL-1.1", 0.95),
+            ("SIL Open Font License, Version 1.1", Some("http://scripts.sil.org/OFL"), "OFL-1.1", 1.0),
+            // Only the versioned title tells 1.0 from 1.1 at the shared URL
+            ("SIL Open Font License, Version 1.0", Some("http://scripts.sil.org/OFL"), "OFL-1.0", 1.0),
+            ("", Some("http://www.apache.org/licenses/LICENSE-2.0"), "Apache-2.0", 0.95),
+            ("Licensed under the Apache License, Version 2.0", None, "Apache-2.0", 0.9),
+            ("Apache License", None, "Apache-2.0", 0.6),
+            ("Licensed under the Ubuntu Font Licence 1.0.", None, "UFL-1.0", 0.9),
+            ("", Some("http://font.ubuntu.com/ufl/"), "UFL-1.0", 0.95),
+            ("Ubuntu Font License", Some("http://font.ubuntu.com/ufl"), "UFL-1.0", 1.0),
+            ("Copyright (c) 2003 by Bitstream, Inc. All Rights Reserved. Bitstream Vera is a trademark of Bitstream, Inc.", None, "Bitstream-Vera", 0.9),
+            ("Fonts are (c) Bitstream (see below).", None, "Bitstream-Vera", 0.8),
+            ("", Some("http://dejavu-fonts.org/wiki/License"), "Bitstream-Vera", 0.95),
+        ];
+
+        for (description, url, spdx_id, confidence) in cases {
+            let (license, identification) = identify_license(Some(description), *url)
+                .unwrap_or_else(|| panic!("{:?} {:?} not identified", description, url));
+            assert_eq!(license.spdx_id, *spdx_id, "{:?} {:?}", description, url);
+            assert_eq!(identification.spdx_id, *spdx_id);
+            assert!((identification.confidence - confidence).abs() < 1e-6,
+                "{:?} {:?}: {} != {}", description, url, identification.confidence, confidence);
+        }
+
+        assert!(identify_license(None, None).is_none());
+        assert!(identify_license(Some("Copyright 2021 Some Foundry"), Some("https://example.com")).is_none());
+    }
+
+    #[test]
+    fn test_min_license_confidence_cut_off() {
+        let with_spdx = |spdx_id: &str, confidence: f32| {
+            let mut info = license_info("");
+            info.spdx = Some(LicenseIdentification { spdx_id: spdx_id.to_string(), confidence });
+            font("Mystery Grotesk", Some(info))
+        };
+        let checker = LicenseChecker::new();
+
+        let accepted = checker.check_font(&with_spdx("OFL-1.1", MIN_LICENSE_CONFIDENCE));
+        assert_eq!(accepted.spdx.map(|id| id.spdx_id).as_deref(), Some("OFL-1.1"));
+        assert_eq!(accepted.license_type, LicenseType::OpenSource);
+
+        let rejected = checker.check_font(&with_spdx("OFL-1.1", MIN_LICENSE_CONFIDENCE - 0.01));
+        assert!(rejected.spdx.is_none());
+        assert_eq!(rejected.license_type, LicenseType::Unknown);
+
+        // A bare "monotype" mention is too weak to name the license on its own
+        let (_, mention) = identify_license(Some("Designed by Monotype"), None).unwrap();
+        assert!(mention.confidence < MIN_LICENSE_CONFIDENCE);
+        let mention = checker.check_font(&font("Mystery Grotesk", Some(license_info("Designed by Monotype"))));
+        assert!(mention.spdx.is_none());
+
+        // Identified from the description when no SPDX id was stored: "eula" alone is 0.6
+        let eula = checker.check_font(&font("Mystery Grotesk", Some(license_info("See the EULA"))));
+        assert_eq!(eula.spdx.map(|id| id.spdx_id).as_deref(), Some("LicenseRef-Proprietary-EULA"));
+        assert_eq!(eula.license_type, LicenseType::Commercial);
+    }
+
+    #[test]
+    fn test_extract_reserved_font_names() {
+        let cases: &[(&str, &[&str])] = &[
+            ("Copyright 2014 Adobe (http://www.adobe.com/), with Reserved Font Name 'Source'.", &["Source"]),
+            ("Copyright 2012 The Fira Project Authors, with Reserved Font Name “Fira”.", &["Fira"]),
+            ("Copyright (c) 2011, Huerta Tipográfica, with Reserved Font Name Alegreya.", &["Alegreya"]),
+            ("with Reserved Font Names \"Alegreya\" and \"Alegreya SC\".", &["Alegreya", "Alegreya SC"]),
+            ("with Reserved Font Names: Noto, Noto Sans & Arimo; all rights reserved", &["Noto", "Noto Sans", "Arimo"]),
+            ("Reserved Font Name \"Font St. Pierre\". Other text", &["Font St. Pierre"]),
+            // Repeated declarations across records are reported once
+            ("with Reserved Font Name 'Source'.\nwith reserved font name 'SOURCE'.", &["Source"]),
+            ("Copyright 2016 Google Inc., with no Reserved Font Name.", &[]),
+            ("Copyright 2016 Google Inc. All Rights Reserved.", &[]),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(extract_reserved_font_names(text), *expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_ofl_boilerplate_declares_no_names() {
+        let ofl = "Copyright (c) <dates>, <Copyright Holder> (<URL|email>),\n\
+            with Reserved Font Name <Reserved Font Name>.\n\
+            This Font Software is licensed under the SIL Open Font License, Version 1.1.\n\
+            DEFINITIONS\n\
+            \"Reserved Font Name\" refers to any names specified as such after the\n\
+            copyright statement(s).\n\
+            3) No Modified Version of the Font Software may use the Reserved Font\n\
+            Name(s) unless explicit written permission is granted by the corresponding\n\
+            Copyright Holder. This restriction only applies to the primary font name as\n\
+            presented to the users.";
+        assert!(extract_reserved_font_names(ofl).is_empty());
+
+        let declared = format!("Copyright 2010 The Ubuntu Authors, with Reserved Font Name 'Ubuntu'.\n{}", ofl);
+        assert_eq!(extract_reserved_font_names(&declared), ["Ubuntu"]);
+    }
+
+    #[test]
+    fn test_name_words() {
+        assert_eq!(name_words("SourceSans3-Regular"), ["source", "sans", "3", "regular"]);
+        assert_eq!(name_words("IBMPlexSans"), ["ibm", "plex", "sans"]);
+        assert_eq!(name_words("Resource Sans"), ["resource", "sans"]);
+        assert!(uses_reserved_name("sourcesans pro", "Source Sans"));
+        assert!(!uses_reserved_name("Sources Sans", "Source"));
+        assert!(!uses_reserved_name("Anything", ""));
+    }
+
+    #[test]
+    fn test_derived_font_matches_reserved_names_as_words() {
+        let mut info = license_info("SIL Open Font License, Version 1.1");
+        info.reserved_font_names = vec!["Source".to_string()];
+        let font = font("Source Sans 3", Some(info));
+        let checker = LicenseChecker::new();
+
+        for derived in ["Source Sans 3", "SourceSans3 Subset", "Source-Serif"] {
+            let check = checker.check_derived_font(&font, derived, Derivation::Subset);
+            assert_eq!(check.conflicts, ["Source"], "{}", derived);
+            assert_eq!(check.warning_level, WarningLevel::Critical);
+            let suggestion = check.suggested_family.unwrap();
+            assert!(!uses_reserved_name(&suggestion, "Source"), "{}", suggestion);
+        }
+        assert_eq!(checker.check_derived_font(&font, "Source Sans 3", Derivation::Subset).suggested_family.as_deref(), Some("Subset Sans 3"));
+
+        for derived in ["Resource Sans", "Open Sourcery", "Subset Sans 3"] {
+            let check = checker.check_derived_font(&font, derived, Derivation::Subset);
+            assert!(check.is_compliant(), "{}: {}", derived, check.message);
+            assert_eq!(check.warning_level, WarningLevel::Info);
+        }
+    }
+
+    fn with_fs_type(fs_type: Option<u16>) -> FontDescriptor {
+        let mut info = license_info("SIL Open Font License, Version 1.1");
+        info.embedding = fs_type.map(EmbeddingPermissions::from_fs_type);
+        font("Embed Test", Some(info))
+    }
+
+    #[test]
+    fn test_embedding_level_of_bit_combinations() {
+        use EmbeddingLevel::*;
+        // Restrictiveness, most restrictive first
+        assert!(RestrictedLicense > PreviewAndPrint && PreviewAndPrint > Editable && Editable > Installable);
+
+        // Where old fonts set several usage bits the least restrictive one applies
+        let cases = [
+            (0x0000, Installable), (0x0001, Installable), (0x0002, RestrictedLicense),
+            (0x0004, PreviewAndPrint), (0x0008, Editable), (0x0006, PreviewAndPrint),
+            (0x000A, Editable), (0x000C, Editable), (0x000E, Editable),
+            (0x0302, RestrictedLicense), (0x0304, PreviewAndPrint), (0x0300, Installable),
+        ];
+        for (fs_type, level) in cases {
+            let permissions = EmbeddingPermissions::from_fs_type(fs_type);
+            assert_eq!(permissions.level(), level, "fsType {:#06x}", fs_type);
+            assert_eq!(permissions.allows_embedding(), level != RestrictedLicense);
+            assert_eq!(permissions.allows_subsetting(), fs_type & EmbeddingPermissions::NO_SUBSETTING == 0);
+            assert_eq!(permissions.bitmap_only(), fs_type & EmbeddingPermissions::BITMAP_ONLY != 0);
+        }
+    }
+
+    #[test]
+    fn test_check_operation_per_permission_bits() {
+        use WarningLevel::*;
+        // fsType, then the verdict for PDF embedding, web subsetting and editing
+        let cases: &[(u16, [WarningLevel; 3])] = &[
+            (0x0000, [Info, Info, Info]),
+            (0x0008, [Info, Info, Info]),
+            (0x0004, [Info, Warning, Critical]),
+            (0x0002, [Critical, Critical, Critical]),
+            // Restricted overrides the subsetting and bitmap bits
+            (0x0302, [Critical, Critical, Critical]),
+            (0x0100, [Warning, Critical, Info]),
+            (0x0108, [Warning, Critical, Info]),
+            (0x0200, [Warning, Critical, Warning]),
+            (0x0300, [Warning, Critical, Warning]),
+            (0x0104, [Warning, Critical, Critical]),
+            (0x0204, [Warning, Critical, Critical]),
+            (0x0006, [Info, Warning, Critical]),
+        ];
+        let checker = LicenseChecker::new();
+        let operations = [FontOperation::EmbedInPdf, FontOperation::SubsetForWeb, FontOperation::EditDocument];
+
+        for (fs_type, expected) in cases {
+            let font = with_fs_type(Some(*fs_type));
+            for (operation, level) in operations.iter().zip(expected) {
+                let check = checker.check_operation(&font, *operation);
+                assert_eq!(check.warning_level, *level, "fsType {:#06x}, {}", fs_type, operation);
+                assert_eq!(check.is_allowed(), *level != Critical);
+                assert_eq!(check.permissions, Some(EmbeddingPermissions::from_fs_type(*fs_type)));
+            }
+        }
+
+        let refused = checker.check_operation(&with_fs_type(Some(0x0002)), FontOperation::EmbedInPdf);
+        assert!(refused.message.starts_with("Refused: embed into PDF 'Embed Test'"), "{}", refused.message);
+    }
+
+    #[test]
+    fn test_check_operation_without_fs_type() {
+        let checker = LicenseChecker::new();
+        let declared = checker.check_operation(&with_fs_type(None), FontOperation::SubsetForWeb);
+        assert_eq!(declared.warning_level, WarningLevel::Info);
+        assert!(declared.permissions.is_none());
+
+        let unknown = checker.check_operation(&font("Embed Test", None), FontOperation::EmbedInPdf);
+        assert_eq!(unknown.warning_level, WarningLevel::Warning);
+        assert!(unknown.message.starts_with("Caution:"));
+    }
+
+    #[test]
+    fn test_check_font_escalates_with_embedding_bits() {
+        let checker = LicenseChecker::new();
+        let level = |fs_type| checker.check_font(&with_fs_type(fs_type)).warning_level;
+        assert_eq!(level(None), WarningLevel::Info);
+        assert_eq!(level(Some(0x0008)), WarningLevel::Info);
+        assert_eq!(level(Some(0x0004)), WarningLevel::Warning);
+        assert_eq!(level(Some(0x0100)), WarningLevel::Warning);
+        assert_eq!(level(Some(0x0200)), WarningLevel::Warning);
+        assert_eq!(level(Some(0x0002)), WarningLevel::Critical);
+        assert!(checker.check_font(&with_fs_type(Some(0x0002))).message.contains("Embedding permissions: Restricted"));
+    }
+
+    fn knowledge(json: &str) -> FontKnowledgeBase {
+        FontKnowledgeBase::from_json(json).unwrap()
+    }
+
+    const KNOWLEDGE: &str = r#"{
+        "version": "2026.9.30",
+        "foundries": [{ "id": "monotype", "name": "Monotype", "vendor_ids": ["MONO"], "licensing": "commercial" }],
+        "families": [
+            { "family": "Helvetica", "aliases": ["Helvetica Neue"], "postscript_patterns": ["helveticaneue"], "licensing": "commercial" },
+            { "family": "Arial", "aliases": ["Arial MT"], "postscript_patterns": ["arialmt"], "licensing": "system-bundled" },
+            { "family": "Arial Nova", "licensing": "commercial" }
+        ],
+        "generic_alternatives": [
+            { "family": "Inter", "license": "OFL-1.1", "relation": "look-alike", "score": 0.7, "evidence": "", "category": "sans-serif" },
+            { "family": "Lora", "license": "OFL-1.1", "relation": "look-alike", "score": 0.6, "evidence": "", "category": "serif" }
+        ]
+    }"#;
+
+    #[test]
+    fn test_knowledge_base_version_ordering() {
+        let newer = |a: &str, b: &str| knowledge(&format!(r#"{{ "version": "{}" }}"#, a))
+            .is_newer_than(&knowledge(&format!(r#"{{ "version": "{}" }}"#, b)));
+
+        // Parts compare as numbers, not strings
+        assert!(newer("2026.10.1", "2026.9.30"));
+        assert!(!newer("2026.9.30", "2026.10.1"));
+        assert!(newer("2026.10.10", "2026.10.9"));
+        assert!(newer("2027.1.1", "2026.12.31"));
+        assert!(!newer("2026.10.1", "2026.10.1"));
+        assert!(newer("2026.10.1", "2026.10"));
+        // Unparseable parts count as 0
+        assert!(!newer("latest", "2026.1.1"));
+        assert!(newer("2026.1.1", "2026.x.5"));
+    }
+
+    #[test]
+    fn test_knowledge_base_merge() {
+        let mut base = knowledge(KNOWLEDGE);
+        base.merge(knowledge(r#"{
+            "version": "local",
+            "foundries": [
+                { "id": "MONOTYPE", "name": "Monotype Imaging", "vendor_ids": ["MONO", "MTY"] },
+                { "id": "acme", "name": "Acme Type", "vendor_ids": ["ACME"], "licensing": "open-source" }
+            ],
+            "families": [
+                { "family": "arial", "licensing": "commercial" },
+                { "family": "Acme Grotesk", "foundry": "acme", "licensing": "open-source" }
+            ],
+            "generic_alternatives": [
+                { "family": "INTER", "license": "OFL-1.1", "relation": "metric-compatible", "score": 0.9, "evidence": "local" }
+            ]
+        }"#));
+
+        // The base keeps its own version
+        assert_eq!(base.version, "2026.9.30");
+
+        // Foundries are replaced by id, case-insensitively, or added
+        assert_eq!(base.foundries.len(), 2);
+        assert_eq!(base.foundry("monotype").unwrap().name, "Monotype Imaging");
+        assert_eq!(base.foundry_for_vendor("MTY ").unwrap().id, "MONOTYPE");
+        assert_eq!(base.foundry_for_vendor("acme").unwrap().licensing, Some(Licensing::OpenSource));
+
+        // Families are replaced whole by name, so the overlay's missing aliases are gone too
+        assert_eq!(base.families.len(), 4);
+        let arial = base.find_family("Arial", "").unwrap();
+        assert_eq!(arial.licensing, Licensing::Commercial);
+        assert!(arial.aliases.is_empty());
+        assert!(base.find_family("Arial MT", "").is_none());
+        assert_eq!(base.find_family("Acme Grotesk", "").unwrap().foundry.as_deref(), Some("acme"));
+        assert_eq!(base.find_family("Helvetica Neue", "").unwrap().family, "Helvetica");
+
+        // Replaced generic alternatives move to the end
+        let generic: Vec<(&str, f32)> = base.generic_alternatives.iter().map(|a| (a.family.as_str(), a.score)).collect();
+        assert_eq!(generic, [("Lora", 0.6), ("INTER", 0.9)]);
+    }
+
+    #[test]
+    fn test_find_family() {
+        let base = knowledge(KNOWLEDGE);
+        let found = |family: &str, postscript_name: &str| base.find_family(family, postscript_name).map(|f| f.family.as_str());
+
+        assert_eq!(found("Arial", ""), Some("Arial"));
+        assert_eq!(found("HELVETICA NEUE", "Whatever"), Some("Helvetica"));
+        assert_eq!(found("arial mt", ""), Some("Arial"));
+        // Names must match whole; "Arial Nova" is its own family, not Arial
+        assert_eq!(found("Arial Nova", "ArialNova-Bold"), Some("Arial Nova"));
+        assert_eq!(found("Arial Narrow", ""), None);
+
+        // PostScript patterns are a fallback for unlisted family names
+        assert_eq!(found("Helvetica Neue LT Std", "HelveticaNeueLTStd-Roman"), Some("Helvetica"));
+        assert_eq!(found("Arial Unicode", "ArialMT-Unicode"), Some("Arial"));
+        assert_eq!(found("Arial", "HelveticaNeue-Bold"), Some("Arial"));
+        assert_eq!(found("Mystery Grotesk", "MysteryGrotesk-Regular"), None);
+        assert_eq!(found("Mystery Grotesk", ""), None);
+    }
+
+    #[test]
+    fn test_warning_level_per_license_type() {
+        let checker = LicenseChecker::with_knowledge_base(knowledge(KNOWLEDGE));
+        let monotype = || Some(license_info("Copyright Monotype Imaging Inc. All rights reserved."));
+        let check = |family: &str, license| {
+            let warning = checker.check_font(&font(family, license));
+            (warning.license_type, warning.warning_level)
+        };
+
+        assert_eq!(check("Helvetica", None), (LicenseType::Commercial, WarningLevel::Warning));
+        assert_eq!(check("Arial", None), (LicenseType::SystemEmbedded, WarningLevel::Info));
+        assert_eq!(check("Mystery Grotesk", None), (LicenseType::Unknown, WarningLevel::Info));
+        // A foundry EULA in the name table is the OS's license on bundled families
+        assert_eq!(check("Arial", monotype()), (LicenseType::SystemEmbedded, WarningLevel::Info));
+        assert_eq!(check("Helvetica", monotype()), (LicenseType::Commercial, WarningLevel::Warning));
+        assert_eq!(check("Mystery Grotesk", monotype()), (LicenseType::Commercial, WarningLevel::Warning));
+        // License text naming an open license wins over the knowledge base
+        let ofl = Some(license_info("Licensed under the SIL Open Font License, Version 1.1"));
+        assert_eq!(check("Arial", ofl), (LicenseType::OpenSource, WarningLevel::Info));
+    }
+
+}
@@ -1,6 +1,6 @@
 use regex::Regex;
 use lazy_static::lazy_static;
-use font_core::{FontRequest, FontStyle, FontError};
+use font_core::{width_class_from_name, FontRequest, FontStyle, FontError};
 
 pub struct FontNormalizer;
 
@@ -40,6 +40,7 @@ impl FontNormalizer {
             style: if italic { FontStyle::Italic } else { FontStyle::Normal },
             italic,
             monospaced: final_monospaced,
+            width: width_class_from_name(&without_encoding),
         })
     }
     
@@ -1,12 +1,15 @@
 use std::fs;
 use std::path::Path;
 use ttf_parser::Face;
-use font_core::{FontDescriptor, FontFormat, FontMetrics, LicenseInfo, FontError};
+use font_glyph::ShapeClassifier;
+use font_core::{EmbeddingPermissions, FontClassification, FontDescriptor, FontFormat, FontMetrics, LicenseInfo, FontError};
 
 #[derive(Debug, Clone)]
 pub struct FontParser;
 
 impl FontParser {
+    /// Parse a font's names, metrics, license and declared classification. The outline
+    /// style is left unmeasured; see `classify_shape`.
     pub fn parse_font_file<P: AsRef<Path>>(&self, path: P) -> Result<FontDescriptor, FontError> {
         let path = path.as_ref();
         
@@ -45,6 +48,9 @@ impl FontParser {
         // Detect license
         let license = self.detect_license(&face, &family);
         
+        // PANOSE and sFamilyClass as declared in OS/2
+        let classification = self.extract_classification(&face);
+        
         Ok(FontDescriptor {
             family,
             subfamily,
@@ -58,14 +64,52 @@ impl FontParser {
             variable,
             metrics,
             license,
+            classification,
+        })
+    }
+    
+    /// Like `parse_font_file`, also measuring the style from the outlines
+    pub fn parse_classified_font_file<P: AsRef<Path>>(&self, path: P) -> Result<FontDescriptor, FontError> {
+        let mut descriptor = self.parse_font_file(path)?;
+        self.classify_shape(&mut descriptor)?;
+        Ok(descriptor)
+    }
+    
+    /// Measure the style of a parsed font from its outlines, unless it already has one.
+    /// This reads the file again and extracts a few dozen glyphs, so it is left to the
+    /// callers that need the shape (database and index builds) rather than every scan.
+    pub fn classify_shape(&self, descriptor: &mut FontDescriptor) -> Result<(), FontError> {
+        if descriptor.classification.is_some_and(|c| c.shape.is_some()) {
+            return Ok(());
+        }
+        
+        let font_data = fs::read(&descriptor.path).map_err(FontError::Io)?;
+        let face = Face::parse(&font_data, 0)
+            .map_err(|e| FontError::Parse(format!("Failed to parse font: {}", e)))?;
+        
+        if let Some(shape) = ShapeClassifier::new().classify_face(&face) {
+            descriptor.classification
+                .get_or_insert(FontClassification { panose: [0; 10], family_class: 0, shape: None })
+                .shape = Some(shape);
+        }
+        Ok(())
+    }
+    
+    fn extract_classification(&self, face: &Face) -> Option<FontClassification> {
+        // sFamilyClass at offset 30 and the 10 PANOSE bytes right after it, in every OS/2 version
+        face.raw_face().table(ttf_parser::Tag::from_bytes(b"OS/2")).and_then(|os2| {
+            let family_class = i16::from_be_bytes(os2.get(30..32)?.try_into().ok()?);
+            let panose: [u8; 10] = os2.get(32..42)?.try_into().ok()?;
+            Some(FontClassification { panose, family_class, shape: None })
         })
     }
     
     fn extract_string(&self, face: &Face, name_id: u16) -> Option<String> {
+        // Skip records ttf-parser cannot decode (e.g. Mac Roman) instead of stopping at the first
         face.names()
             .into_iter()
-            .find(|name| name.name_id == name_id)
-            .and_then(|name| name.to_string())
+            .filter(|name| name.name_id == name_id)
+            .find_map(|name| name.to_string())
     }
     
     fn determine_format(&self, path: &Path, data: &[u8]) -> FontFormat {
@@ -192,15 +236,29 @@ impl FontParser {
         })
     }
     
+    /// OS/2 `fsType`, read from the raw table as ttf-parser only exposes part of it
+    fn extract_fs_type(&self, face: &Face) -> Option<u16> {
+        let os2 = face.raw_face().table(ttf_parser::Tag::from_bytes(b"OS/2"))?;
+        os2.get(8..10).map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+    
     fn detect_license(&self, face: &Face, family: &str) -> Option<LicenseInfo> {
-        // Try to extract license info from font names
-        let license_text = self.extract_string(face, 13)  // 13 = License Description in OpenType spec
-        .or_else(|| self.extract_string(face, 14));  // 14 = License URL
+        let description = self.extract_string(face, ttf_parser::name_id::LICENSE);
+        let url = self.extract_string(face, ttf_parser::name_id::LICENSE_URL);
+        let identified = font_license::identify_license(description.as_deref(), url.as_deref());
         
-        // Simple license detection based on family name patterns
+        // Fall back to family name patterns when the license text is missing or unrecognized
         let family_lower = family.to_lowercase();
         let (name, allows_embedding, allows_modification, requires_attribution, allows_commercial_use) = 
-            if family_lower.contains("noto") {
+            if let Some((license, _)) = &identified {
+                (
+                    license.name.to_string(),
+                    license.allows_embedding,
+                    license.allows_modification,
+                    license.requires_attribution,
+                    license.allows_commercial_use,
+                )
+            } else if family_lower.contains("noto") {
                 ("SIL Open Font License".to_string(), true, true, false, true)
             } else if family_lower.contains("liberation") {
                 ("SIL Open Font License".to_string(), true, true, false, true)
@@ -215,13 +273,60 @@ impl FontParser {
                 ("Unknown (Commercial?)".to_string(), false, false, true, false)
             };
         
+        // The font's own declaration overrides the name-based guess
+        let embedding = self.extract_fs_type(face).map(EmbeddingPermissions::from_fs_type);
+        let allows_embedding = embedding.map_or(allows_embedding, |p| p.allows_embedding());
+        
+        let mut reserved_font_names = Vec::new();
+        for text in [self.extract_string(face, ttf_parser::name_id::COPYRIGHT_NOTICE), description].into_iter().flatten() {
+            for name in font_license::extract_reserved_font_names(&text) {
+                if !reserved_font_names.iter().any(|n: &String| n.eq_ignore_ascii_case(&name)) {
+                    reserved_font_names.push(name);
+                }
+            }
+        }
+        
         Some(LicenseInfo {
             name,
-            url: license_text,
+            url,
             allows_embedding,
             allows_modification,
             requires_attribution,
             allows_commercial_use, // ADDED THIS FIELD
+            embedding,
+            spdx: identified.map(|(_, identification)| identification),
+            reserved_font_names,
         })
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shape_is_classified_on_demand() {
+        let path = Path::new("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf");
+        if !path.exists() {
+            eprintln!("skipping: {} not installed", path.display());
+            return;
+        }
+
+        let mut descriptor = FontParser.parse_font_file(path).unwrap();
+        let declared = descriptor.classification.unwrap();
+        assert!(declared.has_panose());
+        assert!(declared.shape.is_none());
+
+        FontParser.classify_shape(&mut descriptor).unwrap();
+        let classified = descriptor.classification.unwrap();
+        assert_eq!(classified.panose, declared.panose);
+        assert_eq!(classified.family_class, declared.family_class);
+        assert!(classified.shape.is_some());
+        assert_eq!(FontParser.parse_classified_font_file(path).unwrap().classification, Some(classified));
+
+        // A descriptor whose file is gone keeps its declared classification
+        let mut moved = FontParser.parse_font_file(path).unwrap();
+        moved.path = "/nonexistent/DejaVuSans.ttf".into();
+        assert!(FontParser.classify_shape(&mut moved).is_err());
+        assert_eq!(moved.classification, Some(declared));
+    }
+}
@@ -1,24 +1,33 @@
 use font_core::{
     FontRequest, ResolutionResult, ResolverConfig, FontError, FontDescriptor, 
     FontSource, SubstitutionReason, FontMatchScore, FontResult,
-    EnhancedResolverConfig, FontMetrics, LicenseInfo, FontFormat
+    EnhancedResolverConfig, FontMetrics, LicenseInfo, FontFormat, ScoringProfile, FontClassification
 };
 use font_normalizer::FontNormalizer;
-use font_license::{LicenseChecker, LicenseWarning};
+use font_license::{Derivation, FontOperation, LicenseChecker, LicenseWarning, OperationCheck, ReservedNameCheck};
 use font_sources::FontSourceManager;
 use font_scanner::FontScanner;
 use std::collections::HashMap;
-use font_similarity::{FontSimilarityEngine, MatchTier}; // Removed TieredMatchResult
+use std::sync::{Arc, Mutex, PoisonError};
+use font_similarity::{
+    CandidateComponents, FitReport, FontPairing, FontSimilarityEngine, GlyphSignatureStore, LabelledQuery, MatchTier,
+    PairingRole, PairingScope, ProfileFitter, QuerySample, ScoreModel, SimilarityGraph,
+}; // Removed TieredMatchResult
 use font_acquisition::FontAcquisitionManager;
 use font_compressor::{CompressedFontDatabase, FontCompressor};
 use font_updater::FontUpdater;
-use font_cache::HybridFontCache;
+use font_cache::{CacheLookup, CachedRequest, HybridFontCache, VerifyReport};
+use font_substitutes::SubstituteRegistry;
+use font_web_db::{WebFontQuery, WebFontSearchPage};
 use serde::{Serialize, Deserialize};
 
 pub struct FontResolver {
     normalizer: FontNormalizer,
     config: ResolverConfig,
     scanner: FontScanner,
+    /// Verified against the system fonts on construction, again whenever the scanned fonts change
+    substitutes: Mutex<Option<Arc<SubstituteRegistry>>>,
+    profile: ScoringProfile,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +46,7 @@ pub struct SuggestionResult {
 }
 
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum TieredResolutionResult {
     Exact(FontDescriptor, f32), // font, similarity score
     Similar(Vec<FontDescriptor>, f32), // fonts, best score
@@ -59,8 +69,16 @@ impl FontResolver {
             normalizer: FontNormalizer,
             config,
             scanner: FontScanner,
+            substitutes: load_substitutes_at_startup(&FontScanner),
+            profile: ScoringProfile::shared().clone(),
         }
     }
+    
+    /// Use `profile`'s resolver weights instead of the embedded profile's
+    pub fn with_profile(mut self, profile: ScoringProfile) -> Self {
+        self.profile = profile;
+        self
+    }
 
     /// Resolve a font name to an actual system font
     pub fn resolve(&self, font_name: &str) -> Result<ResolutionResult, FontError> {
@@ -75,6 +93,11 @@ impl FontResolver {
             self.find_best_match(&request, &system_fonts);
         
         let mut warnings = Vec::new();
+        if substituted {
+            for failure in self.substitute_registry(&system_fonts).failures_for(&request.family) {
+                warnings.push(format!("Skipped unverified metric-compatible substitute: {}", failure.message));
+            }
+        }
         
         // If we couldn't find a good match, try fallback
         let (font, source, substituted, substitution_reason) = if let Some(font) = best_match {
@@ -127,6 +150,16 @@ impl FontResolver {
             }
         }
         
+        // Try metric-compatible substitutes whose advance widths were verified locally
+        for substitute in self.substitute_registry(system_fonts).verified_substitutes(&request.family) {
+            if let Some(family_fonts) = fonts_by_family.get(&substitute.to_lowercase()) {
+                let (best_match, score) = self.find_best_in_family(request, family_fonts);
+                if score.overall > 0.6 {
+                    return (Some(best_match), score, true, Some(SubstitutionReason::FontNotFound));
+                }
+            }
+        }
+        
         // Try common font substitutions
         let substituted_family = self.get_font_substitution(&request.family);
         if let Some(family_fonts) = fonts_by_family.get(&substituted_family.to_lowercase()) {
@@ -237,11 +270,12 @@ impl FontResolver {
         };
         
         // Overall score (weighted average)
-        let overall = family_score * 0.4 
-                    + weight_score * 0.3 
-                    + style_score * 0.2 
-                    + monospaced_score * 0.05 
-                    + metrics_score * 0.05;
+        let weights = &self.profile.resolver;
+        let overall = family_score * weights.family
+                    + weight_score * weights.weight
+                    + style_score * weights.style
+                    + monospaced_score * weights.monospaced
+                    + metrics_score * weights.metrics;
         
         FontMatchScore {
             overall,
@@ -269,14 +303,15 @@ impl FontResolver {
         (best_font, best_score)
     }
     
-    /// Get font substitution (common substitutions like Helvetica -> Arial)
+    /// Metric-compatible substitutes, verified against `system_fonts`
+    fn substitute_registry(&self, system_fonts: &[FontDescriptor]) -> Arc<SubstituteRegistry> {
+        verified_registry(&self.substitutes, system_fonts)
+    }
+    
+    /// Get font substitution for names that don't promise matching metrics (symbol fonts, CSS generics);
+    /// metric-compatible substitutes come from the verified registry instead
     fn get_font_substitution(&self, family: &str) -> String {
         let substitutions: HashMap<&str, &str> = [
-            ("helvetica", "arial"),
-            ("helvetica neue", "arial"),
-            ("times", "times new roman"),
-            ("times roman", "times new roman"),
-            ("courier", "courier new"),
             ("zapfdingbats", "wingdings"),
             ("symbol", "wingdings"),
             ("monospace", "courier new"),
@@ -364,11 +399,81 @@ impl FontResolver {
         
         Ok(results)
     }
+    
+    /// Component scores of every candidate for a labelled query, for `ProfileFitter`
+    pub fn query_sample(&self, request: &FontRequest, expected: &str, fonts: &[FontDescriptor]) -> QuerySample {
+        let candidates = fonts.iter()
+            .map(|font| {
+                let score = self.calculate_match_score(request, font);
+                CandidateComponents {
+                    family: font.family.clone(),
+                    values: [score.family, score.weight, score.style, score.monospaced, score.metrics],
+                    visual: None,
+                    monospace_mismatch: false,
+                }
+            })
+            .collect();
+        
+        QuerySample::new(ScoreModel::Resolver, expected, candidates)
+    }
+}
+
+/// The cached registry while it was verified against `fonts`, otherwise a newly loaded one
+fn verified_registry(cached: &Mutex<Option<Arc<SubstituteRegistry>>>, fonts: &[FontDescriptor]) -> Arc<SubstituteRegistry> {
+    let mut cached = cached.lock().unwrap_or_else(PoisonError::into_inner);
+    match cached.as_ref() {
+        Some(registry) if registry.is_verified_against(fonts) => registry.clone(),
+        _ => {
+            let registry = load_substitutes(fonts);
+            *cached = Some(registry.clone());
+            registry
+        }
+    }
+}
+
+/// Load the substitute registry against the system fonts, reporting pairs that failed verification.
+/// Without a font list the registry is left to load on first use.
+fn load_substitutes_at_startup(scanner: &FontScanner) -> Mutex<Option<Arc<SubstituteRegistry>>> {
+    match scanner.scan_system_fonts() {
+        Ok(fonts) => Mutex::new(Some(load_substitutes(&fonts))),
+        Err(e) => {
+            eprintln!("⚠️  Failed to scan system fonts for substitute verification: {}", e);
+            Mutex::new(None)
+        }
+    }
+}
+
+fn load_substitutes(fonts: &[FontDescriptor]) -> Arc<SubstituteRegistry> {
+    let registry = SubstituteRegistry::load(fonts);
+    for failure in registry.failures() {
+        eprintln!(
+            "⚠️  Metric-compatible substitute {} → {} failed verification: {}",
+            failure.pair.original, failure.pair.substitute, failure.message
+        );
+    }
+    Arc::new(registry)
 }
 
 // ============================================================
 // ENHANCED FONT RESOLVER WITH CACHE
 // ============================================================
+
+/// Glyph databases built by `fr build-glyph-db`, loaded when present for visual similarity
+const GLYPH_DATABASE_PATHS: &[&str] = &["data/glyph_signatures.bin", "data/web_glyph_signatures.bin"];
+
+fn load_default_glyph_store() -> Option<GlyphSignatureStore> {
+    let mut store = GlyphSignatureStore::new();
+    for path in GLYPH_DATABASE_PATHS {
+        if !std::path::Path::new(path).exists() {
+            continue;
+        }
+        match font_glyph_db::load_database_from_file(path) {
+            Ok(database) => store.add_database(&database),
+            Err(e) => eprintln!("⚠️  Ignoring glyph database {}: {}", path, e),
+        }
+    }
+    (!store.is_empty()).then_some(store)
+}
 #[allow(dead_code)]
 pub struct EnhancedFontResolver {
     normalizer: FontNormalizer,
@@ -381,18 +486,22 @@ pub struct EnhancedFontResolver {
     acquisition_manager: Option<FontAcquisitionManager>,
     updater: Option<FontUpdater>,
     compressed_database: Option<CompressedFontDatabase>,
+    substitutes: Mutex<Option<Arc<SubstituteRegistry>>>,
 }
 
 impl EnhancedFontResolver {
     pub fn new(config: EnhancedResolverConfig) -> FontResult<Self> {
         // Initialize cache
         let cache = if config.cache_enabled {
-            match HybridFontCache::new(
+            let cache_dir = config.cache_dir.clone()
+                .unwrap_or_else(font_cache::default_cache_dir);
+            match HybridFontCache::with_dir(
+                cache_dir,
                 config.memory_limit_mb,
                 config.disk_limit_mb,
                 config.auto_pin_threshold,
             ) {
-                Ok(cache) => Some(cache),
+                Ok(cache) => Some(cache.with_cleanup_mode(config.cache_cleanup_mode.clone())),
                 Err(e) => {
                     eprintln!("⚠️  Failed to initialize cache: {}", e);
                     eprintln!("   Continuing without cache...");
@@ -416,8 +525,10 @@ impl EnhancedFontResolver {
         // Set source priority
         source_manager.set_priority(config.font_source_priority.clone());
         
-        // Initialize similarity engine with empty data
-        let similarity_engine = FontSimilarityEngine::new(None);
+        // Initialize similarity engine with empty data; glyph databases add visual similarity
+        let mut similarity_engine = FontSimilarityEngine::new(None);
+        similarity_engine.set_glyph_store(load_default_glyph_store());
+        similarity_engine.set_profile(config.load_scoring_profile()?);
         
         // Initialize acquisition manager
         let mut acquisition_manager = FontAcquisitionManager::new();
@@ -436,6 +547,7 @@ impl EnhancedFontResolver {
             acquisition_manager: Some(acquisition_manager),
             updater: None,
             compressed_database: None,
+            substitutes: load_substitutes_at_startup(&FontScanner),
         })
     }
     
@@ -463,19 +575,36 @@ impl EnhancedFontResolver {
             }
         }
         
-        // Initialize similarity engine with precomputed data
-        resolver.similarity_engine = FontSimilarityEngine::new(
+        // Initialize similarity engine with the database's neighbour index
+        resolver.similarity_engine.set_neighbour_index(
             resolver.compressed_database.as_ref()
-                .and_then(|db| db.similarity_matrix.clone())
+                .and_then(|db| db.neighbour_index.clone())
         );
         
         Ok(resolver)
     }
     
     pub fn resolve_with_sources(&self, font_name: &str) -> FontResult<ResolutionResult> {
-        // Check cache first
+        // Normalize the request
+        let request = self.normalizer.normalize(font_name)?;
+        let cache_key = self.cache_key_for(&request);
+        
+        // Check cache first; stale entries are dropped by the cache and re-resolved below
+        let mut stale_warning = None;
         if let Some(cache) = &self.cache {
-            if let Some(cached_font) = cache.get(font_name) {
+            let cached_font = match cache.lookup(&cache_key) {
+                CacheLookup::Hit(font) => Some(font),
+                CacheLookup::Stale { font, status } => {
+                    stale_warning = Some(format!(
+                        "Cached entry was stale ({}: {}), re-resolved",
+                        status, font.path.display()
+                    ));
+                    None
+                }
+                CacheLookup::NotFound => return Err(FontError::NotFound(font_name.to_string())),
+                CacheLookup::Miss => None,
+            };
+            if let Some(cached_font) = cached_font {
                 // Check license if warnings are enabled
                 let mut warnings = vec!["Loaded from cache".to_string()];
                 if self.config.license_warnings != font_core::LicenseWarningLevel::Off {
@@ -497,16 +626,13 @@ impl EnhancedFontResolver {
             }
         }
         
-        // Normalize the request
-        let request = self.normalizer.normalize(font_name)?;
-        
         // Try to find the font in sources
         let mut found_font = None;
         let mut source_type = FontSource::System;
         
         // Use source manager to find font
         let mut temp_source_manager = self.source_manager.clone();
-        if let Ok(Some(font)) = temp_source_manager.find_font(&request.family) {
+        if let Ok(Some(font)) = temp_source_manager.find_font_for(&request) {
             found_font = Some(font);
         }
         
@@ -529,7 +655,7 @@ impl EnhancedFontResolver {
         match found_font {
             Some(font) => {
                 // Check license
-                let mut warnings = Vec::new();
+                let mut warnings: Vec<String> = stale_warning.into_iter().collect();
                 if self.config.license_warnings != font_core::LicenseWarningLevel::Off {
                     let license_warning = self.license_checker.check_font(&font);
                     if license_warning.warning_level != font_license::WarningLevel::Info {
@@ -548,7 +674,13 @@ impl EnhancedFontResolver {
                 
                 // Cache the result
                 if let Some(cache) = &self.cache {
-                    if let Err(e) = cache.put(font_name, font.clone()) {
+                    let cached_request = CachedRequest {
+                        family: font.family.clone(),
+                        weight: request.weight,
+                        italic: request.italic,
+                        width: request.width,
+                    };
+                    if let Err(e) = cache.put_request(&cache_key, cached_request, font.clone()) {
                         warnings.push(format!("Failed to cache: {}", e));
                     }
                 }
@@ -564,12 +696,44 @@ impl EnhancedFontResolver {
                 })
             }
             None => {
-                // Font not found
+                // Font not found; remember that for a while so repeated misses skip the scan
+                if let Some(cache) = &self.cache {
+                    if self.config.negative_cache_ttl_secs > 0 {
+                        let ttl = std::time::Duration::from_secs(self.config.negative_cache_ttl_secs);
+                        if let Err(e) = cache.put_not_found(&cache_key, ttl) {
+                            eprintln!("⚠️  Failed to cache not-found result: {}", e);
+                        }
+                    }
+                }
                 Err(FontError::NotFound(font_name.to_string()))
             }
         }
     }
     
+    /// Cache key for a request: the requested face plus the source setup that answers it,
+    /// so changing sources or priority never serves an answer produced under another setup
+    fn cache_key_for(&self, request: &FontRequest) -> String {
+        let mut sources = Vec::new();
+        if self.config.system_fonts_enabled {
+            sources.push("system");
+        }
+        if self.config.web_fonts_enabled {
+            sources.push("web");
+        }
+        if self.config.custom_fonts_enabled {
+            sources.push("custom");
+        }
+        format!("{}@{:?}[{}]", request.cache_key(), self.config.font_source_priority, sources.join("+"))
+    }
+    
+    /// Cache key for a font name as `resolve_with_sources` would use it
+    fn cache_key_for_name(&self, font_name: &str) -> String {
+        match self.normalizer.normalize(font_name) {
+            Ok(request) => self.cache_key_for(&request),
+            Err(_) => font_name.to_string(),
+        }
+    }
+    
     pub async fn get_suggestions(
         &self,
         font_name: &str,
@@ -580,7 +744,9 @@ impl EnhancedFontResolver {
 
         // 1. Get matches from local sources
         let all_fonts = self.get_all_available_fonts()?;
-        let local_matches = self.similarity_engine.find_tiered_matches(&request, &all_fonts, 20);
+        let reference = self.catalogued_classification(&request.family);
+        let local_matches = self.similarity_engine
+            .find_tiered_matches_with_reference(&request, reference.as_ref(), &all_fonts, 20);
 
         for m in local_matches.matches {
             let is_critical = self.is_license_critical(&m.font, false);
@@ -642,10 +808,13 @@ impl EnhancedFontResolver {
             }
         }
 
+        let thresholds = &self.similarity_engine.profile().suggestions;
+
         // 3. Worst-Case Safety: If no good results, explicitly look for substitutions
-        if results.is_empty() || results.iter().all(|r| r.score < 0.6) {
+        if results.is_empty() || results.iter().all(|r| r.score < thresholds.substitute_below) {
             // Find best substitutes based on metrics only
-            let substitutes = self.similarity_engine.find_tiered_matches(&request, &all_fonts, 5);
+            let substitutes = self.similarity_engine
+                .find_tiered_matches_with_reference(&request, reference.as_ref(), &all_fonts, 5);
             for sub in substitutes.matches {
                  results.push(SuggestionResult {
                     family: sub.font.family.clone(),
@@ -668,12 +837,12 @@ impl EnhancedFontResolver {
 
         // 3. APPLY SMART THRESHOLDING
         if let Some(best) = results.first() {
-            if best.score > 0.98 {
+            if best.score > thresholds.perfect {
                 // If perfect match found, only return it + top 3 alternatives
                 results.truncate(4);
-            } else if best.score > 0.90 {
-                // If good match found, truncate anything < 75%
-                results.retain(|r| r.score >= 0.75);
+            } else if best.score > thresholds.good {
+                // If good match found, truncate anything below the minimum
+                results.retain(|r| r.score >= thresholds.good_min_score);
                 results.truncate(10);
             } else {
                 // No good match, show best 20 available
@@ -711,8 +880,10 @@ impl EnhancedFontResolver {
         let all_fonts = self.get_all_available_fonts()?;
         
         // Use similarity engine for tiered matching
-        let tiered_result = self.similarity_engine.find_tiered_matches(
+        let reference = self.catalogued_classification(&request.family);
+        let tiered_result = self.similarity_engine.find_tiered_matches_with_reference(
             &request,
+            reference.as_ref(),
             &all_fonts,
             5, // limit per tier
         );
@@ -779,6 +950,18 @@ impl EnhancedFontResolver {
         Ok(fonts)
     }
 
+    /// Available fonts with their style measured from the outlines, for the index, graph and
+    /// pairings. Fonts whose files can't be read keep their declared classification.
+    fn get_classified_available_fonts(&self) -> FontResult<Vec<FontDescriptor>> {
+        let mut fonts = self.get_all_available_fonts()?;
+        for font in &mut fonts {
+            if font.path.exists() {
+                let _ = font_parser::FontParser.classify_shape(font);
+            }
+        }
+        Ok(fonts)
+    }
+
     fn remember_font_metadata(&self, _font: &font_compressor::CompressedFontData) {
         // Implementation for saving to a local JSON persistent cache
         // (Simplified for this task: would use font-cache or a new JSON file)
@@ -861,7 +1044,46 @@ impl EnhancedFontResolver {
         // Try to find the font first
         let mut temp_source_manager = self.source_manager.clone();
         if let Ok(Some(font)) = temp_source_manager.find_font(font_name) {
-            Ok(self.license_checker.check_font(&font))
+            let mut warning = self.license_checker.check_font(&font);
+            
+            // Don't promise matching metrics where the installed fonts were shown to differ
+            let registry = self.substitute_registry();
+            for alt in warning.alternatives.iter_mut().filter(|a| a.metric_compatible) {
+                if registry.failures_for(&font.family).any(|f| f.pair.substitute.eq_ignore_ascii_case(&alt.family)) {
+                    alt.metric_compatible = false;
+                }
+            }
+            Ok(warning)
+        } else {
+            Err(FontError::NotFound(font_name.to_string()))
+        }
+    }
+    
+    /// Metric-compatible substitutes, verified against the system fonts when the resolver was
+    /// created; verified again if the scanned fonts have changed since
+    pub fn substitute_registry(&self) -> Arc<SubstituteRegistry> {
+        let fonts = self.scanner.scan_system_fonts().unwrap_or_else(|e| {
+            eprintln!("⚠️  Failed to scan system fonts for substitute verification: {}", e);
+            Vec::new()
+        });
+        verified_registry(&self.substitutes, &fonts)
+    }
+    
+    /// Check whether a font's embedding permissions allow an operation
+    pub fn check_operation(&self, font_name: &str, operation: FontOperation) -> FontResult<OperationCheck> {
+        let mut temp_source_manager = self.source_manager.clone();
+        if let Ok(Some(font)) = temp_source_manager.find_font(font_name) {
+            Ok(self.license_checker.check_operation(&font, operation))
+        } else {
+            Err(FontError::NotFound(font_name.to_string()))
+        }
+    }
+    
+    /// Check that a font derived from `font_name` doesn't keep one of its Reserved Font Names
+    pub fn check_derived_font(&self, font_name: &str, derived_family: &str, derivation: Derivation) -> FontResult<ReservedNameCheck> {
+        let mut temp_source_manager = self.source_manager.clone();
+        if let Ok(Some(font)) = temp_source_manager.find_font(font_name) {
+            Ok(self.license_checker.check_derived_font(&font, derived_family, derivation))
         } else {
             Err(FontError::NotFound(font_name.to_string()))
         }
@@ -871,6 +1093,11 @@ impl EnhancedFontResolver {
         self.source_manager.get_web_db().map(|db| db.count())
     }
 
+    /// Faceted search over the web font database (requires web fonts to be enabled)
+    pub fn search_web_fonts(&self, query: &WebFontQuery) -> Option<WebFontSearchPage> {
+        self.source_manager.get_web_db().map(|db| db.search(query))
+    }
+
     pub fn get_cache_stats(&self) -> Option<FontResult<font_core::CacheStats>> {
         self.cache.as_ref().map(|cache| cache.stats())
     }
@@ -882,9 +1109,19 @@ impl EnhancedFontResolver {
         }
     }
 
+    /// Check cached descriptors against their font files, optionally removing dangling ones
+    pub fn verify_cache(&self, purge: bool) -> Option<FontResult<VerifyReport>> {
+        self.cache.as_ref().map(|cache| cache.verify(purge))
+    }
+
     pub fn remove_from_cache(&self, font_names: Vec<String>) -> FontResult<usize> {
         match &self.cache {
-            Some(cache) => cache.remove_entries(&font_names),
+            Some(cache) => {
+                let keys: Vec<String> = font_names.iter()
+                    .map(|name| self.cache_key_for_name(name))
+                    .collect();
+                cache.remove_entries(&keys)
+            }
             None => Ok(0),
         }
     }
@@ -916,23 +1153,61 @@ impl EnhancedFontResolver {
         Err(FontError::NotFound(font_name.to_string()))
     }
 
+    /// Pin a font so cleanup never removes it. `font_name` may be a family, which pins
+    /// every cached face of it, a face name such as "Arial Bold", or a raw cache key.
+    /// A font that isn't cached yet is resolved first so there is an entry to keep.
     pub fn pin_font(&self, font_name: &str) -> FontResult<()> {
-        match &self.cache {
-            Some(cache) => {
-                cache.pin_font(font_name);
-                Ok(())
-            }
-            None => Err(FontError::CacheError("Cache is disabled".to_string())),
+        let cache = self.cache.as_ref()
+            .ok_or_else(|| FontError::CacheError("Cache is disabled".to_string()))?;
+        
+        let mut keys = self.cache_keys_matching(cache, font_name);
+        if keys.is_empty() {
+            self.resolve_with_sources(font_name)?;
+            keys.push(self.cache_key_for_name(font_name));
+        }
+        for key in keys {
+            cache.pin_font(&key);
         }
+        Ok(())
     }
 
+    /// Unpin a font, named as for `pin_font`
     pub fn unpin_font(&self, font_name: &str) -> FontResult<()> {
-        match &self.cache {
-            Some(cache) => {
-                cache.unpin_font(font_name);
-                Ok(())
+        let cache = self.cache.as_ref()
+            .ok_or_else(|| FontError::CacheError("Cache is disabled".to_string()))?;
+        
+        let mut keys = self.cache_keys_matching(cache, font_name);
+        keys.push(self.cache_key_for_name(font_name));
+        for key in keys {
+            cache.unpin_font(&key);
+        }
+        Ok(())
+    }
+    
+    /// Cache keys a user-supplied name refers to: itself when it is a key, every cached face
+    /// when it names a family without a style, otherwise the face it names if that is cached
+    fn cache_keys_matching(&self, cache: &HybridFontCache, font_name: &str) -> Vec<String> {
+        if cache.contains_key(font_name) {
+            return vec![font_cache::normalize_key(font_name)];
+        }
+        let Ok(request) = self.normalizer.normalize(font_name) else {
+            return Vec::new();
+        };
+        
+        let key = font_cache::normalize_key(&self.cache_key_for(&request));
+        let bare_family = request.weight == 400 && !request.italic && request.width == font_core::WIDTH_NORMAL;
+        if bare_family {
+            let mut keys = cache.keys_matching(|cached| {
+                self.normalizer.normalize(&cached.family).is_ok_and(|r| r.family == request.family)
+            });
+            if cache.contains_key(&key) && !keys.contains(&key) {
+                keys.push(key);
             }
-            None => Err(FontError::CacheError("Cache is disabled".to_string())),
+            keys
+        } else if cache.contains_key(&key) {
+            vec![key]
+        } else {
+            Vec::new()
         }
     }
 
@@ -958,15 +1233,81 @@ impl EnhancedFontResolver {
             self.compressed_database = Some(new_database);
             
             // Update similarity engine
-            self.similarity_engine = FontSimilarityEngine::new(
+            self.similarity_engine.set_neighbour_index(
                 self.compressed_database.as_ref()
-                    .and_then(|db| db.similarity_matrix.clone())
+                    .and_then(|db| db.neighbour_index.clone())
             );
         }
         
         Ok(())
     }
     
+    /// Add a glyph signature database's families to the visual similarity store
+    pub fn load_glyph_database<P: AsRef<std::path::Path>>(&mut self, path: P) -> FontResult<usize> {
+        let database = font_glyph_db::load_database_from_file(path.as_ref())
+            .map_err(|e| FontError::Parse(format!("Failed to load glyph database {}: {}", path.as_ref().display(), e)))?;
+        
+        let mut store = self.similarity_engine.glyph_store().cloned().unwrap_or_default();
+        store.add_database(&database);
+        let families = store.len();
+        self.similarity_engine.set_glyph_store(Some(store));
+        Ok(families)
+    }
+    
+    /// Add every available font to the similarity engine's neighbour index, so that
+    /// installed and project fonts get neighbour lookups too. Returns how many were new.
+    pub fn index_available_fonts(&mut self) -> FontResult<usize> {
+        let fonts = self.get_classified_available_fonts()?;
+        Ok(self.similarity_engine.index_fonts(&fonts))
+    }
+    
+    pub fn scoring_profile(&self) -> &ScoringProfile {
+        self.similarity_engine.profile()
+    }
+    
+    pub fn set_scoring_profile(&mut self, profile: ScoringProfile) {
+        self.similarity_engine.set_profile(profile);
+    }
+    
+    /// Fit the scoring profile in use to labelled queries over the available fonts,
+    /// maximising top-`k` accuracy. The resolver's profile is left unchanged.
+    pub fn fit_scoring_profile(&self, queries: &[LabelledQuery], k: usize) -> FontResult<FitReport> {
+        let fonts = self.get_all_available_fonts()?;
+        let profile = self.similarity_engine.profile().clone();
+        let resolver = FontResolver::new(self.config.base.clone()).with_profile(profile.clone());
+        
+        let mut samples = Vec::new();
+        for labelled in queries {
+            let request = self.normalizer.normalize(&labelled.query)?;
+            let reference = self.catalogued_classification(&request.family);
+            samples.push(self.similarity_engine.query_sample(&request, reference.as_ref(), &labelled.expected, &fonts));
+            samples.push(resolver.query_sample(&request, &labelled.expected, &fonts));
+        }
+        
+        Ok(ProfileFitter::new(k).fit(&profile, &samples))
+    }
+    
+    /// Similarity graph over every available font, scored with the resolver's profile and glyph store
+    pub fn similarity_graph(&self) -> FontResult<SimilarityGraph> {
+        let fonts = self.get_classified_available_fonts()?;
+        Ok(SimilarityGraph::build(&self.similarity_engine, &fonts))
+    }
+    
+    /// Heading/body partners for `font_name` among the available fonts, best first
+    pub fn find_pairings(&self, font_name: &str, role: PairingRole, scope: PairingScope, limit: usize) -> FontResult<Vec<FontPairing>> {
+        let request = self.normalizer.normalize(font_name)?;
+        let fonts = self.get_classified_available_fonts()?;
+        self.similarity_engine.find_pairings(&request.family, role, &fonts, scope, limit)
+    }
+    
+    pub fn set_glyph_store(&mut self, store: Option<GlyphSignatureStore>) {
+        self.similarity_engine.set_glyph_store(store);
+    }
+    
+    pub fn glyph_store(&self) -> Option<&GlyphSignatureStore> {
+        self.similarity_engine.glyph_store()
+    }
+    
     pub fn get_database_stats(&self) -> Option<DatabaseStats> {
         self.compressed_database.as_ref().map(|db| DatabaseStats {
             font_count: db.metadata.font_count,
@@ -977,6 +1318,15 @@ impl EnhancedFontResolver {
         })
     }
 
+    /// OS/2 classification of `family` from the font database, so requests for fonts that
+    /// aren't installed can still be compared by PANOSE
+    fn catalogued_classification(&self, family: &str) -> Option<FontClassification> {
+        let database = self.compressed_database.as_ref()?;
+        font_similarity::best_classification(database.fonts.iter()
+            .filter(|font| font.family.eq_ignore_ascii_case(family))
+            .filter_map(|font| font.classification))
+    }
+    
     fn compressed_to_font(&self, compressed: &font_compressor::CompressedFontData) -> FontDescriptor {
         // Convert compressed data to FontDescriptor
         FontDescriptor {
@@ -1006,7 +1356,175 @@ impl EnhancedFontResolver {
                 allows_modification: compressed.license.allows_modification,
                 requires_attribution: compressed.license.requires_attribution,
                 allows_commercial_use: compressed.license.allows_commercial_use,
+                embedding: None,
+                spdx: None,
+                reserved_font_names: Vec::new(),
             }),
+            classification: compressed.classification,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use font_core::FontSourcePriority;
+
+    fn resolver(configure: impl FnOnce(&mut EnhancedResolverConfig)) -> EnhancedFontResolver {
+        let mut config = EnhancedResolverConfig {
+            cache_enabled: false,
+            ..Default::default()
+        };
+        configure(&mut config);
+        EnhancedFontResolver::new(config).unwrap()
+    }
+
+    #[test]
+    fn test_cache_key_depends_on_sources_and_priority() {
+        let base = resolver(|_| {});
+        let key = base.cache_key_for_name("Open Sans Bold");
+        assert_eq!(key, base.cache_key_for_name("open  sans bold"));
+        assert_ne!(key, base.cache_key_for_name("Open Sans"));
+
+        let variants = [
+            resolver(|c| c.font_source_priority = FontSourcePriority::SystemThenWeb),
+            resolver(|c| c.font_source_priority = FontSourcePriority::AllCustomFirst),
+            resolver(|c| c.custom_fonts_enabled = true),
+            resolver(|c| c.system_fonts_enabled = false),
+        ];
+        let mut keys: Vec<String> = variants.iter().map(|r| r.cache_key_for_name("Open Sans Bold")).collect();
+        keys.push(key);
+        let distinct: std::collections::HashSet<&String> = keys.iter().collect();
+        assert_eq!(distinct.len(), keys.len(), "{:?}", keys);
+    }
+
+    #[test]
+    fn test_unresolved_names_are_cached_for_the_configured_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let name = "Zzqx Nonexistent Grotesk";
+        for (ttl, remembered) in [(3600, true), (0, false)] {
+            let resolver = resolver(|c| {
+                c.cache_enabled = true;
+                c.cache_dir = Some(dir.path().join(ttl.to_string()));
+                c.negative_cache_ttl_secs = ttl;
+            });
+            assert!(matches!(resolver.resolve_with_sources(name), Err(FontError::NotFound(_))));
+
+            let lookup = resolver.cache.as_ref().unwrap().lookup(&resolver.cache_key_for_name(name));
+            assert_eq!(matches!(lookup, CacheLookup::NotFound), remembered, "ttl {}", ttl);
+            assert!(matches!(resolver.resolve_with_sources(name), Err(FontError::NotFound(_))));
+        }
+    }
+
+    #[test]
+    fn test_pins_by_family_face_or_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let resolver = resolver(|c| {
+            c.cache_enabled = true;
+            c.cache_dir = Some(dir.path().to_path_buf());
+            c.auto_pin_threshold = 0;
+        });
+        let cache = resolver.cache.as_ref().unwrap();
+        for (name, family) in [("Open Sans", "Open Sans"), ("Open Sans Bold Italic", "Open Sans"), ("Roboto", "Roboto")] {
+            let request = resolver.normalizer.normalize(name).unwrap();
+            let cached = CachedRequest {
+                family: family.to_string(),
+                weight: request.weight,
+                italic: request.italic,
+                width: request.width,
+            };
+            let font = FontDescriptor {
+                family: family.to_string(),
+                subfamily: None,
+                postscript_name: name.replace(' ', ""),
+                full_name: None,
+                path: std::path::PathBuf::from(format!("/nonexistent/{}.ttf", name)),
+                format: FontFormat::Ttf,
+                weight: request.weight,
+                italic: request.italic,
+                monospaced: false,
+                variable: false,
+                metrics: None,
+                license: None,
+                classification: None,
+            };
+            cache.put_request(&resolver.cache_key_for(&request), cached, font).unwrap();
+        }
+
+        // A family pins every cached face, listed by request rather than by key
+        resolver.pin_font("open  sans").unwrap();
+        assert_eq!(resolver.list_pinned_fonts().unwrap(), ["Open Sans (400)", "Open Sans (700, italic)"]);
+
+        // A face name only affects that face
+        resolver.unpin_font("Open Sans Bold Italic").unwrap();
+        assert_eq!(resolver.list_pinned_fonts().unwrap(), ["Open Sans (400)"]);
+
+        // Raw keys are still accepted
+        resolver.pin_font(&resolver.cache_key_for_name("Roboto")).unwrap();
+        assert_eq!(resolver.list_pinned_fonts().unwrap(), ["Open Sans (400)", "Roboto (400)"]);
+        resolver.unpin_font("Open Sans").unwrap();
+        assert_eq!(resolver.list_pinned_fonts().unwrap(), ["Roboto (400)"]);
+
+        // Fonts that are neither cached nor resolvable can't be pinned
+        assert!(resolver.pin_font("Zzqx Nonexistent Grotesk").is_err());
+        assert_eq!(resolver.list_pinned_fonts().unwrap(), ["Roboto (400)"]);
+    }
+
+    #[test]
+    fn test_substitutes_are_verified_on_construction() {
+        let resolver = resolver(|_| {});
+        let loaded = resolver.substitutes.lock().unwrap().clone().expect("registry loaded with the resolver");
+        assert_eq!(loaded.results().len(), loaded.pairs().len());
+        // Still current, so lookups reuse it rather than verifying again
+        assert!(Arc::ptr_eq(&loaded, &resolver.substitute_registry()));
+    }
+
+    #[test]
+    fn test_catalogued_classification_comes_from_the_database() {
+        let panose = [2, 2, 6, 3, 5, 4, 5, 2, 3, 4];
+        let catalogued = FontDescriptor {
+            family: "Catalogued Serif".to_string(),
+            subfamily: None,
+            postscript_name: "CataloguedSerif".to_string(),
+            full_name: None,
+            path: std::path::PathBuf::from("/fonts/catalogued.ttf"),
+            format: FontFormat::Ttf,
+            weight: 400,
+            italic: false,
+            monospaced: false,
+            variable: false,
+            metrics: None,
+            license: None,
+            classification: Some(FontClassification { panose, family_class: 0x0105, shape: None }),
+        };
+        let compressor = FontCompressor::new(5, true);
+        let data = compressor.compress_font_database(&[catalogued], false).unwrap();
+
+        let mut resolver = resolver(|_| {});
+        assert_eq!(resolver.catalogued_classification("Catalogued Serif"), None);
+        resolver.compressed_database = Some(compressor.decompress_font_database(&data).unwrap());
+        let classification = resolver.catalogued_classification("catalogued serif").unwrap();
+        assert_eq!(classification.panose, panose);
+        assert_eq!(resolver.catalogued_classification("Other Serif"), None);
+    }
+
+    #[test]
+    fn test_configured_scoring_profile_reaches_the_similarity_engine() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profile.json");
+        let mut profile = ScoringProfile::embedded();
+        profile.name = "fitted".to_string();
+        profile.similarity.visual = 0.9;
+        profile.save_to_file(&path).unwrap();
+
+        assert_eq!(resolver(|_| {}).similarity_engine.profile(), &ScoringProfile::embedded());
+        assert_eq!(resolver(|c| c.scoring_profile = Some(path.clone())).similarity_engine.profile(), &profile);
+
+        let missing = EnhancedResolverConfig {
+            cache_enabled: false,
+            scoring_profile: Some(dir.path().join("missing.json")),
+            ..Default::default()
+        };
+        assert!(EnhancedFontResolver::new(missing).is_err());
+    }
+}
@@ -65,6 +65,8 @@ pub fn apply_setup(config: &SetupConfig) -> EnhancedResolverConfig {
         disk_limit_mb: 10,
         auto_pin_threshold: if config.auto_pin_fonts { 5 } else { 0 },
         cache_cleanup_mode: font_core::CacheCleanupMode::Manual,
+        cache_dir: None,
+        negative_cache_ttl_secs: EnhancedResolverConfig::default().negative_cache_ttl_secs,
         system_fonts_enabled: true,
         web_fonts_enabled: config.enable_web_fonts,
         custom_fonts_enabled: false,
@@ -83,6 +85,7 @@ pub fn apply_setup(config: &SetupConfig) -> EnhancedResolverConfig {
         telemetry_enabled: false,
         dynamic_learning_enabled: true,
         project_asset_dirs: Vec::new(),
+        scoring_profile: None,
     }
 }
 
@@ -133,6 +136,10 @@ pub fn show_current_config() -> Result<(), Box<dyn std::error::Error>> {
     println!("   Disk limit: {}MB", config.disk_limit_mb);
     println!("   Auto-pin threshold: {} uses", config.auto_pin_threshold);
     println!("   Cleanup mode: {:?}", config.cache_cleanup_mode);
+    match &config.cache_dir {
+        Some(dir) => println!("   Directory: {}", dir.display()),
+        None => println!("   Directory: {} (default)", font_cache::default_cache_dir().display()),
+    }
     
     println!("\n🔤 Font Sources:");
     println!("   System fonts: {}", config.system_fonts_enabled);
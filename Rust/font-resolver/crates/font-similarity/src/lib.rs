@@ -1,6 +1,48 @@
-use std::collections::HashMap;
-use font_core::{FontDescriptor, FontRequest, FontMatchScore};
-use font_compressor::FontCategory;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use font_core::{
+    FontClassification, FontDescriptor, FontRequest, FontMatchScore, FontError, FontMetrics, FontResult, ScoringProfile,
+    ShapeClassification, SignatureWeights, SimilarityWeights, TierThresholds, TypefaceStyle,
+};
+use font_compressor::{category_from_classification, FontCategory};
+use font_glyph::{GlyphError, GlyphExtractor, MicroSignature};
+use font_glyph_db::GlyphDatabase;
+use font_index::{family_key, font_key, FontFeatures, FontIndex, VISUAL_DIMENSIONS};
+use font_license::{LicenseChecker, LicenseType};
+use petgraph::algo::astar;
+use petgraph::dot::Dot;
+use petgraph::graph::{EdgeReference, NodeIndex, UnGraph};
+use petgraph::unionfind::UnionFind;
+use petgraph::visit::EdgeRef;
+use serde::{Serialize, Deserialize};
+
+/// Signature similarity of two unrelated glyphs; visual scores are rescaled so this maps to 0.
+/// Measured as the mean over pairs of different characters from different fonts in
+/// `data/glyph_signatures.bin` (0.833 with the embedded signature weights).
+const VISUAL_BASELINE: f32 = 0.84;
+
+/// Fewer shared characters than this says too little about how two fonts look
+const MIN_SHARED_SIGNATURES: usize = 8;
+
+/// Neighbours fetched from the index per request
+const NEIGHBOUR_COUNT: usize = 32;
+
+/// Neighbours less similar than this aren't used as a shortcut score
+const MIN_NEIGHBOUR_SIMILARITY: f32 = 0.5;
+
+/// Weight of each PANOSE digit for Latin text faces; bFamilyType gates the comparison instead
+const PANOSE_TEXT_WEIGHTS: [f32; 10] = [
+    0.0,  // bFamilyType
+    0.25, // bSerifStyle
+    0.10, // bWeight (also scored separately from usWeightClass)
+    0.15, // bProportion
+    0.15, // bContrast
+    0.05, // bStrokeVariation
+    0.10, // bArmStyle
+    0.10, // bLetterform
+    0.05, // bMidline
+    0.05, // bXHeight
+];
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum MatchTier {
@@ -10,10 +52,16 @@ pub enum MatchTier {
 }
 
 impl MatchTier {
+    /// Tier for a score under the shared scoring profile's thresholds
     pub fn from_score(score: f32) -> Self {
+        Self::from_score_with(score, &ScoringProfile::shared().tiers)
+    }
+    
+    /// Tier for a score under a scoring profile's thresholds
+    pub fn from_score_with(score: f32, thresholds: &TierThresholds) -> Self {
         match score {
-            s if s >= 0.9 => MatchTier::Exact(s),
-            s if s >= 0.8 => MatchTier::Similar(s),
+            s if s >= thresholds.exact => MatchTier::Exact(s),
+            s if s >= thresholds.similar => MatchTier::Similar(s),
             s => MatchTier::Low(s),
         }
     }
@@ -65,17 +113,200 @@ pub struct SimilarityDetails {
     pub style_similarity: f32,
     pub category_similarity: f32,
     pub metrics_similarity: f32,
+    /// Glyph shape similarity, when both families have signatures in the glyph store
+    pub visual_similarity: Option<f32>,
+    /// PANOSE similarity, when both the requested family and the font declare PANOSE digits;
+    /// it then stands in for the name-guessed category
+    pub panose_similarity: Option<f32>,
+}
+
+/// Glyph signatures per family, used to compare how fonts look rather than what they're called
+#[derive(Debug, Clone, Default)]
+pub struct GlyphSignatureStore {
+    /// Lowercase family name -> signatures of its regular face
+    families: HashMap<String, Vec<(char, MicroSignature)>>,
+}
+
+impl GlyphSignatureStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// One entry per family from a glyph database, preferring the regular face
+    pub fn from_database(database: &GlyphDatabase) -> Self {
+        let mut store = Self::new();
+        store.add_database(database);
+        store
+    }
+    
+    pub fn add_database(&mut self, database: &GlyphDatabase) {
+        for entry in &database.fonts {
+            let key = entry.family.to_lowercase();
+            if entry.signatures.is_empty() {
+                continue;
+            }
+            if is_regular_subfamily(entry.subfamily.as_deref()) || !self.families.contains_key(&key) {
+                self.families.insert(key, entry.signatures.clone());
+            }
+        }
+    }
+    
+    /// Extract signatures from a font file and store them under `family`
+    pub fn add_font_file<P: AsRef<Path>>(&mut self, path: P, family: &str) -> Result<(), GlyphError> {
+        let signatures = GlyphExtractor::new().extract_alphanumeric_signatures(path)?;
+        if !signatures.is_empty() {
+            self.insert(family, signatures);
+        }
+        Ok(())
+    }
+    
+    pub fn insert(&mut self, family: &str, signatures: Vec<(char, MicroSignature)>) {
+        self.families.insert(family.to_lowercase(), signatures);
+    }
+    
+    pub fn get(&self, family: &str) -> Option<&[(char, MicroSignature)]> {
+        self.families.get(&family.to_lowercase()).map(|s| s.as_slice())
+    }
+    
+    pub fn contains(&self, family: &str) -> bool {
+        self.families.contains_key(&family.to_lowercase())
+    }
+    
+    pub fn len(&self) -> usize {
+        self.families.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.families.is_empty()
+    }
+    
+    /// A family's signature features averaged over its characters, scaled to 0.0 - 1.0,
+    /// as the visual part of its index features
+    pub fn embedding(&self, family: &str) -> Option<[f32; VISUAL_DIMENSIONS]> {
+        let signatures = self.get(family)?;
+        if signatures.is_empty() {
+            return None;
+        }
+        
+        let mut embedding = [0.0; VISUAL_DIMENSIONS];
+        for (_, signature) in signatures {
+            for (value, byte) in embedding.iter_mut().zip(signature.to_bytes()) {
+                *value += byte as f32 / 255.0;
+            }
+        }
+        embedding.iter_mut().for_each(|value| *value /= signatures.len() as f32);
+        Some(embedding)
+    }
+    
+    /// Visual similarity of two families (0.0 - 1.0), comparing the same characters in each
+    /// with the scoring profile's signature weights
+    pub fn visual_similarity(&self, family1: &str, family2: &str, weights: &SignatureWeights) -> Option<f32> {
+        let signatures1 = self.get(family1)?;
+        let signatures2 = self.get(family2)?;
+        
+        let mut total = 0.0;
+        let mut shared = 0;
+        for (ch, signature) in signatures1 {
+            if let Some((_, other)) = signatures2.iter().find(|(other_ch, _)| other_ch == ch) {
+                total += signature.similarity_with(other, weights);
+                shared += 1;
+            }
+        }
+        
+        if shared < MIN_SHARED_SIGNATURES {
+            return None;
+        }
+        
+        // Raw signature similarity is compressed near the top of the range; spread it out
+        let raw = total / shared as f32;
+        Some(((raw - VISUAL_BASELINE) / (1.0 - VISUAL_BASELINE)).clamp(0.0, 1.0))
+    }
+}
+
+fn is_regular_subfamily(subfamily: Option<&str>) -> bool {
+    match subfamily {
+        None => true,
+        Some(s) => matches!(s.to_lowercase().as_str(), "regular" | "book" | "normal" | "roman"),
+    }
 }
 
 pub struct FontSimilarityEngine {
-    precomputed_similarities: HashMap<String, Vec<(String, f32)>>,
+    neighbour_index: Option<FontIndex>,
+    glyph_store: Option<GlyphSignatureStore>,
+    profile: ScoringProfile,
 }
 
 impl FontSimilarityEngine {
-    pub fn new(similarity_matrix: Option<HashMap<String, Vec<(String, f32)>>>) -> Self {
+    pub fn new(neighbour_index: Option<FontIndex>) -> Self {
         Self {
-            precomputed_similarities: similarity_matrix.unwrap_or_default(),
+            neighbour_index,
+            glyph_store: None,
+            profile: ScoringProfile::shared().clone(),
+        }
+    }
+    
+    /// Score with `profile` instead of the embedded scoring profile
+    pub fn with_profile(mut self, profile: ScoringProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+    
+    pub fn set_profile(&mut self, profile: ScoringProfile) {
+        self.profile = profile;
+    }
+    
+    pub fn profile(&self) -> &ScoringProfile {
+        &self.profile
+    }
+    
+    /// Replace the nearest-neighbour index, keeping the glyph store
+    pub fn set_neighbour_index(&mut self, neighbour_index: Option<FontIndex>) {
+        self.neighbour_index = neighbour_index;
+    }
+    
+    pub fn neighbour_index(&self) -> Option<&FontIndex> {
+        self.neighbour_index.as_ref()
+    }
+    
+    /// Add fonts to the neighbour index, creating it if needed, with visual features from the
+    /// glyph store where it has the family. Returns how many fonts were new.
+    pub fn index_fonts(&mut self, fonts: &[FontDescriptor]) -> usize {
+        let features: Vec<FontFeatures> = fonts.iter().map(|font| self.font_features(font)).collect();
+        let index = self.neighbour_index.get_or_insert_with(FontIndex::new);
+        let mut added = 0;
+        for (font, features) in fonts.iter().zip(features) {
+            if index.insert(&font_key(font), &font.family, features) {
+                added += 1;
+            }
         }
+        added
+    }
+    
+    /// Index features of a font, with its family's glyph embedding when the store has one
+    pub fn font_features(&self, font: &FontDescriptor) -> FontFeatures {
+        let features = FontFeatures::from_descriptor(font);
+        match self.glyph_store.as_ref().and_then(|store| store.embedding(&font.family)) {
+            Some(embedding) => features.with_visual(embedding),
+            None => features,
+        }
+    }
+    
+    /// Mix visual similarity from the store into every comparison where both families have signatures
+    pub fn with_glyph_store(mut self, store: GlyphSignatureStore) -> Self {
+        self.set_glyph_store(Some(store));
+        self
+    }
+    
+    pub fn set_glyph_store(&mut self, store: Option<GlyphSignatureStore>) {
+        self.glyph_store = store.filter(|s| !s.is_empty());
+    }
+    
+    pub fn glyph_store(&self) -> Option<&GlyphSignatureStore> {
+        self.glyph_store.as_ref()
+    }
+    
+    fn visual_similarity(&self, family1: &str, family2: &str) -> Option<f32> {
+        self.glyph_store.as_ref()?.visual_similarity(family1, family2, &self.profile.signature)
     }
     
     pub fn calculate_comprehensive_similarity(
@@ -84,11 +315,38 @@ impl FontSimilarityEngine {
         font: &FontDescriptor,
         use_precomputed: bool,
     ) -> (FontMatchScore, SimilarityDetails) {
+        self.calculate_similarity_with_reference(request, None, font, use_precomputed)
+    }
+    
+    /// Like `calculate_comprehensive_similarity`, with the requested family's OS/2 classification
+    /// (e.g. from an installed or catalogued face of that family) for PANOSE comparison
+    pub fn calculate_similarity_with_reference(
+        &self,
+        request: &FontRequest,
+        reference: Option<&FontClassification>,
+        font: &FontDescriptor,
+        use_precomputed: bool,
+    ) -> (FontMatchScore, SimilarityDetails) {
+        let neighbours = use_precomputed.then(|| self.neighbour_similarities(request, reference));
+        self.score_candidate(request, reference, font, neighbours.as_ref())
+    }
+    
+    /// Score one candidate, taking the index similarity instead when the candidate is among
+    /// the request's nearest neighbours
+    fn score_candidate(
+        &self,
+        request: &FontRequest,
+        reference: Option<&FontClassification>,
+        font: &FontDescriptor,
+        neighbours: Option<&HashMap<String, f32>>,
+    ) -> (FontMatchScore, SimilarityDetails) {
+        let visual_similarity = self.visual_similarity(&request.family, &font.family);
+        
         // Try precomputed first for speed
-        if use_precomputed {
-            if let Some(precomputed) = self.get_precomputed_similarity(&request.family, &font.family) {
+        if let Some(neighbours) = neighbours {
+            if let Some(&precomputed) = neighbours.get(&font_key(font)) {
                 let score = FontMatchScore {
-                    overall: precomputed,
+                    overall: blend_visual(precomputed, visual_similarity, &self.profile.similarity),
                     family: precomputed,
                     weight: 1.0,
                     style: 1.0,
@@ -102,6 +360,8 @@ impl FontSimilarityEngine {
                     style_similarity: 1.0,
                     category_similarity: 1.0,
                     metrics_similarity: 1.0,
+                    visual_similarity,
+                    panose_similarity: None,
                 });
             }
         }
@@ -110,18 +370,26 @@ impl FontSimilarityEngine {
         let name_similarity = self.calculate_name_similarity(&request.family, &font.family);
         let weight_similarity = self.calculate_weight_similarity(request.weight, font.weight);
         let style_similarity = self.calculate_style_similarity(request.italic, font.italic);
-        let category_similarity = self.calculate_category_similarity(request, font);
+        let panose_similarity = match (reference, font.classification.as_ref()) {
+            (Some(reference), Some(classification)) => panose_similarity(&reference.panose, &classification.panose),
+            _ => None,
+        };
+        let category_similarity = match panose_similarity {
+            Some(similarity) => similarity,
+            None => self.calculate_category_similarity(request, reference, font),
+        };
         let metrics_similarity = self.calculate_metrics_similarity(request, font);
         
-        let overall = self.combine_scores(
+        let details = SimilarityDetails {
             name_similarity,
             weight_similarity,
             style_similarity,
             category_similarity,
             metrics_similarity,
-            request.monospaced,
-            font.monospaced,
-        );
+            visual_similarity,
+            panose_similarity,
+        };
+        let overall = weighted_score(&self.profile.similarity, &details, request.monospaced != font.monospaced);
         
         let score = FontMatchScore {
             overall,
@@ -132,24 +400,61 @@ impl FontSimilarityEngine {
             metrics: metrics_similarity,
         };
         
-        let details = SimilarityDetails {
-            name_similarity,
-            weight_similarity,
-            style_similarity,
-            category_similarity,
-            metrics_similarity,
-        };
-        
         (score, details)
     }
     
+    /// Components of every candidate for a labelled query, for `ProfileFitter`. Scores are
+    /// computed in full, without the neighbour index shortcut.
+    /// `reference` is the requested family's classification when known (e.g. from a catalogue);
+    /// otherwise it is taken from an installed face of the family, if any.
+    pub fn query_sample(
+        &self,
+        request: &FontRequest,
+        reference: Option<&FontClassification>,
+        expected: &str,
+        fonts: &[FontDescriptor],
+    ) -> QuerySample {
+        let reference = reference.copied().or_else(|| reference_classification(&request.family, fonts));
+        let candidates = fonts.iter()
+            .map(|font| {
+                let (_, details) = self.calculate_similarity_with_reference(request, reference.as_ref(), font, false);
+                CandidateComponents {
+                    family: font.family.clone(),
+                    values: [
+                        details.name_similarity,
+                        details.weight_similarity,
+                        details.style_similarity,
+                        details.category_similarity,
+                        details.metrics_similarity,
+                    ],
+                    visual: details.visual_similarity,
+                    monospace_mismatch: request.monospaced != font.monospaced,
+                }
+            })
+            .collect();
+        
+        QuerySample::new(ScoreModel::Similarity, expected, candidates)
+    }
+    
     pub fn find_tiered_matches(
         &self,
         request: &FontRequest,
         fonts: &[FontDescriptor],
         limit_per_tier: usize,
     ) -> TieredMatchResult {
-        self.find_matches_internal(Some(request), None, fonts, limit_per_tier)
+        self.find_matches_internal(Some(request), None, None, fonts, limit_per_tier)
+    }
+    
+    /// Like `find_tiered_matches`, with the requested family's classification from elsewhere
+    /// (e.g. the font database) for when no face of the family is among `fonts`
+    pub fn find_tiered_matches_with_reference(
+        &self,
+        request: &FontRequest,
+        reference: Option<&FontClassification>,
+        fonts: &[FontDescriptor],
+        limit_per_tier: usize,
+    ) -> TieredMatchResult {
+        self.find_matches_internal(Some(request), reference, None, fonts, limit_per_tier)
     }
 
     pub fn find_matches_by_metrics(
@@ -158,13 +463,14 @@ impl FontSimilarityEngine {
         fonts: &[FontDescriptor],
         limit: usize,
     ) -> Vec<FontMatch> {
-        let result = self.find_matches_internal(None, Some(metrics), fonts, limit);
+        let result = self.find_matches_internal(None, None, Some(metrics), fonts, limit);
         result.matches
     }
 
     fn find_matches_internal(
         &self,
         request: Option<&FontRequest>,
+        reference: Option<&FontClassification>,
         target_metrics: Option<&font_core::FontMetrics>,
         fonts: &[FontDescriptor],
         limit_per_tier: usize,
@@ -173,17 +479,25 @@ impl FontSimilarityEngine {
         let mut similar_matches = Vec::new();
         let mut low_matches = Vec::new();
         
+        // Without a given classification, a face of the requested family among the candidates
+        // tells us its design
+        let reference = reference.copied()
+            .or_else(|| request.and_then(|req| reference_classification(&req.family, fonts)));
+        
+        // Nearest neighbours of the request, looked up once for all candidates
+        let neighbours = request.map(|req| self.neighbour_similarities(req, reference.as_ref()));
+        
         // Calculate scores for all fonts
         for font in fonts {
             let (score, details) = if let Some(req) = request {
-                self.calculate_comprehensive_similarity(req, font, true)
+                self.score_candidate(req, reference.as_ref(), font, neighbours.as_ref())
             } else if let Some(metrics) = target_metrics {
                 self.calculate_pure_metrics_similarity(metrics, font)
             } else {
                 continue;
             };
 
-            let tier = MatchTier::from_score(score.overall);
+            let tier = MatchTier::from_score_with(score.overall, &self.profile.tiers);
             
             let match_result = FontMatch {
                 font: font.clone(),
@@ -229,6 +543,7 @@ impl FontSimilarityEngine {
                 style: font_core::FontStyle::Normal,
                 italic: false,
                 monospaced: false,
+                width: font_core::WIDTH_NORMAL,
             }),
             matches: all_matches,
             best_tier,
@@ -258,6 +573,8 @@ impl FontSimilarityEngine {
             style_similarity: 0.0,
             category_similarity: 0.0,
             metrics_similarity,
+            visual_similarity: None,
+            panose_similarity: None,
         };
         
         (score, details)
@@ -298,8 +615,8 @@ impl FontSimilarityEngine {
         let name1_lower = name1.to_lowercase();
         let name2_lower = name2.to_lowercase();
         
-        // Exact match
-        if name1_lower == name2_lower {
+        // Exact match, also between "DejaVu Sans" and a normalized "dejavu-sans"
+        if name1_lower == name2_lower || family_key(name1) == family_key(name2) {
             return 1.0;
         }
         
@@ -347,8 +664,8 @@ impl FontSimilarityEngine {
         }
     }
     
-    fn calculate_category_similarity(&self, request: &FontRequest, font: &FontDescriptor) -> f32 {
-        let request_category = self.detect_request_category(request);
+    fn calculate_category_similarity(&self, request: &FontRequest, reference: Option<&FontClassification>, font: &FontDescriptor) -> f32 {
+        let request_category = self.detect_request_category(request, reference);
         let font_category = self.detect_font_category(font);
         
         if request_category == font_category {
@@ -400,42 +717,13 @@ impl FontSimilarityEngine {
         (1.0 - (total_diff * 5.0)).max(0.0)
     }
     
-    fn combine_scores(
-        &self,
-        name: f32,
-        weight: f32,
-        style: f32,
-        category: f32,
-        metrics: f32,
-        requested_mono: bool,
-        font_mono: bool,
-    ) -> f32 {
-        let mut weights = HashMap::new();
-        weights.insert("name", 0.35);
-        weights.insert("weight", 0.25);
-        weights.insert("style", 0.20);
-        weights.insert("category", 0.15);
-        weights.insert("metrics", 0.05);
-        
-        let base_score = name * weights["name"]
-            + weight * weights["weight"]
-            + style * weights["style"]
-            + category * weights["category"]
-            + metrics * weights["metrics"];
-        
-        // Penalize monospace mismatch
-        if requested_mono != font_mono {
-            base_score * 0.7
-        } else {
-            base_score
-        }
-    }
-    
-    fn detect_request_category(&self, request: &FontRequest) -> FontCategory {
+    fn detect_request_category(&self, request: &FontRequest, reference: Option<&FontClassification>) -> FontCategory {
         let name_lower = request.family.to_lowercase();
         
         if request.monospaced {
             FontCategory::Monospace
+        } else if let Some(category) = reference.and_then(category_from_classification) {
+            category
         } else if name_lower.contains("serif") {
             FontCategory::Serif
         } else if name_lower.contains("sans") {
@@ -454,6 +742,8 @@ impl FontSimilarityEngine {
         
         if font.monospaced {
             FontCategory::Monospace
+        } else if let Some(category) = font.classification.as_ref().and_then(category_from_classification) {
+            category
         } else if name_lower.contains("serif") {
             FontCategory::Serif
         } else if name_lower.contains("sans") {
@@ -467,14 +757,50 @@ impl FontSimilarityEngine {
         }
     }
     
-    fn get_precomputed_similarity(&self, name1: &str, name2: &str) -> Option<f32> {
-        self.precomputed_similarities
-            .get(name1)
-            .and_then(|similarities| {
-                similarities.iter()
-                    .find(|(name, _)| name == name2)
-                    .map(|(_, score)| *score)
-            })
+    /// Similarity of the faces nearest to the request in the neighbour index, by
+    /// `font_key`. The requested family itself is left to the detailed comparison, as are
+    /// neighbours sharing no design features with the request.
+    fn neighbour_similarities(&self, request: &FontRequest, reference: Option<&FontClassification>) -> HashMap<String, f32> {
+        let mut similarities = HashMap::new();
+        let Some(index) = &self.neighbour_index else {
+            return similarities;
+        };
+        
+        let query = self.request_features(index, request, reference);
+        let requested = family_key(&request.family);
+        for neighbour in index.nearest(&query, NEIGHBOUR_COUNT) {
+            let similarity = neighbour.similarity();
+            if family_key(&neighbour.entry.family) == requested
+                || similarity <= MIN_NEIGHBOUR_SIMILARITY
+                || !query.shares_design_features(&neighbour.entry.features)
+            {
+                continue;
+            }
+            similarities.insert(neighbour.entry.key.clone(), similarity);
+        }
+        similarities
+    }
+    
+    /// Index features for a request: an indexed face of the requested family restyled to the
+    /// requested weight and style, or else what the request and reference classification say
+    fn request_features(&self, index: &FontIndex, request: &FontRequest, reference: Option<&FontClassification>) -> FontFeatures {
+        let requested_weight = (request.weight as f32 - 100.0) / 800.0;
+        let indexed = index.family(&request.family)
+            .min_by(|a, b| {
+                let mismatch = |features: &FontFeatures| {
+                    (features.weight - requested_weight).abs() + if features.italic == request.italic { 0.0 } else { 1.0 }
+                };
+                mismatch(&a.features).total_cmp(&mismatch(&b.features))
+            });
+        if let Some(entry) = indexed {
+            return entry.features.clone().restyled(request.weight, request.italic);
+        }
+        
+        let features = FontFeatures::from_request(request, reference);
+        match self.glyph_store.as_ref().and_then(|store| store.embedding(&request.family)) {
+            Some(embedding) => features.with_visual(embedding),
+            None => features,
+        }
     }
     
     fn generate_suggestion_reason(&self, font_match: &FontMatch) -> String {
@@ -484,6 +810,11 @@ impl FontSimilarityEngine {
             MatchTier::Exact(_) => {
                 format!("Exact match ({}% similarity)", (font_match.score.overall * 100.0) as u8)
             }
+            MatchTier::Similar(_) if details.visual_similarity.is_some_and(|v| v >= 0.7) => {
+                format!("Looks similar ({}% match, {}% glyph shapes)",
+                        (font_match.score.overall * 100.0) as u8,
+                        (details.visual_similarity.unwrap_or(0.0) * 100.0) as u8)
+            }
             MatchTier::Similar(_) => {
                 format!("Similar font ({}% match)", (font_match.score.overall * 100.0) as u8)
             }
@@ -499,6 +830,12 @@ impl FontSimilarityEngine {
                 if details.style_similarity < 0.6 {
                     reasons.push("different style".to_string());
                 }
+                if details.visual_similarity.is_some_and(|v| v < 0.5) {
+                    reasons.push("different glyph shapes".to_string());
+                }
+                if details.panose_similarity.is_some_and(|p| p < 0.5) {
+                    reasons.push("different design class".to_string());
+                }
                 
                 if reasons.is_empty() {
                     "Best available alternative".to_string()
@@ -539,6 +876,7 @@ impl FontSimilarityEngine {
                         variable: false,
                         metrics: None,
                         license: None,
+                        classification: None,
                     },
                     similarity_score: 0.7,
                     reason: format!("Common substitution for {}", from),
@@ -562,10 +900,1960 @@ impl FontSimilarityEngine {
                 variable: false,
                 metrics: None,
                 license: None,
+                classification: None,
             },
             similarity_score: 0.5,
             reason: "System default font".to_string(),
             source: SuggestionSource::Substitution,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Weighted sum of the name/attribute components, penalized for a monospace mismatch,
+/// with visual similarity mixed in
+fn weighted_score(weights: &SimilarityWeights, details: &SimilarityDetails, monospace_mismatch: bool) -> f32 {
+    let base_score = details.name_similarity * weights.name
+        + details.weight_similarity * weights.weight
+        + details.style_similarity * weights.style
+        + details.category_similarity * weights.category
+        + details.metrics_similarity * weights.metrics;
+    
+    let base_score = if monospace_mismatch {
+        base_score * weights.monospace_mismatch_penalty
+    } else {
+        base_score
+    };
+    blend_visual(base_score, details.visual_similarity, weights)
+}
+
+/// Mix visual similarity into a name/attribute score; without it the score is unchanged
+fn blend_visual(score: f32, visual_similarity: Option<f32>, weights: &SimilarityWeights) -> f32 {
+    match visual_similarity {
+        Some(visual) => score * (1.0 - weights.visual) + visual * weights.visual,
+        None => score,
+    }
+}
+
+/// Classification of a face of `family` among `fonts`, preferring one that declares
+/// PANOSE and was measured from its outlines
+fn reference_classification(family: &str, fonts: &[FontDescriptor]) -> Option<FontClassification> {
+    best_classification(fonts.iter()
+        .filter(|font| font.family.eq_ignore_ascii_case(family))
+        .filter_map(|font| font.classification))
+}
+
+/// Most complete of a family's classifications: one with both PANOSE and a measured shape
+pub fn best_classification(classifications: impl IntoIterator<Item = FontClassification>) -> Option<FontClassification> {
+    let completeness = |classification: &FontClassification| {
+        classification.has_panose() as u8 + classification.shape.is_some() as u8
+    };
+    let mut best: Option<FontClassification> = None;
+    for classification in classifications {
+        if completeness(&classification) == 2 {
+            return Some(classification);
+        }
+        if best.is_none_or(|best| completeness(&classification) > completeness(&best)) {
+            best = Some(classification);
+        }
+    }
+    best
+}
+
+/// PANOSE similarity (0.0 - 1.0) with per-digit weights; None when either side leaves PANOSE unset.
+/// Digits set to 0 ("any") or 1 ("no fit") on either side are left out.
+pub fn panose_similarity(a: &[u8; 10], b: &[u8; 10]) -> Option<f32> {
+    if a[0] < 2 || b[0] < 2 {
+        return None;
+    }
+    // Different family kinds (text vs script vs decorative) share nothing
+    if a[0] != b[0] {
+        return Some(0.0);
+    }
+    
+    let mut total = 0.0;
+    let mut total_weight = 0.0;
+    for digit in 1..10 {
+        let (x, y) = (a[digit], b[digit]);
+        if x < 2 || y < 2 {
+            continue;
+        }
+        
+        let (weight, similarity) = if a[0] == FontClassification::PANOSE_LATIN_TEXT {
+            (PANOSE_TEXT_WEIGHTS[digit], panose_text_digit_similarity(digit, x, y))
+        } else {
+            // Digits of the other family kinds are unordered categories
+            (1.0, if x == y { 1.0 } else { 0.3 })
+        };
+        total += weight * similarity;
+        total_weight += weight;
+    }
+    
+    if total_weight == 0.0 {
+        return None;
+    }
+    Some(total / total_weight)
+}
+
+/// Similarity of two differing-or-equal values of one Latin text PANOSE digit
+fn panose_text_digit_similarity(digit: usize, x: u8, y: u8) -> f32 {
+    if x == y {
+        return 1.0;
+    }
+    let distance = (x as f32 - y as f32).abs();
+    let same_group = |split: u8| (x < split) == (y < split);
+    
+    match digit {
+        // Serif style: 2-10 serifed, 11-15 sans
+        1 if same_group(11) => 0.6,
+        1 => 0.0,
+        // Weight: 2 (very light) to 11 (extra black)
+        2 => 1.0 - distance / 9.0,
+        // Proportion: 9 is monospaced, everything else is a proportional width class
+        3 if x == 9 || y == 9 => 0.0,
+        3 => 0.5,
+        // Contrast: 2 (none) to 9 (very high)
+        4 => 1.0 - distance / 7.0,
+        // Arm style: 2-6 straight arms, 7-11 non-straight
+        6 if same_group(7) => 0.6,
+        6 => 0.2,
+        // Letterform: 2-8 normal, 9-16 oblique
+        7 if same_group(9) => 0.6,
+        7 => 0.2,
+        // Stroke variation, midline, x-height
+        _ => 0.5,
+    }
+}
+
+// =============================================================================
+// PROFILE FITTING
+// =============================================================================
+
+/// Coordinate-descent step sizes, coarse to fine
+const FIT_STEPS: [f32; 4] = [0.2, 0.1, 0.05, 0.02];
+
+/// Upper bound on improvement passes per step size
+const MAX_FIT_PASSES: usize = 50;
+
+/// Folds used to measure held-out accuracy when fitting
+pub const FIT_FOLDS: usize = 5;
+
+/// A query and the family a correct answer belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelledQuery {
+    pub query: String,
+    pub expected: String,
+}
+
+/// Load labelled queries from a JSON array, or JSON lines, of `{"query": ..., "expected": ...}`
+pub fn load_labelled_queries<P: AsRef<Path>>(path: P) -> FontResult<Vec<LabelledQuery>> {
+    let text = std::fs::read_to_string(path)?;
+    let invalid = |e: serde_json::Error| FontError::Parse(format!("Invalid labelled queries: {}", e));
+    
+    if text.trim_start().starts_with('[') {
+        return serde_json::from_str(&text).map_err(invalid);
+    }
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(invalid))
+        .collect()
+}
+
+/// Which scorer a sample's components feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreModel {
+    /// `FontSimilarityEngine`: name, weight, style, category and metrics, plus visual similarity
+    Similarity,
+    /// `FontResolver`: family, weight, style, monospaced and metrics
+    Resolver,
+}
+
+/// Component scores of one candidate font
+#[derive(Debug, Clone)]
+pub struct CandidateComponents {
+    pub family: String,
+    /// In the order of the model's weights
+    pub values: [f32; 5],
+    pub visual: Option<f32>,
+    pub monospace_mismatch: bool,
+}
+
+impl CandidateComponents {
+    fn score(&self, profile: &ScoringProfile, model: ScoreModel) -> f32 {
+        match model {
+            ScoreModel::Similarity => {
+                let details = SimilarityDetails {
+                    name_similarity: self.values[0],
+                    weight_similarity: self.values[1],
+                    style_similarity: self.values[2],
+                    category_similarity: self.values[3],
+                    metrics_similarity: self.values[4],
+                    visual_similarity: self.visual,
+                    panose_similarity: None,
+                };
+                weighted_score(&profile.similarity, &details, self.monospace_mismatch)
+            }
+            ScoreModel::Resolver => {
+                let w = &profile.resolver;
+                [w.family, w.weight, w.style, w.monospaced, w.metrics].iter()
+                    .zip(&self.values)
+                    .map(|(weight, value)| weight * value)
+                    .sum()
+            }
+        }
+    }
+}
+
+/// Every candidate's components for one labelled query, computed once so that many
+/// weightings can be compared without rescoring fonts
+#[derive(Debug, Clone)]
+pub struct QuerySample {
+    pub model: ScoreModel,
+    candidates: Vec<CandidateComponents>,
+    /// Index into `families` of each candidate
+    family_index: Vec<usize>,
+    families: Vec<String>,
+    expected: Option<usize>,
+}
+
+impl QuerySample {
+    pub fn new(model: ScoreModel, expected: &str, candidates: Vec<CandidateComponents>) -> Self {
+        let mut families: Vec<String> = Vec::new();
+        let family_index = candidates.iter()
+            .map(|candidate| {
+                let family = candidate.family.to_lowercase();
+                families.iter().position(|f| *f == family).unwrap_or_else(|| {
+                    families.push(family);
+                    families.len() - 1
+                })
+            })
+            .collect();
+        let expected = families.iter().position(|f| f.eq_ignore_ascii_case(expected));
+        
+        Self { model, candidates, family_index, families, expected }
+    }
+    
+    /// Best family score, and the 1-based rank of the expected family (None when it isn't a candidate)
+    fn rank(&self, profile: &ScoringProfile) -> (f32, Option<usize>) {
+        let mut best = vec![f32::MIN; self.families.len()];
+        for (candidate, &family) in self.candidates.iter().zip(&self.family_index) {
+            best[family] = best[family].max(candidate.score(profile, self.model));
+        }
+        
+        let top = best.iter().cloned().fold(0.0, f32::max);
+        let rank = self.expected.map(|expected| {
+            1 + best.iter().enumerate()
+                .filter(|(family, score)| *family != expected && **score >= best[expected])
+                .count()
+        });
+        (top, rank)
+    }
+}
+
+/// Accuracy of a profile on labelled queries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct ProfileEvaluation {
+    pub queries: usize,
+    pub k: usize,
+    pub top1: usize,
+    pub top_k: usize,
+    /// Queries whose expected family wasn't among the candidates at all
+    pub unreachable: usize,
+    pub mean_reciprocal_rank: f32,
+}
+
+impl ProfileEvaluation {
+    pub fn top1_accuracy(&self) -> f32 {
+        self.top1 as f32 / self.queries.max(1) as f32
+    }
+    
+    pub fn top_k_accuracy(&self) -> f32 {
+        self.top_k as f32 / self.queries.max(1) as f32
+    }
+    
+    /// Add another evaluation's queries to this one
+    fn combine(&mut self, other: &ProfileEvaluation) {
+        let queries = self.queries + other.queries;
+        self.mean_reciprocal_rank = (self.mean_reciprocal_rank * self.queries as f32
+            + other.mean_reciprocal_rank * other.queries as f32) / queries.max(1) as f32;
+        self.queries = queries;
+        self.k = other.k;
+        self.top1 += other.top1;
+        self.top_k += other.top_k;
+        self.unreachable += other.unreachable;
+    }
+    
+    /// Top-k hits first; top-1 hits and reciprocal rank break ties and smooth out plateaus
+    fn objective(&self) -> f32 {
+        self.top_k as f32 + 0.25 * self.top1 as f32 + 0.1 * self.mean_reciprocal_rank * self.queries as f32
+    }
+}
+
+/// Result of `ProfileFitter::fit`: the fitted profile and accuracy before and after, per model
+#[derive(Debug, Clone, Serialize)]
+pub struct FitReport {
+    pub profile: ScoringProfile,
+    /// Accuracy on the samples the profile was fitted to
+    pub similarity: Option<(ProfileEvaluation, ProfileEvaluation)>,
+    pub resolver: Option<(ProfileEvaluation, ProfileEvaluation)>,
+    /// Cross-validated accuracy: each fold scored by weights fitted to the other folds.
+    /// None with fewer than two samples.
+    pub similarity_held_out: Option<(ProfileEvaluation, ProfileEvaluation)>,
+    pub resolver_held_out: Option<(ProfileEvaluation, ProfileEvaluation)>,
+    /// The fitted exact threshold was below `suggestions.good_min_score`, so
+    /// `suggestions.good` was raised to it rather than following the exact tier
+    pub good_clamped: bool,
+}
+
+/// Fits scoring weights and tier thresholds to labelled queries by maximising top-k accuracy.
+///
+/// Weights are fitted by coordinate descent on each model's samples. The exact and similar
+/// tier thresholds are then set to the top scores that best separate queries answered
+/// correctly at rank 1 (exact) or within the top k (similar) from the rest; the good
+/// suggestion threshold follows the exact tier but never drops below `good_min_score`.
+/// Signature weights are left as they are.
+///
+/// Held-out accuracy is measured by `FIT_FOLDS`-fold cross-validation, since accuracy on
+/// the fitted samples alone overstates how well the weights generalise.
+#[derive(Debug, Clone)]
+pub struct ProfileFitter {
+    pub k: usize,
+}
+
+impl ProfileFitter {
+    pub fn new(k: usize) -> Self {
+        Self { k: k.max(1) }
+    }
+    
+    /// Accuracy of `profile` on samples of one model
+    pub fn evaluate(&self, profile: &ScoringProfile, samples: &[QuerySample]) -> ProfileEvaluation {
+        self.evaluate_refs(profile, &samples.iter().collect::<Vec<_>>())
+    }
+    
+    fn evaluate_refs(&self, profile: &ScoringProfile, samples: &[&QuerySample]) -> ProfileEvaluation {
+        let mut evaluation = ProfileEvaluation { queries: samples.len(), k: self.k, ..Default::default() };
+        let mut reciprocal_ranks = 0.0;
+        
+        for sample in samples {
+            match sample.rank(profile).1 {
+                Some(rank) => {
+                    if rank == 1 {
+                        evaluation.top1 += 1;
+                    }
+                    if rank <= self.k {
+                        evaluation.top_k += 1;
+                    }
+                    reciprocal_ranks += 1.0 / rank as f32;
+                }
+                None => evaluation.unreachable += 1,
+            }
+        }
+        
+        evaluation.mean_reciprocal_rank = reciprocal_ranks / samples.len().max(1) as f32;
+        evaluation
+    }
+    
+    pub fn fit(&self, base: &ScoringProfile, samples: &[QuerySample]) -> FitReport {
+        let mut profile = base.clone();
+        let mut report = FitReport {
+            profile: base.clone(),
+            similarity: None,
+            resolver: None,
+            similarity_held_out: None,
+            resolver_held_out: None,
+            good_clamped: false,
+        };
+        
+        for model in [ScoreModel::Similarity, ScoreModel::Resolver] {
+            let model_samples: Vec<&QuerySample> = samples.iter().filter(|s| s.model == model).collect();
+            if model_samples.is_empty() {
+                continue;
+            }
+            
+            let held_out = self.cross_validate(&profile, model, &model_samples);
+            let before = self.evaluate_refs(&profile, &model_samples);
+            self.fit_weights(&mut profile, model, &model_samples);
+            if model == ScoreModel::Similarity {
+                report.good_clamped = self.fit_tiers(&mut profile, &model_samples);
+            }
+            let after = self.evaluate_refs(&profile, &model_samples);
+            
+            match model {
+                ScoreModel::Similarity => {
+                    report.similarity = Some((before, after));
+                    report.similarity_held_out = held_out;
+                }
+                ScoreModel::Resolver => {
+                    report.resolver = Some((before, after));
+                    report.resolver_held_out = held_out;
+                }
+            }
+        }
+        
+        report.profile = profile;
+        report
+    }
+    
+    fn fit_weights(&self, profile: &mut ScoringProfile, model: ScoreModel, samples: &[&QuerySample]) {
+        let mut params = model_params(profile, model);
+        let mut best = self.evaluate_refs(profile, samples).objective();
+        
+        for step in FIT_STEPS {
+            for _ in 0..MAX_FIT_PASSES {
+                let mut improved = false;
+                
+                for i in 0..params.len() {
+                    for delta in [step, -step] {
+                        let mut candidate = params.clone();
+                        candidate[i] = (candidate[i] + delta).clamp(0.0, 1.0);
+                        normalize_weights(&mut candidate[..5]);
+                        
+                        set_model_params(profile, model, &candidate);
+                        let objective = self.evaluate_refs(profile, samples).objective();
+                        if objective > best + 1e-6 {
+                            best = objective;
+                            params = candidate;
+                            improved = true;
+                        }
+                    }
+                }
+                
+                set_model_params(profile, model, &params);
+                if !improved {
+                    break;
+                }
+            }
+        }
+    }
+    
+    /// Accuracy of `profile` and of weights fitted without each fold, summed over the folds
+    fn cross_validate(&self, profile: &ScoringProfile, model: ScoreModel, samples: &[&QuerySample])
+        -> Option<(ProfileEvaluation, ProfileEvaluation)>
+    {
+        let folds = FIT_FOLDS.min(samples.len());
+        if folds < 2 {
+            return None;
+        }
+        
+        let mut before = ProfileEvaluation { k: self.k, ..Default::default() };
+        let mut after = before;
+        for fold in 0..folds {
+            let in_fold = |i: &usize| i % folds == fold;
+            let held_out: Vec<&QuerySample> = samples.iter().enumerate()
+                .filter(|(i, _)| in_fold(i)).map(|(_, s)| *s).collect();
+            let training: Vec<&QuerySample> = samples.iter().enumerate()
+                .filter(|(i, _)| !in_fold(i)).map(|(_, s)| *s).collect();
+            
+            let mut fitted = profile.clone();
+            self.fit_weights(&mut fitted, model, &training);
+            before.combine(&self.evaluate_refs(profile, &held_out));
+            after.combine(&self.evaluate_refs(&fitted, &held_out));
+        }
+        Some((before, after))
+    }
+    
+    /// Returns whether `suggestions.good` had to be raised to `good_min_score`
+    fn fit_tiers(&self, profile: &mut ScoringProfile, samples: &[&QuerySample]) -> bool {
+        let outcomes: Vec<(f32, Option<usize>)> = samples.iter().map(|s| s.rank(profile)).collect();
+        let mut good_clamped = false;
+        
+        if let Some(exact) = best_threshold(&outcomes, profile.tiers.exact, |rank| rank == Some(1)) {
+            profile.tiers.exact = exact;
+            // A good threshold under good_min_score would let the best suggestion itself be dropped
+            good_clamped = exact < profile.suggestions.good_min_score;
+            profile.suggestions.good = exact.max(profile.suggestions.good_min_score);
+        }
+        if let Some(similar) = best_threshold(&outcomes, profile.tiers.similar, |rank| rank.is_some_and(|r| r <= self.k)) {
+            profile.tiers.similar = similar.min(profile.tiers.exact);
+        }
+        good_clamped
+    }
+}
+
+fn model_params(profile: &ScoringProfile, model: ScoreModel) -> Vec<f32> {
+    match model {
+        ScoreModel::Similarity => {
+            let w = &profile.similarity;
+            vec![w.name, w.weight, w.style, w.category, w.metrics, w.monospace_mismatch_penalty, w.visual]
+        }
+        ScoreModel::Resolver => {
+            let w = &profile.resolver;
+            vec![w.family, w.weight, w.style, w.monospaced, w.metrics]
+        }
+    }
+}
+
+fn set_model_params(profile: &mut ScoringProfile, model: ScoreModel, params: &[f32]) {
+    match model {
+        ScoreModel::Similarity => {
+            let w = &mut profile.similarity;
+            [w.name, w.weight, w.style, w.category, w.metrics, w.monospace_mismatch_penalty, w.visual] = params[..7].try_into().unwrap();
+        }
+        ScoreModel::Resolver => {
+            let w = &mut profile.resolver;
+            [w.family, w.weight, w.style, w.monospaced, w.metrics] = params[..5].try_into().unwrap();
+        }
+    }
+}
+
+fn normalize_weights(weights: &mut [f32]) {
+    let total: f32 = weights.iter().sum();
+    if total > 0.0 {
+        weights.iter_mut().for_each(|w| *w /= total);
+    }
+}
+
+/// Threshold on the top score that best separates queries where `correct` holds from the
+/// rest, preferring the one closest to `current` among equals; None unless both kinds occur
+fn best_threshold(outcomes: &[(f32, Option<usize>)], current: f32, correct: impl Fn(Option<usize>) -> bool) -> Option<f32> {
+    let positives = outcomes.iter().filter(|(_, rank)| correct(*rank)).count();
+    if positives == 0 || positives == outcomes.len() {
+        return None;
+    }
+    
+    let agreement = |threshold: f32| outcomes.iter()
+        .filter(|(score, rank)| (*score >= threshold) == correct(*rank))
+        .count();
+    
+    outcomes.iter()
+        .map(|(score, _)| *score)
+        .chain(std::iter::once(current))
+        .map(|threshold| (agreement(threshold), threshold))
+        .max_by(|a, b| a.0.cmp(&b.0).then_with(|| {
+            (b.1 - current).abs().partial_cmp(&(a.1 - current).abs()).unwrap_or(std::cmp::Ordering::Equal)
+        }))
+        .map(|(_, threshold)| threshold)
+}
+
+// =============================================================================
+// SIMILARITY GRAPH
+// =============================================================================
+
+/// Nearest families examined for look-alike edges from each family
+const GRAPH_NEIGHBOURS: usize = 8;
+
+/// Least similarity for two unrelated families to be linked as look-alikes
+pub const MIN_EDGE_SIMILARITY: f32 = 0.7;
+
+/// Similarity from which two unrelated families count as clones of one design
+pub const CLONE_SIMILARITY: f32 = 0.95;
+
+/// Family-name words that name a member of a superfamily rather than the superfamily itself
+const VARIANT_WORDS: &[&str] = &[
+    "sans", "serif", "slab", "mono", "monospace", "code", "text", "display", "heading",
+    "condensed", "semicondensed", "narrow", "compressed", "extended", "expanded", "wide",
+    "rounded", "light", "book", "caption", "subhead", "titling", "ui", "pro", "std", "lgc",
+    "script", "math", "symbols", "emoji", "mt", "ms",
+];
+
+/// A font family in the similarity graph, represented by its regular face
+#[derive(Debug, Clone, Serialize)]
+pub struct FontNode {
+    pub family: String,
+    /// Faces of the family in the catalog
+    pub faces: usize,
+    pub category: FontCategory,
+    pub license: LicenseType,
+}
+
+impl std::fmt::Display for FontNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.family)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum EdgeKind {
+    /// Members of one superfamily, such as "Noto Sans" and "Noto Serif Display"
+    Superfamily,
+    /// Unrelated families of near-identical design
+    Clone,
+    /// Unrelated families that look alike
+    LookAlike,
+}
+
+impl EdgeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EdgeKind::Superfamily => "superfamily",
+            EdgeKind::Clone => "clone",
+            EdgeKind::LookAlike => "look-alike",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimilarityEdge {
+    /// Design and visual similarity blended as the engine blends them for matches
+    pub similarity: f32,
+    /// Similarity of the families' index features
+    pub design: f32,
+    /// Glyph signature similarity, when both families have signatures
+    pub visual: Option<f32>,
+    pub kind: EdgeKind,
+}
+
+impl std::fmt::Display for SimilarityEdge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.2}", self.similarity)
+    }
+}
+
+/// Which edges hold a cluster together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ClusterKind {
+    /// Families sharing a name stem
+    Superfamily,
+    /// Unrelated families of near-identical design
+    Clones,
+    /// Unrelated families that look alike, e.g. an original and its knock-offs
+    LookAlikes,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FontCluster {
+    pub kind: ClusterKind,
+    pub families: Vec<String>,
+    /// Mean similarity over the edges joining the cluster
+    pub mean_similarity: f32,
+    pub open_source_count: usize,
+    pub commercial_count: usize,
+}
+
+/// A chain of look-alike families, each step to a family similar to the previous one
+#[derive(Debug, Clone, Serialize)]
+pub struct LookAlikePath {
+    pub families: Vec<String>,
+    /// Similarity of each step, one fewer than the families
+    pub similarities: Vec<f32>,
+    /// Sum of (1 - similarity) over the steps
+    pub cost: f32,
+}
+
+/// Summary of a connected component of the graph
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentSummary {
+    pub size: usize,
+    /// The family with the most connections
+    pub hub: String,
+    /// Up to `COMPONENT_SAMPLE` families, best connected first
+    pub families: Vec<String>,
+    /// Families per category, most common first
+    pub categories: Vec<(FontCategory, usize)>,
+    pub open_source_count: usize,
+    pub commercial_count: usize,
+    pub other_license_count: usize,
+    /// Mean similarity over the component's edges; 0 for a single family
+    pub mean_similarity: f32,
+}
+
+/// Families listed in a component summary
+const COMPONENT_SAMPLE: usize = 10;
+
+/// Families as nodes and weighted similarity edges between them: superfamily members,
+/// clones and look-alikes. Look-alike candidates come from the nearest-neighbour index,
+/// so building the graph does not compare every pair of families.
+#[derive(Debug, Clone)]
+pub struct SimilarityGraph {
+    graph: UnGraph<FontNode, SimilarityEdge>,
+    by_family: HashMap<String, NodeIndex>,
+}
+
+impl SimilarityGraph {
+    /// Build the graph over `fonts`, scoring edges with `engine`'s profile and glyph store
+    pub fn build(engine: &FontSimilarityEngine, fonts: &[FontDescriptor]) -> Self {
+        // One node per family, represented by its face closest to regular
+        let families = group_by_family(fonts);
+        let checker = LicenseChecker::new();
+        let mut graph = UnGraph::new_undirected();
+        let mut by_family = HashMap::new();
+        let mut features = Vec::with_capacity(families.len());
+        for (key, faces) in &families {
+            let regular = regular_face(faces);
+            let node = graph.add_node(FontNode {
+                family: regular.family.clone(),
+                faces: faces.len(),
+                category: engine.detect_font_category(regular),
+                license: checker.check_font(regular).license_type,
+            });
+            by_family.insert(key.clone(), node);
+            features.push(engine.font_features(regular).restyled(400, false));
+        }
+        
+        // Candidate pairs, true for members of one superfamily. Families without any design
+        // features are left out of the look-alike search, as their distances say nothing.
+        let mut pairs: BTreeMap<(usize, usize), bool> = BTreeMap::new();
+        let mut index = FontIndex::new();
+        let described: Vec<usize> = (0..features.len())
+            .filter(|&i| features[i].shares_design_features(&features[i]))
+            .collect();
+        for &i in &described {
+            index.insert(&i.to_string(), &families[i].1[0].family, features[i].clone());
+        }
+        for &i in &described {
+            let query = &features[i];
+            for neighbour in index.nearest(query, GRAPH_NEIGHBOURS + 1) {
+                let Ok(j) = neighbour.entry.key.parse::<usize>() else { continue };
+                if j != i && query.shares_design_features(&features[j]) {
+                    pairs.entry((i.min(j), i.max(j))).or_insert(false);
+                }
+            }
+        }
+        
+        // Members of a superfamily are linked to its shortest-named member
+        let family_stems: Vec<Option<String>> = graph.node_indices()
+            .map(|node| superfamily_stem(&graph[node].family))
+            .collect();
+        let mut stems: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (i, stem) in family_stems.iter().enumerate() {
+            if let Some(stem) = stem {
+                stems.entry(stem).or_default().push(i);
+            }
+        }
+        for members in stems.values().filter(|members| members.len() > 1) {
+            let root = *members.iter()
+                .min_by_key(|&&i| (graph[NodeIndex::new(i)].family.len(), i))
+                .expect("superfamilies have members");
+            for &member in members.iter().filter(|&&member| member != root) {
+                pairs.insert((root.min(member), root.max(member)), true);
+            }
+        }
+        
+        for ((a, b), superfamily) in pairs {
+            // Other members of a superfamily are reached through its root, not as look-alikes
+            if !superfamily && family_stems[a].is_some() && family_stems[a] == family_stems[b] {
+                continue;
+            }
+            let (a, b) = (NodeIndex::new(a), NodeIndex::new(b));
+            let design = features[a.index()].similarity(&features[b.index()]);
+            let visual = engine.visual_similarity(&graph[a].family, &graph[b].family);
+            let similarity = blend_visual(design, visual, &engine.profile.similarity);
+            
+            let kind = if superfamily {
+                EdgeKind::Superfamily
+            } else if similarity >= CLONE_SIMILARITY {
+                EdgeKind::Clone
+            } else if similarity >= MIN_EDGE_SIMILARITY {
+                EdgeKind::LookAlike
+            } else {
+                continue;
+            };
+            graph.add_edge(a, b, SimilarityEdge { similarity, design, visual, kind });
+        }
+        
+        Self { graph, by_family }
+    }
+    
+    pub fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+    
+    pub fn edge_count(&self) -> usize {
+        self.graph.edge_count()
+    }
+    
+    pub fn graph(&self) -> &UnGraph<FontNode, SimilarityEdge> {
+        &self.graph
+    }
+    
+    pub fn node(&self, family: &str) -> Option<&FontNode> {
+        self.find(family).map(|node| &self.graph[node])
+    }
+    
+    /// Families linked to `family`, most similar first
+    pub fn neighbours(&self, family: &str) -> Vec<(&FontNode, &SimilarityEdge)> {
+        let Some(node) = self.find(family) else {
+            return Vec::new();
+        };
+        let mut neighbours: Vec<_> = self.graph.edges(node)
+            .map(|edge| (&self.graph[other_end(&edge, node)], edge.weight()))
+            .collect();
+        neighbours.sort_by(|a, b| b.1.similarity.total_cmp(&a.1.similarity));
+        neighbours
+    }
+    
+    /// Groups of two or more families joined by `kind` edges of at least `min_similarity`,
+    /// largest first
+    pub fn clusters(&self, kind: ClusterKind, min_similarity: f32) -> Vec<FontCluster> {
+        let joins = |edge: &SimilarityEdge| edge.similarity >= min_similarity && match kind {
+            ClusterKind::Superfamily => edge.kind == EdgeKind::Superfamily,
+            ClusterKind::Clones => edge.kind == EdgeKind::Clone,
+            ClusterKind::LookAlikes => edge.kind != EdgeKind::Superfamily,
+        };
+        
+        self.groups(joins)
+            .into_iter()
+            .filter(|group| group.nodes.len() > 1)
+            .map(|group| FontCluster {
+                kind,
+                families: group.nodes.iter().map(|&node| self.graph[node].family.clone()).collect(),
+                mean_similarity: group.mean_similarity(),
+                open_source_count: self.count_license(&group.nodes, |l| *l == LicenseType::OpenSource),
+                commercial_count: self.count_license(&group.nodes, |l| *l == LicenseType::Commercial),
+            })
+            .collect()
+    }
+    
+    /// Cheapest chain of look-alikes from `from` to `to`, or to the nearest open-source
+    /// family when `to` is None; each step costs one minus its similarity
+    pub fn lookalike_path(&self, from: &str, to: Option<&str>) -> Option<LookAlikePath> {
+        let start = self.find(from)?;
+        let goal = match to {
+            Some(to) => Some(self.find(to)?),
+            None => None,
+        };
+        let is_goal = |node: NodeIndex| match goal {
+            Some(goal) => node == goal,
+            None => node != start && self.graph[node].license == LicenseType::OpenSource,
+        };
+        
+        let (cost, nodes) = astar(
+            &self.graph,
+            start,
+            is_goal,
+            |edge| 1.0 - edge.weight().similarity,
+            |_| 0.0,
+        )?;
+        let similarities = nodes.windows(2)
+            .filter_map(|step| self.graph.find_edge(step[0], step[1]))
+            .map(|edge| self.graph[edge].similarity)
+            .collect();
+        
+        Some(LookAlikePath {
+            families: nodes.iter().map(|&node| self.graph[node].family.clone()).collect(),
+            similarities,
+            cost,
+        })
+    }
+    
+    /// Connected components over all edges, largest first
+    pub fn component_summaries(&self) -> Vec<ComponentSummary> {
+        self.groups(|_| true)
+            .into_iter()
+            .map(|group| {
+                let mut nodes = group.nodes.clone();
+                nodes.sort_by_key(|&node| std::cmp::Reverse(self.graph.edges(node).count()));
+                
+                let mut categories: Vec<(FontCategory, usize)> = Vec::new();
+                for &node in &nodes {
+                    let category = &self.graph[node].category;
+                    match categories.iter_mut().find(|(c, _)| c == category) {
+                        Some((_, count)) => *count += 1,
+                        None => categories.push((category.clone(), 1)),
+                    }
+                }
+                categories.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+                
+                let open_source_count = self.count_license(&nodes, |l| *l == LicenseType::OpenSource);
+                let commercial_count = self.count_license(&nodes, |l| *l == LicenseType::Commercial);
+                ComponentSummary {
+                    size: nodes.len(),
+                    hub: self.graph[nodes[0]].family.clone(),
+                    families: nodes.iter().take(COMPONENT_SAMPLE).map(|&node| self.graph[node].family.clone()).collect(),
+                    categories,
+                    open_source_count,
+                    commercial_count,
+                    other_license_count: nodes.len() - open_source_count - commercial_count,
+                    mean_similarity: group.mean_similarity(),
+                }
+            })
+            .collect()
+    }
+    
+    /// Graphviz DOT, with open-source families in green, commercial ones in red and
+    /// superfamily edges dashed
+    pub fn to_dot(&self) -> String {
+        let edge_attributes = |_, edge: EdgeReference<'_, SimilarityEdge>| match edge.weight().kind {
+            EdgeKind::Superfamily => "style = \"dashed\" ".to_string(),
+            EdgeKind::Clone => "penwidth = 2 ".to_string(),
+            EdgeKind::LookAlike => String::new(),
+        };
+        let node_attributes = |_, (_, node): (NodeIndex, &FontNode)| {
+            let colour = match node.license {
+                LicenseType::OpenSource => "darkgreen",
+                LicenseType::Commercial => "firebrick",
+                _ => "gray40",
+            };
+            format!("color = \"{}\" ", colour)
+        };
+        format!("{}", Dot::with_attr_getters(&self.graph, &[], &edge_attributes, &node_attributes))
+    }
+    
+    /// GraphML with the node and edge fields as data attributes
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"family\" for=\"node\" attr.name=\"family\" attr.type=\"string\"/>\n",
+            "  <key id=\"faces\" for=\"node\" attr.name=\"faces\" attr.type=\"int\"/>\n",
+            "  <key id=\"category\" for=\"node\" attr.name=\"category\" attr.type=\"string\"/>\n",
+            "  <key id=\"license\" for=\"node\" attr.name=\"license\" attr.type=\"string\"/>\n",
+            "  <key id=\"similarity\" for=\"edge\" attr.name=\"similarity\" attr.type=\"double\"/>\n",
+            "  <key id=\"design\" for=\"edge\" attr.name=\"design\" attr.type=\"double\"/>\n",
+            "  <key id=\"visual\" for=\"edge\" attr.name=\"visual\" attr.type=\"double\"/>\n",
+            "  <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+            "  <graph id=\"fonts\" edgedefault=\"undirected\">\n",
+        ));
+        
+        for node in self.graph.node_indices() {
+            let font = &self.graph[node];
+            xml.push_str(&format!("    <node id=\"n{}\">\n", node.index()));
+            xml.push_str(&format!("      <data key=\"family\">{}</data>\n", xml_escape(&font.family)));
+            xml.push_str(&format!("      <data key=\"faces\">{}</data>\n", font.faces));
+            xml.push_str(&format!("      <data key=\"category\">{:?}</data>\n", font.category));
+            xml.push_str(&format!("      <data key=\"license\">{:?}</data>\n", font.license));
+            xml.push_str("    </node>\n");
+        }
+        for edge in self.graph.edge_references() {
+            let weight = edge.weight();
+            xml.push_str(&format!(
+                "    <edge id=\"e{}\" source=\"n{}\" target=\"n{}\">\n",
+                edge.id().index(), edge.source().index(), edge.target().index(),
+            ));
+            xml.push_str(&format!("      <data key=\"similarity\">{:.4}</data>\n", weight.similarity));
+            xml.push_str(&format!("      <data key=\"design\">{:.4}</data>\n", weight.design));
+            if let Some(visual) = weight.visual {
+                xml.push_str(&format!("      <data key=\"visual\">{:.4}</data>\n", visual));
+            }
+            xml.push_str(&format!("      <data key=\"kind\">{}</data>\n", weight.kind.as_str()));
+            xml.push_str("    </edge>\n");
+        }
+        
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+    
+    fn find(&self, family: &str) -> Option<NodeIndex> {
+        self.by_family.get(&family_key(family)).copied()
+    }
+    
+    fn count_license(&self, nodes: &[NodeIndex], matches: impl Fn(&LicenseType) -> bool) -> usize {
+        nodes.iter().filter(|&&node| matches(&self.graph[node].license)).count()
+    }
+    
+    /// Nodes joined by edges passing `joins`, with the similarities of those edges,
+    /// largest group first
+    fn groups(&self, joins: impl Fn(&SimilarityEdge) -> bool) -> Vec<NodeGroup> {
+        let mut sets = UnionFind::<usize>::new(self.graph.node_count());
+        let joining: Vec<_> = self.graph.edge_references().filter(|edge| joins(edge.weight())).collect();
+        for edge in &joining {
+            sets.union(edge.source().index(), edge.target().index());
+        }
+        
+        let mut groups: BTreeMap<usize, NodeGroup> = BTreeMap::new();
+        for node in self.graph.node_indices() {
+            groups.entry(sets.find(node.index())).or_default().nodes.push(node);
+        }
+        for edge in &joining {
+            if let Some(group) = groups.get_mut(&sets.find(edge.source().index())) {
+                group.similarities.push(edge.weight().similarity);
+            }
+        }
+        
+        let mut groups: Vec<NodeGroup> = groups.into_values().collect();
+        groups.sort_by_key(|group| std::cmp::Reverse(group.nodes.len()));
+        groups
+    }
+}
+
+#[derive(Default)]
+struct NodeGroup {
+    nodes: Vec<NodeIndex>,
+    similarities: Vec<f32>,
+}
+
+impl NodeGroup {
+    fn mean_similarity(&self) -> f32 {
+        if self.similarities.is_empty() {
+            0.0
+        } else {
+            self.similarities.iter().sum::<f32>() / self.similarities.len() as f32
+        }
+    }
+}
+
+/// Faces grouped by family key, in order of first appearance
+fn group_by_family(fonts: &[FontDescriptor]) -> Vec<(String, Vec<&FontDescriptor>)> {
+    let mut families: Vec<(String, Vec<&FontDescriptor>)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for font in fonts {
+        let key = family_key(&font.family);
+        if key.is_empty() {
+            continue;
+        }
+        let position = *positions.entry(key.clone()).or_insert_with(|| {
+            families.push((key, Vec::new()));
+            families.len() - 1
+        });
+        families[position].1.push(font);
+    }
+    families
+}
+
+/// The upright face closest to regular weight
+fn regular_face<'a>(faces: &[&'a FontDescriptor]) -> &'a FontDescriptor {
+    faces.iter()
+        .min_by_key(|font| (font.italic, (font.weight as i32 - 400).abs()))
+        .expect("a family has at least one face")
+}
+
+fn other_end(edge: &EdgeReference<'_, SimilarityEdge>, node: NodeIndex) -> NodeIndex {
+    if edge.source() == node { edge.target() } else { edge.source() }
+}
+
+/// Superfamily a family name belongs to: the name without trailing variant words, as a
+/// family key. None when nothing but variant words is left.
+fn superfamily_stem(family: &str) -> Option<String> {
+    let words: Vec<String> = family
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let mut end = words.len();
+    while end > 1 && VARIANT_WORDS.contains(&words[end - 1].as_str()) {
+        end -= 1;
+    }
+    let stem = family_key(&words[..end].join(" "));
+    (!stem.is_empty() && !VARIANT_WORDS.contains(&stem.as_str())).then_some(stem)
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// =============================================================================
+// FONT PAIRING
+// =============================================================================
+
+/// Share of a pairing score from contrast between the two designs
+const PAIRING_CONTRAST_WEIGHT: f32 = 0.5;
+
+/// Share of a pairing score from matching x-height ratios
+const PAIRING_X_HEIGHT_WEIGHT: f32 = 0.3;
+
+/// Share of a pairing score from matching average widths
+const PAIRING_WIDTH_WEIGHT: f32 = 0.2;
+
+/// Added for partners from the same superfamily, which are designed to be used together
+const PAIRING_SUPERFAMILY_BONUS: f32 = 0.1;
+
+/// Difference in x-height (fraction of the em) at which x-heights stop matching at all
+const X_HEIGHT_TOLERANCE: f32 = 0.08;
+
+/// Difference in average width (fraction of the em) at which widths stop matching at all
+const WIDTH_TOLERANCE: f32 = 0.12;
+
+/// Which role the font being paired plays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PairingRole {
+    /// Suggest body fonts for this heading font
+    Heading,
+    /// Suggest heading fonts for this body font
+    Body,
+}
+
+/// Which fonts may be suggested as partners
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PairingScope {
+    /// Installed fonts and open-source fonts
+    Available,
+    /// Installed fonts only
+    Local,
+    /// Open-source fonts only
+    LicenseSafe,
+}
+
+/// Broad design class used to judge how two fonts contrast
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PairingClass {
+    Serif,
+    SlabSerif,
+    GeometricSans,
+    HumanistSans,
+    GrotesqueSans,
+    Sans,
+    Monospace,
+    Script,
+    Display,
+    Symbol,
+}
+
+impl PairingClass {
+    pub fn is_sans(&self) -> bool {
+        matches!(self, PairingClass::GeometricSans | PairingClass::HumanistSans | PairingClass::GrotesqueSans | PairingClass::Sans)
+    }
+    
+    pub fn is_serif(&self) -> bool {
+        matches!(self, PairingClass::Serif | PairingClass::SlabSerif)
+    }
+    
+    /// Whether the class reads well at text sizes
+    pub fn is_text(&self) -> bool {
+        self.is_sans() || self.is_serif() || *self == PairingClass::Monospace
+    }
+    
+    pub fn describe(&self) -> &'static str {
+        match self {
+            PairingClass::Serif => "serif",
+            PairingClass::SlabSerif => "slab serif",
+            PairingClass::GeometricSans => "geometric sans",
+            PairingClass::HumanistSans => "humanist sans",
+            PairingClass::GrotesqueSans => "grotesque sans",
+            PairingClass::Sans => "sans-serif",
+            PairingClass::Monospace => "monospace",
+            PairingClass::Script => "script",
+            PairingClass::Display => "display",
+            PairingClass::Symbol => "symbol",
+        }
+    }
+}
+
+/// A suggested heading/body combination
+#[derive(Debug, Clone, Serialize)]
+pub struct FontPairing {
+    pub heading: String,
+    pub body: String,
+    /// The suggested family, heading or body depending on the role asked for
+    pub partner: String,
+    pub partner_class: PairingClass,
+    /// 0.0 - 1.0
+    pub score: f32,
+    /// How well the two designs contrast, 0.0 - 1.0
+    pub contrast: f32,
+    /// How closely the x-height ratios match, when both fonts have metrics
+    pub x_height_match: Option<f32>,
+    /// How closely the average widths match, when both fonts have metrics
+    pub width_match: Option<f32>,
+    /// The partner is installed
+    pub local: bool,
+    pub license: LicenseType,
+    pub reasons: Vec<String>,
+}
+
+impl FontSimilarityEngine {
+    /// Partners for `family` among `fonts`: contrasting designs with compatible x-heights and
+    /// widths, best first. `role` is the part `family` plays; partners play the other.
+    /// Fails with `InvalidRequest` when a body font that isn't a text design is given.
+    pub fn find_pairings(
+        &self,
+        family: &str,
+        role: PairingRole,
+        fonts: &[FontDescriptor],
+        scope: PairingScope,
+        limit: usize,
+    ) -> FontResult<Vec<FontPairing>> {
+        let families = group_by_family(fonts);
+        let anchor_key = family_key(family);
+        let anchor = families.iter()
+            .find(|(key, _)| *key == anchor_key)
+            .map(|(_, faces)| regular_face(faces))
+            .ok_or_else(|| FontError::NotFound(family.to_string()))?;
+        let anchor_class = self.pairing_class(anchor);
+        if role == PairingRole::Body && !anchor_class.is_text() {
+            return Err(FontError::InvalidRequest(format!(
+                "{} is a {} design and doesn't read well as body text; pair it as a heading instead",
+                anchor.family, anchor_class.describe()
+            )));
+        }
+        let anchor_stem = superfamily_stem(&anchor.family);
+        
+        let checker = LicenseChecker::new();
+        let mut pairings = Vec::new();
+        for (key, faces) in &families {
+            if *key == anchor_key {
+                continue;
+            }
+            let partner = regular_face(faces);
+            let partner_class = self.pairing_class(partner);
+            let (heading, body, heading_class, body_class) = match role {
+                PairingRole::Heading => (anchor, partner, anchor_class, partner_class),
+                PairingRole::Body => (partner, anchor, partner_class, anchor_class),
+            };
+            // Body text needs a text design with a regular upright face
+            if partner_class == PairingClass::Symbol
+                || (role == PairingRole::Heading && (!body_class.is_text() || partner.italic || !(300..=500).contains(&partner.weight)))
+            {
+                continue;
+            }
+            
+            let local = faces.iter().any(|font| font.path.is_file());
+            let license = checker.check_font(partner).license_type;
+            let allowed = match scope {
+                PairingScope::Available => local || license == LicenseType::OpenSource,
+                PairingScope::Local => local,
+                PairingScope::LicenseSafe => license == LicenseType::OpenSource,
+            };
+            if !allowed {
+                continue;
+            }
+            
+            let mut reasons = Vec::new();
+            let contrast = pairing_contrast(heading_class, body_class);
+            reasons.push(contrast_reason(heading_class, body_class, contrast));
+            
+            let x_height_match = metric_match(heading, body, |m| m.x_height, X_HEIGHT_TOLERANCE);
+            if let Some((matched, heading_ratio, body_ratio)) = x_height_match {
+                reasons.push(match matched {
+                    m if m >= 0.75 => format!("x-heights match ({:.2} and {:.2} em), so mixed lines sit evenly", heading_ratio, body_ratio),
+                    m if m < 0.4 => format!("x-heights differ ({:.2} and {:.2} em)", heading_ratio, body_ratio),
+                    _ => format!("x-heights are close ({:.2} and {:.2} em)", heading_ratio, body_ratio),
+                });
+            }
+            let width_match = metric_match(heading, body, |m| m.average_width, WIDTH_TOLERANCE);
+            if let Some((matched, heading_width, body_width)) = width_match {
+                if matched >= 0.75 {
+                    reasons.push("similar average widths".to_string());
+                } else if matched < 0.4 {
+                    let wider = if heading_width > body_width { "heading" } else { "body" };
+                    reasons.push(format!("the {} font sets noticeably wider", wider));
+                }
+            }
+            
+            let mut score = PAIRING_CONTRAST_WEIGHT * contrast
+                + PAIRING_X_HEIGHT_WEIGHT * x_height_match.map_or(0.5, |(m, _, _)| m)
+                + PAIRING_WIDTH_WEIGHT * width_match.map_or(0.5, |(m, _, _)| m);
+            if anchor_stem.is_some() && superfamily_stem(&partner.family) == anchor_stem {
+                score += PAIRING_SUPERFAMILY_BONUS;
+                reasons.push(format!("same superfamily as {}, designed to be used together", anchor.family));
+            }
+            if local {
+                reasons.push("installed locally".to_string());
+            } else if license == LicenseType::OpenSource {
+                reasons.push("open source, free to embed".to_string());
+            }
+            
+            pairings.push(FontPairing {
+                heading: heading.family.clone(),
+                body: body.family.clone(),
+                partner: partner.family.clone(),
+                partner_class,
+                score: score.min(1.0),
+                contrast,
+                x_height_match: x_height_match.map(|(m, _, _)| m),
+                width_match: width_match.map(|(m, _, _)| m),
+                local,
+                license,
+                reasons,
+            });
+        }
+        
+        pairings.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.partner.cmp(&b.partner)));
+        pairings.truncate(limit);
+        Ok(pairings)
+    }
+    
+    /// Design class from the outline classifier when it is confident, otherwise from the
+    /// declared classification and the name
+    pub fn pairing_class(&self, font: &FontDescriptor) -> PairingClass {
+        if font.monospaced {
+            return PairingClass::Monospace;
+        }
+        let classification = font.classification.as_ref();
+        let shape_label = classification
+            .and_then(|c| c.shape)
+            .and_then(|shape| shape.labels().into_iter().next().filter(|(_, c)| *c >= ShapeClassification::CONFIDENT).map(|(style, _)| (shape, style)));
+        if let Some((shape, style)) = shape_label {
+            return match style {
+                TypefaceStyle::Serif => PairingClass::Serif,
+                TypefaceStyle::SlabSerif => PairingClass::SlabSerif,
+                TypefaceStyle::Geometric => PairingClass::GeometricSans,
+                TypefaceStyle::Humanist => PairingClass::HumanistSans,
+                TypefaceStyle::Grotesque => PairingClass::GrotesqueSans,
+                TypefaceStyle::SansSerif => match shape.sans_kind() {
+                    Some((TypefaceStyle::Geometric, _)) => PairingClass::GeometricSans,
+                    Some((TypefaceStyle::Humanist, _)) => PairingClass::HumanistSans,
+                    Some((TypefaceStyle::Grotesque, _)) => PairingClass::GrotesqueSans,
+                    _ => PairingClass::Sans,
+                },
+                TypefaceStyle::Script => PairingClass::Script,
+                TypefaceStyle::Blackletter => PairingClass::Display,
+                TypefaceStyle::Monospace => PairingClass::Monospace,
+            };
+        }
+        
+        match self.detect_font_category(font) {
+            FontCategory::Serif if classification.is_some_and(|c| c.class_id() == FontClassification::CLASS_SLAB_SERIFS) => PairingClass::SlabSerif,
+            FontCategory::Serif => PairingClass::Serif,
+            FontCategory::Monospace => PairingClass::Monospace,
+            FontCategory::Handwriting => PairingClass::Script,
+            FontCategory::Display | FontCategory::Decorative => PairingClass::Display,
+            FontCategory::Symbol => PairingClass::Symbol,
+            FontCategory::SansSerif | FontCategory::Other => PairingClass::Sans,
+        }
+    }
+}
+
+/// How well a heading class contrasts with a body class, 0.0 - 1.0
+fn pairing_contrast(heading: PairingClass, body: PairingClass) -> f32 {
+    use PairingClass::*;
+    match (heading, body) {
+        (a, b) if a == b => 0.15,
+        (Symbol, _) | (_, Symbol) => 0.0,
+        (a, b) if a.is_sans() && b.is_sans() => 0.4,
+        (Serif, SlabSerif) | (SlabSerif, Serif) => 0.5,
+        (a, b) if (a.is_sans() && b == Serif) || (a == Serif && b.is_sans()) => 1.0,
+        (a, b) if (a.is_sans() && b == SlabSerif) || (a == SlabSerif && b.is_sans()) => 0.85,
+        (Script | Display, b) if b.is_text() => 0.8,
+        (Monospace, _) | (_, Monospace) => 0.6,
+        _ => 0.1,
+    }
+}
+
+fn contrast_reason(heading: PairingClass, body: PairingClass, contrast: f32) -> String {
+    if heading == body {
+        format!("both {}: little contrast between heading and body", heading.describe())
+    } else if contrast >= 0.8 {
+        format!("{} heading over a {} body: a classic contrast", heading.describe(), body.describe())
+    } else {
+        format!("{} heading over a {} body", heading.describe(), body.describe())
+    }
+}
+
+/// How closely a metric, as a fraction of the em, matches between two fonts, with both
+/// ratios; None when either font lacks metrics
+fn metric_match(a: &FontDescriptor, b: &FontDescriptor, metric: impl Fn(&FontMetrics) -> i16, tolerance: f32) -> Option<(f32, f32, f32)> {
+    let ratio = |font: &FontDescriptor| {
+        let metrics = font.metrics.as_ref()?;
+        let value = metric(metrics);
+        (metrics.units_per_em > 0 && value > 0).then(|| value as f32 / metrics.units_per_em as f32)
+    };
+    let (a, b) = (ratio(a)?, ratio(b)?);
+    Some(((1.0 - (a - b).abs() / tolerance).clamp(0.0, 1.0), a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use font_core::{FontFormat, FontStyle};
+
+    const SERIF_PANOSE: [u8; 10] = [2, 2, 6, 3, 5, 4, 5, 2, 3, 4];
+    const SANS_PANOSE: [u8; 10] = [2, 11, 6, 4, 2, 2, 2, 2, 2, 4];
+
+    fn font(family: &str, panose: Option<[u8; 10]>) -> FontDescriptor {
+        FontDescriptor {
+            family: family.to_string(),
+            subfamily: Some("Regular".to_string()),
+            postscript_name: family.replace(' ', ""),
+            full_name: None,
+            path: std::path::PathBuf::from(format!("/fonts/{}.ttf", family)),
+            format: FontFormat::Ttf,
+            weight: 400,
+            italic: false,
+            monospaced: false,
+            variable: false,
+            metrics: None,
+            license: None,
+            classification: panose.map(|panose| FontClassification { panose, family_class: 0, shape: None }),
+        }
+    }
+
+    fn request(family: &str) -> FontRequest {
+        FontRequest {
+            original_name: family.to_string(),
+            normalized_name: family.to_lowercase(),
+            family: family.to_string(),
+            weight: 400,
+            style: FontStyle::Normal,
+            italic: false,
+            monospaced: false,
+            width: font_core::WIDTH_NORMAL,
+        }
+    }
+
+    /// Signature store built from the glyph database shipped in `data/`
+    fn shipped_glyph_store() -> (GlyphDatabase, GlyphSignatureStore) {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../data/glyph_signatures.bin");
+        let database = font_glyph_db::load_database(&std::fs::read(path).unwrap()).unwrap();
+        let store = GlyphSignatureStore::from_database(&database);
+        (database, store)
+    }
+
+    #[test]
+    fn test_visual_baseline_is_unrelated_glyph_similarity() {
+        let (database, _) = shipped_glyph_store();
+        let weights = &ScoringProfile::shared().signature;
+
+        // Different characters from different fonts have nothing in common but being glyphs
+        let (mut total, mut count) = (0.0, 0);
+        for (i, a) in database.fonts.iter().enumerate() {
+            for b in &database.fonts[i + 1..] {
+                for (ch_a, signature_a) in &a.signatures {
+                    for (ch_b, signature_b) in &b.signatures {
+                        if ch_a != ch_b {
+                            total += signature_a.similarity_with(signature_b, weights);
+                            count += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert!(count > 10_000);
+        let mean = total / count as f32;
+        assert!((mean - VISUAL_BASELINE).abs() < 0.01, "unrelated glyphs average {}", mean);
+    }
+
+    #[test]
+    fn test_visual_similarity_outranks_shared_name() {
+        let (_, mut store) = shipped_glyph_store();
+        let weights = &ScoringProfile::shared().signature;
+        assert_eq!(store.visual_similarity("DejaVu Sans", "DejaVu Sans", weights), Some(1.0));
+        let mono = store.visual_similarity("DejaVu Sans", "DejaVu Sans Mono", weights).unwrap();
+        let serif = store.visual_similarity("DejaVu Sans", "DejaVu Serif", weights).unwrap();
+        assert!(mono > serif, "{} vs {}", mono, serif);
+
+        // A serif from the same family, against Menlo under a name of its own; Menlo is
+        // drawn from DejaVu Sans Mono
+        let mono = store.get("DejaVu Sans Mono").unwrap().to_vec();
+        store.insert("Menlo", mono);
+        let fonts = [font("DejaVu Serif", None), font("Menlo", None)];
+        let ranked = |engine: &FontSimilarityEngine| -> Vec<String> {
+            engine.find_tiered_matches(&request("DejaVu Sans"), &fonts, 5).matches.into_iter()
+                .map(|m| m.font.family)
+                .collect()
+        };
+
+        assert_eq!(ranked(&FontSimilarityEngine::new(None)), ["DejaVu Serif", "Menlo"]);
+        assert_eq!(ranked(&FontSimilarityEngine::new(None).with_glyph_store(store)), ["Menlo", "DejaVu Serif"]);
+    }
+
+    #[test]
+    fn test_panose_similarity() {
+        assert_eq!(panose_similarity(&SERIF_PANOSE, &SERIF_PANOSE), Some(1.0));
+        // Unset on either side
+        assert_eq!(panose_similarity(&[0; 10], &SERIF_PANOSE), None);
+        assert_eq!(panose_similarity(&SERIF_PANOSE, &[1, 2, 6, 3, 5, 4, 5, 2, 3, 4]), None);
+        // Latin text against hand-written
+        assert_eq!(panose_similarity(&SERIF_PANOSE, &[3, 2, 6, 3, 5, 4, 5, 2, 3, 4]), Some(0.0));
+        // Only the family kind set leaves no digit to compare
+        assert_eq!(panose_similarity(&[2, 0, 0, 0, 0, 0, 0, 0, 0, 0], &SERIF_PANOSE), None);
+
+        let serif_vs_sans = panose_similarity(&SERIF_PANOSE, &SANS_PANOSE).unwrap();
+        let mut bolder = SERIF_PANOSE;
+        bolder[2] = 8;
+        let serif_vs_bolder = panose_similarity(&SERIF_PANOSE, &bolder).unwrap();
+        assert!(serif_vs_sans < serif_vs_bolder, "{} vs {}", serif_vs_sans, serif_vs_bolder);
+        assert!(serif_vs_bolder < 1.0);
+
+        // "Any" and "no fit" digits are left out instead of counting as mismatches
+        let mut partial = SERIF_PANOSE;
+        partial[5..].copy_from_slice(&[0, 1, 0, 1, 0]);
+        assert_eq!(panose_similarity(&SERIF_PANOSE, &partial), Some(1.0));
+
+        // Other family kinds compare digits as unordered categories
+        let script = [3, 2, 2, 2, 2, 2, 2, 2, 2, 2];
+        let mut other_script = script;
+        other_script[1] = 5;
+        let expected = (8.0 + 0.3) / 9.0;
+        assert!((panose_similarity(&script, &other_script).unwrap() - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_panose_text_digit_similarity() {
+        let cases: [(usize, u8, u8, f32); 14] = [
+            (1, 4, 4, 1.0),
+            (1, 2, 10, 0.6),  // both serifed
+            (1, 11, 15, 0.6), // both sans
+            (1, 4, 11, 0.0),
+            (2, 2, 11, 0.0),
+            (2, 5, 8, 1.0 - 3.0 / 9.0),
+            (3, 9, 3, 0.0),   // monospaced against proportional
+            (3, 3, 5, 0.5),
+            (4, 2, 9, 0.0),
+            (4, 3, 4, 1.0 - 1.0 / 7.0),
+            (6, 2, 6, 0.6),
+            (6, 6, 7, 0.2),
+            (7, 9, 16, 0.6),
+            (9, 2, 5, 0.5),
+        ];
+        for (digit, x, y, expected) in cases {
+            let similarity = panose_text_digit_similarity(digit, x, y);
+            assert!((similarity - expected).abs() < 1e-6, "digit {} ({}, {}): {}", digit, x, y, similarity);
+            assert_eq!(similarity, panose_text_digit_similarity(digit, y, x));
+        }
+    }
+
+    #[test]
+    fn test_catalogued_reference_enables_panose_scoring() {
+        let engine = FontSimilarityEngine::new(None);
+        let fonts = [font("Alpha Book", Some(SANS_PANOSE)), font("Alpha Text", Some(SERIF_PANOSE))];
+        let request = request("Uninstalled Serif");
+
+        // No face of the requested family is installed, so there is nothing to compare against
+        let without = engine.find_tiered_matches(&request, &fonts, 5);
+        assert!(without.matches.iter().all(|m| m.similarity_details.panose_similarity.is_none()));
+
+        let reference = FontClassification { panose: SERIF_PANOSE, family_class: 0, shape: None };
+        let with = engine.find_tiered_matches_with_reference(&request, Some(&reference), &fonts, 5);
+        assert_eq!(with.matches[0].font.family, "Alpha Text");
+        assert_eq!(with.matches[0].similarity_details.panose_similarity, Some(1.0));
+        assert!(with.matches[1].similarity_details.panose_similarity.unwrap() < 1.0);
+
+        // Fitting samples see the PANOSE-based category component too
+        let category = |sample: &QuerySample| -> Vec<f32> {
+            sample.candidates.iter().map(|c| c.values[3]).collect()
+        };
+        let sample = engine.query_sample(&request, Some(&reference), "Alpha Text", &fonts);
+        assert_eq!(category(&sample)[1], 1.0);
+        assert_ne!(category(&sample), category(&engine.query_sample(&request, None, "Alpha Text", &fonts)));
+    }
+
+    #[test]
+    fn test_best_classification_prefers_complete_entries() {
+        let bare = FontClassification { panose: [0; 10], family_class: 0x0801, shape: None };
+        let panose = FontClassification { panose: SANS_PANOSE, family_class: 0x0801, shape: None };
+        let complete = FontClassification {
+            shape: Some(ShapeClassification { sans_serif: 0.9, ..Default::default() }),
+            ..panose
+        };
+        assert_eq!(best_classification([bare, panose, complete]), Some(complete));
+        assert_eq!(best_classification([bare, panose]), Some(panose));
+        assert_eq!(best_classification([]), None);
+    }
+
+    fn candidate(family: &str, values: [f32; 5]) -> CandidateComponents {
+        CandidateComponents { family: family.to_string(), values, visual: None, monospace_mismatch: false }
+    }
+
+    /// Resolver samples whose expected family wins on weight but loses on the family name
+    fn weight_led_samples(count: usize) -> Vec<QuerySample> {
+        (0..count).map(|i| {
+            let jitter = i as f32 * 0.002;
+            QuerySample::new(ScoreModel::Resolver, "Expected", vec![
+                candidate("Expected", [0.5 + jitter, 1.0, 1.0, 1.0, 1.0]),
+                candidate("Decoy", [1.0, 0.5 - jitter, 1.0, 1.0, 1.0]),
+            ])
+        }).collect()
+    }
+
+    #[test]
+    fn test_fit_improves_training_and_held_out_accuracy() {
+        let samples = weight_led_samples(10);
+        let fitter = ProfileFitter::new(1);
+        let base = ScoringProfile::embedded();
+        
+        let report = fitter.fit(&base, &samples);
+        let (before, after) = report.resolver.unwrap();
+        assert_eq!((before.queries, before.top1), (10, 0));
+        assert_eq!(after.top1, 10);
+        assert!(report.profile.resolver.weight > base.resolver.weight);
+        assert!(report.similarity.is_none() && report.similarity_held_out.is_none());
+        
+        let (held_before, held_after) = report.resolver_held_out.unwrap();
+        assert_eq!(held_before.queries, 10);
+        assert_eq!(held_before.top1, 0);
+        assert_eq!(held_after.top1, 10);
+        
+        // A single sample can't be split into folds
+        assert!(fitter.fit(&base, &samples[..1]).resolver_held_out.is_none());
+    }
+
+    #[test]
+    fn test_evaluation_counts_unreachable_and_reciprocal_rank() {
+        let mut samples = weight_led_samples(2);
+        samples.push(QuerySample::new(ScoreModel::Resolver, "Missing", vec![candidate("Decoy", [1.0; 5])]));
+        
+        let evaluation = ProfileFitter::new(2).evaluate(&ScoringProfile::embedded(), &samples);
+        assert_eq!(evaluation.queries, 3);
+        assert_eq!((evaluation.top1, evaluation.top_k, evaluation.unreachable), (0, 2, 1));
+        assert!((evaluation.mean_reciprocal_rank - 1.0 / 3.0).abs() < 1e-6);
+        
+        let mut combined = evaluation;
+        combined.combine(&ProfileEvaluation { queries: 1, k: 2, top1: 1, top_k: 1, unreachable: 0, mean_reciprocal_rank: 1.0 });
+        assert_eq!((combined.queries, combined.top1, combined.top_k), (4, 1, 3));
+        assert!((combined.mean_reciprocal_rank - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_from_score_uses_shared_profile_tiers() {
+        let tiers = &ScoringProfile::shared().tiers;
+        for score in [0.0, tiers.similar, tiers.exact, 1.0] {
+            assert_eq!(MatchTier::from_score(score), MatchTier::from_score_with(score, tiers));
+        }
+        assert!(MatchTier::from_score(1.0).is_good_match());
+    }
+
+    #[test]
+    fn test_fit_tiers_keeps_good_threshold_above_min_score() {
+        // Correct answers top out at 0.6, wrong ones at 0.4
+        let samples: Vec<QuerySample> = (0..4).map(|i| {
+            let (expected, decoy) = if i % 2 == 0 { (0.6, 0.3) } else { (0.2, 0.4) };
+            QuerySample::new(ScoreModel::Resolver, "Expected", vec![
+                candidate("Expected", [expected; 5]),
+                candidate("Decoy", [decoy; 5]),
+            ])
+        }).collect();
+        let refs: Vec<&QuerySample> = samples.iter().collect();
+        
+        let mut profile = ScoringProfile::embedded();
+        assert!(ProfileFitter::new(1).fit_tiers(&mut profile, &refs));
+        assert!((profile.tiers.exact - 0.6).abs() < 1e-6);
+        assert_eq!(profile.suggestions.good, profile.suggestions.good_min_score);
+        assert!(profile.tiers.similar <= profile.tiers.exact);
+        
+        // Above good_min_score the good threshold follows the exact tier
+        let mut profile = ScoringProfile::embedded();
+        profile.suggestions.good_min_score = 0.5;
+        assert!(!ProfileFitter::new(1).fit_tiers(&mut profile, &refs));
+        assert_eq!(profile.suggestions.good, profile.tiers.exact);
+    }
+
+    fn designed(family: &str, panose: [u8; 10], x_height: i16, average_width: i16) -> FontDescriptor {
+        FontDescriptor {
+            metrics: Some(FontMetrics {
+                units_per_em: 1000,
+                ascender: 900,
+                descender: -220,
+                x_height,
+                cap_height: 710,
+                average_width,
+                max_advance_width: 1200,
+            }),
+            ..font(family, Some(panose))
+        }
+    }
+
+    fn catalog() -> Vec<FontDescriptor> {
+        const GROTESQUE: [u8; 10] = [2, 11, 6, 4, 2, 2, 2, 2, 2, 4];
+        const HUMANIST: [u8; 10] = [2, 11, 6, 6, 3, 5, 4, 2, 2, 4];
+        let mut bold = designed("Helvetica", GROTESQUE, 523, 500);
+        bold.weight = 700;
+        vec![
+            designed("Helvetica", GROTESQUE, 523, 500),
+            bold,
+            designed("Arial", GROTESQUE, 519, 500),
+            designed("Roboto", GROTESQUE, 528, 470),
+            designed("Open Sans", HUMANIST, 545, 480),
+            designed("Noto Sans", HUMANIST, 536, 480),
+            designed("Noto Serif", SERIF_PANOSE, 536, 480),
+            designed("Noto Sans Mono", HUMANIST, 536, 600),
+            designed("Times New Roman", SERIF_PANOSE, 448, 420),
+            designed("Georgia", SERIF_PANOSE, 481, 470),
+            font("Mystery Grotesk", None),
+        ]
+    }
+
+    fn families(mut families: Vec<String>) -> Vec<String> {
+        families.sort();
+        families
+    }
+
+    #[test]
+    fn test_similarity_graph_edges() {
+        let graph = SimilarityGraph::build(&FontSimilarityEngine::new(None), &catalog());
+        // One node per family, represented by its regular face
+        assert_eq!(graph.node_count(), 10);
+        assert_eq!(graph.node("HELVETICA").unwrap().faces, 2);
+        assert_eq!(graph.node("Roboto").unwrap().license, LicenseType::OpenSource);
+        assert_eq!(graph.node("Helvetica").unwrap().license, LicenseType::Commercial);
+
+        for edge in graph.graph().edge_references() {
+            let weight = edge.weight();
+            match weight.kind {
+                EdgeKind::Clone => assert!(weight.similarity >= CLONE_SIMILARITY),
+                EdgeKind::LookAlike => assert!((MIN_EDGE_SIMILARITY..CLONE_SIMILARITY).contains(&weight.similarity)),
+                EdgeKind::Superfamily => {}
+            }
+            assert_eq!(weight.visual, None);
+            assert_eq!(weight.similarity, weight.design);
+        }
+
+        // Superfamily members hang off the shortest name, however different they look
+        let kinds = |family: &str| -> Vec<(String, EdgeKind)> {
+            graph.neighbours(family).into_iter().map(|(node, edge)| (node.family.clone(), edge.kind)).collect()
+        };
+        assert!(kinds("Noto Serif").contains(&("Noto Sans".to_string(), EdgeKind::Superfamily)));
+        assert!(kinds("Noto Sans Mono").contains(&("Noto Sans".to_string(), EdgeKind::Superfamily)));
+        assert!(!kinds("Noto Serif").iter().any(|(family, _)| family == "Noto Sans Mono"));
+        assert!(kinds("Noto Serif").contains(&("Georgia".to_string(), EdgeKind::Clone)));
+
+        let arial = graph.neighbours("arial");
+        assert_eq!(arial[0].0.family, "Helvetica");
+        assert!(arial.windows(2).all(|pair| pair[0].1.similarity >= pair[1].1.similarity));
+        // Serifs and sans only meet through the Noto superfamily
+        assert!(arial.iter().all(|(node, _)| node.category == FontCategory::SansSerif));
+        // Families without design features are never compared
+        assert!(graph.neighbours("Mystery Grotesk").is_empty());
+        assert!(graph.neighbours("Unlisted").is_empty());
+    }
+
+    #[test]
+    fn test_similarity_graph_clusters() {
+        let graph = SimilarityGraph::build(&FontSimilarityEngine::new(None), &catalog());
+        let clusters = |kind, min_similarity| -> Vec<Vec<String>> {
+            let mut found: Vec<Vec<String>> = graph.clusters(kind, min_similarity).into_iter()
+                .map(|cluster| families(cluster.families))
+                .collect();
+            found.sort();
+            found
+        };
+
+        assert_eq!(clusters(ClusterKind::Superfamily, 0.0), [["Noto Sans", "Noto Sans Mono", "Noto Serif"]]);
+        // The serif member is linked by name, not by looks
+        assert_eq!(clusters(ClusterKind::Superfamily, 0.5), [["Noto Sans", "Noto Sans Mono"]]);
+        assert_eq!(clusters(ClusterKind::Clones, 0.0), [
+            vec!["Arial", "Helvetica", "Roboto"],
+            vec!["Georgia", "Noto Serif", "Times New Roman"],
+            vec!["Noto Sans", "Open Sans"],
+        ]);
+        assert_eq!(clusters(ClusterKind::Clones, 0.99), [["Arial", "Helvetica"], ["Noto Sans", "Open Sans"]]);
+        // Look-alike clusters take clones too, but not superfamily links
+        assert_eq!(clusters(ClusterKind::LookAlikes, 0.0), [
+            vec!["Arial", "Helvetica", "Noto Sans", "Noto Sans Mono", "Open Sans", "Roboto"],
+            vec!["Georgia", "Noto Serif", "Times New Roman"],
+        ]);
+
+        let sans = graph.clusters(ClusterKind::Clones, 0.0).into_iter()
+            .find(|cluster| cluster.families.contains(&"Roboto".to_string()))
+            .unwrap();
+        assert_eq!((sans.open_source_count, sans.commercial_count), (1, 1));
+        let edges: Vec<f32> = ["Arial", "Roboto"].iter()
+            .flat_map(|family| graph.neighbours(family))
+            .filter(|(node, edge)| edge.kind == EdgeKind::Clone && ["Helvetica", "Roboto"].contains(&node.family.as_str()))
+            .map(|(_, edge)| edge.similarity)
+            .collect();
+        assert_eq!(edges.len(), 3);
+        assert!((sans.mean_similarity - edges.iter().sum::<f32>() / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_lookalike_path() {
+        let graph = SimilarityGraph::build(&FontSimilarityEngine::new(None), &catalog());
+        let path = |from, to| graph.lookalike_path(from, to).map(|path| path.families);
+
+        // Without a target, the nearest open-source family
+        let to_free = graph.lookalike_path("Helvetica", None).unwrap();
+        assert_eq!(to_free.families, ["Helvetica", "Roboto"]);
+        assert_eq!(to_free.similarities.len(), 1);
+        assert!((to_free.cost - (1.0 - to_free.similarities[0])).abs() < 1e-6);
+        assert_eq!(path("Times New Roman", None).unwrap(), ["Times New Roman", "Noto Serif"]);
+        // An open-source start doesn't count as its own answer
+        assert_eq!(path("Roboto", None).unwrap().len(), 2);
+
+        // From sans to serif, only across the superfamily link
+        let across = graph.lookalike_path("Helvetica", Some("georgia")).unwrap();
+        assert_eq!(across.families, ["Helvetica", "Noto Sans", "Noto Serif", "Georgia"]);
+        assert_eq!(across.similarities.len(), 3);
+        let cost: f32 = across.similarities.iter().map(|s| 1.0 - s).sum();
+        assert!((across.cost - cost).abs() < 1e-5);
+
+        assert_eq!(path("Helvetica", Some("Helvetica")).unwrap(), ["Helvetica"]);
+        assert_eq!(path("Mystery Grotesk", None), None);
+        assert_eq!(path("Helvetica", Some("Mystery Grotesk")), None);
+        assert_eq!(path("Unlisted", None), None);
+        assert_eq!(path("Helvetica", Some("Unlisted")), None);
+    }
+
+    #[test]
+    fn test_component_summaries() {
+        let graph = SimilarityGraph::build(&FontSimilarityEngine::new(None), &catalog());
+        let components = graph.component_summaries();
+        assert_eq!(components.iter().map(|c| c.size).collect::<Vec<_>>(), [9, 1]);
+
+        let main = &components[0];
+        assert_eq!(main.hub, "Noto Sans");
+        assert_eq!(main.families.len(), 9);
+        assert_eq!(main.families[0], "Noto Sans");
+        assert_eq!(main.categories, [(FontCategory::SansSerif, 6), (FontCategory::Serif, 3)]);
+        assert_eq!((main.open_source_count, main.commercial_count, main.other_license_count), (5, 1, 3));
+        let edges: Vec<f32> = graph.graph().edge_weights().map(|edge| edge.similarity).collect();
+        assert!((main.mean_similarity - edges.iter().sum::<f32>() / edges.len() as f32).abs() < 1e-6);
+
+        let single = &components[1];
+        assert_eq!((single.hub.as_str(), single.mean_similarity), ("Mystery Grotesk", 0.0));
+        assert_eq!((single.open_source_count, single.commercial_count, single.other_license_count), (0, 0, 1));
+    }
+
+    /// Names of the elements in `xml`, checking that every start tag is closed in order
+    fn xml_elements(xml: &str) -> Vec<String> {
+        let mut open: Vec<&str> = Vec::new();
+        let mut elements = Vec::new();
+        let mut rest = xml;
+        while let Some(start) = rest.find('<') {
+            let text = &rest[..start];
+            assert!(!text.contains('>') && !text.contains('"'), "unescaped text {:?}", text);
+            assert!(text.match_indices('&').all(|(i, _)| ["&amp;", "&lt;", "&gt;", "&quot;"]
+                .iter().any(|entity| text[i..].starts_with(entity))), "unescaped text {:?}", text);
+            let end = start + rest[start..].find('>').expect("tags are closed");
+            let tag = &rest[start + 1..end];
+            rest = &rest[end + 1..];
+            assert_eq!(tag.matches('"').count() % 2, 0, "attribute quotes in {:?}", tag);
+            if tag.starts_with('?') {
+                continue;
+            }
+            if let Some(name) = tag.strip_prefix('/') {
+                assert_eq!(open.pop(), Some(name));
+            } else {
+                let name = tag.split_whitespace().next().unwrap().trim_end_matches('/');
+                elements.push(name.to_string());
+                if !tag.ends_with('/') {
+                    open.push(name);
+                }
+            }
+        }
+        assert!(open.is_empty(), "unclosed {:?}", open);
+        assert!(rest.trim().is_empty());
+        elements
+    }
+
+    #[test]
+    fn test_graph_exports_are_well_formed() {
+        let mut fonts = catalog();
+        fonts.push(designed(r#"Smith & "Sons" <Grotesk>"#, [2, 11, 6, 4, 2, 2, 2, 2, 2, 4], 523, 500));
+        let graph = SimilarityGraph::build(&FontSimilarityEngine::new(None), &fonts);
+
+        let graphml = graph.to_graphml();
+        let elements = xml_elements(&graphml);
+        assert_eq!(elements.iter().filter(|e| *e == "node").count(), graph.node_count());
+        assert_eq!(elements.iter().filter(|e| *e == "edge").count(), graph.edge_count());
+        assert!(graphml.contains("<data key=\"family\">Smith &amp; &quot;Sons&quot; &lt;Grotesk&gt;</data>"));
+        assert!(graphml.contains("<data key=\"kind\">superfamily</data>"));
+        assert_eq!(xml_escape("a & \"b\""), "a &amp; &quot;b&quot;");
+        // Every edge points at a declared node
+        for edge in graph.graph().edge_references() {
+            assert!(graphml.contains(&format!("source=\"n{}\" target=\"n{}\"", edge.source().index(), edge.target().index())));
+        }
+
+        let dot = graph.to_dot();
+        let lines: Vec<&str> = dot.lines().collect();
+        assert_eq!((lines[0], *lines.last().unwrap()), ("graph {", "}"));
+        assert_eq!(lines.len(), 2 + graph.node_count() + graph.edge_count());
+        for line in &lines[1..lines.len() - 1] {
+            // Quotes inside labels are escaped, so every line has an even number of unescaped ones
+            assert_eq!(line.replace("\\\"", "").matches('"').count() % 2, 0, "{}", line);
+            assert!(line.trim_end().ends_with(']'), "{}", line);
+        }
+        assert!(dot.contains(r#"label = "Smith & \"Sons\" <Grotesk>""#));
+        assert!(dot.contains("style = \"dashed\""));
+        assert!(dot.contains("color = \"firebrick\""));
+        assert!(dot.contains("color = \"darkgreen\""));
+    }
+
+    #[test]
+    fn test_pairing_contrast() {
+        use PairingClass::*;
+
+        let cases = [
+            (GeometricSans, Serif, 1.0),
+            (Serif, HumanistSans, 1.0),
+            (GrotesqueSans, SlabSerif, 0.85),
+            (SlabSerif, Sans, 0.85),
+            (Script, Serif, 0.8),
+            (Display, GeometricSans, 0.8),
+            (Display, Monospace, 0.8),
+            (Monospace, Serif, 0.6),
+            (Serif, Monospace, 0.6),
+            (Serif, SlabSerif, 0.5),
+            (GeometricSans, HumanistSans, 0.4),
+            (Serif, Serif, 0.15),
+            (Symbol, Symbol, 0.15),
+            (Script, Display, 0.1),
+            (Serif, Script, 0.1),
+            (Symbol, Serif, 0.0),
+            (Sans, Symbol, 0.0),
+        ];
+        for (heading, body, contrast) in cases {
+            assert_eq!(pairing_contrast(heading, body), contrast, "{:?} over {:?}", heading, body);
+        }
+    }
+
+    #[test]
+    fn test_metric_match() {
+        let x_height = |font: &FontDescriptor, other: &FontDescriptor| {
+            metric_match(font, other, |m| m.x_height, X_HEIGHT_TOLERANCE)
+        };
+        let base = designed("Base", SANS_PANOSE, 500, 500);
+
+        assert_eq!(x_height(&base, &base), Some((1.0, 0.5, 0.5)));
+        let (matched, a, b) = x_height(&base, &designed("Taller", SANS_PANOSE, 540, 500)).unwrap();
+        assert!((matched - 0.5).abs() < 1e-5 && a == 0.5 && (b - 0.54).abs() < 1e-6);
+        // Beyond the tolerance nothing matches
+        assert_eq!(x_height(&base, &designed("Tallest", SANS_PANOSE, 600, 500)).unwrap().0, 0.0);
+
+        // Compared as fractions of the em, whatever the units
+        let mut scaled = designed("Scaled", SANS_PANOSE, 1024, 1024);
+        scaled.metrics.as_mut().unwrap().units_per_em = 2048;
+        assert_eq!(x_height(&base, &scaled).unwrap().0, 1.0);
+        let width = metric_match(&base, &scaled, |m| m.average_width, WIDTH_TOLERANCE).unwrap();
+        assert_eq!(width, (1.0, 0.5, 0.5));
+
+        // Missing or unset metrics give nothing to compare
+        assert_eq!(x_height(&base, &font("Unmeasured", None)), None);
+        assert_eq!(x_height(&base, &designed("Unset", SANS_PANOSE, 0, 500)), None);
+        scaled.metrics.as_mut().unwrap().units_per_em = 0;
+        assert_eq!(x_height(&scaled, &base), None);
+    }
+
+    const SCRIPT_PANOSE: [u8; 10] = [3, 2, 6, 3, 5, 4, 5, 2, 3, 4];
+
+    #[test]
+    fn test_pairing_scope() {
+        let dir = tempfile::tempdir().unwrap();
+        let installed = dir.path().join("Georgia.ttf");
+        std::fs::write(&installed, b"").unwrap();
+        let mut georgia = designed("Georgia", SERIF_PANOSE, 481, 470);
+        georgia.path = installed;
+
+        let mut bold_only = designed("Open Sans", SANS_PANOSE, 545, 480);
+        bold_only.weight = 700;
+        let fonts = [
+            designed("Lora", SERIF_PANOSE, 500, 480),
+            designed("Roboto", SANS_PANOSE, 528, 470),
+            designed("Helvetica", SANS_PANOSE, 523, 500),
+            georgia,
+            bold_only,
+            designed("Lobster Script", SCRIPT_PANOSE, 500, 480),
+        ];
+        let engine = FontSimilarityEngine::new(None);
+        let partners = |role, scope| -> Vec<String> {
+            let mut partners: Vec<String> = engine.find_pairings("Lora", role, &fonts, scope, 10).unwrap()
+                .into_iter().map(|pairing| pairing.partner).collect();
+            partners.sort();
+            partners
+        };
+
+        // Commercial fonts are suggested only when installed; open-source ones always
+        assert_eq!(partners(PairingRole::Heading, PairingScope::Available), ["Georgia", "Roboto"]);
+        assert_eq!(partners(PairingRole::Heading, PairingScope::Local), ["Georgia"]);
+        assert_eq!(partners(PairingRole::Heading, PairingScope::LicenseSafe), ["Roboto"]);
+        // Headings may be bold or decorative; body text may not
+        assert_eq!(partners(PairingRole::Body, PairingScope::LicenseSafe), ["Open Sans", "Roboto"]);
+
+        let pairings = engine.find_pairings("lora", PairingRole::Heading, &fonts, PairingScope::Available, 1).unwrap();
+        assert_eq!(pairings.len(), 1);
+        let best = &pairings[0];
+        assert_eq!((best.heading.as_str(), best.body.as_str(), best.partner.as_str()), ("Lora", "Roboto", "Roboto"));
+        assert_eq!(best.contrast, 1.0);
+        assert!(!best.local && best.license == LicenseType::OpenSource);
+        assert!(best.reasons.iter().any(|reason| reason == "open source, free to embed"));
+    }
+
+    #[test]
+    fn test_body_pairing_needs_text_anchor() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut lobster = designed("Lobster Script", SCRIPT_PANOSE, 500, 480);
+        lobster.path = dir.path().join("LobsterScript.ttf");
+        std::fs::write(&lobster.path, b"").unwrap();
+        let fonts = [
+            lobster,
+            designed("Roboto", SANS_PANOSE, 528, 470),
+            designed("Noto Serif", SERIF_PANOSE, 500, 480),
+        ];
+        let engine = FontSimilarityEngine::new(None);
+        let pair = |family, role| engine.find_pairings(family, role, &fonts, PairingScope::Available, 5);
+
+        let error = pair("Lobster Script", PairingRole::Body).unwrap_err();
+        assert!(matches!(&error, FontError::InvalidRequest(message) if message.contains("script design")), "{}", error);
+        // As a heading it pairs with text faces
+        let headings: Vec<String> = pair("Lobster Script", PairingRole::Heading).unwrap().into_iter().map(|p| p.body).collect();
+        assert_eq!(headings, ["Noto Serif", "Roboto"]);
+        // Body fonts that are text designs take decorative headings
+        let for_body = pair("Noto Serif", PairingRole::Body).unwrap();
+        let script = for_body.iter().find(|p| p.partner == "Lobster Script").unwrap();
+        assert_eq!((script.heading.as_str(), script.partner_class, script.contrast), ("Lobster Script", PairingClass::Script, 0.8));
+
+        assert!(matches!(pair("Unlisted", PairingRole::Body), Err(FontError::NotFound(_))));
+    }
+
+}
@@ -1,4 +1,4 @@
-use font_core::{FontDescriptor, FontError, FontResult, FontSourcePriority};
+use font_core::{width_class_from_name, FontDescriptor, FontError, FontRequest, FontResult, FontSourcePriority, FontStyle, WIDTH_NORMAL};
 use font_parser::FontParser;
 use font_web_db::WebFontDatabase;
 use std::collections::HashMap;
@@ -25,6 +25,7 @@ pub struct FontSourceManager {
     custom_sources: Vec<SourceType>,
     priority: FontSourcePriority,
     web_db: Option<WebFontDatabase>,
+    /// Found fonts keyed by `FontRequest::cache_key`; cleared whenever the source setup changes
     font_cache: HashMap<String, FontDescriptor>,
     parser: FontParser,
 }
@@ -45,7 +46,7 @@ impl FontSourceManager {
     pub fn enable_web_fonts(&mut self, enable: bool) -> FontResult<()> {
         if enable {
             if self.web_db.is_none() {
-                let db = WebFontDatabase::load_embedded();
+                let db = WebFontDatabase::load_default();
                 if db.is_loaded() {
                     self.web_db = Some(db);
                     self.web_sources = vec![SourceType::Web];
@@ -57,6 +58,7 @@ impl FontSourceManager {
             self.web_db = None;
             self.web_sources.clear();
         }
+        self.font_cache.clear();
         Ok(())
     }
     
@@ -91,12 +93,14 @@ impl FontSourceManager {
                 return Err(FontError::Parse("Invalid source type".to_string()));
             }
         }
+        self.font_cache.clear();
         Ok(())
     }
     
     pub fn remove_custom_source(&mut self, index: usize) -> FontResult<()> {
         if index < self.custom_sources.len() {
             self.custom_sources.remove(index);
+            self.font_cache.clear();
             Ok(())
         } else {
             Err(FontError::Parse(format!("Invalid source index: {}", index)))
@@ -104,6 +108,9 @@ impl FontSourceManager {
     }
     
     pub fn set_priority(&mut self, priority: FontSourcePriority) {
+        if self.priority != priority {
+            self.font_cache.clear();
+        }
         self.priority = priority;
     }
     
@@ -238,22 +245,51 @@ impl FontSourceManager {
         }
     }
     
+    /// Find the regular face of a family
     pub fn find_font(&mut self, family: &str) -> FontResult<Option<FontDescriptor>> {
-        // Check cache first
-        if let Some(font) = self.font_cache.get(family) {
-            return Ok(Some(font.clone()));
+        self.find_font_for(&FontRequest {
+            original_name: family.to_string(),
+            normalized_name: family.to_string(),
+            family: family.to_string(),
+            weight: 400,
+            style: FontStyle::Normal,
+            italic: false,
+            monospaced: false,
+            width: WIDTH_NORMAL,
+        })
+    }
+    
+    /// Find the face of the requested family closest to its weight, style and width
+    pub fn find_font_for(&mut self, request: &FontRequest) -> FontResult<Option<FontDescriptor>> {
+        let key = request.cache_key();
+        
+        // Check cache first, ignoring fonts whose file has since been removed
+        if let Some(font) = self.font_cache.get(&key) {
+            if font.path.exists() {
+                return Ok(Some(font.clone()));
+            }
+            self.font_cache.remove(&key);
         }
         
         // Scan sources if not in cache
         let fonts = self.scan_sources()?;
         
-        // Find the font
+        // Pick the closest face: italic must match first, then width, then weight
+        let family = family_key(&request.family);
         let font = fonts.into_iter()
-            .find(|f| f.family.to_lowercase() == family.to_lowercase());
+            .filter(|f| family_key(&f.family) == family)
+            .min_by_key(|f| {
+                let style_name = f.subfamily.as_deref().or(f.full_name.as_deref()).unwrap_or("");
+                (
+                    f.italic != request.italic,
+                    width_class_from_name(style_name).abs_diff(request.width),
+                    f.weight.abs_diff(request.weight),
+                )
+            });
         
         // Cache if found
         if let Some(ref font) = font {
-            self.font_cache.insert(family.to_string(), font.clone());
+            self.font_cache.insert(key, font.clone());
         }
         
         Ok(font)
@@ -334,6 +370,11 @@ impl FontSourceManager {
     }
 }
 
+/// Family name with case and separators ignored ("Times New Roman" == "times-new-roman")
+fn family_key(family: &str) -> String {
+    family.to_lowercase().replace([' ', '-', '_'], "")
+}
+
 impl Clone for FontSourceManager {
     fn clone(&self) -> Self {
         Self {
@@ -0,0 +1,658 @@
+use font_core::{FontDescriptor, FontError, FontResult};
+use font_license::{AlternativeRelation, FontKnowledgeBase, LicenseChecker};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Largest advance width difference, in 1/1000 em, that still counts as the same width
+pub const ADVANCE_TOLERANCE: f32 = 1.0;
+
+/// Fewer shared glyphs than this can't show that two fonts lay out text the same way
+pub const MIN_SHARED_GLYPHS: usize = 64;
+
+/// Pairs the knowledge base doesn't list because the substitute isn't a free font
+const BUILTIN_PAIRS: &[(&str, &str, &str)] = &[
+    ("Helvetica", "Arial", "Arial was drawn to Helvetica's advance widths"),
+    ("Times", "Times New Roman", "Times New Roman shares the Times advance widths"),
+    ("Times Roman", "Times New Roman", "Times New Roman shares the Times advance widths"),
+    ("Courier", "Courier New", "both are 600/1000 em monospaced"),
+];
+
+/// Advance widths of U+0020..=U+007E in 1/1000 em, from the PDF standard 14 font metrics
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556,
+    278, 278, 584, 584, 584, 556, 1015,
+    667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833,
+    722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611,
+    278, 278, 278, 469, 556, 333,
+    556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833,
+    556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500,
+    334, 260, 334, 584,
+];
+
+const TIMES_WIDTHS: [u16; 95] = [
+    250, 333, 408, 500, 500, 833, 778, 180, 333, 333, 500, 564, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500,
+    278, 278, 564, 564, 564, 444, 921,
+    722, 667, 667, 722, 611, 556, 722, 722, 333, 389, 722, 611, 889,
+    722, 722, 556, 722, 667, 556, 611, 722, 722, 944, 722, 722, 611,
+    333, 278, 333, 469, 500, 333,
+    444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500, 278, 778,
+    500, 500, 500, 500, 333, 389, 278, 500, 500, 722, 500, 500, 444,
+    480, 200, 480, 541,
+];
+
+const COURIER_WIDTHS: [u16; 95] = [600; 95];
+
+/// Families whose regular face can be checked against published widths when the original isn't installed
+const REFERENCE_METRICS: &[(&str, &[u16; 95])] = &[
+    ("helvetica", &HELVETICA_WIDTHS),
+    ("arial", &HELVETICA_WIDTHS),
+    ("times", &TIMES_WIDTHS),
+    ("times roman", &TIMES_WIDTHS),
+    ("times new roman", &TIMES_WIDTHS),
+    ("courier", &COURIER_WIDTHS),
+    ("courier new", &COURIER_WIDTHS),
+];
+
+/// A claim that `substitute` can replace `original` without reflowing text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubstitutePair {
+    pub original: String,
+    /// Other names the original is requested by
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub substitute: String,
+    #[serde(default)]
+    pub evidence: String,
+}
+
+impl SubstitutePair {
+    pub fn new(original: &str, substitute: &str) -> Self {
+        Self {
+            original: original.to_string(),
+            aliases: Vec::new(),
+            substitute: substitute.to_string(),
+            evidence: String::new(),
+        }
+    }
+
+    fn matches_original(&self, family: &str) -> bool {
+        let family = family.to_lowercase();
+        self.original.to_lowercase() == family
+            || self.aliases.iter().any(|alias| alias.to_lowercase() == family)
+    }
+
+    fn is_pair(&self, original: &str, substitute: &str) -> bool {
+        self.matches_original(original) && self.substitute.eq_ignore_ascii_case(substitute)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationStatus {
+    /// Installed fonts have matching advance widths
+    Verified,
+    /// Installed fonts were compared and their advance widths differ
+    Mismatch,
+    /// Not enough fonts installed to compare
+    Unavailable,
+}
+
+impl fmt::Display for VerificationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationStatus::Verified => write!(f, "verified"),
+            VerificationStatus::Mismatch => write!(f, "mismatch"),
+            VerificationStatus::Unavailable => write!(f, "unavailable"),
+        }
+    }
+}
+
+/// What the original side of a comparison was
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ComparedAgainst {
+    /// An installed face of the original family
+    Installed { path: PathBuf },
+    /// Published widths of the original family
+    Reference { family: String },
+}
+
+/// Advance width comparison of one substitute face
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FaceComparison {
+    pub substitute_path: PathBuf,
+    pub weight: u16,
+    pub italic: bool,
+    pub against: ComparedAgainst,
+    pub glyphs_compared: usize,
+    /// Largest advance width difference in 1/1000 em
+    pub max_deviation: f32,
+    pub worst_char: Option<char>,
+}
+
+impl FaceComparison {
+    pub fn matches(&self) -> bool {
+        self.glyphs_compared >= MIN_SHARED_GLYPHS && self.max_deviation <= ADVANCE_TOLERANCE
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairVerification {
+    pub pair: SubstitutePair,
+    pub status: VerificationStatus,
+    pub faces: Vec<FaceComparison>,
+    pub message: String,
+}
+
+/// Metric-compatible substitutes, each checked against the fonts installed on this machine
+#[derive(Debug, Clone, Default)]
+pub struct SubstituteRegistry {
+    pairs: Vec<SubstitutePair>,
+    results: Vec<PairVerification>,
+    /// Fingerprint of the font set the results were verified against
+    verified_fonts: Option<u64>,
+}
+
+impl SubstituteRegistry {
+    /// Built-in pairs plus the metric-compatible alternatives of the default knowledge base
+    pub fn new() -> Self {
+        Self::from_knowledge(LicenseChecker::new().knowledge_base())
+    }
+
+    /// Built-in and knowledge base pairs, each verified against the installed `fonts` as the
+    /// registry loads; `results` and `failures` report the outcome per pair
+    pub fn load(fonts: &[FontDescriptor]) -> Self {
+        let mut registry = Self::new();
+        registry.verify(fonts);
+        registry
+    }
+
+    pub fn from_knowledge(knowledge: &FontKnowledgeBase) -> Self {
+        let mut registry = Self::default();
+
+        for (original, substitute, evidence) in BUILTIN_PAIRS {
+            let mut pair = SubstitutePair::new(original, substitute);
+            pair.evidence = evidence.to_string();
+            registry.add_pair(pair);
+        }
+
+        for family in &knowledge.families {
+            for alt in family.alternatives.iter().filter(|a| a.relation == AlternativeRelation::MetricCompatible) {
+                registry.add_pair(SubstitutePair {
+                    original: family.family.clone(),
+                    aliases: family.aliases.clone(),
+                    substitute: alt.family.clone(),
+                    evidence: alt.evidence.clone(),
+                });
+            }
+        }
+
+        registry
+    }
+
+    /// Add a pair; it stays unverified until the next `verify`
+    pub fn add_pair(&mut self, pair: SubstitutePair) {
+        if !self.pairs.iter().any(|p| p.is_pair(&pair.original, &pair.substitute)) {
+            self.pairs.push(pair);
+            self.verified_fonts = None;
+        }
+    }
+
+    pub fn pairs(&self) -> &[SubstitutePair] {
+        &self.pairs
+    }
+
+    /// Compare every pair's advance widths using the given installed fonts
+    pub fn verify(&mut self, fonts: &[FontDescriptor]) -> &[PairVerification] {
+        self.results = self.pairs.iter().map(|pair| verify_pair(pair, fonts)).collect();
+        self.verified_fonts = Some(font_set_fingerprint(fonts));
+        &self.results
+    }
+
+    /// Whether the results are those of a `verify` against this same set of fonts,
+    /// in any order; a font added, removed or moved calls for verifying again
+    pub fn is_verified_against(&self, fonts: &[FontDescriptor]) -> bool {
+        self.verified_fonts == Some(font_set_fingerprint(fonts))
+    }
+
+    /// Results of the last `verify`
+    pub fn results(&self) -> &[PairVerification] {
+        &self.results
+    }
+
+    /// Pairs whose installed fonts were compared and found to differ
+    pub fn failures(&self) -> impl Iterator<Item = &PairVerification> {
+        self.results.iter().filter(|r| r.status == VerificationStatus::Mismatch)
+    }
+
+    pub fn verification(&self, original: &str, substitute: &str) -> Option<&PairVerification> {
+        self.results.iter().find(|r| r.pair.is_pair(original, substitute))
+    }
+
+    pub fn is_verified(&self, original: &str, substitute: &str) -> bool {
+        self.verification(original, substitute)
+            .map(|r| r.status == VerificationStatus::Verified)
+            .unwrap_or(false)
+    }
+
+    /// Verified substitutes for a family, safe to use without reflowing text
+    pub fn verified_substitutes(&self, family: &str) -> Vec<&str> {
+        self.results.iter()
+            .filter(|r| r.status == VerificationStatus::Verified && r.pair.matches_original(family))
+            .map(|r| r.pair.substitute.as_str())
+            .collect()
+    }
+
+    /// Failed verifications for substitutes of a family
+    pub fn failures_for<'a>(&'a self, family: &'a str) -> impl Iterator<Item = &'a PairVerification> + 'a {
+        self.failures().filter(move |r| r.pair.matches_original(family))
+    }
+}
+
+fn font_set_fingerprint(fonts: &[FontDescriptor]) -> u64 {
+    fonts.iter()
+        .map(|font| {
+            let mut hasher = DefaultHasher::new();
+            (font.family.to_lowercase(), &font.path, font.weight, font.italic).hash(&mut hasher);
+            hasher.finish()
+        })
+        .fold(fonts.len() as u64, u64::wrapping_add)
+}
+
+fn verify_pair(pair: &SubstitutePair, fonts: &[FontDescriptor]) -> PairVerification {
+    verify_pair_with(pair, fonts, |path| advance_widths(path, sample_chars()))
+}
+
+/// `verify_pair` with the advance widths of each font file read by `widths`
+fn verify_pair_with(
+    pair: &SubstitutePair,
+    fonts: &[FontDescriptor],
+    widths: impl Fn(&Path) -> FontResult<HashMap<char, f32>>,
+) -> PairVerification {
+    let substitute_faces = faces_of(fonts, &pair.substitute);
+    let original_faces = faces_of(fonts, &pair.original);
+
+    let result = |status, faces, message: String| PairVerification {
+        pair: pair.clone(),
+        status,
+        faces,
+        message,
+    };
+
+    if substitute_faces.is_empty() {
+        return result(
+            VerificationStatus::Unavailable,
+            Vec::new(),
+            format!("{} is not installed", pair.substitute),
+        );
+    }
+
+    let mut faces = Vec::new();
+    let mut errors = Vec::new();
+
+    if !original_faces.is_empty() {
+        // Compare every style both families have, or the regular faces if they share none
+        let mut matched: Vec<(&FontDescriptor, &FontDescriptor)> = substitute_faces.iter()
+            .filter_map(|sub| {
+                original_faces.iter()
+                    .find(|orig| orig.weight == sub.weight && orig.italic == sub.italic)
+                    .map(|orig| (*orig, *sub))
+            })
+            .collect();
+        if matched.is_empty() {
+            if let (Some(orig), Some(sub)) = (regular_face(&original_faces), regular_face(&substitute_faces)) {
+                matched.push((orig, sub));
+            }
+        }
+
+        for (orig, sub) in matched {
+            match compare_installed(orig, sub, &widths) {
+                Ok(comparison) => faces.push(comparison),
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+    } else if let Some(reference) = reference_widths(pair) {
+        let Some(sub) = regular_face(&substitute_faces) else {
+            return result(VerificationStatus::Unavailable, Vec::new(), format!("{} has no regular face", pair.substitute));
+        };
+        match compare_reference(&reference, sub, &pair.original, &widths) {
+            Ok(comparison) => faces.push(comparison),
+            Err(e) => errors.push(e.to_string()),
+        }
+    } else {
+        return result(
+            VerificationStatus::Unavailable,
+            Vec::new(),
+            format!("{} is not installed and has no reference metrics", pair.original),
+        );
+    }
+
+    if faces.is_empty() {
+        return result(
+            VerificationStatus::Unavailable,
+            Vec::new(),
+            format!("Could not read advance widths: {}", errors.join("; ")),
+        );
+    }
+
+    match faces.iter().find(|face| !face.matches()) {
+        Some(face) if face.glyphs_compared < MIN_SHARED_GLYPHS => {
+            let message = format!(
+                "{} and {} share only {} glyphs ({})",
+                pair.original, pair.substitute, face.glyphs_compared, face.substitute_path.display()
+            );
+            result(VerificationStatus::Mismatch, faces, message)
+        }
+        Some(face) => {
+            let message = format!(
+                "{} differs from {} by {:.1}/1000 em{} ({})",
+                pair.substitute,
+                pair.original,
+                face.max_deviation,
+                face.worst_char.map(|c| format!(" at {:?}", c)).unwrap_or_default(),
+                face.substitute_path.display()
+            );
+            result(VerificationStatus::Mismatch, faces, message)
+        }
+        None => {
+            let glyphs: usize = faces.iter().map(|f| f.glyphs_compared).sum();
+            let message = format!(
+                "{} matches {} ({} faces, {} glyphs)",
+                pair.substitute, pair.original, faces.len(), glyphs
+            );
+            result(VerificationStatus::Verified, faces, message)
+        }
+    }
+}
+
+fn faces_of<'a>(fonts: &'a [FontDescriptor], family: &str) -> Vec<&'a FontDescriptor> {
+    fonts.iter()
+        .filter(|font| font.family.eq_ignore_ascii_case(family) && !font.path.as_os_str().is_empty())
+        .collect()
+}
+
+/// The upright face closest to weight 400
+fn regular_face<'a>(faces: &[&'a FontDescriptor]) -> Option<&'a FontDescriptor> {
+    faces.iter()
+        .min_by_key(|font| (font.italic, (font.weight as i32 - 400).abs()))
+        .copied()
+}
+
+fn reference_widths(pair: &SubstitutePair) -> Option<HashMap<char, f32>> {
+    let original = pair.original.to_lowercase();
+    let widths = REFERENCE_METRICS.iter()
+        .find(|(family, _)| *family == original)
+        .map(|(_, widths)| *widths)?;
+
+    Some((0x20u8..=0x7E).zip(widths.iter()).map(|(c, w)| (c as char, *w as f32)).collect())
+}
+
+/// Printable ASCII and Latin-1
+fn sample_chars() -> impl Iterator<Item = char> {
+    ('\u{20}'..='\u{7E}').chain('\u{A0}'..='\u{FF}')
+}
+
+/// Advance widths in 1/1000 em of the characters the font maps
+pub fn advance_widths<P: AsRef<Path>>(path: P, chars: impl Iterator<Item = char>) -> FontResult<HashMap<char, f32>> {
+    let path = path.as_ref();
+    let data = fs::read(path)?;
+    let face = ttf_parser::Face::parse(&data, 0)
+        .map_err(|e| FontError::Parse(format!("Failed to parse {}: {}", path.display(), e)))?;
+    let units_per_em = face.units_per_em() as f32;
+
+    Ok(chars
+        .filter_map(|c| {
+            let glyph = face.glyph_index(c)?;
+            let advance = face.glyph_hor_advance(glyph)?;
+            Some((c, advance as f32 * 1000.0 / units_per_em))
+        })
+        .collect())
+}
+
+fn compare_widths(original: &HashMap<char, f32>, substitute: &HashMap<char, f32>) -> (usize, f32, Option<char>) {
+    let mut compared = 0;
+    let mut max_deviation = 0.0f32;
+    let mut worst_char = None;
+
+    let mut chars: Vec<&char> = original.keys().collect();
+    chars.sort();
+    for c in chars {
+        if let Some(width) = substitute.get(c) {
+            compared += 1;
+            let deviation = (original[c] - width).abs();
+            if deviation > max_deviation {
+                max_deviation = deviation;
+                worst_char = Some(*c);
+            }
+        }
+    }
+
+    (compared, max_deviation, worst_char)
+}
+
+fn compare_installed(
+    original: &FontDescriptor,
+    substitute: &FontDescriptor,
+    widths: impl Fn(&Path) -> FontResult<HashMap<char, f32>>,
+) -> FontResult<FaceComparison> {
+    let original_widths = widths(&original.path)?;
+    let substitute_widths = widths(&substitute.path)?;
+    let (glyphs_compared, max_deviation, worst_char) = compare_widths(&original_widths, &substitute_widths);
+
+    Ok(FaceComparison {
+        substitute_path: substitute.path.clone(),
+        weight: substitute.weight,
+        italic: substitute.italic,
+        against: ComparedAgainst::Installed { path: original.path.clone() },
+        glyphs_compared,
+        max_deviation,
+        worst_char,
+    })
+}
+
+fn compare_reference(
+    reference: &HashMap<char, f32>,
+    substitute: &FontDescriptor,
+    family: &str,
+    widths: impl Fn(&Path) -> FontResult<HashMap<char, f32>>,
+) -> FontResult<FaceComparison> {
+    let substitute_widths = widths(&substitute.path)?;
+    let (glyphs_compared, max_deviation, worst_char) = compare_widths(reference, &substitute_widths);
+
+    Ok(FaceComparison {
+        substitute_path: substitute.path.clone(),
+        weight: substitute.weight,
+        italic: substitute.italic,
+        against: ComparedAgainst::Reference { family: family.to_string() },
+        glyphs_compared,
+        max_deviation,
+        worst_char,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use font_core::FontFormat;
+
+    fn face(family: &str, weight: u16, italic: bool) -> FontDescriptor {
+        let style = format!("{}{}", weight, if italic { "i" } else { "" });
+        FontDescriptor {
+            family: family.to_string(),
+            subfamily: None,
+            postscript_name: family.replace(' ', ""),
+            full_name: None,
+            path: PathBuf::from(format!("/fonts/{}-{}.ttf", family.replace(' ', ""), style)),
+            format: FontFormat::Ttf,
+            weight,
+            italic,
+            monospaced: false,
+            variable: false,
+            metrics: None,
+            license: None,
+            classification: None,
+        }
+    }
+
+    /// Printable ASCII, each advance `width(c)`
+    fn ascii_widths(width: impl Fn(char) -> f32) -> HashMap<char, f32> {
+        ('\u{20}'..='\u{7E}').map(|c| (c, width(c))).collect()
+    }
+
+    /// Widths per font file, as `advance_widths` would read them
+    fn loader(files: Vec<(FontDescriptor, HashMap<char, f32>)>) -> impl Fn(&Path) -> FontResult<HashMap<char, f32>> {
+        move |path| files.iter()
+            .find(|(font, _)| font.path == path)
+            .map(|(_, widths)| widths.clone())
+            .ok_or_else(|| FontError::Parse(format!("unreadable {}", path.display())))
+    }
+
+    #[test]
+    fn test_compare_widths() {
+        let original = ascii_widths(|c| if c == 'W' { 944.0 } else { 500.0 });
+        let mut substitute = ascii_widths(|c| if c == 'W' { 950.0 } else { 500.5 });
+        substitute.remove(&'a');
+        substitute.insert('é', 2000.0);
+
+        let (compared, max_deviation, worst) = compare_widths(&original, &substitute);
+        assert_eq!(compared, 94);
+        assert_eq!(max_deviation, 6.0);
+        assert_eq!(worst, Some('W'));
+
+        assert_eq!(compare_widths(&original, &original), (95, 0.0, None));
+        assert_eq!(compare_widths(&original, &HashMap::new()), (0, 0.0, None));
+    }
+
+    #[test]
+    fn test_reference_width_tables() {
+        let helvetica = reference_widths(&SubstitutePair::new("Helvetica", "Arial")).unwrap();
+        assert_eq!(helvetica.len(), 95);
+        assert_eq!((helvetica[&' '], helvetica[&'A'], helvetica[&'@'], helvetica[&'~']), (278.0, 667.0, 1015.0, 584.0));
+        assert_eq!(reference_widths(&SubstitutePair::new("arial", "Liberation Sans")), Some(helvetica));
+
+        let times = reference_widths(&SubstitutePair::new("Times Roman", "Times New Roman")).unwrap();
+        assert_eq!((times[&' '], times[&'A'], times[&'m'], times[&'~']), (250.0, 722.0, 778.0, 541.0));
+        let courier = reference_widths(&SubstitutePair::new("Courier", "Courier New")).unwrap();
+        assert!(courier.values().all(|w| *w == 600.0));
+        assert!(reference_widths(&SubstitutePair::new("Futura", "Jost")).is_none());
+
+        // Figures are tabular in every table, which catches entries shifted by a position
+        for (family, widths) in REFERENCE_METRICS {
+            let digits = &widths[0x30 - 0x20..=0x39 - 0x20];
+            assert!(digits.iter().all(|w| *w == digits[0]), "{} digits differ", family);
+        }
+    }
+
+    #[test]
+    fn test_verify_installed_pairs() {
+        let pair = SubstitutePair::new("Helvetica", "Nimbus Sans");
+        let (orig, orig_bold) = (face("Helvetica", 400, false), face("Helvetica", 700, false));
+        let (sub, sub_bold) = (face("Nimbus Sans", 400, false), face("Nimbus Sans", 700, false));
+        let regular = ascii_widths(|c| c as u32 as f32 * 5.0);
+        let bold = ascii_widths(|c| c as u32 as f32 * 6.0);
+        let fonts = [orig.clone(), orig_bold.clone(), sub.clone(), sub_bold.clone()];
+
+        let widths = loader(vec![
+            (orig.clone(), regular.clone()), (orig_bold.clone(), bold.clone()),
+            (sub.clone(), regular.clone()), (sub_bold.clone(), bold.clone()),
+        ]);
+        let verified = verify_pair_with(&pair, &fonts, widths);
+        assert_eq!(verified.status, VerificationStatus::Verified);
+        assert_eq!(verified.faces.len(), 2);
+        assert!(verified.faces.iter().all(|f| f.glyphs_compared == 95 && matches!(f.against, ComparedAgainst::Installed { .. })));
+
+        // The bold substitute is compared with the bold original, not the regular one
+        let mut wide_bold = bold.clone();
+        wide_bold.insert('M', bold[&'M'] + 12.0);
+        let widths = loader(vec![
+            (orig.clone(), regular.clone()), (orig_bold.clone(), bold.clone()),
+            (sub.clone(), regular.clone()), (sub_bold.clone(), wide_bold),
+        ]);
+        let mismatch = verify_pair_with(&pair, &fonts, widths);
+        assert_eq!(mismatch.status, VerificationStatus::Mismatch);
+        assert!(mismatch.message.contains("by 12.0/1000 em at 'M'"), "{}", mismatch.message);
+
+        // Matching widths over too few glyphs prove nothing
+        let few: HashMap<char, f32> = regular.iter().take(MIN_SHARED_GLYPHS - 1).map(|(c, w)| (*c, *w)).collect();
+        let widths = loader(vec![(orig.clone(), regular.clone()), (sub.clone(), few)]);
+        let sparse = verify_pair_with(&pair, &[orig.clone(), sub.clone()], widths);
+        assert_eq!(sparse.status, VerificationStatus::Mismatch);
+        assert!(sparse.message.contains(&format!("share only {} glyphs", MIN_SHARED_GLYPHS - 1)));
+    }
+
+    #[test]
+    fn test_verify_against_reference_widths() {
+        let pair = SubstitutePair::new("Helvetica", "Arimo");
+        let (arimo, arimo_italic) = (face("Arimo", 400, false), face("Arimo", 400, true));
+        let helvetica = reference_widths(&pair).unwrap();
+        let fonts = [arimo_italic.clone(), arimo.clone()];
+
+        let widths = loader(vec![(arimo.clone(), helvetica.clone())]);
+        let verified = verify_pair_with(&pair, &fonts, widths);
+        assert_eq!(verified.status, VerificationStatus::Verified, "{}", verified.message);
+        assert_eq!(verified.faces.len(), 1);
+        assert_eq!(verified.faces[0].substitute_path, arimo.path);
+        assert!(matches!(&verified.faces[0].against, ComparedAgainst::Reference { family } if family == "Helvetica"));
+
+        let times = reference_widths(&SubstitutePair::new("Times", "Tinos")).unwrap();
+        let widths = loader(vec![(arimo.clone(), times)]);
+        assert_eq!(verify_pair_with(&pair, &fonts, widths).status, VerificationStatus::Mismatch);
+    }
+
+    #[test]
+    fn test_verify_unavailable() {
+        let widths = || loader(Vec::new());
+        let pair = SubstitutePair::new("Futura", "Jost");
+
+        let missing_substitute = verify_pair_with(&pair, &[face("Futura", 400, false)], widths());
+        assert_eq!(missing_substitute.status, VerificationStatus::Unavailable);
+        assert_eq!(missing_substitute.message, "Jost is not installed");
+
+        let no_reference = verify_pair_with(&pair, &[face("Jost", 400, false)], widths());
+        assert_eq!(no_reference.status, VerificationStatus::Unavailable);
+        assert!(no_reference.message.contains("no reference metrics"));
+
+        let unreadable = verify_pair_with(&pair, &[face("Futura", 400, false), face("Jost", 400, false)], widths());
+        assert_eq!(unreadable.status, VerificationStatus::Unavailable);
+        assert!(unreadable.message.starts_with("Could not read advance widths"));
+        assert!(unreadable.faces.is_empty());
+    }
+
+    #[test]
+    fn test_load_verifies_every_pair() {
+        let fonts = vec![face("Helvetica", 400, false)];
+        let registry = SubstituteRegistry::load(&fonts);
+        assert!(registry.is_verified_against(&fonts));
+        assert_eq!(registry.results().len(), registry.pairs().len());
+        assert!(registry.results().iter().any(|r| r.pair.is_pair("Helvetica", "Arial")));
+        // Nothing here can be compared, so nothing is verified and nothing failed
+        assert!(registry.results().iter().all(|r| r.status == VerificationStatus::Unavailable));
+        assert_eq!(registry.failures().count(), 0);
+    }
+
+    #[test]
+    fn test_verification_tracks_the_font_set() {
+        let fonts = vec![face("Arial", 400, false), face("Arial", 700, false)];
+        let mut registry = SubstituteRegistry::default();
+        registry.add_pair(SubstitutePair::new("Helvetica", "Arial"));
+        assert!(!registry.is_verified_against(&fonts));
+
+        registry.verify(&fonts);
+        assert!(registry.is_verified_against(&fonts));
+        assert!(registry.is_verified_against(&[fonts[1].clone(), fonts[0].clone()]));
+        assert!(!registry.is_verified_against(&fonts[..1]));
+
+        let mut moved = fonts.clone();
+        moved[0].path = PathBuf::from("/other/Arial.ttf");
+        assert!(!registry.is_verified_against(&moved));
+
+        registry.add_pair(SubstitutePair::new("Courier", "Courier New"));
+        assert!(!registry.is_verified_against(&fonts));
+    }
+}
@@ -3,11 +3,17 @@ use std::path::{Path, PathBuf};
 use font_core::FontResult;
 use font_compressor::{CompressedFontDatabase, FontCompressor};
 use font_acquisition::FontAcquisitionManager;
+use font_license::{FontKnowledgeBase, KNOWLEDGE_FILE};
 use serde::{Deserialize, Serialize};
 use indicatif::{ProgressBar, ProgressStyle};
 use sha2::{Sha256, Digest};
 use futures_util::StreamExt;
 
+/// Where the update manifest listing the current database and knowledge base is published
+pub const UPDATE_MANIFEST_URL: &str = "https://updates.font-resolver.com/update_manifest.json";
+/// Where the curated license knowledge base is published
+pub const KNOWLEDGE_BASE_URL: &str = "https://updates.font-resolver.com/knowledge/font_knowledge.json";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateManifest {
     pub version: String,
@@ -18,6 +24,25 @@ pub struct UpdateManifest {
     pub checksum: String,
     pub incremental_from: Option<String>,
     pub changes: UpdateChanges,
+    /// License knowledge base published alongside the database
+    #[serde(default)]
+    pub knowledge_base: Option<ManifestItem>,
+}
+
+/// A separately versioned file listed in the update manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestItem {
+    pub version: String,
+    pub url: String,
+    /// SHA-256 of the file
+    pub checksum: String,
+}
+
+/// What `perform_update` installed
+#[derive(Debug, Clone, Default)]
+pub struct UpdateReport {
+    pub database_version: Option<String>,
+    pub knowledge_base_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +55,7 @@ pub struct UpdateChanges {
 
 pub struct FontUpdater {
     base_path: PathBuf,
+    manifest_url: String,
     acquisition_manager: FontAcquisitionManager,
     compression_quality: u32,
 }
@@ -38,15 +64,71 @@ impl FontUpdater {
     pub fn new(base_path: PathBuf) -> Self {
         Self {
             base_path,
+            manifest_url: UPDATE_MANIFEST_URL.to_string(),
             acquisition_manager: FontAcquisitionManager::new(),
             compression_quality: 11,
         }
     }
     
-    pub async fn check_for_updates(&self, _current_version: &str) -> FontResult<Option<UpdateManifest>> {
-        // Simulating update check - in real implementation, fetch from server
-        // For now, return None to indicate no updates
-        Ok(None)
+    /// Read the update manifest from a URL or a local file instead of the official one
+    pub fn with_manifest_url(mut self, url: impl Into<String>) -> Self {
+        self.manifest_url = url.into();
+        self
+    }
+    
+    /// Fetch the update manifest and return it if the database or the knowledge base it
+    /// lists is newer than the installed one. Items that are up to date are cleared.
+    pub async fn check_for_updates(&self) -> FontResult<Option<UpdateManifest>> {
+        let data = fetch(&self.manifest_url).await?;
+        let mut manifest: UpdateManifest = serde_json::from_slice(&data)
+            .map_err(|e| font_core::FontError::Parse(format!("Invalid update manifest: {}", e)))?;
+        
+        let installed_knowledge = self.installed_knowledge_base().version;
+        manifest.knowledge_base = manifest.knowledge_base
+            .filter(|item| is_newer_version(&item.version, &installed_knowledge));
+        
+        let database_pending = self.database_update_pending(&manifest).await;
+        if database_pending || manifest.knowledge_base.is_some() {
+            Ok(Some(manifest))
+        } else {
+            Ok(None)
+        }
+    }
+    
+    /// Install everything in a manifest from `check_for_updates` that is newer than what
+    /// is installed: the knowledge base first, then the font database.
+    pub async fn perform_update(&self, manifest: &UpdateManifest) -> FontResult<UpdateReport> {
+        let mut report = UpdateReport::default();
+        
+        if let Some(item) = &manifest.knowledge_base {
+            println!("📥 Fetching license knowledge base v{}...", item.version);
+            let data = fetch(&item.url).await?;
+            let checksum = self.calculate_checksum(&data);
+            if checksum != item.checksum {
+                return Err(font_core::FontError::Parse(
+                    format!("Checksum verification failed: expected {}, got {}", item.checksum, checksum)
+                ));
+            }
+            let json = String::from_utf8(data)
+                .map_err(|e| font_core::FontError::Parse(format!("Invalid font knowledge base: {}", e)))?;
+            report.knowledge_base_version = self.install_knowledge_base(&json)?;
+        }
+        
+        if self.database_update_pending(manifest).await {
+            let update_path = self.download_incremental_update(manifest, None).await?;
+            let current_db = self.load_current_database().await?;
+            let updated = self.apply_incremental_update(&update_path, &current_db).await?;
+            report.database_version = Some(updated.metadata.version);
+        }
+        
+        Ok(report)
+    }
+    
+    async fn database_update_pending(&self, manifest: &UpdateManifest) -> bool {
+        match self.load_current_database().await {
+            Ok(current) => is_newer_version(&manifest.version, &current.metadata.version),
+            Err(_) => true,
+        }
     }
     
     pub async fn download_incremental_update(
@@ -147,14 +229,17 @@ impl FontUpdater {
             .map(|f| self.compressed_to_font(f))
             .collect();
         
-        let compressed = compressor.compress_font_database(
+        // Extend the current neighbour index rather than rebuilding it
+        let mut neighbour_index = merged.neighbour_index.clone().unwrap_or_default();
+        let compressed = compressor.compress_font_database_with_index(
             &fonts_for_compression,
-            true,
+            &mut neighbour_index,
         ).map_err(|e| font_core::FontError::Parse(e.to_string()))?;
         
         // Update metadata with correct compressed size
         let mut merged_with_size = merged;
         merged_with_size.metadata.compressed_size_bytes = compressed.len();
+        merged_with_size.neighbour_index = Some(neighbour_index);
         
         // Save new database
         let new_path = self.base_path.join("fonts_latest.bin");
@@ -222,14 +307,16 @@ impl FontUpdater {
             .map(|f| self.compressed_to_font(f))
             .collect();
         
-        let compressed = compressor.compress_font_database(
+        let mut neighbour_index = font_index::FontIndex::new();
+        let compressed = compressor.compress_font_database_with_index(
             &fonts_for_compression,
-            true,
+            &mut neighbour_index,
         ).map_err(|e| font_core::FontError::Parse(e.to_string()))?;
         
         // Create database with correct metadata
         let database = CompressedFontDatabase {
             metadata: font_compressor::FontDatabaseMetadata {
+                format_version: font_compressor::FORMAT_VERSION,
                 version: env!("CARGO_PKG_VERSION").to_string(),
                 font_count: all_fonts.len(),
                 compressed_size_bytes: compressed.len(),
@@ -243,7 +330,7 @@ impl FontUpdater {
                 include_full_data: true,
             },
             fonts: all_fonts,
-            similarity_matrix: None,
+            neighbour_index: Some(neighbour_index),
         };
         
         println!("✅ Updated database with {} fonts ({:.2}MB compressed)", 
@@ -275,16 +362,14 @@ impl FontUpdater {
         match choice {
             1 => {
                 println!("Checking for updates...");
-                if let Some(manifest) = self.check_for_updates("0.1.0").await? {
+                if let Some(manifest) = self.check_for_updates().await? {
                     println!("Found update v{} with {} fonts", 
                         manifest.version, manifest.font_count);
                     
-                    let update_path = self.download_incremental_update(&manifest, None).await?;
-                    println!("✅ Update downloaded successfully");
-                    
-                    // Load current database and apply update
-                    let current_db = self.load_current_database().await?;
-                    self.apply_incremental_update(&update_path, &current_db).await?;
+                    let report = self.perform_update(&manifest).await?;
+                    if let Some(version) = report.database_version {
+                        println!("✅ Font database updated to v{}", version);
+                    }
                 } else {
                     println!("✅ Already up to date");
                 }
@@ -299,6 +384,50 @@ impl FontUpdater {
         Ok(())
     }
     
+    /// Download the license knowledge base and install it if it is newer than the one in use.
+    /// Returns the installed version.
+    pub async fn update_knowledge_base(&self, url: Option<&str>) -> FontResult<Option<String>> {
+        let url = url.unwrap_or(KNOWLEDGE_BASE_URL);
+        println!("📥 Fetching license knowledge base from {}...", url);
+        
+        let data = fetch(url).await?;
+        let json = String::from_utf8(data)
+            .map_err(|e| font_core::FontError::Parse(format!("Invalid font knowledge base: {}", e)))?;
+        
+        self.install_knowledge_base(&json)
+    }
+    
+    /// Validate a knowledge base and save it next to the font database if it is newer
+    /// than the installed or embedded one. Returns the installed version.
+    pub fn install_knowledge_base(&self, json: &str) -> FontResult<Option<String>> {
+        let candidate = FontKnowledgeBase::from_json(json)?;
+        let current = self.installed_knowledge_base();
+        
+        if !candidate.is_newer_than(&current) {
+            println!("✅ License knowledge base v{} is up to date", current.version);
+            return Ok(None);
+        }
+        
+        std::fs::create_dir_all(&self.base_path)?;
+        std::fs::write(self.knowledge_base_path(), json)?;
+        println!("✅ Installed license knowledge base v{} ({} families, {} foundries)",
+            candidate.version, candidate.families.len(), candidate.foundries.len());
+        
+        Ok(Some(candidate.version))
+    }
+    
+    /// The installed knowledge base if it is newer than the embedded one, else the embedded one
+    fn installed_knowledge_base(&self) -> FontKnowledgeBase {
+        let embedded = FontKnowledgeBase::embedded();
+        FontKnowledgeBase::load_from_file(self.knowledge_base_path()).ok()
+            .filter(|installed| installed.is_newer_than(&embedded))
+            .unwrap_or(embedded)
+    }
+    
+    fn knowledge_base_path(&self) -> PathBuf {
+        self.base_path.join(KNOWLEDGE_FILE)
+    }
+    
     fn calculate_checksum(&self, data: &[u8]) -> String {
         let mut hasher = Sha256::new();
         hasher.update(data);
@@ -337,6 +466,7 @@ impl FontUpdater {
         
         Ok(CompressedFontDatabase {
             metadata: font_compressor::FontDatabaseMetadata {
+                format_version: font_compressor::FORMAT_VERSION,
                 version: update.metadata.version.clone(),
                 font_count: merged_fonts.len(),
                 compressed_size_bytes: 0, // Will be set after compression
@@ -350,7 +480,7 @@ impl FontUpdater {
                 include_full_data: current.metadata.include_full_data || update.metadata.include_full_data,
             },
             fonts: merged_fonts,
-            similarity_matrix: None, // Will be regenerated
+            neighbour_index: current.neighbour_index.clone(), // Extended when recompressed
         })
     }
     
@@ -368,6 +498,7 @@ impl FontUpdater {
             // Return empty database
             Ok(CompressedFontDatabase {
                 metadata: font_compressor::FontDatabaseMetadata {
+                    format_version: font_compressor::FORMAT_VERSION,
                     version: "0.1.0".to_string(),
                     font_count: 0,
                     compressed_size_bytes: 0,
@@ -377,7 +508,7 @@ impl FontUpdater {
                     include_full_data: true,
                 },
                 fonts: Vec::new(),
-                similarity_matrix: None,
+                neighbour_index: None,
             })
         }
     }
@@ -410,7 +541,119 @@ impl FontUpdater {
                 allows_modification: compressed.license.allows_modification,
                 requires_attribution: compressed.license.requires_attribution,
                 allows_commercial_use: compressed.license.allows_commercial_use,
+                embedding: None,
+                spdx: None,
+                reserved_font_names: Vec::new(),
             }),
+            classification: compressed.classification,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Read an http(s) URL or a local file
+async fn fetch(source: &str) -> FontResult<Vec<u8>> {
+    if !(source.starts_with("http://") || source.starts_with("https://")) {
+        return Ok(tokio::fs::read(source).await?);
+    }
+    
+    let response = reqwest::get(source).await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| font_core::FontError::Parse(format!("Download failed: {}", e)))?;
+    let body = response.bytes().await
+        .map_err(|e| font_core::FontError::Parse(format!("Download failed: {}", e)))?;
+    Ok(body.to_vec())
+}
+
+/// Compare dotted versions numerically ("2026.10.1" > "2026.9.3")
+fn is_newer_version(candidate: &str, current: &str) -> bool {
+    fn parts(version: &str) -> Vec<u64> {
+        version.split('.').map(|part| part.trim().parse().unwrap_or(0)).collect()
+    }
+    parts(candidate) > parts(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn newer_knowledge_base() -> String {
+        let mut knowledge = FontKnowledgeBase::embedded();
+        knowledge.version = "9999.1.0".to_string();
+        serde_json::to_string(&knowledge).unwrap()
+    }
+    
+    fn write_manifest(dir: &Path, knowledge: &str, checksum: String) -> PathBuf {
+        let knowledge_path = dir.join("published_knowledge.json");
+        std::fs::write(&knowledge_path, knowledge).unwrap();
+        
+        let manifest = UpdateManifest {
+            version: "0.1.0".to_string(),
+            font_count: 0,
+            total_size_bytes: 0,
+            compressed_size_bytes: 0,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            checksum: String::new(),
+            incremental_from: None,
+            changes: UpdateChanges {
+                added_fonts: Vec::new(),
+                removed_fonts: Vec::new(),
+                updated_fonts: Vec::new(),
+                security_fixes: Vec::new(),
+            },
+            knowledge_base: Some(ManifestItem {
+                version: "9999.1.0".to_string(),
+                url: knowledge_path.to_string_lossy().into_owned(),
+                checksum,
+            }),
+        };
+        let manifest_path = dir.join("update_manifest.json");
+        std::fs::write(&manifest_path, serde_json::to_string(&manifest).unwrap()).unwrap();
+        manifest_path
+    }
+    
+    #[test]
+    fn test_shipped_manifest_lists_knowledge_base() {
+        let manifest: UpdateManifest = serde_json::from_str(
+            include_str!("../../../data/update_manifest.json")
+        ).unwrap();
+        let item = manifest.knowledge_base.expect("knowledge base item");
+        assert_eq!(item.version, FontKnowledgeBase::embedded().version);
+    }
+    
+    #[tokio::test]
+    async fn test_knowledge_base_is_updated_through_the_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let knowledge = newer_knowledge_base();
+        let checksum = format!("{:x}", Sha256::digest(knowledge.as_bytes()));
+        let manifest_path = write_manifest(dir.path(), &knowledge, checksum);
+        
+        let updater = FontUpdater::new(dir.path().join("data"))
+            .with_manifest_url(manifest_path.to_string_lossy());
+        
+        let manifest = updater.check_for_updates().await.unwrap().expect("pending knowledge base");
+        assert_eq!(manifest.knowledge_base.as_ref().unwrap().version, "9999.1.0");
+        
+        let report = updater.perform_update(&manifest).await.unwrap();
+        assert_eq!(report.knowledge_base_version.as_deref(), Some("9999.1.0"));
+        assert_eq!(report.database_version, None);
+        
+        let installed = FontKnowledgeBase::load_from_file(dir.path().join("data").join(KNOWLEDGE_FILE)).unwrap();
+        assert_eq!(installed.version, "9999.1.0");
+        
+        // Nothing is pending once the listed version is installed
+        assert!(updater.check_for_updates().await.unwrap().is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_knowledge_base_checksum_is_verified() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = write_manifest(dir.path(), &newer_knowledge_base(), "0".repeat(64));
+        
+        let updater = FontUpdater::new(dir.path().join("data"))
+            .with_manifest_url(manifest_path.to_string_lossy());
+        
+        let manifest = updater.check_for_updates().await.unwrap().unwrap();
+        assert!(updater.perform_update(&manifest).await.is_err());
+        assert!(!dir.path().join("data").join(KNOWLEDGE_FILE).exists());
+    }
+}
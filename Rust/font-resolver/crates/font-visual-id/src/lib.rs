@@ -6,15 +6,17 @@
 //! - `VisualIdentifier` - Main engine for font identification
 //! - Sub-millisecond lookups using LSH acceleration
 //! - Multi-character matching for higher accuracy
+//! - Matching of glyphs whose characters are unknown
 
 use std::path::Path;
 use serde::{Serialize, Deserialize};
 use font_glyph::{GlyphExtractor, MicroSignature};
-use font_glyph_db::{GlyphDatabase, GlyphDatabaseBuilder, load_database, load_database_from_file, DatabaseStats};
+use font_core::ScoringProfile;
+use font_glyph_db::{GlyphDatabase, GlyphDatabaseBuilder, load_database, load_database_from_file, DatabaseStats, Script};
 
 // Re-export key types for convenience
-pub use font_glyph::{MicroSignature as Signature, GlyphOutline};
-pub use font_glyph_db::MatchResult;
+pub use font_glyph::{MicroSignature as Signature, GlyphDescriptor, GlyphOutline};
+pub use font_glyph_db::{MatchResult, UnlabeledGlyph};
 
 // =============================================================================
 // IDENTIFICATION RESULT
@@ -33,6 +35,9 @@ pub struct IdentificationResult {
     pub matched_chars: Vec<char>,
     /// Source of the match ("Database" or "Local")
     pub source: String,
+    /// For unlabeled glyphs, the character inferred for each one in query order
+    #[serde(default)]
+    pub inferred_chars: Vec<Option<char>>,
 }
 
 impl IdentificationResult {
@@ -44,6 +49,7 @@ impl IdentificationResult {
             confidence: result.similarity,
             matched_chars: chars,
             source: "Database".to_string(),
+            inferred_chars: result.inferred_chars.clone(),
         }
     }
 }
@@ -72,6 +78,12 @@ impl VisualIdentifier {
         }
     }
     
+    /// Score matches with the given scoring profile's glyph weights
+    pub fn with_profile(mut self, profile: &ScoringProfile) -> Self {
+        self.database.set_profile(profile);
+        self
+    }
+    
     /// Load a visual identifier from compressed database bytes
     pub fn from_bytes(data: &[u8]) -> Result<Self, IdentificationError> {
         let database = load_database(data)
@@ -106,9 +118,10 @@ impl VisualIdentifier {
             .map_err(|e| IdentificationError::ExtractionError(e.to_string()))?;
         
         let signature = MicroSignature::from_outline(&outline);
+        let descriptor = GlyphDescriptor::from_outline(&outline);
         
         // Find matches
-        let matches = self.database.find_matches(&signature, limit);
+        let matches = self.database.find_matches_reranked(&signature, &descriptor, Script::of(character), limit);
         
         Ok(matches.iter()
             .map(|m| IdentificationResult::from_match_result(m, vec![character]))
@@ -130,18 +143,24 @@ impl VisualIdentifier {
         characters: &str,
         limit: usize,
     ) -> Result<Vec<IdentificationResult>, IdentificationError> {
-        // Extract signatures for all characters
-        let signatures = self.extractor.extract_signatures(&font_path, characters)
+        // Extract outlines for all characters
+        let outlines = self.extractor.extract_outlines(&font_path, characters)
             .map_err(|e| IdentificationError::ExtractionError(e.to_string()))?;
         
-        if signatures.is_empty() {
+        if outlines.is_empty() {
             return Ok(Vec::new());
         }
         
-        let chars: Vec<char> = signatures.iter().map(|(c, _)| *c).collect();
+        let chars: Vec<char> = outlines.iter().map(|outline| outline.character).collect();
+        let signatures: Vec<(char, MicroSignature)> = outlines.iter()
+            .map(|outline| (outline.character, MicroSignature::from_outline(outline)))
+            .collect();
+        let descriptors: Vec<(char, GlyphDescriptor)> = outlines.iter()
+            .map(|outline| (outline.character, GlyphDescriptor::from_outline(outline)))
+            .collect();
         
-        // Find matches using multiple signatures
-        let matches = self.database.find_matches_multi(&signatures, limit);
+        // Find matches using multiple signatures, re-ranked by descriptor
+        let matches = self.database.find_matches_multi_reranked(&signatures, &descriptors, limit);
         
         Ok(matches.iter()
             .map(|m| IdentificationResult::from_match_result(m, chars.clone()))
@@ -175,11 +194,42 @@ impl VisualIdentifier {
             .collect()
     }
     
+    /// Identify a font from glyphs whose characters are unknown (e.g. cut out of an
+    /// image), inferring which character each glyph is
+    /// 
+    /// `matched_chars` lists the inferred characters and `inferred_chars` gives the
+    /// one for each glyph. Pass the glyphs' `script` when known, so that only fonts
+    /// and characters of that script are considered.
+    pub fn identify_unlabeled(
+        &self,
+        glyphs: &[UnlabeledGlyph],
+        script: Option<Script>,
+        limit: usize,
+    ) -> Vec<IdentificationResult> {
+        let matches = self.database.find_matches_unlabeled(glyphs, script, limit);
+        matches.iter()
+            .map(|m| IdentificationResult::from_match_result(m, m.inferred_chars.iter().flatten().copied().collect()))
+            .collect()
+    }
+    
+    /// Extract glyphs for `characters` from a font file and drop their labels, e.g.
+    /// to check how well `identify_unlabeled` reads them back
+    pub fn extract_unlabeled<P: AsRef<Path>>(
+        &self,
+        font_path: P,
+        characters: &str,
+    ) -> Result<Vec<UnlabeledGlyph>, IdentificationError> {
+        let outlines = self.extractor.extract_outlines(&font_path, characters)
+            .map_err(|e| IdentificationError::ExtractionError(e.to_string()))?;
+        Ok(outlines.iter().map(UnlabeledGlyph::from_outline).collect())
+    }
+    
     /// Get database statistics
     pub fn database_stats(&self) -> DatabaseInfo {
         DatabaseInfo {
             font_count: self.database.fonts.len(),
             char_count: self.database.header.char_count as usize,
+            scripts: self.database.fonts_per_script(),
             lsh_stats: self.database.lsh_index.stats(),
         }
     }
@@ -216,6 +266,8 @@ impl VisualIdentifier {
 pub struct DatabaseInfo {
     pub font_count: usize,
     pub char_count: usize,
+    /// Fonts indexed for each script
+    pub scripts: Vec<(Script, usize)>,
     pub lsh_stats: font_glyph_db::LshIndexStats,
 }
 
@@ -223,7 +275,10 @@ impl std::fmt::Display for DatabaseInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Database Info:\n")?;
         write!(f, "  Fonts indexed: {}\n", self.font_count)?;
-        write!(f, "  Characters per font: {}\n", self.char_count)?;
+        write!(f, "  Characters indexed: {}\n", self.char_count)?;
+        for (script, fonts) in &self.scripts {
+            writeln!(f, "  {} fonts: {}", script, fonts)?;
+        }
         write!(f, "  LSH non-empty buckets: {}", self.lsh_stats.non_empty_buckets)
     }
 }
@@ -250,8 +305,8 @@ pub fn build_database<P: AsRef<Path>>(
     
     Ok(DatabaseStats {
         font_count: db.fonts.len(),
-        char_count: 62,
-        uncompressed_size: db.fonts.len() * 62 * 16,
+        char_count: builder.char_count(),
+        uncompressed_size: db.fonts.iter().map(|font| font.signatures.len() * 16).sum(),
         compressed_size: 0, // Not compressed yet
         compression_ratio: 0.0,
         build_time_seconds: 0.0,
@@ -318,6 +373,7 @@ mod tests {
             confidence: 0.95,
             matched_chars: vec!['R', 'A'],
             source: "Database".to_string(),
+            inferred_chars: Vec::new(),
         };
         
         assert_eq!(result.family, "Arial");
@@ -329,6 +385,7 @@ mod tests {
         let info = DatabaseInfo {
             font_count: 1000,
             char_count: 62,
+            scripts: vec![(Script::Latin, 1000)],
             lsh_stats: font_glyph_db::LshIndexStats {
                 table_count: 8,
                 bucket_count: 256,
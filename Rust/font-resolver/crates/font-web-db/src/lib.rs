@@ -1,7 +1,43 @@
-use font_core::{FontDescriptor, FontFormat, FontMetrics, LicenseInfo};
+use font_core::{FontClassification, FontDescriptor, FontError, FontFormat, FontMetrics, FontResult, LicenseInfo, ShapeClassification, TypefaceStyle};
+use font_glyph::{GlyphExtractor, MicroSignature};
+use font_parser::FontParser;
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::path::Path;
 
+/// Characters sampled for the per-family glyph signatures stored in the database
+pub const SIGNATURE_CHARS: &str = "AEGHMNOQRSWabegnopst";
+
+/// Default on-disk location of a built web font database
+pub const DEFAULT_DB_PATH: &str = "data/web_fonts.bin";
+
+/// Magic bytes at the start of versioned web font databases
+pub const MAGIC_BYTES: &[u8; 8] = b"WEBFNDB1";
+
+/// Current database layout version; unversioned files are version 1
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Representative characters used to detect subset coverage from a font's cmap
+const SUBSET_PROBES: &[(&str, char)] = &[
+    ("latin", 'A'),
+    ("latin-ext", '\u{0100}'),
+    ("vietnamese", '\u{1EA1}'),
+    ("greek", '\u{03BB}'),
+    ("cyrillic", '\u{0416}'),
+    ("arabic", '\u{0628}'),
+    ("hebrew", '\u{05D0}'),
+    ("devanagari", '\u{0915}'),
+    ("thai", '\u{0E01}'),
+];
+
+/// Default page size for `WebFontDatabase::search`
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// A web font family as stored in the database
+///
+/// bincode ignores `#[serde(default)]`, which only helps JSON readers: any change
+/// to the field list needs a `FORMAT_VERSION` bump and a legacy layout to upgrade.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebFont {
     pub family: String,
@@ -11,6 +47,122 @@ pub struct WebFont {
     pub last_updated: String,
     pub license: WebFontLicense,
     pub similar_fonts: Vec<String>,
+    /// Supported character subsets (e.g. "latin", "cyrillic")
+    #[serde(default)]
+    pub subsets: Vec<String>,
+    /// Variable font axis tags (e.g. "wght", "wdth"); empty for static families
+    #[serde(default)]
+    pub axes: Vec<String>,
+    /// Metrics measured from the font file when the database was built
+    #[serde(default)]
+    pub metrics: Option<FontMetrics>,
+    /// Glyph signatures for `SIGNATURE_CHARS`, measured when the database was built
+    #[serde(default)]
+    pub signatures: Vec<(char, MicroSignature)>,
+    /// PANOSE, sFamilyClass and outline-based style, measured when the database was built
+    #[serde(default)]
+    pub classification: Option<FontClassification>,
+}
+
+impl WebFont {
+    /// Measure real metrics and glyph signatures from a downloaded font file
+    pub fn measure_from_file<P: AsRef<Path>>(&mut self, path: P) -> FontResult<()> {
+        let path = path.as_ref();
+        let descriptor = FontParser.parse_classified_font_file(path)?;
+        
+        let signatures = GlyphExtractor::new()
+            .extract_signatures(path, SIGNATURE_CHARS)
+            .map_err(|e| FontError::Parse(e.to_string()))?;
+        
+        let font_data = std::fs::read(path).map_err(FontError::Io)?;
+        let face = ttf_parser::Face::parse(&font_data, 0)
+            .map_err(|e| FontError::Parse(format!("Failed to parse font: {}", e)))?;
+        
+        for (subset, probe) in SUBSET_PROBES {
+            if face.glyph_index(*probe).is_some() && !self.subsets.iter().any(|s| s == subset) {
+                self.subsets.push(subset.to_string());
+            }
+        }
+        for axis in face.variation_axes() {
+            let tag = axis.tag.to_string();
+            if !self.axes.contains(&tag) {
+                self.axes.push(tag);
+            }
+        }
+        
+        // Providers without a category of their own get the one measured from the outlines
+        if self.category == FontCategory::Other {
+            if let Some(category) = descriptor.classification.as_ref()
+                .and_then(|c| c.shape.as_ref())
+                .and_then(FontCategory::from_shape)
+            {
+                self.category = category;
+            }
+        }
+        
+        self.metrics = descriptor.metrics;
+        self.signatures = signatures;
+        self.classification = descriptor.classification;
+        Ok(())
+    }
+    
+    /// Smallest file size among the variants, in KB
+    pub fn min_file_size_kb(&self) -> Option<u32> {
+        self.variants.iter().map(|v| v.file_size_kb).min()
+    }
+    
+    /// Whether build-time measurements are available for this font
+    pub fn is_measured(&self) -> bool {
+        self.metrics.is_some() || !self.signatures.is_empty()
+    }
+    
+    /// Metric similarity to another font (0.0 - 1.0), if both were measured
+    pub fn metric_similarity(&self, other: &WebFont) -> Option<f32> {
+        match (&self.metrics, &other.metrics) {
+            (Some(a), Some(b)) => Some(metric_similarity(a, b)),
+            _ => None,
+        }
+    }
+    
+    /// Average glyph signature similarity over the characters both fonts share
+    pub fn visual_similarity(&self, other: &WebFont) -> Option<f32> {
+        let mut total = 0.0;
+        let mut count = 0;
+        
+        for (ch, sig) in &self.signatures {
+            if let Some((_, other_sig)) = other.signatures.iter().find(|(c, _)| c == ch) {
+                total += sig.similarity(other_sig);
+                count += 1;
+            }
+        }
+        
+        if count > 0 {
+            Some(total / count as f32)
+        } else {
+            None
+        }
+    }
+}
+
+/// Compare two sets of metrics, normalized by units per em (0.0 - 1.0)
+pub fn metric_similarity(a: &FontMetrics, b: &FontMetrics) -> f32 {
+    let upm_a = a.units_per_em.max(1) as f32;
+    let upm_b = b.units_per_em.max(1) as f32;
+    
+    let pairs = [
+        (a.x_height as f32, b.x_height as f32),
+        (a.cap_height as f32, b.cap_height as f32),
+        (a.average_width as f32, b.average_width as f32),
+        (a.ascender as f32, b.ascender as f32),
+        (a.descender as f32, b.descender as f32),
+    ];
+    
+    // A difference of a quarter em or more in any metric counts as no similarity
+    let total: f32 = pairs.iter()
+        .map(|(x, y)| 1.0 - ((x / upm_a - y / upm_b).abs() * 4.0).min(1.0))
+        .sum();
+    
+    total / pairs.len() as f32
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,7 +175,7 @@ pub struct WebFontVariant {
     pub file_size_kb: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum FontCategory {
     Serif,
     SansSerif,
@@ -33,6 +185,249 @@ pub enum FontCategory {
     Other,
 }
 
+impl FontCategory {
+    /// Parse a category name as used by Google Fonts ("sans-serif", "monospace", ...)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace(['_', ' '], "-").as_str() {
+            "serif" => Some(FontCategory::Serif),
+            "sans-serif" | "sans" | "sansserif" => Some(FontCategory::SansSerif),
+            "monospace" | "mono" => Some(FontCategory::Monospace),
+            "display" => Some(FontCategory::Display),
+            "handwriting" | "script" => Some(FontCategory::Handwriting),
+            "other" => Some(FontCategory::Other),
+            _ => None,
+        }
+    }
+    
+    /// Name accepted by `from_name`, as used by Google Fonts
+    pub fn name(&self) -> &'static str {
+        match self {
+            FontCategory::Serif => "serif",
+            FontCategory::SansSerif => "sans-serif",
+            FontCategory::Monospace => "monospace",
+            FontCategory::Display => "display",
+            FontCategory::Handwriting => "handwriting",
+            FontCategory::Other => "other",
+        }
+    }
+    
+    /// Category for a confident outline-based style label
+    pub fn from_shape(shape: &ShapeClassification) -> Option<Self> {
+        if shape.monospace >= ShapeClassification::CONFIDENT {
+            return Some(FontCategory::Monospace);
+        }
+        
+        let (style, confidence) = shape.primary();
+        if confidence < ShapeClassification::CONFIDENT {
+            return None;
+        }
+        match style {
+            TypefaceStyle::Serif | TypefaceStyle::SlabSerif => Some(FontCategory::Serif),
+            TypefaceStyle::Script => Some(FontCategory::Handwriting),
+            TypefaceStyle::Blackletter => Some(FontCategory::Display),
+            _ => Some(FontCategory::SansSerif),
+        }
+    }
+}
+
+/// Ordering applied to search results
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SearchSort {
+    /// Name match quality, then popularity
+    #[default]
+    Relevance,
+    /// Most popular first
+    Popularity,
+    /// Alphabetical by family
+    Name,
+}
+
+/// Faceted query over the web font database
+///
+/// Every list filter requires all of its values (e.g. `weights: [400, 700]`
+/// only matches families offering both). Empty lists and `None` match anything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WebFontQuery {
+    /// Fuzzy family name text
+    pub text: Option<String>,
+    /// Accept any of these categories
+    pub categories: Vec<FontCategory>,
+    /// Required subsets (e.g. "latin-ext", "cyrillic")
+    pub subsets: Vec<String>,
+    /// Required weights
+    pub weights: Vec<u16>,
+    /// Required variable axes (e.g. "wght")
+    pub axes: Vec<String>,
+    /// Case-insensitive substring of the license name (e.g. "OFL", "Apache")
+    pub license: Option<String>,
+    /// Only fonts licensed for commercial use
+    pub commercial_use: bool,
+    pub min_popularity: Option<u8>,
+    /// Maximum size of the smallest variant
+    pub max_file_size_kb: Option<u32>,
+    pub sort: SearchSort,
+    pub offset: usize,
+    /// Page size; 0 means `DEFAULT_SEARCH_LIMIT`
+    pub limit: usize,
+}
+
+/// A single ranked search result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebFontHit {
+    pub font: WebFont,
+    /// Name match quality (1.0 when no text was given)
+    pub name_score: f32,
+    /// Final ranking score (0.0 - 1.0)
+    pub score: f32,
+}
+
+/// One page of search results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebFontSearchPage {
+    pub hits: Vec<WebFontHit>,
+    /// Number of matches before pagination
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// Trigram index over normalized family names and aliases
+#[derive(Debug, Clone, Default)]
+struct NameIndex {
+    trigrams: HashMap<String, HashSet<String>>,
+    /// Searchable name -> font key
+    names: HashMap<String, String>,
+}
+
+impl NameIndex {
+    fn insert(&mut self, name: &str, key: &str) {
+        let name = normalize_search_text(name);
+        for trigram in trigrams(&name) {
+            self.trigrams.entry(trigram).or_default().insert(name.clone());
+        }
+        self.names.insert(name, key.to_string());
+    }
+    
+    /// Best name score per font key for the given query text
+    fn lookup(&self, text: &str) -> HashMap<String, f32> {
+        let query = normalize_search_text(text);
+        let query_trigrams = trigrams(&query);
+        
+        // Narrow down with the trigram index, falling back to a full scan
+        // for very short or heavily misspelled queries
+        let mut candidates: HashSet<&String> = query_trigrams.iter()
+            .filter_map(|t| self.trigrams.get(t))
+            .flatten()
+            .collect();
+        if candidates.is_empty() {
+            candidates = self.names.keys().collect();
+        }
+        
+        let mut scores: HashMap<String, f32> = HashMap::new();
+        for name in candidates {
+            let score = name_match_score(&query, &query_trigrams, name);
+            if score < 0.3 {
+                continue;
+            }
+            let key = &self.names[name];
+            let best = scores.entry(key.clone()).or_insert(0.0);
+            if score > *best {
+                *best = score;
+            }
+        }
+        scores
+    }
+}
+
+fn normalize_search_text(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn trigrams(text: &str) -> HashSet<String> {
+    let padded: Vec<char> = format!("  {} ", text).chars().collect();
+    padded.windows(3).map(|w| w.iter().collect()).collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    
+    prev[b.len()]
+}
+
+/// Score how well `name` matches the normalized query (0.0 - 1.0)
+fn name_match_score(query: &str, query_trigrams: &HashSet<String>, name: &str) -> f32 {
+    if query.is_empty() {
+        return 0.0;
+    }
+    if name == query {
+        return 1.0;
+    }
+    if name.starts_with(query) {
+        return 0.9;
+    }
+    if name.contains(query) {
+        return 0.8;
+    }
+    
+    let name_trigrams = trigrams(name);
+    let shared = query_trigrams.intersection(&name_trigrams).count() as f32;
+    let dice = 2.0 * shared / (query_trigrams.len() + name_trigrams.len()) as f32;
+    
+    let max_len = query.chars().count().max(name.chars().count()) as f32;
+    let edit = 1.0 - levenshtein(query, name) as f32 / max_len;
+    
+    // Fuzzy matches never outrank substring matches
+    (dice.max(edit) * 0.75).min(0.75)
+}
+
+/// Unversioned layout, written before fonts carried build-time measurements
+#[derive(Deserialize)]
+struct WebFontV1 {
+    family: String,
+    variants: Vec<WebFontVariant>,
+    category: FontCategory,
+    popularity: u8,
+    last_updated: String,
+    license: WebFontLicense,
+    similar_fonts: Vec<String>,
+}
+
+impl From<WebFontV1> for WebFont {
+    fn from(font: WebFontV1) -> Self {
+        Self {
+            family: font.family,
+            variants: font.variants,
+            category: font.category,
+            popularity: font.popularity,
+            last_updated: font.last_updated,
+            license: font.license,
+            similar_fonts: font.similar_fonts,
+            subsets: Vec::new(),
+            axes: Vec::new(),
+            metrics: None,
+            signatures: Vec::new(),
+            classification: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebFontLicense {
     pub name: String,
@@ -47,9 +442,32 @@ pub struct WebFontDatabase {
     fonts: HashMap<String, WebFont>,
     family_aliases: HashMap<String, String>,
     version: String,
+    name_index: NameIndex,
 }
 
 impl WebFontDatabase {
+    /// Create an empty database (used by the web-db build step)
+    pub fn new(version: &str) -> Self {
+        Self::from_parts(HashMap::new(), HashMap::new(), version.to_string())
+    }
+    
+    fn from_parts(fonts: HashMap<String, WebFont>, family_aliases: HashMap<String, String>, version: String) -> Self {
+        let mut name_index = NameIndex::default();
+        for (key, font) in &fonts {
+            name_index.insert(&font.family, key);
+        }
+        for (alias, key) in &family_aliases {
+            name_index.insert(alias, key);
+        }
+        
+        Self {
+            fonts,
+            family_aliases,
+            version,
+            name_index,
+        }
+    }
+    
     /// Load web font database from embedded binary data
     pub fn load_embedded() -> Self {
         // Check if we have embedded data
@@ -59,24 +477,8 @@ impl WebFontDatabase {
             
             // If file exists and has content
             if !compressed_data.is_empty() {
-                match std::io::Cursor::new(compressed_data)
-                    .and_then(|cursor| {
-                        let mut decoder = flate2::read::GzDecoder::new(cursor);
-                        let mut decompressed_data = Vec::new();
-                        std::io::copy(&mut decoder, &mut decompressed_data)?;
-                        Ok(decompressed_data)
-                    })
-                    .and_then(|data| {
-                        bincode::deserialize(&data)
-                            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
-                    }) {
-                    Ok((fonts, aliases, version)) => {
-                        return Self {
-                            fonts,
-                            family_aliases: aliases,
-                            version,
-                        };
-                    }
+                match Self::from_compressed_bytes(compressed_data) {
+                    Ok(db) => return db,
                     Err(e) => {
                         eprintln!("⚠️  Failed to load embedded web fonts: {}", e);
                     }
@@ -88,6 +490,95 @@ impl WebFontDatabase {
         Self::create_minimal_database()
     }
     
+    /// Load a database built by `save_to_file`, falling back to the embedded one
+    pub fn load_default() -> Self {
+        if Path::new(DEFAULT_DB_PATH).exists() {
+            match Self::load_from_file(DEFAULT_DB_PATH) {
+                Ok(db) if db.is_loaded() => return db,
+                Ok(_) => {}
+                Err(e) => eprintln!("⚠️  Failed to load web font database: {}", e),
+            }
+        }
+        
+        Self::load_embedded()
+    }
+    
+    /// Load a gzip-compressed database from disk
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let compressed_data = std::fs::read(path)?;
+        Self::from_compressed_bytes(&compressed_data)
+    }
+    
+    /// Decode a database, upgrading the unversioned layout written before `FORMAT_VERSION` 2
+    fn from_compressed_bytes(compressed_data: &[u8]) -> std::io::Result<Self> {
+        let invalid = |message: String| std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+        let (versioned, compressed_data) = match compressed_data.strip_prefix(MAGIC_BYTES) {
+            Some(rest) => (true, rest),
+            None => (false, compressed_data),
+        };
+        
+        let mut decoder = flate2::read::GzDecoder::new(compressed_data);
+        let mut decompressed_data = Vec::new();
+        decoder.read_to_end(&mut decompressed_data)?;
+        
+        if !versioned {
+            let (fonts, family_aliases, version): (HashMap<String, WebFontV1>, HashMap<String, String>, String) =
+                bincode::deserialize(&decompressed_data).map_err(|e| invalid(format!(
+                    "Unrecognized web font database layout ({}); rebuild it with `fr build-web-db`", e
+                )))?;
+            let fonts = fonts.into_iter().map(|(key, font)| (key, font.into())).collect();
+            return Ok(Self::from_parts(fonts, family_aliases, version));
+        }
+        
+        // The format version is serialized first
+        let format_version = decompressed_data.get(0..4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().expect("four bytes")))
+            .ok_or_else(|| invalid("Truncated web font database header".to_string()))?;
+        if format_version != FORMAT_VERSION {
+            return Err(invalid(format!(
+                "Unsupported web font database format version {} (expected {}); rebuild it with `fr build-web-db`",
+                format_version, FORMAT_VERSION
+            )));
+        }
+        
+        let (_, fonts, family_aliases, version): (u32, HashMap<String, WebFont>, HashMap<String, String>, String) =
+            bincode::deserialize(&decompressed_data).map_err(|e| invalid(e.to_string()))?;
+        Ok(Self::from_parts(fonts, family_aliases, version))
+    }
+    
+    /// Save the database to disk as gzip-compressed bincode, prefixed with `MAGIC_BYTES`
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_compressed_bytes()?)
+    }
+    
+    fn to_compressed_bytes(&self) -> std::io::Result<Vec<u8>> {
+        let data = (FORMAT_VERSION, &self.fonts, &self.family_aliases, &self.version);
+        let serialized = bincode::serialize(&data)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        
+        let mut encoder = flate2::write::GzEncoder::new(MAGIC_BYTES.to_vec(), flate2::Compression::default());
+        encoder.write_all(&serialized)?;
+        encoder.finish()
+    }
+    
+    /// Add or replace a font, keyed by its normalized family name
+    pub fn insert_font(&mut self, font: WebFont) {
+        let key = Self::normalize_key(&font.family);
+        self.name_index.insert(&font.family, &key);
+        self.fonts.insert(key, font);
+    }
+    
+    /// Register an alias that resolves to an existing family
+    pub fn add_alias(&mut self, alias: &str, family: &str) {
+        let key = Self::normalize_key(family);
+        self.name_index.insert(alias, &key);
+        self.family_aliases.insert(Self::normalize_key(alias), key);
+    }
+    
+    fn normalize_key(family: &str) -> String {
+        family.to_lowercase().replace(' ', "-")
+    }
+    
     fn create_minimal_database() -> Self {
         use FontCategory::*;
         
@@ -126,6 +617,17 @@ impl WebFontDatabase {
                 requires_attribution: false,
             },
             similar_fonts: vec!["open-sans".to_string(), "lato".to_string()],
+            subsets: vec![
+                "latin".to_string(),
+                "latin-ext".to_string(),
+                "cyrillic".to_string(),
+                "greek".to_string(),
+                "vietnamese".to_string(),
+            ],
+            axes: vec!["wdth".to_string(), "wght".to_string()],
+            metrics: None,
+            signatures: Vec::new(),
+            classification: None,
         });
         
         // Add common aliases
@@ -133,11 +635,7 @@ impl WebFontDatabase {
         aliases.insert("helvetica".to_string(), "roboto".to_string());
         aliases.insert("sans-serif".to_string(), "roboto".to_string());
         
-        Self {
-            fonts,
-            family_aliases: aliases,
-            version: "0.1.0-minimal".to_string(),
-        }
+        Self::from_parts(fonts, aliases, "0.1.0-minimal".to_string())
     }
     
     /// Public getter for fonts (to avoid exposing private field)
@@ -157,7 +655,7 @@ impl WebFontDatabase {
     
     /// Find a font by family name
     pub fn find_font(&self, family: &str) -> Option<&WebFont> {
-        let normalized = family.to_lowercase().replace(' ', "-");
+        let normalized = Self::normalize_key(family);
         
         // Direct match
         if let Some(font) = self.fonts.get(&normalized) {
@@ -179,6 +677,87 @@ impl WebFontDatabase {
         None
     }
     
+    /// Faceted search with fuzzy name matching, ranked and paginated
+    pub fn search(&self, query: &WebFontQuery) -> WebFontSearchPage {
+        let name_scores = query.text.as_deref()
+            .filter(|text| !text.trim().is_empty())
+            .map(|text| self.name_index.lookup(text));
+        
+        let license = query.license.as_ref().map(|l| l.to_lowercase());
+        let subsets: Vec<String> = query.subsets.iter().map(|s| s.to_lowercase()).collect();
+        
+        let mut hits: Vec<WebFontHit> = self.fonts.iter()
+            .filter_map(|(key, font)| {
+                let name_score = match &name_scores {
+                    Some(scores) => *scores.get(key)?,
+                    None => 1.0,
+                };
+                
+                if !query.categories.is_empty() && !query.categories.contains(&font.category) {
+                    return None;
+                }
+                if !subsets.iter().all(|s| font.subsets.iter().any(|fs| fs.eq_ignore_ascii_case(s))) {
+                    return None;
+                }
+                if !query.weights.iter().all(|w| font.variants.iter().any(|v| v.weight == *w)) {
+                    return None;
+                }
+                if !query.axes.iter().all(|a| font.axes.iter().any(|fa| fa.eq_ignore_ascii_case(a))) {
+                    return None;
+                }
+                if let Some(license) = &license {
+                    if !font.license.name.to_lowercase().contains(license.as_str()) {
+                        return None;
+                    }
+                }
+                if query.commercial_use && !font.license.allows_commercial_use {
+                    return None;
+                }
+                if query.min_popularity.is_some_and(|min| font.popularity < min) {
+                    return None;
+                }
+                if let Some(max_size) = query.max_file_size_kb {
+                    if font.min_file_size_kb().is_none_or(|size| size > max_size) {
+                        return None;
+                    }
+                }
+                
+                let popularity = font.popularity.min(100) as f32 / 100.0;
+                let score = if name_scores.is_some() {
+                    name_score * 0.85 + popularity * 0.15
+                } else {
+                    popularity
+                };
+                
+                Some(WebFontHit { font: font.clone(), name_score, score })
+            })
+            .collect();
+        
+        match query.sort {
+            SearchSort::Relevance => hits.sort_by(|a, b| {
+                b.score.partial_cmp(&a.score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| a.font.family.cmp(&b.font.family))
+            }),
+            SearchSort::Popularity => hits.sort_by(|a, b| {
+                b.font.popularity.cmp(&a.font.popularity)
+                    .then_with(|| a.font.family.cmp(&b.font.family))
+            }),
+            SearchSort::Name => hits.sort_by(|a, b| a.font.family.cmp(&b.font.family)),
+        }
+        
+        let total = hits.len();
+        let limit = if query.limit == 0 { DEFAULT_SEARCH_LIMIT } else { query.limit };
+        let hits = hits.into_iter().skip(query.offset).take(limit).collect();
+        
+        WebFontSearchPage {
+            hits,
+            total,
+            offset: query.offset,
+            limit,
+        }
+    }
+    
     /// Find similar fonts (for substitutions)
     pub fn find_similar_fonts(&self, family: &str, limit: usize) -> Vec<&WebFont> {
         let search_family = family.to_lowercase();
@@ -187,27 +766,22 @@ impl WebFontDatabase {
         
         // First, check if this font is in our database
         if let Some(font) = self.find_font(family) {
-            // Get fonts marked as similar
-            for similar_name in &font.similar_fonts {
-                if let Some(similar_font) = self.find_font(similar_name) {
-                    if !used_families.contains(&similar_font.family) {
-                        results.push(similar_font);
-                        used_families.insert(similar_font.family.clone());
-                        if results.len() >= limit {
-                            return results;
-                        }
-                    }
-                }
-            }
+            let mut scored: Vec<(f32, &WebFont)> = self.fonts.values()
+                .filter(|candidate| candidate.family != font.family)
+                .filter_map(|candidate| {
+                    self.similarity_score(font, candidate).map(|score| (score, candidate))
+                })
+                .collect();
+            
+            scored.sort_by(|a, b| {
+                b.0.partial_cmp(&a.0)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then(b.1.popularity.cmp(&a.1.popularity))
+            });
             
-            // If we still need more fonts, use the same category
-            let category = font.category.clone();
-            for font_in_category in self.fonts.values() {
-                if font_in_category.category == category && 
-                   font_in_category.family != font.family &&
-                   !used_families.contains(&font_in_category.family) {
-                    results.push(font_in_category);
-                    used_families.insert(font_in_category.family.clone());
+            for (_, candidate) in scored {
+                if used_families.insert(candidate.family.clone()) {
+                    results.push(candidate);
                     if results.len() >= limit {
                         return results;
                     }
@@ -240,6 +814,38 @@ impl WebFontDatabase {
         results
     }
     
+    /// Score a candidate substitute for `reference` (0.0 - 1.0)
+    ///
+    /// Combines the curated `similar_fonts` list and category with build-time
+    /// metrics and glyph signatures; components missing on either side are
+    /// left out. Returns `None` for unrelated candidates without measurements.
+    fn similarity_score(&self, reference: &WebFont, candidate: &WebFont) -> Option<f32> {
+        let listed = reference.similar_fonts.iter()
+            .any(|name| Self::normalize_key(name) == Self::normalize_key(&candidate.family));
+        let same_category = reference.category == candidate.category;
+        let metric = reference.metric_similarity(candidate);
+        let visual = reference.visual_similarity(candidate);
+        
+        if !listed && !same_category && metric.is_none() && visual.is_none() {
+            return None;
+        }
+        
+        let mut components = vec![
+            (0.25, if listed { 1.0 } else { 0.0 }),
+            (0.15, if same_category { 1.0 } else { 0.0 }),
+        ];
+        if let Some(metric) = metric {
+            components.push((0.30, metric));
+        }
+        if let Some(visual) = visual {
+            components.push((0.30, visual));
+        }
+        
+        let total_weight: f32 = components.iter().map(|(w, _)| w).sum();
+        let score: f32 = components.iter().map(|(w, v)| w * v).sum();
+        Some(score / total_weight)
+    }
+    
     /// Convert web font to font descriptor
     pub fn to_font_descriptor(&self, web_font: &WebFont, variant: &WebFontVariant) -> FontDescriptor {
         FontDescriptor {
@@ -253,7 +859,8 @@ impl WebFontDatabase {
             italic: variant.italic,
             monospaced: web_font.category == FontCategory::Monospace,
             variable: false, // Web fonts typically aren't variable
-            metrics: Some(FontMetrics {
+            // Fall back to typical web font metrics when the font was never measured
+            metrics: Some(web_font.metrics.clone().unwrap_or(FontMetrics {
                 units_per_em: 1000, // Default for web fonts
                 ascender: 800,
                 descender: -200,
@@ -261,7 +868,7 @@ impl WebFontDatabase {
                 cap_height: 700,
                 average_width: 500,
                 max_advance_width: 1200,
-            }),
+            })),
             license: Some(LicenseInfo {
                 name: web_font.license.name.clone(),
                 url: Some(web_font.license.url.clone()),
@@ -269,8 +876,12 @@ impl WebFontDatabase {
                 allows_modification: web_font.license.allows_modification,
                 requires_attribution: web_font.license.requires_attribution,
                 allows_commercial_use: web_font.license.allows_commercial_use,
+                embedding: None,
+                spdx: None,
+                reserved_font_names: Vec::new(),
                 // allows_commercial_use: compressed.license.allows_commercial_use, // Removed as it is duplicated
             }),
+            classification: web_font.classification,
         }
     }
     
@@ -339,6 +950,19 @@ pub mod download {
                         requires_attribution: true,
                     },
                     similar_fonts: Vec::new(),
+                    subsets: item["subsets"].as_array()
+                        .map(|subsets| subsets.iter()
+                            .filter_map(|s| s.as_str().map(String::from))
+                            .collect())
+                        .unwrap_or_default(),
+                    axes: item["axes"].as_array()
+                        .map(|axes| axes.iter()
+                            .filter_map(|a| a["tag"].as_str().map(String::from))
+                            .collect())
+                        .unwrap_or_default(),
+                    metrics: None,
+                    signatures: Vec::new(),
+                    classification: None,
                 };
                 
                 web_fonts.push(web_font);
@@ -392,20 +1016,418 @@ pub mod download {
                          version: &str, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
         let font_map: HashMap<String, WebFont> = fonts
             .iter()
-            .map(|f| (f.family.to_lowercase().replace(' ', "-"), f.clone()))
+            .map(|f| (WebFontDatabase::normalize_key(&f.family), f.clone()))
             .collect();
         
-        let data = (font_map, aliases.clone(), version.to_string());
-        let serialized = bincode::serialize(&data)?;
-        
-        // Compress
-        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
-        encoder.write_all(&serialized)?;
-        let compressed = encoder.finish()?;
+        let compressed = WebFontDatabase::from_parts(font_map, aliases.clone(), version.to_string())
+            .to_compressed_bytes()?;
         
         let mut file = File::create(output_path)?;
         file.write_all(&compressed)?;
         
         Ok(())
     }
-}   
\ No newline at end of file
+}   
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn web_font(family: &str, category: FontCategory, popularity: u8) -> WebFont {
+        WebFont {
+            family: family.to_string(),
+            variants: [400, 700].iter().map(|&weight| WebFontVariant {
+                weight,
+                italic: false,
+                style: if weight == 400 { "Regular" } else { "Bold" }.to_string(),
+                file_url: String::new(),
+                file_format: FontFormat::Woff2,
+                file_size_kb: 40 + popularity as u32,
+            }).collect(),
+            category,
+            popularity,
+            last_updated: "2024-01-01".to_string(),
+            license: WebFontLicense {
+                name: "SIL Open Font License 1.1".to_string(),
+                url: "https://openfontlicense.org".to_string(),
+                allows_commercial_use: true,
+                allows_modification: true,
+                requires_attribution: false,
+            },
+            similar_fonts: Vec::new(),
+            subsets: vec!["latin".to_string()],
+            axes: Vec::new(),
+            metrics: None,
+            signatures: Vec::new(),
+            classification: None,
+        }
+    }
+
+    fn database() -> WebFontDatabase {
+        use FontCategory::*;
+
+        let mut db = WebFontDatabase::new("test");
+        db.insert_font(web_font("Roboto", SansSerif, 95));
+        db.insert_font(web_font("Roboto Mono", Monospace, 70));
+        db.insert_font(web_font("Roboto Slab", Serif, 60));
+        db.insert_font(web_font("Open Sans", SansSerif, 90));
+        db.insert_font(web_font("Merriweather", Serif, 75));
+        db.insert_font(web_font("Source Code Pro", Monospace, 65));
+
+        let mut inter = web_font("Inter", SansSerif, 80);
+        inter.subsets.push("cyrillic".to_string());
+        inter.axes.push("wght".to_string());
+        inter.variants.retain(|v| v.weight == 400);
+        db.insert_font(inter);
+
+        let mut commercial = web_font("Proxima Nova", SansSerif, 85);
+        commercial.license.name = "Commercial".to_string();
+        commercial.license.allows_commercial_use = false;
+        db.insert_font(commercial);
+
+        db.add_alias("Arial", "Open Sans");
+        db
+    }
+
+    fn families(page: &WebFontSearchPage) -> Vec<&str> {
+        page.hits.iter().map(|hit| hit.font.family.as_str()).collect()
+    }
+
+    fn text(text: &str) -> WebFontQuery {
+        WebFontQuery { text: Some(text.to_string()), ..Default::default() }
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        let cases = [
+            ("", "", 0),
+            ("roboto", "roboto", 0),
+            ("", "inter", 5),
+            ("robto", "roboto", 1),
+            ("merriwether", "merriweather", 1),
+            ("kitten", "sitting", 3),
+            ("ß", "ss", 2),
+        ];
+        for (a, b, distance) in cases {
+            assert_eq!(levenshtein(a, b), distance, "{} / {}", a, b);
+            assert_eq!(levenshtein(b, a), distance, "{} / {}", b, a);
+        }
+    }
+
+    #[test]
+    fn test_category_names_round_trip() {
+        use FontCategory::*;
+        for category in [Serif, SansSerif, Monospace, Display, Handwriting, Other] {
+            assert_eq!(FontCategory::from_name(category.name()), Some(category.clone()));
+        }
+        assert_eq!(SansSerif.name(), "sans-serif");
+    }
+
+    #[test]
+    fn test_name_match_score() {
+        let score = |query: &str, name: &str| {
+            let query = normalize_search_text(query);
+            name_match_score(&query, &trigrams(&query), name)
+        };
+
+        assert_eq!(score("Roboto", "roboto"), 1.0);
+        assert_eq!(score("robo", "roboto mono"), 0.9);
+        assert_eq!(score("mono", "roboto mono"), 0.8);
+        assert_eq!(score("", "roboto"), 0.0);
+        // Fuzzy matches stay below substring matches but above the lookup cut-off
+        let typo = score("Robtoo", "roboto");
+        assert!(typo > 0.3 && typo <= 0.75, "{}", typo);
+        assert!(score("Merriwether", "merriweather") > 0.6);
+        assert!(score("Inter", "source code pro") < 0.3);
+    }
+
+    #[test]
+    fn test_name_index_recall() {
+        let db = database();
+
+        // Every family sharing a trigram with the query is scored, not just prefix matches
+        let roboto = db.name_index.lookup("roboto");
+        assert_eq!(roboto["roboto"], 1.0);
+        assert_eq!(roboto["roboto-mono"], 0.9);
+        assert_eq!(roboto["roboto-slab"], 0.9);
+        assert!(!roboto.contains_key("inter"));
+
+        assert_eq!(db.name_index.lookup("slab")["roboto-slab"], 0.8);
+        assert_eq!(db.name_index.lookup("code pro")["source-code-pro"], 0.8);
+        // Punctuation and case are normalized away
+        assert_eq!(db.name_index.lookup("  OPEN-sans ")["open-sans"], 1.0);
+        // Aliases score the family they point to
+        assert_eq!(db.name_index.lookup("arial")["open-sans"], 1.0);
+    }
+
+    #[test]
+    fn test_search_tolerates_typos() {
+        let db = database();
+        for (query, family) in [("Robotto", "Roboto"), ("Merriwether", "Merriweather"), ("Opn Sans", "Open Sans"), ("Sorce Code", "Source Code Pro")] {
+            let page = db.search(&text(query));
+            assert_eq!(families(&page).first(), Some(&family), "{}", query);
+        }
+
+        // Nothing shares a trigram with the query: the full scan finds nothing close enough either
+        assert_eq!(db.search(&text("zzqx")).total, 0);
+        // Blank text is no name filter at all
+        assert_eq!(db.search(&text("   ")).total, db.count());
+    }
+
+    #[test]
+    fn test_search_ranks_exact_before_prefix() {
+        let page = database().search(&text("roboto"));
+        assert_eq!(families(&page), ["Roboto", "Roboto Mono", "Roboto Slab"]);
+        assert_eq!(page.hits[0].name_score, 1.0);
+        assert!((page.hits[0].score - (0.85 + 0.95 * 0.15)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_search_facets() {
+        use FontCategory::*;
+
+        let db = database();
+        let search = |query: WebFontQuery| {
+            let mut found = families(&db.search(&WebFontQuery { sort: SearchSort::Name, ..query }))
+                .into_iter().map(str::to_string).collect::<Vec<_>>();
+            found.sort();
+            found
+        };
+
+        assert_eq!(search(WebFontQuery { categories: vec![Monospace, Serif], ..Default::default() }),
+            ["Merriweather", "Roboto Mono", "Roboto Slab", "Source Code Pro"]);
+        assert_eq!(search(WebFontQuery { subsets: vec!["Cyrillic".to_string()], ..Default::default() }), ["Inter"]);
+        assert_eq!(search(WebFontQuery { axes: vec!["WGHT".to_string()], ..Default::default() }), ["Inter"]);
+        // Every listed weight is required
+        assert!(!search(WebFontQuery { weights: vec![400, 700], ..Default::default() }).contains(&"Inter".to_string()));
+        assert!(search(WebFontQuery { weights: vec![400], ..Default::default() }).contains(&"Inter".to_string()));
+        assert!(search(WebFontQuery { weights: vec![300], ..Default::default() }).is_empty());
+
+        assert_eq!(search(WebFontQuery { license: Some("commercial".to_string()), ..Default::default() }), ["Proxima Nova"]);
+        assert!(!search(WebFontQuery { commercial_use: true, ..Default::default() }).contains(&"Proxima Nova".to_string()));
+        assert_eq!(search(WebFontQuery { min_popularity: Some(90), ..Default::default() }), ["Open Sans", "Roboto"]);
+        assert_eq!(search(WebFontQuery { max_file_size_kb: Some(100), ..Default::default() }), ["Roboto Slab"]);
+
+        // Facets combine with the name filter
+        assert_eq!(search(WebFontQuery { text: Some("roboto".to_string()), categories: vec![Serif], ..Default::default() }), ["Roboto Slab"]);
+    }
+
+    #[test]
+    fn test_search_sort_and_pagination() {
+        let db = database();
+        let page = |sort, offset, limit| db.search(&WebFontQuery { sort, offset, limit, ..Default::default() });
+
+        let by_popularity = page(SearchSort::Popularity, 0, 3);
+        assert_eq!(families(&by_popularity), ["Roboto", "Open Sans", "Proxima Nova"]);
+        assert_eq!((by_popularity.total, by_popularity.offset, by_popularity.limit), (8, 0, 3));
+
+        let all = families(&page(SearchSort::Name, 0, 0)).join(",");
+        let mut paged = Vec::new();
+        for offset in (0..8).step_by(3) {
+            let next = page(SearchSort::Name, offset, 3);
+            assert_eq!(next.total, 8);
+            assert!(next.hits.len() <= 3);
+            paged.extend(families(&next).into_iter().map(str::to_string));
+        }
+        assert_eq!(paged.join(","), all);
+
+        // Limit 0 is the default page size; offsets past the end give an empty page
+        assert_eq!(page(SearchSort::Name, 0, 0).limit, DEFAULT_SEARCH_LIMIT);
+        let past_end = page(SearchSort::Name, 8, 3);
+        assert!(past_end.hits.is_empty());
+        assert_eq!(past_end.total, 8);
+        assert!(page(SearchSort::Name, usize::MAX, 3).hits.is_empty());
+    }
+
+    fn metrics(x_height: i16) -> FontMetrics {
+        FontMetrics {
+            units_per_em: 1000,
+            ascender: 800,
+            descender: -200,
+            x_height,
+            cap_height: 700,
+            average_width: 500,
+            max_advance_width: 1200,
+        }
+    }
+
+    fn signatures(density: u8) -> Vec<(char, MicroSignature)> {
+        SIGNATURE_CHARS.chars()
+            .map(|ch| (ch, MicroSignature { density, aspect_ratio: 40, quadrant_nw: 90, ..MicroSignature::new() }))
+            .collect()
+    }
+
+    #[test]
+    fn test_similarity_score() {
+        use FontCategory::*;
+
+        let db = WebFontDatabase::new("test");
+        let mut reference = web_font("Roboto", SansSerif, 95);
+        reference.similar_fonts = vec!["Open Sans".to_string()];
+
+        // Unmeasured candidates are scored on the curated list and category alone
+        assert_eq!(db.similarity_score(&reference, &web_font("Open Sans", SansSerif, 90)), Some(1.0));
+        assert_eq!(db.similarity_score(&reference, &web_font("Open Sans", Serif, 90)), Some(0.625));
+        assert_eq!(db.similarity_score(&reference, &web_font("Lato", SansSerif, 90)), Some(0.375));
+        assert_eq!(db.similarity_score(&reference, &web_font("Lobster", Display, 90)), None);
+
+        reference.metrics = Some(metrics(500));
+        reference.signatures = signatures(120);
+        let mut twin = web_font("Lato", SansSerif, 90);
+        twin.metrics = Some(metrics(500));
+        twin.signatures = signatures(120);
+        assert!((db.similarity_score(&reference, &twin).unwrap() - 0.75).abs() < 1e-6);
+
+        // A quarter em off in one of five metrics costs a fifth of the metric component
+        twin.metrics = Some(metrics(250));
+        assert!((twin.metric_similarity(&reference).unwrap() - 0.8).abs() < 1e-6);
+        assert!((db.similarity_score(&reference, &twin).unwrap() - (0.15 + 0.3 * 0.8 + 0.3) / 1.0).abs() < 1e-6);
+
+        // Measurements make any category comparable; missing ones on either side are left out
+        let mut display = web_font("Lobster", Display, 90);
+        display.signatures = signatures(120);
+        assert!((db.similarity_score(&reference, &display).unwrap() - 0.3 / 0.7).abs() < 1e-6);
+        reference.signatures.clear();
+        assert_eq!(db.similarity_score(&reference, &display), None);
+    }
+
+    #[test]
+    fn test_find_similar_prefers_measured_matches() {
+        use FontCategory::*;
+
+        let mut db = WebFontDatabase::new("test");
+        let mut reference = web_font("Roboto", SansSerif, 95);
+        reference.metrics = Some(metrics(528));
+        reference.signatures = signatures(120);
+        db.insert_font(reference);
+
+        let mut close = web_font("Inter", SansSerif, 40);
+        close.metrics = Some(metrics(540));
+        close.signatures = signatures(125);
+        db.insert_font(close);
+        let mut far = web_font("Anton", SansSerif, 90);
+        far.metrics = Some(metrics(700));
+        far.signatures = signatures(250);
+        db.insert_font(far);
+        db.insert_font(web_font("Lato", SansSerif, 99));
+        db.insert_font(web_font("Lobster", Display, 99));
+
+        let similar: Vec<&str> = db.find_similar_fonts("Roboto", 10).iter().map(|f| f.family.as_str()).collect();
+        assert_eq!(similar, ["Inter", "Anton", "Lato"]);
+    }
+
+    #[test]
+    fn test_measure_from_file() {
+        let mut font = web_font("DejaVu Sans", FontCategory::Other, 50);
+        font.subsets = vec!["latin".to_string()];
+        assert!(!font.is_measured());
+        assert!(font.measure_from_file("/nonexistent/DejaVuSans.ttf").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let garbage = dir.path().join("garbage.ttf");
+        std::fs::write(&garbage, b"not a font").unwrap();
+        assert!(font.measure_from_file(&garbage).is_err());
+        assert!(!font.is_measured());
+
+        let path = Path::new("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf");
+        if !path.exists() {
+            eprintln!("skipping: {} not installed", path.display());
+            return;
+        }
+        font.measure_from_file(path).unwrap();
+        assert!(font.is_measured());
+        // Probed subsets are added to the listed ones without duplicates
+        assert_eq!(font.subsets, ["latin", "latin-ext", "vietnamese", "greek", "cyrillic", "arabic", "hebrew"]);
+        assert!(font.axes.is_empty());
+        assert_eq!(font.metrics.as_ref().map(|m| m.units_per_em), Some(2048));
+        assert_eq!(font.signatures.len(), SIGNATURE_CHARS.chars().count());
+        assert!(font.classification.is_some());
+        // The provider gave no category, so the outlines decide
+        assert_eq!(font.category, FontCategory::SansSerif);
+
+        let mut listed = web_font("DejaVu Sans", FontCategory::Display, 50);
+        listed.measure_from_file(path).unwrap();
+        assert_eq!(listed.category, FontCategory::Display);
+        assert_eq!(listed.visual_similarity(&font), Some(1.0));
+        assert_eq!(listed.metric_similarity(&font), Some(1.0));
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_database_round_trip() {
+        let mut db = database();
+        let mut measured = web_font("Lato", FontCategory::SansSerif, 80);
+        measured.metrics = Some(metrics(506));
+        measured.signatures = signatures(100);
+        db.insert_font(measured);
+
+        let bytes = db.to_compressed_bytes().unwrap();
+        assert!(bytes.starts_with(MAGIC_BYTES));
+        let loaded = WebFontDatabase::from_compressed_bytes(&bytes).unwrap();
+        assert_eq!(loaded.count(), db.count());
+        assert_eq!(loaded.version(), "test");
+        let lato = loaded.find_font("lato").unwrap();
+        assert_eq!(lato.signatures.len(), SIGNATURE_CHARS.chars().count());
+        assert_eq!(lato.metrics.as_ref().map(|m| m.x_height), Some(506));
+        // The name index is rebuilt on load, aliases included
+        assert_eq!(loaded.search(&text("arial")).hits[0].font.family, "Open Sans");
+    }
+
+    #[test]
+    fn test_unversioned_database_is_upgraded() {
+        #[derive(Serialize)]
+        struct Unversioned<'a> {
+            family: &'a str,
+            variants: Vec<WebFontVariant>,
+            category: FontCategory,
+            popularity: u8,
+            last_updated: &'a str,
+            license: WebFontLicense,
+            similar_fonts: Vec<&'a str>,
+        }
+
+        let roboto = web_font("Roboto", FontCategory::SansSerif, 95);
+        let fonts = HashMap::from([("roboto", Unversioned {
+            family: "Roboto",
+            variants: roboto.variants,
+            category: FontCategory::SansSerif,
+            popularity: 95,
+            last_updated: "2024-01-01",
+            license: roboto.license,
+            similar_fonts: vec!["open-sans"],
+        })]);
+        let aliases = HashMap::from([("helvetica", "roboto")]);
+        let legacy = gzip(&bincode::serialize(&(fonts, aliases, "2024.01.01")).unwrap());
+
+        let db = WebFontDatabase::from_compressed_bytes(&legacy).unwrap();
+        assert_eq!(db.version(), "2024.01.01");
+        let font = db.find_font("Helvetica").unwrap();
+        assert_eq!(font.family, "Roboto");
+        assert_eq!(font.similar_fonts, ["open-sans"]);
+        assert!(font.subsets.is_empty() && !font.is_measured() && font.classification.is_none());
+    }
+
+    #[test]
+    fn test_unreadable_database_asks_for_rebuild() {
+        let error = |bytes: &[u8]| WebFontDatabase::from_compressed_bytes(bytes).unwrap_err().to_string();
+
+        // Unversioned files in any other layout can't be told apart from corruption
+        assert!(error(&gzip(b"\x05not a database")).contains("rebuild it with `fr build-web-db`"));
+
+        let mut future = MAGIC_BYTES.to_vec();
+        future.extend(gzip(&bincode::serialize(&(FORMAT_VERSION + 1, "newer")).unwrap()));
+        let message = error(&future);
+        assert!(message.contains(&format!("format version {}", FORMAT_VERSION + 1)), "{}", message);
+        assert!(message.contains("rebuild it"));
+
+        let mut truncated = MAGIC_BYTES.to_vec();
+        truncated.extend(gzip(&[2, 0]));
+        assert!(error(&truncated).contains("Truncated"));
+        assert!(WebFontDatabase::from_compressed_bytes(b"not gzip").is_err());
+    }
+
+}
@@ -12,6 +12,9 @@ use colored::*;
 use tiny_http;
 use serde_json;
 
+/// `fr audit` exit status when the project breaks its font policy
+const AUDIT_VIOLATIONS_EXIT_CODE: i32 = 3;
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
@@ -29,7 +32,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Ensure database exists for commands that need it
-    if !matches!(&cli.command, Some(Commands::Update) | Some(Commands::Setup)) {
+    if !matches!(&cli.command, Some(Commands::Update) | Some(Commands::Setup) | Some(Commands::Audit { .. })) {
         if let Err(e) = ensure_database_exists() {
             eprintln!("⚠️  Could not create database: {}", e);
             eprintln!("   Continuing with system fonts only...");
@@ -113,7 +116,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             // **FIX: Use the basic FontResolver instead of EnhancedFontResolver**
             let basic_resolver = font_resolver_engine::FontResolver::new(
                 font_core::ResolverConfig::default()
-            );
+            ).with_profile(config.load_scoring_profile()?);
             
             match basic_resolver.resolve(&font_name) {
                 Ok(result) => {
@@ -157,7 +160,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
         
-        Some(Commands::TieredResolve { font_name, enable_internet }) => {
+        Some(Commands::TieredResolve { font_name, enable_internet, glyph_db }) => {
             println!("🎯 Tiered Matching for: '{}'", font_name.bold());
             println!("{}", "-".repeat(50));
             
@@ -165,7 +168,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             config.web_fonts_enabled = true; // Enable web fonts for tiered matching
             
             // Try to load or create database
-            let resolver = load_or_create_database(&config)?;
+            let mut resolver = load_or_create_database(&config)?;
+            if let Some(path) = &glyph_db {
+                let families = resolver.load_glyph_database(path)?;
+                println!("🔬 Visual similarity: {} families with glyph signatures", families);
+            }
             
             // Show progress
             let pb = ProgressBar::new_spinner();
@@ -265,6 +272,50 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             handle_memory_limit(&mut config.clone());
         }
         
+        Some(Commands::LicenseDb(cmd)) => match cmd {
+            LicenseDbCommands::Info => {
+                let checker = font_license::LicenseChecker::new();
+                let knowledge = checker.knowledge_base();
+                let embedded = font_license::FontKnowledgeBase::embedded();
+                
+                println!("{}", "LICENSE KNOWLEDGE BASE".bold());
+                println!("{}", "-".repeat(40));
+                println!("Version: {}", knowledge.version);
+                if knowledge.version == embedded.version {
+                    println!("Source: embedded");
+                } else {
+                    println!("Source: {}", font_license::knowledge_path().display());
+                }
+                let local = font_license::local_knowledge_path();
+                if local.exists() {
+                    println!("Local additions: {}", local.display());
+                }
+                println!("Foundries: {}", knowledge.foundries.len());
+                println!("Families: {}", knowledge.families.len());
+                println!("Curated alternatives: {}", knowledge.families.iter().map(|f| f.alternatives.len()).sum::<usize>());
+            }
+            
+            LicenseDbCommands::Update { from } => {
+                let updater = font_updater::FontUpdater::new(font_core::default_data_dir());
+                let result = match from {
+                    Some(source) => updater.update_knowledge_base(Some(&source)).await.map(|_| ()),
+                    None => match updater.check_for_updates().await {
+                        Ok(Some(manifest)) => updater.perform_update(&manifest).await.map(|_| ()),
+                        Ok(None) => {
+                            println!("✅ Already up to date");
+                            Ok(())
+                        }
+                        Err(e) => Err(e),
+                    },
+                };
+                
+                if let Err(e) = result {
+                    println!("❌ Update failed: {}", e);
+                    process::exit(1);
+                }
+            }
+        },
+        
         Some(Commands::Cache(cmd)) => match cmd {
             CacheCommands::Stats => {
                 println!("📊 Loading cache statistics...");
@@ -311,15 +362,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             println!("   Memory usage: {:.2}MB / {}MB", 
                                     stats.memory_usage_mb, resolver.get_config().memory_limit_mb);
                             
-                            // Show note if disk usage is estimated
-                            if stats.disk_entries >= 100 {
-                                println!("   Disk usage: ~{:.2}MB / {}MB (estimated)", 
-                                        stats.disk_usage_mb, resolver.get_config().disk_limit_mb);
-                                println!("   {} Note: Disk usage is estimated for large caches", "ℹ️".blue());
-                            } else {
-                                println!("   Disk usage: {:.2}MB / {}MB", 
-                                        stats.disk_usage_mb, resolver.get_config().disk_limit_mb);
-                            }
+                            println!("   Disk usage: {:.2}MB / {}MB", 
+                                    stats.disk_usage_mb, resolver.get_config().disk_limit_mb);
+                            println!("   Hit rate: {:.1}% ({} hits, {} misses)", 
+                                    stats.hit_rate * 100.0, stats.hits, stats.misses);
+                            println!("   Evictions: {} ({} policy)", 
+                                    stats.evictions, resolver.get_config().cache_cleanup_mode);
                             
                             let memory_percent = (stats.memory_usage_mb / resolver.get_config().memory_limit_mb as f64) * 100.0;
                             let disk_percent = (stats.disk_usage_mb / resolver.get_config().disk_limit_mb as f64) * 100.0;
@@ -452,6 +500,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     None => println!("⚠️  Cache is disabled"),
                 }
             }
+            
+            CacheCommands::Verify { purge } => {
+                let config = load_config()?;
+                let resolver = EnhancedFontResolver::new(config)?;
+                
+                match resolver.verify_cache(purge) {
+                    Some(Ok(report)) => {
+                        println!("🔍 Checked {} cached entries ({} without a local file to verify)",
+                            report.checked, report.unverified);
+                        if report.dangling.is_empty() {
+                            println!("✅ All cached fonts match their files");
+                        } else {
+                            println!("{}", format!("⚠️  {} dangling entries:", report.dangling.len()).yellow());
+                            for entry in &report.dangling {
+                                println!("   • {} ({}: {})", entry.font_name, entry.status, entry.path.display());
+                            }
+                            if purge {
+                                println!("🗑️  Removed {} entries", report.removed);
+                            } else {
+                                println!("\nRun: {}", "fr cache verify --purge".cyan());
+                            }
+                        }
+                    }
+                    Some(Err(e)) => println!("❌ Error: {}", e),
+                    None => println!("⚠️  Cache is disabled"),
+                }
+            }
         },
         
         Some(Commands::Config(subcommand)) => match subcommand {
@@ -541,6 +616,67 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         }
                     }
                     
+                    "cleanup_mode" | "cleanup" => {
+                        let mode = match value.to_lowercase().replace(['-', '_'], "").as_str() {
+                            "manual" => Some(font_core::CacheCleanupMode::Manual),
+                            "size" | "sizebased" => Some(font_core::CacheCleanupMode::SizeBased),
+                            "time" | "timebased" => Some(font_core::CacheCleanupMode::TimeBased),
+                            "smart" => Some(font_core::CacheCleanupMode::Smart),
+                            _ => None,
+                        };
+                        match mode {
+                            Some(mode) => {
+                                println!("✅ Cache cleanup mode set to {}", mode);
+                                config.cache_cleanup_mode = mode;
+                            }
+                            None => println!("❌ Use manual, size-based, time-based or smart"),
+                        }
+                    }
+                    
+                    "cache_dir" | "cache" => {
+                        match value.to_lowercase().as_str() {
+                            "default" | "" => {
+                                config.cache_dir = None;
+                                println!("✅ Cache directory reset to {}", font_cache::default_cache_dir().display());
+                            }
+                            _ => {
+                                config.cache_dir = Some(PathBuf::from(&value));
+                                println!("✅ Cache directory set to {}", value);
+                            }
+                        }
+                    }
+                    
+                    "negative_cache_ttl" | "negative_ttl" => {
+                        if let Ok(secs) = value.parse::<u64>() {
+                            config.negative_cache_ttl_secs = secs;
+                            if secs == 0 {
+                                println!("✅ Negative caching disabled");
+                            } else {
+                                println!("✅ Not-found results are remembered for {}s", secs);
+                            }
+                        } else {
+                            println!("❌ Invalid TTL. Use a number of seconds, or 0 to disable");
+                        }
+                    }
+                    
+                    "scoring_profile" | "profile" => {
+                        match value.to_lowercase().as_str() {
+                            "default" | "" => {
+                                config.scoring_profile = None;
+                                println!("✅ Scoring profile reset to the embedded profile");
+                            }
+                            _ => {
+                                let path = PathBuf::from(&value);
+                                if let Err(e) = font_core::ScoringProfile::load_from_file(&path) {
+                                    println!("❌ Invalid scoring profile: {}", e);
+                                    return Ok(());
+                                }
+                                config.scoring_profile = Some(path);
+                                println!("✅ Scoring profile set to {}", value);
+                            }
+                        }
+                    }
+                    
                     _ => {
                         println!("❌ Unknown configuration key: {}", key);
                         println!("   Available keys:");
@@ -549,6 +685,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         println!("     - web_fonts: Enable/disable web fonts");
                         println!("     - license_warnings: Enable/disable license warnings");
                         println!("     - auto_pin: Enable/disable auto-pinning");
+                        println!("     - cleanup_mode: manual, size-based, time-based or smart");
+                        println!("     - cache_dir: Cache directory path, or 'default'");
+                        println!("     - negative_cache_ttl: Seconds to remember not-found fonts (0 disables)");
+                        println!("     - scoring_profile: Scoring profile JSON path, or 'default'");
                         return Ok(());
                     }
                 }
@@ -685,26 +825,143 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("   Or: {}", format!("fr resolve {} --web", font_name).cyan());
         }
         
-        Some(Commands::CheckLicense { font_name }) => {
+        Some(Commands::Search { query, categories, subsets, weights, axes, license, commercial, min_popularity, max_size_kb, sort, page, limit, json }) => {
+            let mut config = load_config()?;
+            config.web_fonts_enabled = true;
+            let resolver = EnhancedFontResolver::new(config)?;
+            
+            let mut parsed_categories = Vec::new();
+            for name in &categories {
+                match font_web_db::FontCategory::from_name(name) {
+                    Some(category) => parsed_categories.push(category),
+                    None => {
+                        eprintln!("❌ Unknown category: {}", name);
+                        process::exit(2);
+                    }
+                }
+            }
+            
+            let web_query = font_web_db::WebFontQuery {
+                text: query.clone(),
+                categories: parsed_categories,
+                subsets,
+                weights,
+                axes,
+                license,
+                commercial_use: commercial,
+                min_popularity,
+                max_file_size_kb: max_size_kb,
+                sort: sort.into(),
+                offset: page.saturating_sub(1) * limit,
+                limit,
+            };
+            
+            let Some(results) = resolver.search_web_fonts(&web_query) else {
+                println!("❌ Web font database is not available");
+                return Ok(());
+            };
+            
+            if json {
+                println!("{}", serde_json::to_string_pretty(&results)?);
+                return Ok(());
+            }
+            
+            match &query {
+                Some(text) => println!("🔎 Web fonts matching '{}'", text),
+                None => println!("🔎 Web fonts"),
+            }
+            println!("{}", "=".repeat(40));
+            
+            if results.hits.is_empty() {
+                println!("No fonts matched the given filters.");
+                return Ok(());
+            }
+            
+            for (i, hit) in results.hits.iter().enumerate() {
+                let weights: Vec<String> = hit.font.variants.iter()
+                    .map(|v| if v.italic { format!("{}i", v.weight) } else { v.weight.to_string() })
+                    .collect();
+                println!("{:>3}. {} {}", results.offset + i + 1, hit.font.family.bold(),
+                        format!("({:.0}%)", hit.score * 100.0).dimmed());
+                println!("     {:?} • {} • popularity {} • weights {}",
+                        hit.font.category, hit.font.license.name, hit.font.popularity, weights.join(", "));
+                if !hit.font.subsets.is_empty() {
+                    println!("     subsets: {}", hit.font.subsets.join(", "));
+                }
+                if !hit.font.axes.is_empty() {
+                    println!("     axes: {}", hit.font.axes.join(", "));
+                }
+            }
+            
+            let pages = results.total.div_ceil(results.limit.max(1));
+            println!("\nPage {} of {} ({} matches)", page.max(1), pages.max(1), results.total);
+        }
+        
+        Some(Commands::CheckLicense { font_name, operation, derived_name, derivation }) => {
             println!("⚖️  Checking license for '{}'...", font_name);
             
             let config = load_config()?;
             let resolver = EnhancedFontResolver::new(config)?;
             
+            if let Some(derived_name) = derived_name {
+                match resolver.check_derived_font(&font_name, &derived_name, derivation.into()) {
+                    Ok(check) => {
+                        if check.reserved_names.is_empty() {
+                            println!("Reserved Font Names: none declared");
+                        } else {
+                            println!("Reserved Font Names: {}", check.reserved_names.join(", "));
+                        }
+                        if check.is_compliant() {
+                            println!("✅ {}", check.message.green());
+                        } else {
+                            println!("❌ {}", check.message.red());
+                            process::exit(1);
+                        }
+                    }
+                    Err(e) => println!("❌ Error: {}", e),
+                }
+                return Ok(());
+            }
+            
+            if let Some(operation) = operation {
+                match resolver.check_operation(&font_name, operation.into()) {
+                    Ok(check) => {
+                        let permissions = check.permissions
+                            .map(|p| format!("{} (fsType 0x{:04X})", p, p.fs_type))
+                            .unwrap_or_else(|| "not declared".to_string());
+                        println!("Embedding permissions: {}", permissions);
+                        match check.warning_level {
+                            font_license::WarningLevel::Critical => {
+                                println!("❌ {}", check.message.red());
+                                process::exit(1);
+                            }
+                            font_license::WarningLevel::Warning => println!("⚠️  {}", check.message.yellow()),
+                            font_license::WarningLevel::Info => println!("✅ {}", check.message.green()),
+                        }
+                    }
+                    Err(e) => println!("❌ Error: {}", e),
+                }
+                return Ok(());
+            }
+            
             match resolver.check_license(&font_name) {
                 Ok(warning) => {
                     println!("{}", "LICENSE ANALYSIS".bold());
                     println!("{}", "-".repeat(40));
                     println!("Font: {}", font_name);
                     println!("License type: {:?}", warning.license_type);
+                    if let Some(spdx) = &warning.spdx {
+                        println!("SPDX: {} ({:.0}% confidence)", spdx.spdx_id, spdx.confidence * 100.0);
+                    }
                     println!("Warning level: {:?}", warning.warning_level);
                     println!("Message: {}", warning.message);
                     
                     if !warning.alternatives.is_empty() {
                         println!("\n{}", "FREE ALTERNATIVES".bold());
                         for alt in &warning.alternatives {
-                            println!("  • {} ({:.0}% similar) - {}", 
-                                    alt.family, alt.similarity_score * 100.0, alt.reason);
+                            let metrics = if alt.metric_compatible { ", metric-compatible" } else { "" };
+                            println!("  • {} ({:.0}% similar{}) - {}", 
+                                    alt.family, alt.similarity_score * 100.0, metrics, alt.reason);
                         }
                     }
                 }
@@ -712,6 +969,374 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
         
+        Some(Commands::Substitutes { family, json }) => {
+            let resolver = EnhancedFontResolver::new(load_config()?)?;
+            let registry = resolver.substitute_registry();
+            let results: Vec<_> = registry.results().iter()
+                .filter(|r| family.as_deref().is_none_or(|f| {
+                    r.pair.original.eq_ignore_ascii_case(f) || r.pair.aliases.iter().any(|a| a.eq_ignore_ascii_case(f))
+                }))
+                .collect();
+            
+            if json {
+                println!("{}", serde_json::to_string_pretty(&results)?);
+            } else {
+                println!("{}", "METRIC-COMPATIBLE SUBSTITUTES".bold());
+                println!("{}", "-".repeat(40));
+                for result in &results {
+                    let status = match result.status {
+                        font_substitutes::VerificationStatus::Verified => "✅",
+                        font_substitutes::VerificationStatus::Mismatch => "❌",
+                        font_substitutes::VerificationStatus::Unavailable => "⚪",
+                    };
+                    println!("{} {} → {} ({})", status, result.pair.original, result.pair.substitute, result.status);
+                    println!("   {}", result.message.dimmed());
+                }
+            }
+            
+            let failures = results.iter().filter(|r| r.status == font_substitutes::VerificationStatus::Mismatch).count();
+            if failures > 0 {
+                eprintln!("⚠️  {} substitute pair(s) failed verification and won't be used for substitution", failures);
+            }
+        }
+        
+        Some(Commands::Classify { file, json }) => {
+            let features = font_glyph::ShapeClassifier::new().measure_file(&file)?;
+            let classification = features.classify();
+            
+            if json {
+                let report = serde_json::json!({
+                    "file": file,
+                    "features": features,
+                    "classification": classification,
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("{}", format!("STYLE OF {}", file.display()).bold());
+                println!("{}", "-".repeat(40));
+                match classification {
+                    Some(classification) => {
+                        for (style, confidence) in classification.labels() {
+                            if confidence >= 0.05 {
+                                println!("  {:<12} {:>4.0}%", style.to_string(), confidence * 100.0);
+                            }
+                        }
+                        let show = |name: &str, value: Option<f32>| {
+                            if let Some(value) = value {
+                                println!("   {}", format!("{}: {:.2}", name, value).dimmed());
+                            }
+                        };
+                        println!();
+                        show("stroke contrast", features.stroke_contrast);
+                        show("stress angle", features.stress_angle);
+                        show("serif spread", features.serif_spread);
+                        show("aperture", features.aperture);
+                        show("x-height ratio", features.x_height_ratio);
+                    }
+                    None => println!("⚠️  The font lacks the glyphs the classifier measures (o, n, e, a, H)"),
+                }
+            }
+        }
+        
+        Some(Commands::FitProfile { labels, top_k, output, dry_run, json }) => {
+            let queries = font_similarity::load_labelled_queries(&labels)?;
+            let config = load_config()?;
+            let resolver = if PathBuf::from("data/font_database.bin").exists() {
+                load_or_create_database(&config).or_else(|_| EnhancedFontResolver::new(config))?
+            } else {
+                EnhancedFontResolver::new(config)?
+            };
+            let report = resolver.fit_scoring_profile(&queries, top_k)?;
+            
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("{}", format!("SCORING PROFILE FIT ({} queries, top-{})", queries.len(), top_k).bold());
+                println!("{}", "-".repeat(40));
+                let show = |name: &str, evaluations: Option<(font_similarity::ProfileEvaluation, font_similarity::ProfileEvaluation)>| {
+                    if let Some((before, after)) = evaluations {
+                        println!("  {:<12} top-1 {:>5.1}% → {:>5.1}%   top-{} {:>5.1}% → {:>5.1}%",
+                            name,
+                            before.top1_accuracy() * 100.0, after.top1_accuracy() * 100.0,
+                            top_k, before.top_k_accuracy() * 100.0, after.top_k_accuracy() * 100.0);
+                        if after.unreachable > 0 {
+                            println!("   {}", format!("{} expected families are not installed or in the database", after.unreachable).dimmed());
+                        }
+                    }
+                };
+                show("similarity", report.similarity);
+                show("  held out", report.similarity_held_out);
+                show("resolver", report.resolver);
+                show("  held out", report.resolver_held_out);
+                if report.good_clamped {
+                    println!("   {}", format!("Good suggestion threshold kept at good_min_score ({:.2})",
+                        report.profile.suggestions.good_min_score).dimmed());
+                }
+            }
+            
+            if !dry_run {
+                report.profile.save_to_file(&output)?;
+                eprintln!("✅ Saved scoring profile to {}", output.display());
+                eprintln!("   Use it with: fr config set scoring_profile {}", output.display());
+            }
+        }
+        
+        Some(Commands::Pair { font, role, scope, limit, json }) => {
+            let config = load_config()?;
+            let resolver = if PathBuf::from("data/font_database.bin").exists() {
+                load_or_create_database(&config).or_else(|_| EnhancedFontResolver::new(config))?
+            } else {
+                EnhancedFontResolver::new(config)?
+            };
+            let pairings = resolver.find_pairings(&font, role.into(), scope.into(), limit)?;
+            
+            if json {
+                println!("{}", serde_json::to_string_pretty(&pairings)?);
+            } else if pairings.is_empty() {
+                println!("No partners for '{}' among the fonts in scope", font);
+            } else {
+                let partners = match role {
+                    PairingRoleArg::Heading => "BODY FONTS",
+                    PairingRoleArg::Body => "HEADING FONTS",
+                };
+                println!("{}", format!("{} FOR {}", partners, font.to_uppercase()).bold());
+                println!("{}", "-".repeat(40));
+                for pairing in &pairings {
+                    println!("  {} {} ({}) {:.0}%", "•".cyan(), pairing.partner.bold(), pairing.partner_class.describe(), pairing.score * 100.0);
+                    for reason in &pairing.reasons {
+                        println!("    {}", reason.dimmed());
+                    }
+                }
+            }
+        }
+        
+        Some(Commands::Graph(cmd)) => {
+            let config = load_config()?;
+            let resolver = if PathBuf::from("data/font_database.bin").exists() {
+                load_or_create_database(&config).or_else(|_| EnhancedFontResolver::new(config))?
+            } else {
+                EnhancedFontResolver::new(config)?
+            };
+            let graph = resolver.similarity_graph()?;
+            
+            match cmd {
+                GraphCommands::Summary { limit, json } => {
+                    let components = graph.component_summaries();
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&components)?);
+                        return Ok(());
+                    }
+                    
+                    println!("{}", format!("SIMILARITY GRAPH ({} families, {} edges)", graph.node_count(), graph.edge_count()).bold());
+                    println!("{}", "-".repeat(40));
+                    let isolated = components.iter().filter(|c| c.size == 1).count();
+                    for component in components.iter().filter(|c| c.size > 1).take(limit) {
+                        let categories: Vec<String> = component.categories.iter()
+                            .map(|(category, count)| format!("{:?} {}", category, count))
+                            .collect();
+                        println!("  {} {} families around {} (mean similarity {:.2})",
+                            "•".cyan(), component.size, component.hub.bold(), component.mean_similarity);
+                        println!("    {}", component.families.join(", ").dimmed());
+                        println!("    {} | {} free, {} commercial, {} other",
+                            categories.join(", "), component.open_source_count, component.commercial_count, component.other_license_count);
+                    }
+                    if isolated > 0 {
+                        println!("  {}", format!("{} families have no similar family", isolated).dimmed());
+                    }
+                }
+                
+                GraphCommands::Clusters { kind, min_similarity, json } => {
+                    let kind = match kind {
+                        ClusterKindArg::Superfamily => font_similarity::ClusterKind::Superfamily,
+                        ClusterKindArg::Clones => font_similarity::ClusterKind::Clones,
+                        ClusterKindArg::LookAlikes => font_similarity::ClusterKind::LookAlikes,
+                    };
+                    let clusters = graph.clusters(kind, min_similarity);
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&clusters)?);
+                        return Ok(());
+                    }
+                    
+                    if clusters.is_empty() {
+                        println!("No {:?} clusters at similarity {:.2} or above", kind, min_similarity);
+                    }
+                    for cluster in &clusters {
+                        println!("  {} {} (mean similarity {:.2}, {} free, {} commercial)",
+                            "•".cyan(), cluster.families.join(", ").bold(), cluster.mean_similarity,
+                            cluster.open_source_count, cluster.commercial_count);
+                    }
+                }
+                
+                GraphCommands::Path { from, to, json } => {
+                    let Some(path) = graph.lookalike_path(&from, to.as_deref()) else {
+                        match to {
+                            Some(to) => println!("❌ No chain of look-alikes from '{}' to '{}'", from, to),
+                            None => println!("❌ No chain of look-alikes from '{}' to a free font", from),
+                        }
+                        process::exit(1);
+                    };
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&path)?);
+                        return Ok(());
+                    }
+                    
+                    println!("  {}", path.families[0].bold());
+                    for (family, similarity) in path.families[1..].iter().zip(&path.similarities) {
+                        println!("  {} {} ({:.2})", "→".cyan(), family.bold(), similarity);
+                    }
+                }
+                
+                GraphCommands::Export { format, output } => {
+                    let rendered = match format {
+                        GraphFormatArg::Dot => graph.to_dot(),
+                        GraphFormatArg::Graphml => graph.to_graphml(),
+                    };
+                    match output {
+                        Some(path) => {
+                            fs::write(&path, rendered)?;
+                            eprintln!("📝 Graph of {} families written to {}", graph.node_count(), path.display());
+                        }
+                        None => print!("{}", rendered),
+                    }
+                }
+            }
+        }
+        
+        Some(Commands::Audit { dir, policy, format, output, fail_on_warnings }) => {
+            let policy_path = policy.or_else(|| {
+                let default = dir.join("font-policy.toml");
+                default.exists().then_some(default)
+            });
+            let policy = match &policy_path {
+                Some(path) => font_audit::AuditPolicy::load(path)?,
+                None => font_audit::AuditPolicy::default(),
+            };
+            
+            let report = font_audit::audit_project(&dir, &policy)?;
+            let rendered = match format {
+                AuditFormatArg::Json => report.to_json()?,
+                AuditFormatArg::Markdown => report.to_markdown(),
+                AuditFormatArg::Junit => report.to_junit(),
+            };
+            
+            match output {
+                Some(path) => {
+                    fs::write(&path, rendered)?;
+                    eprintln!("📝 Audit report written to {}", path.display());
+                }
+                None => print!("{}", rendered),
+            }
+            
+            let errors = report.error_count();
+            let warnings = report.warning_count();
+            eprintln!("🔎 {} font(s) audited: {} error(s), {} warning(s)", report.fonts.len(), errors, warnings);
+            if errors > 0 || (fail_on_warnings && warnings > 0) {
+                process::exit(AUDIT_VIOLATIONS_EXIT_CODE);
+            }
+        }
+        
+        Some(Commands::Sbom { dirs, document, acquired, format, name, output }) => {
+            if dirs.is_empty() && document.is_empty() && !acquired {
+                eprintln!("❌ Nothing to list: give a directory, --document or --acquired");
+                process::exit(2);
+            }
+            
+            let name = name.unwrap_or_else(|| {
+                dirs.iter().chain(document.iter())
+                    .filter_map(|p| fs::canonicalize(p).ok())
+                    .find_map(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+                    .unwrap_or_else(|| "fonts".to_string())
+            });
+            let mut sbom = font_audit::FontSbom::new(&name);
+            
+            for dir in &dirs {
+                let added = sbom.add_directory(dir, &format!("Directory: {}", dir.display()))?;
+                eprintln!("📁 {}: {} font file(s)", dir.display(), added);
+            }
+            
+            if !document.is_empty() {
+                let resolver = EnhancedFontResolver::new(load_config()?)?;
+                for path in &document {
+                    for font_name in font_audit::referenced_font_names(path)? {
+                        match resolver.resolve_with_sources(&font_name) {
+                            Ok(result) => {
+                                if let Err(e) = sbom.add_resolved(&result) {
+                                    eprintln!("⚠️  {} resolved to {} but could not be read: {}", font_name, result.font.path.display(), e);
+                                }
+                            }
+                            Err(e) => eprintln!("⚠️  Could not resolve '{}' from {}: {}", font_name, path.display(), e),
+                        }
+                    }
+                }
+            }
+            
+            if acquired {
+                let manager = font_acquisition::FontAcquisitionManager::new();
+                let cache = manager.download_cache();
+                if cache.is_dir() {
+                    let added = sbom.add_directory(cache, "Acquisition cache")?;
+                    eprintln!("🌐 Acquisition cache: {} font file(s)", added);
+                }
+            }
+            
+            let rendered = match format {
+                SbomFormatArg::Spdx => sbom.to_spdx()?,
+                SbomFormatArg::Cyclonedx => sbom.to_cyclonedx()?,
+            };
+            match output {
+                Some(path) => {
+                    fs::write(&path, rendered)?;
+                    eprintln!("📝 SBOM with {} font(s) written to {}", sbom.components.len(), path.display());
+                }
+                None => println!("{}", rendered),
+            }
+        }
+        
+        Some(Commands::Notice { fonts, dir, document, output }) => {
+            let mut descriptors = Vec::new();
+            let parser = font_parser::FontParser;
+            
+            for dir in &dir {
+                for entry in walkdir::WalkDir::new(dir).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()) {
+                    let is_font = entry.path().extension()
+                        .and_then(|ext| ext.to_str())
+                        .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "ttf" | "otf" | "ttc"));
+                    if is_font {
+                        match parser.parse_font_file(entry.path()) {
+                            Ok(descriptor) => descriptors.push(descriptor),
+                            Err(e) => eprintln!("⚠️  Skipping {}: {}", entry.path().display(), e),
+                        }
+                    }
+                }
+            }
+            
+            let mut names = fonts;
+            for path in &document {
+                names.extend(font_audit::referenced_font_names(path)?);
+            }
+            if !names.is_empty() {
+                let resolver = EnhancedFontResolver::new(load_config()?)?;
+                for font_name in &names {
+                    match resolver.resolve_with_sources(font_name) {
+                        Ok(result) => descriptors.push(result.font),
+                        Err(e) => eprintln!("⚠️  Could not resolve '{}': {}", font_name, e),
+                    }
+                }
+            }
+            
+            if descriptors.is_empty() {
+                eprintln!("❌ No fonts to list: give font names, --dir or --document");
+                process::exit(2);
+            }
+            
+            let bundle = font_audit::NoticeBundle::build(&descriptors);
+            let notice_path = bundle.write(&output)?;
+            println!("📝 {} license group(s) written to {}", bundle.groups.len(), notice_path.display());
+            for warning in &bundle.warnings {
+                println!("⚠️  {}", warning.yellow());
+            }
+        }
+        
         Some(Commands::Update) => {
             println!("{}", "🌐 UPDATING FONT DATABASE".bold());
             println!("{}", "=".repeat(40));
@@ -774,7 +1399,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             // Already handled at the beginning
         }
         
-        Some(Commands::Identify { path, chars, db, verbose, json }) => {
+        Some(Commands::Identify { path, chars, db, verbose, json, unlabeled }) => {
             if !json {
                 println!("👁️  IDENTIFYING FONT VISUALLY");
                 println!("{}", "=".repeat(40));
@@ -804,8 +1429,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             if !json {
                 println!("📦 Loading database...");
             }
+            let profile = load_config()?.load_scoring_profile()?;
             let identifier = match font_visual_id::VisualIdentifier::from_file(&db_path) {
-                Ok(id) => id,
+                Ok(id) => id.with_profile(&profile),
                 Err(e) => {
                     if json {
                         println!("{{ \"error\": \"Failed to load database: {}\" }}", e);
@@ -842,10 +1468,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             
             if !json {
                 println!("\n🔍 Analyzing font: {}", path.display());
-                println!("   Characters: {}", chars);
+                println!("   Characters: {}{}", chars, if unlabeled { " (unlabeled)" } else { "" });
             }
             
-            match identifier.identify_multi(&path, &chars, 5) {
+            let results = if unlabeled {
+                let script = chars.chars().find_map(font_glyph_db::Script::of);
+                identifier.extract_unlabeled(&path, &chars)
+                    .map(|glyphs| identifier.identify_unlabeled(&glyphs, script, 5))
+            } else {
+                identifier.identify_multi(&path, &chars, 5)
+            };
+            
+            match results {
                 Ok(results) => {
                     if json {
                          println!("{}", serde_json::to_string_pretty(&results).unwrap_or_default());
@@ -876,6 +1510,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                                     result.subfamily.as_deref().unwrap_or("").dimmed(),
                                     colored_score
                                 );
+                                if !result.inferred_chars.is_empty() {
+                                    let read: String = result.inferred_chars.iter().map(|c| c.unwrap_or('?')).collect();
+                                    println!("   Read as: {}", read.dimmed());
+                                }
                             }
                         }
                     }
@@ -920,8 +1558,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             if !json {
                 println!("📦 Loading AI model...");
             }
+            let profile = load_config()?.load_scoring_profile()?;
             let identifier = match font_visual_id::VisualIdentifier::from_file(&db_path) {
-                Ok(id) => id,
+                Ok(id) => id.with_profile(&profile),
                 Err(e) => {
                     if json {
                         println!("{{ \"error\": \"Failed to load AI model: {}\" }}", e);
@@ -987,7 +1626,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
 
-        Some(Commands::BuildWebDb { limit, output }) => {
+        Some(Commands::BuildWebDb { limit, output, web_db_output }) => {
             println!("🌐 BUILDING WEB FONT DATABASE");
             println!("{}", "=".repeat(40));
             
@@ -1027,7 +1666,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .progress_chars("=>-"));
                 
             let mut builder = font_glyph_db::GlyphDatabaseBuilder::new();
+            let mut web_db = font_web_db::WebFontDatabase::new(&chrono::Utc::now().format("%Y.%m.%d").to_string());
             let mut success = 0;
+            let mut measured = 0;
             
             for font_data in all_fonts {
                 pb.set_message(format!("{}", font_data.family));
@@ -1040,6 +1681,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             Ok(_) => success += 1,
                             Err(_) => {}
                         }
+                        
+                        // Measure real metrics and signatures for the web font database
+                        let mut web_font = web_font_from_compressed(&font_data, &descriptor);
+                        if web_font.measure_from_file(&descriptor.path).is_ok() {
+                            measured += 1;
+                        }
+                        web_db.insert_font(web_font);
                     }
                     Err(_) => {
                          // Ignore download failures, common with free APIs
@@ -1064,6 +1712,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Err(e) => println!("❌ Failed to save database: {}", e),
             }
             
+            println!("💾 Saving web font database to: {} ({} of {} fonts measured)",
+                web_db_output.display(), measured, web_db.count());
+            if let Some(parent) = web_db_output.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            if let Err(e) = web_db.save_to_file(&web_db_output) {
+                println!("❌ Failed to save web font database: {}", e);
+            }
+            
             // Allow cleanup of temp dir (optional, user might want them?)
              println!("🧹 Cleaning up temporary files...");
              if let Err(e) = std::fs::remove_dir_all(&temp_dir) {
@@ -1072,7 +1729,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
 
 
-        Some(Commands::BuildGlyphDb { source, output, compression: _, recursive }) => {
+        Some(Commands::BuildGlyphDb { source, output, compression: _, recursive, scripts }) => {
             println!("🏗️  BUILDING GLYPH DATABASE");
             println!("{}", "=".repeat(40));
             
@@ -1113,13 +1770,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 return Ok(());
             }
             
-            println!("🔨 Indexing fonts (this may take a while)...");
+            let scripts: Vec<font_glyph_db::Script> = scripts.into_iter().map(Into::into).collect();
+            let names: Vec<&str> = scripts.iter().map(|script| script.name()).collect();
+            println!("🔨 Indexing fonts for {} (this may take a while)...", names.join(", "));
             let pb = ProgressBar::new(font_paths.len() as u64);
             pb.set_style(ProgressStyle::default_bar()
                 .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}")?
                 .progress_chars("=>-"));
                 
-            let mut builder = font_glyph_db::GlyphDatabaseBuilder::new();
+            let mut builder = font_glyph_db::GlyphDatabaseBuilder::new().with_scripts(&scripts);
             let mut success_count = 0;
             
             for path in &font_paths {
@@ -1181,24 +1840,72 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("📦 Using database: {}", db.display());
             println!("💡 Use POST /api/identify with raw binary font data to identify");
             println!("💡 Use POST /api/ai-suggest with raw binary font data for similarity");
+            println!("💡 Use GET /api/pairings?font=<name>&role=heading|body&scope=available|local|license-safe&limit=5 for pairings");
             
             // Load database into memory once
+            let profile = load_config()?.load_scoring_profile()?;
             let identifier = match font_visual_id::VisualIdentifier::from_file(&db) {
-                Ok(id) => id,
+                Ok(id) => id.with_profile(&profile),
                 Err(e) => {
                     println!("❌ Failed to load AI model database: {}", e);
                     return Ok(());
                 }
             };
+            // The font resolver is only needed for pairings; load it on first use
+            let mut resolver: Option<EnhancedFontResolver> = None;
 
             for mut request in server.incoming_requests() {
-                let response = match (request.method(), request.url()) {
+                let url = request.url().to_string();
+                let (path, query) = url.split_once('?').unwrap_or((url.as_str(), ""));
+                let response = match (request.method(), path) {
+                    (&tiny_http::Method::Get, "/api/pairings") => {
+                        let role = match query_param(query, "role").as_deref() {
+                            None | Some("heading") => Ok(font_similarity::PairingRole::Heading),
+                            Some("body") => Ok(font_similarity::PairingRole::Body),
+                            Some(other) => Err(format!("Unknown role: {}", other)),
+                        };
+                        let scope = match query_param(query, "scope").as_deref() {
+                            None | Some("available") => Ok(font_similarity::PairingScope::Available),
+                            Some("local") => Ok(font_similarity::PairingScope::Local),
+                            Some("license-safe") => Ok(font_similarity::PairingScope::LicenseSafe),
+                            Some(other) => Err(format!("Unknown scope: {}", other)),
+                        };
+                        let limit = query_param(query, "limit").and_then(|l| l.parse().ok()).unwrap_or(5);
+                        
+                        let result = match (query_param(query, "font"), role, scope) {
+                            (None, _, _) => Err((400, "Missing font parameter".to_string())),
+                            (_, Err(e), _) | (_, _, Err(e)) => Err((400, e)),
+                            (Some(font), Ok(role), Ok(scope)) => {
+                                if resolver.is_none() {
+                                    resolver = load_config().ok()
+                                        .and_then(|config| load_or_create_database(&config).ok());
+                                }
+                                match resolver.as_ref() {
+                                    Some(resolver) => resolver.find_pairings(&font, role, scope, limit)
+                                        .map_err(|e| match e {
+                                            FontError::NotFound(_) => (404, e.to_string()),
+                                            FontError::InvalidRequest(_) => (400, e.to_string()),
+                                            _ => (500, e.to_string()),
+                                        }),
+                                    None => Err((503, "Font database not available".to_string())),
+                                }
+                            }
+                        };
+                        
+                        let (status, body) = match result {
+                            Ok(pairings) => (200, serde_json::to_string(&pairings).unwrap_or_default()),
+                            Err((status, error)) => (status, serde_json::json!({ "error": error }).to_string()),
+                        };
+                        tiny_http::Response::from_string(body)
+                            .with_status_code(status)
+                            .with_header(tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap())
+                    },
                     (&tiny_http::Method::Get, "/health") => {
                         tiny_http::Response::from_string("{\"status\": \"ok\", \"engine\": \"intelliFont\"}")
                             .with_header(tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap())
                     },
                     (&tiny_http::Method::Post, "/api/identify") | (&tiny_http::Method::Post, "/api/ai-suggest") => {
-                        let is_suggest = request.url().contains("ai-suggest");
+                        let is_suggest = path.contains("ai-suggest");
                         
                         // Get binary data
                         let mut body = Vec::new();
@@ -1287,11 +1994,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("  {}  Clean up cache", "fr cache cleanup".cyan());
             println!("  {}  Pin a font (never delete)", "fr cache pin <font>".cyan());
             println!("  {}  List pinned fonts", "fr cache list".cyan());
+            println!("  {}  Find entries whose font file changed", "fr cache verify".cyan());
             
             println!("\n{}", "📊 INFORMATION".bold());
             println!("  {}  Show statistics", "fr stats".cyan());
             println!("  {}  Scan system fonts", "fr scan".cyan());
             println!("  {}  Check font license", "fr check-license <font>".cyan());
+            println!("  {}  Verify metric-compatible substitutes", "fr substitutes".cyan());
+            println!("  {}  Classify a font's style from its outlines", "fr classify <file>".cyan());
+            println!("  {}  Fit matching weights to labelled queries", "fr fit-profile <labels>".cyan());
+            println!("  {}  Suggest heading/body pairings", "fr pair <font>".cyan());
+            println!("  {}  Font families clustered by similarity", "fr graph clusters".cyan());
+            println!("  {}  Look-alikes from a font to a free font", "fr graph path <font>".cyan());
+            println!("  {}  Export the similarity graph (DOT/GraphML)", "fr graph export".cyan());
+            println!("  {}  Audit project fonts against a license policy", "fr audit <dir>".cyan());
+            println!("  {}  Export a font SBOM (SPDX/CycloneDX)", "fr sbom <dir>".cyan());
+            println!("  {}  Write attribution notices and license texts", "fr notice <fonts>".cyan());
+            println!("  {}  Update the commercial font knowledge base", "fr license-db update".cyan());
             println!("  {}  Update font database", "fr update".cyan());
             println!("  {}  Show version", "fr --version".cyan());
             
@@ -1305,6 +2024,35 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 // Helper function to load or create database
+/// Percent-decoded value of `name` in a URL query string
+fn query_param(query: &str, name: &str) -> Option<String> {
+    let value = query.split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)?
+        .1;
+    
+    let bytes = value.as_bytes();
+    let hex = |byte: u8| (byte as char).to_digit(16).map(|digit| digit as u8);
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = match bytes.get(i + 1..i + 3) {
+            Some(&[high, low]) if bytes[i] == b'%' => hex(high).zip(hex(low)).map(|(h, l)| h * 16 + l),
+            _ => None,
+        };
+        match (bytes[i], escaped) {
+            (_, Some(byte)) => {
+                decoded.push(byte);
+                i += 2;
+            }
+            (b'+', None) => decoded.push(b' '),
+            (byte, None) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    Some(String::from_utf8_lossy(&decoded).into_owned())
+}
+
 fn load_or_create_database(config: &EnhancedResolverConfig) -> Result<EnhancedFontResolver, Box<dyn std::error::Error>> {
     println!("🔄 Starting load_or_create_database...");
     let database_path = PathBuf::from("data/font_database.bin");
@@ -1475,7 +2223,11 @@ fn create_minimal_database() -> Result<Vec<u8>, Box<dyn std::error::Error>> {
                 allows_modification: false,
                 requires_attribution: false,
                 allows_commercial_use: true,
+                embedding: None,
+                spdx: None,
+                reserved_font_names: Vec::new(),
             }),
+            classification: None,
         },
         FontDescriptor {
             family: "Times New Roman".to_string(),
@@ -1504,7 +2256,11 @@ fn create_minimal_database() -> Result<Vec<u8>, Box<dyn std::error::Error>> {
                 allows_modification: false,
                 requires_attribution: false,
                 allows_commercial_use: true,
+                embedding: None,
+                spdx: None,
+                reserved_font_names: Vec::new(),
             }),
+            classification: None,
         },
         FontDescriptor {
             family: "Courier New".to_string(),
@@ -1533,7 +2289,11 @@ fn create_minimal_database() -> Result<Vec<u8>, Box<dyn std::error::Error>> {
                 allows_modification: false,
                 requires_attribution: false,
                 allows_commercial_use: true,
+                embedding: None,
+                spdx: None,
+                reserved_font_names: Vec::new(),
             }),
+            classification: None,
         },
         FontDescriptor {
             family: "Verdana".to_string(),
@@ -1562,7 +2322,11 @@ fn create_minimal_database() -> Result<Vec<u8>, Box<dyn std::error::Error>> {
                 allows_modification: false,
                 requires_attribution: false,
                 allows_commercial_use: true,
+                embedding: None,
+                spdx: None,
+                reserved_font_names: Vec::new(),
             }),
+            classification: None,
         },
         FontDescriptor {
             family: "Georgia".to_string(),
@@ -1591,7 +2355,11 @@ fn create_minimal_database() -> Result<Vec<u8>, Box<dyn std::error::Error>> {
                 allows_modification: false,
                 requires_attribution: false,
                 allows_commercial_use: true,
+                embedding: None,
+                spdx: None,
+                reserved_font_names: Vec::new(),
             }),
+            classification: None,
         },
     ];
     
@@ -1653,10 +2421,53 @@ fn ensure_database_exists() -> Result<(), Box<dyn std::error::Error>> {
     
     println!("✅ Created database at: {}", database_path.display());
     println!("   Run 'fr update' to download full font database");
-    
+
     Ok(())
 }
 
+/// Build a web font database entry from a provider search result
+fn web_font_from_compressed(font: &font_compressor::CompressedFontData, descriptor: &FontDescriptor) -> font_web_db::WebFont {
+    use font_compressor::FontCategory as Compressed;
+    use font_web_db::FontCategory;
+
+    let category = match font.category {
+        Compressed::Serif => FontCategory::Serif,
+        Compressed::SansSerif => FontCategory::SansSerif,
+        Compressed::Monospace => FontCategory::Monospace,
+        Compressed::Display | Compressed::Decorative => FontCategory::Display,
+        Compressed::Handwriting => FontCategory::Handwriting,
+        Compressed::Symbol | Compressed::Other => FontCategory::Other,
+    };
+
+    font_web_db::WebFont {
+        family: font.family.clone(),
+        variants: vec![font_web_db::WebFontVariant {
+            weight: font.weight,
+            italic: font.italic,
+            style: descriptor.subfamily.clone().unwrap_or_else(|| "Regular".to_string()),
+            file_url: font.download_urls.get(&FontFormat::Ttf).cloned().unwrap_or_default(),
+            file_format: FontFormat::Ttf,
+            file_size_kb: font.file_size_kb,
+        }],
+        category,
+        popularity: font.popularity,
+        last_updated: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+        license: font_web_db::WebFontLicense {
+            name: font.license.name.clone(),
+            url: font.license.url.clone(),
+            allows_commercial_use: font.license.allows_commercial_use,
+            allows_modification: font.license.allows_modification,
+            requires_attribution: font.license.requires_attribution,
+        },
+        similar_fonts: font.similar_fonts.clone(),
+        subsets: Vec::new(),
+        axes: Vec::new(),
+        metrics: None,
+        signatures: Vec::new(),
+        classification: descriptor.classification,
+    }
+}
+
 #[derive(Parser)]
 #[command(name = "intellifont", 
           about = "intelliFont Engine CLI", 
@@ -1713,6 +2524,10 @@ enum Commands {
         /// Allow the engine to search global CDNs if the best local similarity score is below the 80% threshold.
         #[arg(short = 'i', long = "internet")]
         enable_internet: bool,
+        
+        /// Glyph signature database used to rank candidates by how they look (data/glyph_signatures.bin is loaded when present).
+        #[arg(long, value_name = "PATH")]
+        glyph_db: Option<PathBuf>,
     },
     
     /// Initiate the interactive 3-step configuration wizard to initialize your engine settings.
@@ -1726,6 +2541,10 @@ enum Commands {
     #[command(subcommand)]
     Config(ConfigCommands),
     
+    /// Inspect or update the commercial font and foundry knowledge base used for license checks.
+    #[command(subcommand, name = "license-db")]
+    LicenseDb(LicenseDbCommands),
+    
     /// Perform a deep recursive scan of all registered system font directories to update the internal registry.
     Scan {
         /// Display a comprehensive list of all discovered font files, grouped by format and weight.
@@ -1747,11 +2566,219 @@ enum Commands {
         limit: usize,
     },
     
+    /// Search the web font catalogue by fuzzy name and facets (category, subsets, weights, axes, license, popularity, size).
+    #[command(alias = "s")]
+    Search {
+        /// Fuzzy family name to search for. Omit to browse by filters only.
+        #[arg(value_name = "QUERY")]
+        query: Option<String>,
+        
+        /// Restrict to categories (serif, sans-serif, monospace, display, handwriting). Repeatable.
+        #[arg(short = 'c', long = "category")]
+        categories: Vec<String>,
+        
+        /// Require character subsets such as latin-ext or cyrillic. Repeatable.
+        #[arg(long = "subset")]
+        subsets: Vec<String>,
+        
+        /// Require weights such as 400 or 700. Repeatable.
+        #[arg(long = "weight")]
+        weights: Vec<u16>,
+        
+        /// Require variable font axes such as wght or wdth. Repeatable.
+        #[arg(long = "axis")]
+        axes: Vec<String>,
+        
+        /// Only licenses whose name contains this text (e.g. OFL, Apache).
+        #[arg(short = 'l', long)]
+        license: Option<String>,
+        
+        /// Only fonts licensed for commercial use.
+        #[arg(long)]
+        commercial: bool,
+        
+        /// Minimum popularity (0-100).
+        #[arg(long)]
+        min_popularity: Option<u8>,
+        
+        /// Maximum file size of the smallest variant, in KB.
+        #[arg(long)]
+        max_size_kb: Option<u32>,
+        
+        /// Result ordering.
+        #[arg(long, value_enum, default_value = "relevance")]
+        sort: SearchSortArg,
+        
+        /// Page number, starting at 1.
+        #[arg(short = 'p', long, default_value = "1")]
+        page: usize,
+        
+        /// Results per page.
+        #[arg(short = 'n', long = "limit", default_value = "20")]
+        limit: usize,
+        
+        /// Print the result page as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    
     /// Analyze a font's licensing metadata to determine commercial safety and provide open-source alternatives.
     CheckLicense {
         /// The name of the font whose license should be audited.
         #[arg(value_name = "FONT_NAME")]
         font_name: String,
+        
+        /// Check the font's embedding permissions (OS/2 fsType) for an operation; exits non-zero if refused.
+        #[arg(long, value_enum)]
+        operation: Option<OperationArg>,
+        
+        /// Family name a derived copy will use; exits non-zero if it keeps an OFL Reserved Font Name.
+        #[arg(long, value_name = "FAMILY")]
+        derived_name: Option<String>,
+        
+        /// How the copy is derived (with --derived-name).
+        #[arg(long, value_enum, default_value = "subset")]
+        derivation: DerivationArg,
+    },
+    
+    /// Verify metric-compatible substitutes (e.g. Arial → Liberation Sans) against the installed fonts' advance widths.
+    Substitutes {
+        /// Only show substitutes for this family.
+        #[arg(value_name = "FAMILY")]
+        family: Option<String>,
+        
+        /// Print the verification results as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    
+    /// Classify a font's style (serif, sans, slab, script, ...) from its glyph outlines.
+    Classify {
+        /// Font file to classify.
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        
+        /// Print the measured features and confidences as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    
+    /// Fit matching weights and tier thresholds to labelled queries, maximising top-k accuracy.
+    FitProfile {
+        /// JSON array or JSON lines of {"query": ..., "expected": ...}.
+        #[arg(value_name = "LABELS")]
+        labels: PathBuf,
+        
+        /// Count a query as correct when the expected family is among the top K families.
+        #[arg(long, default_value_t = 3)]
+        top_k: usize,
+        
+        /// Where to write the fitted profile.
+        #[arg(short, long, default_value = font_core::DEFAULT_SCORING_PROFILE_PATH)]
+        output: PathBuf,
+        
+        /// Report accuracy without writing the profile.
+        #[arg(long)]
+        dry_run: bool,
+        
+        /// Print the report and fitted profile as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    
+    /// Suggest fonts that pair well with a font for heading/body combinations.
+    Pair {
+        font: String,
+        
+        /// The role FONT plays; partners are suggested for the other role.
+        #[arg(long, value_enum, default_value = "heading")]
+        role: PairingRoleArg,
+        
+        /// Which fonts may be suggested.
+        #[arg(long, value_enum, default_value = "available")]
+        scope: PairingScopeArg,
+        
+        #[arg(short = 'n', long, default_value_t = 5)]
+        limit: usize,
+        
+        #[arg(long)]
+        json: bool,
+    },
+    
+    /// Explore the catalog as a graph of similar families: clusters, look-alike paths and exports.
+    #[command(subcommand)]
+    Graph(GraphCommands),
+    
+    /// Audit the fonts a project uses (font files, @font-face rules, PDFs) against a license policy.
+    ///
+    /// Exit status: 0 when the policy is met, 3 on policy violations (or on warnings with
+    /// --fail-on-warnings), 1 when the audit itself fails, 2 on invalid arguments.
+    Audit {
+        /// Project directory to scan.
+        #[arg(value_name = "DIR", default_value = ".")]
+        dir: PathBuf,
+        
+        /// Policy file (TOML, or JSON by extension); defaults to DIR/font-policy.toml when present.
+        #[arg(long)]
+        policy: Option<PathBuf>,
+        
+        /// Report format.
+        #[arg(long, value_enum, default_value = "markdown")]
+        format: AuditFormatArg,
+        
+        /// Write the report to a file instead of stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        /// Also fail when only warnings were found.
+        #[arg(long)]
+        fail_on_warnings: bool,
+    },
+    
+    /// Generate a bill of materials for font files (SPDX 2.3 or CycloneDX 1.5 JSON).
+    Sbom {
+        /// Directories of font files to include.
+        #[arg(value_name = "DIR")]
+        dirs: Vec<PathBuf>,
+        
+        /// Resolve the fonts a document (PDF, CSS, HTML) refers to and include the files used.
+        #[arg(long)]
+        document: Vec<PathBuf>,
+        
+        /// Include fonts downloaded into the acquisition cache.
+        #[arg(long)]
+        acquired: bool,
+        
+        /// Output format.
+        #[arg(long, value_enum, default_value = "spdx")]
+        format: SbomFormatArg,
+        
+        /// Document name; defaults to the first directory or document name.
+        #[arg(long)]
+        name: Option<String>,
+        
+        /// Write the SBOM to a file instead of stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    
+    /// Build a THIRD-PARTY-NOTICES file and license texts for the fonts you ship.
+    Notice {
+        /// Font names to resolve and include.
+        #[arg(value_name = "FONT_NAME")]
+        fonts: Vec<String>,
+        
+        /// Directories of font files to include.
+        #[arg(long)]
+        dir: Vec<PathBuf>,
+        
+        /// Resolve the fonts a document (PDF, CSS, HTML) refers to and include them.
+        #[arg(long)]
+        document: Vec<PathBuf>,
+        
+        /// Directory to write the notice and licenses/ folder into.
+        #[arg(short, long, default_value = "third-party")]
+        output: PathBuf,
     },
     
     /// Synchronize the local signature database with the latest global updates and regenerate optimized indexes.
@@ -1781,6 +2808,10 @@ enum Commands {
         /// Output results as JSON for programmatic use
         #[arg(long)]
         json: bool,
+        
+        /// Match the glyphs without telling the database which characters they are, and show what each was read as
+        #[arg(long)]
+        unlabeled: bool,
     },
 
     /// AI-powered font similarity finder
@@ -1822,6 +2853,10 @@ enum Commands {
         /// Recursively search for fonts
         #[arg(short, long)]
         recursive: bool,
+        
+        /// Scripts to index; fonts are matched only against queries in the scripts they cover.
+        #[arg(long, value_enum, value_delimiter = ',', default_value = "latin")]
+        scripts: Vec<ScriptArg>,
     },
 
     /// Download and index popular web fonts (Google Fonts via Fontsource)
@@ -1834,6 +2869,10 @@ enum Commands {
         /// Output path
         #[arg(short, long, default_value = "data/web_glyph_signatures.bin")]
         output: PathBuf,
+        
+        /// Output path for the web font database (metrics and signatures per family)
+        #[arg(long, default_value = font_web_db::DEFAULT_DB_PATH)]
+        web_db_output: PathBuf,
     },
 
     
@@ -1861,6 +2900,212 @@ enum Commands {
     },
 }
 
+#[derive(Clone, Copy, ValueEnum)]
+enum OperationArg {
+    /// Embed into a PDF
+    Embed,
+    /// Subset for use as a web font
+    Subset,
+    /// Embed into an editable document
+    Edit,
+}
+
+impl From<OperationArg> for font_license::FontOperation {
+    fn from(operation: OperationArg) -> Self {
+        match operation {
+            OperationArg::Embed => font_license::FontOperation::EmbedInPdf,
+            OperationArg::Subset => font_license::FontOperation::SubsetForWeb,
+            OperationArg::Edit => font_license::FontOperation::EditDocument,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DerivationArg {
+    /// Glyph subset
+    Subset,
+    /// Format conversion (e.g. to WOFF2)
+    Convert,
+    /// Static instance of a variable font
+    Instance,
+}
+
+impl From<DerivationArg> for font_license::Derivation {
+    fn from(derivation: DerivationArg) -> Self {
+        match derivation {
+            DerivationArg::Subset => font_license::Derivation::Subset,
+            DerivationArg::Convert => font_license::Derivation::Converted,
+            DerivationArg::Instance => font_license::Derivation::Instanced,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ScriptArg {
+    Latin,
+    Greek,
+    Cyrillic,
+    Arabic,
+    Devanagari,
+    /// Han ideographs, kana and Hangul
+    Cjk,
+}
+
+impl From<ScriptArg> for font_glyph_db::Script {
+    fn from(script: ScriptArg) -> Self {
+        match script {
+            ScriptArg::Latin => font_glyph_db::Script::Latin,
+            ScriptArg::Greek => font_glyph_db::Script::Greek,
+            ScriptArg::Cyrillic => font_glyph_db::Script::Cyrillic,
+            ScriptArg::Arabic => font_glyph_db::Script::Arabic,
+            ScriptArg::Devanagari => font_glyph_db::Script::Devanagari,
+            ScriptArg::Cjk => font_glyph_db::Script::Cjk,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum AuditFormatArg {
+    Json,
+    Markdown,
+    Junit,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SbomFormatArg {
+    /// SPDX 2.3 JSON
+    Spdx,
+    /// CycloneDX 1.5 JSON
+    Cyclonedx,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SearchSortArg {
+    Relevance,
+    Popularity,
+    Name,
+}
+
+impl From<SearchSortArg> for font_web_db::SearchSort {
+    fn from(sort: SearchSortArg) -> Self {
+        match sort {
+            SearchSortArg::Relevance => font_web_db::SearchSort::Relevance,
+            SearchSortArg::Popularity => font_web_db::SearchSort::Popularity,
+            SearchSortArg::Name => font_web_db::SearchSort::Name,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum GraphCommands {
+    /// Summarise the connected groups of similar families.
+    Summary {
+        /// Show at most this many groups.
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        
+        #[arg(long)]
+        json: bool,
+    },
+    
+    /// List superfamilies, clones or look-alike groups.
+    Clusters {
+        #[arg(long, value_enum, default_value = "look-alikes")]
+        kind: ClusterKindArg,
+        
+        /// Only follow edges at least this similar.
+        #[arg(long, default_value_t = font_similarity::MIN_EDGE_SIMILARITY)]
+        min_similarity: f32,
+        
+        #[arg(long)]
+        json: bool,
+    },
+    
+    /// Find a chain of look-alikes from one family to another, or to the nearest free font.
+    Path {
+        from: String,
+        
+        /// Target family; defaults to the closest open-source family.
+        #[arg(long)]
+        to: Option<String>,
+        
+        #[arg(long)]
+        json: bool,
+    },
+    
+    /// Export the graph for Gephi, yEd or Graphviz.
+    Export {
+        #[arg(long, value_enum, default_value = "graphml")]
+        format: GraphFormatArg,
+        
+        /// Write to a file instead of stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum PairingRoleArg {
+    Heading,
+    Body,
+}
+
+impl From<PairingRoleArg> for font_similarity::PairingRole {
+    fn from(role: PairingRoleArg) -> Self {
+        match role {
+            PairingRoleArg::Heading => Self::Heading,
+            PairingRoleArg::Body => Self::Body,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum PairingScopeArg {
+    /// Installed or open-source fonts
+    Available,
+    /// Installed fonts only
+    Local,
+    /// Open-source fonts only
+    LicenseSafe,
+}
+
+impl From<PairingScopeArg> for font_similarity::PairingScope {
+    fn from(scope: PairingScopeArg) -> Self {
+        match scope {
+            PairingScopeArg::Available => Self::Available,
+            PairingScopeArg::Local => Self::Local,
+            PairingScopeArg::LicenseSafe => Self::LicenseSafe,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ClusterKindArg {
+    Superfamily,
+    Clones,
+    LookAlikes,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum GraphFormatArg {
+    Dot,
+    Graphml,
+}
+
+#[derive(Subcommand)]
+enum LicenseDbCommands {
+    /// Show the knowledge base version and where it was loaded from.
+    Info,
+    
+    /// Install pending updates listed in the update manifest, including the knowledge base,
+    /// or a knowledge base from a URL or a local JSON file.
+    Update {
+        /// URL or file to install the knowledge base from instead of the update server.
+        #[arg(long)]
+        from: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 enum CacheCommands {
     /// Show detailed cache utilization, including hit rates and memory/disk footprints.
@@ -1879,13 +3124,13 @@ enum CacheCommands {
     
     /// Lock a specific font in the cache so it is never removed during automatic cleanup operations.
     Pin {
-        /// The name of the font family to permanently cache.
+        /// A family (pins every cached face), a face such as "Arial Bold", or a raw cache key.
         font_name: String,
     },
     
     /// Remove the permanent lock from a font, allowing it to be managed by the standard cache eviction logic.
     Unpin {
-        /// The name of the font family to release from the pin.
+        /// A family (unpins every face), a face such as "Arial Bold", or a raw cache key.
         font_name: String,
     },
     
@@ -1894,6 +3139,13 @@ enum CacheCommands {
     
     /// Analyze cache usage and suggest specific entries for manual removal to improve performance.
     Suggest,
+    
+    /// Check cached fonts against their files and report entries whose file was modified or removed.
+    Verify {
+        /// Remove the dangling entries instead of only reporting them.
+        #[arg(long)]
+        purge: bool,
+    },
 }
 
 #[derive(Subcommand, Clone)]